@@ -9,6 +9,7 @@ use bevy::{
     ui::ui_layout_system,
 };
 use bevy_editor_camera::{EditorCamera2d, EditorCamera2dPlugin};
+use bevy_editor_core::{AssetNode, SpawnAsset};
 use bevy_editor_styles::Theme;
 use bevy_infinite_grid::{InfiniteGrid, InfiniteGridPlugin, InfiniteGridSettings};
 use bevy_pane_layout::prelude::*;
@@ -130,13 +131,54 @@ fn on_pane_creation(
             move |_trigger: Trigger<Pointer<Out>>, mut query: Query<&mut EditorCamera2d>| {
                 query.get_mut(camera_id).unwrap().enabled = false;
             },
-        );
+        )
+        .observe(drop_asset);
 
     commands
         .entity(structure.root)
         .insert(Bevy2dViewport { camera_id });
 }
 
+/// Spawn the asset of an [`AssetNode`] dropped onto the viewport image at the pointer position.
+fn drop_asset(
+    trigger: Trigger<Pointer<DragDrop>>,
+    asset_nodes: Query<&AssetNode>,
+    parent_query: Query<&ChildOf>,
+    viewports: Query<&Bevy2dViewport>,
+    nodes: Query<(&ComputedNode, &GlobalTransform)>,
+    cameras: Query<(&Camera, &GlobalTransform)>,
+    mut spawn_asset: EventWriter<SpawnAsset>,
+) {
+    let Ok(asset_node) = asset_nodes.get(trigger.dropped) else {
+        return;
+    };
+    let image_node = trigger.target();
+    let Some(viewport) = parent_query
+        .iter_ancestors(image_node)
+        .find_map(|entity| viewports.get(entity).ok())
+    else {
+        return;
+    };
+    let Ok((computed_node, node_transform)) = nodes.get(image_node) else {
+        return;
+    };
+    let Ok((camera, camera_transform)) = cameras.get(viewport.camera_id) else {
+        return;
+    };
+
+    let node_rect = Rect::from_center_size(node_transform.translation().xy(), computed_node.size());
+    let position = trigger.pointer_location.position - node_rect.min;
+    let Ok(point) = camera.viewport_to_world_2d(camera_transform, position) else {
+        return;
+    };
+    spawn_asset.write(SpawnAsset {
+        path: asset_node.0.clone(),
+        transform: Transform::from_translation(point.extend(0.)),
+        parent: None,
+        in_2d: true,
+    });
+}
+
 fn update_render_target_size(
     query: Query<(Entity, &Bevy2dViewport)>,
     mut camera_query: Query<(&Camera, &mut EditorCamera2d)>,
@@ -0,0 +1,66 @@
+//! Dropping assets onto 3D viewports.
+
+use bevy::{prelude::*, render::camera::NormalizedRenderTarget};
+use bevy_editor_core::{AssetNode, SpawnAsset};
+use bevy_transform_gizmos::snapping::Snapper;
+
+use crate::{Bevy3dViewport, ShadingProxy};
+
+/// How far in front of the camera assets are placed when the pointer doesn't hit anything.
+const FALLBACK_DISTANCE: f32 = 10.;
+
+/// Spawn the asset of an [`AssetNode`] dropped onto the viewport image, where the pointer hits
+/// the scene or the grid of the viewport.
+#[expect(clippy::too_many_arguments)]
+pub(crate) fn drop_asset(
+    trigger: Trigger<Pointer<DragDrop>>,
+    asset_nodes: Query<&AssetNode>,
+    parent_query: Query<&ChildOf>,
+    viewports: Query<&Bevy3dViewport>,
+    nodes: Query<(&ComputedNode, &GlobalTransform)>,
+    cameras: Query<(&Camera, &GlobalTransform)>,
+    proxies: Query<(), With<ShadingProxy>>,
+    mut snapper: Snapper,
+    mut spawn_asset: EventWriter<SpawnAsset>,
+) {
+    let Ok(asset_node) = asset_nodes.get(trigger.dropped) else {
+        return;
+    };
+    let image_node = trigger.target();
+    let Some(viewport) = parent_query
+        .iter_ancestors(image_node)
+        .find_map(|entity| viewports.get(entity).ok())
+    else {
+        return;
+    };
+    let Ok((computed_node, node_transform)) = nodes.get(image_node) else {
+        return;
+    };
+    let Ok((camera, camera_transform)) = cameras.get(viewport.camera_id) else {
+        return;
+    };
+
+    // The pointer may already be relocated to the render target of the viewport
+    let location = &trigger.pointer_location;
+    let position = match location.target {
+        NormalizedRenderTarget::Image(_) => location.position,
+        _ => {
+            let node_rect =
+                Rect::from_center_size(node_transform.translation().xy(), computed_node.size());
+            location.position - node_rect.min
+        }
+    };
+    let Ok(ray) = camera.viewport_to_world(camera_transform, position) else {
+        return;
+    };
+
+    let point = snapper
+        .place(viewport.camera_id, ray, &|entity| !proxies.contains(entity))
+        .map_or(ray.get_point(FALLBACK_DISTANCE), |hit| hit.point);
+    spawn_asset.write(SpawnAsset {
+        path: asset_node.0.clone(),
+        transform: Transform::from_translation(point),
+        parent: None,
+        in_2d: false,
+    });
+}
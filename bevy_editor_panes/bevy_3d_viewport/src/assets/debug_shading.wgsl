@@ -0,0 +1,25 @@
+#import bevy_pbr::forward_io::VertexOutput
+
+// 0: world space normals, 1: UV checker
+@group(2) @binding(0) var<uniform> mode: u32;
+
+const CHECKER_CELLS: f32 = 8.0;
+
+@fragment
+fn fragment(in: VertexOutput) -> @location(0) vec4<f32> {
+    if mode == 0u {
+        // Map the normal from [-1, 1] to [0, 1]
+        return vec4(normalize(in.world_normal) * 0.5 + 0.5, 1.0);
+    }
+
+#ifdef VERTEX_UVS_A
+    let cell = vec2<i32>(floor(in.uv * CHECKER_CELLS));
+    let dark = ((cell.x + cell.y) & 1) == 1;
+    // Tint the cells with the UV coordinates, so the orientation of the mapping is visible
+    let tint = vec3(fract(in.uv), 1.0);
+    return vec4(select(tint, tint * 0.4, dark), 1.0);
+#else
+    // Meshes without UVs are shown in magenta
+    return vec4(1.0, 0.0, 1.0, 1.0);
+#endif
+}
@@ -0,0 +1,399 @@
+//! Named camera viewpoints that can be saved from and recalled into a 3D viewport.
+//!
+//! Hold Ctrl (Cmd on macOS) and press a number key while hovering a viewport to save its camera to
+//! that key, and press the number key alone to fly back to it. Bookmarks are stored in the project
+//! settings.
+
+use bevy::prelude::*;
+use bevy_editor_cam::{extensions::look_to::FlyToTrigger, prelude::EditorCam};
+use bevy_editor_settings::{EditorSettingsPlugin, SaveSettings, SettingKey, SettingsType};
+use bevy_focus::Focus;
+use bevy_infinite_grid::InfiniteGrid;
+use bevy_text_editing::keymap::Modifiers;
+
+use crate::{Active, Bevy3dViewport, ViewPreset};
+
+/// The number keys that bookmarks can be bound to, in order.
+const BOOKMARK_KEYS: [KeyCode; 9] = [
+    KeyCode::Digit1,
+    KeyCode::Digit2,
+    KeyCode::Digit3,
+    KeyCode::Digit4,
+    KeyCode::Digit5,
+    KeyCode::Digit6,
+    KeyCode::Digit7,
+    KeyCode::Digit8,
+    KeyCode::Digit9,
+];
+
+pub struct CameraBookmarksPlugin;
+
+impl Plugin for CameraBookmarksPlugin {
+    fn build(&self, app: &mut App) {
+        if !app.is_plugin_added::<EditorSettingsPlugin>() {
+            app.add_plugins(EditorSettingsPlugin);
+        }
+
+        app.init_resource::<CameraBookmarks>()
+            .register_type::<CameraBookmarks>()
+            .add_event::<SaveCameraBookmark>()
+            .add_event::<RecallCameraBookmark>()
+            .add_systems(
+                Update,
+                (bookmark_hotkeys, save_bookmarks, recall_bookmarks).chain(),
+            );
+    }
+}
+
+/// The saved camera viewpoints of the project.
+#[derive(Resource, Reflect, Default, Clone, Debug)]
+#[reflect(Resource, @SettingsType::Project, @SettingKey("camera_bookmarks"))]
+pub struct CameraBookmarks {
+    /// All saved bookmarks. Names are unique.
+    pub bookmarks: Vec<CameraBookmark>,
+}
+
+impl CameraBookmarks {
+    /// Get the bookmark with the given name.
+    pub fn get(&self, name: &str) -> Option<&CameraBookmark> {
+        self.bookmarks.iter().find(|bookmark| bookmark.name == name)
+    }
+
+    /// Get the bookmark bound to the given number key.
+    pub fn get_by_key(&self, key: u8) -> Option<&CameraBookmark> {
+        self.bookmarks
+            .iter()
+            .find(|bookmark| bookmark.key == Some(key))
+    }
+
+    /// Add a bookmark, replacing any bookmark with the same name. If the new bookmark is bound to
+    /// a key, other bookmarks bound to that key are unbound.
+    pub fn insert(&mut self, bookmark: CameraBookmark) {
+        if bookmark.key.is_some() {
+            for other in &mut self.bookmarks {
+                if other.key == bookmark.key {
+                    other.key = None;
+                }
+            }
+        }
+        match self
+            .bookmarks
+            .iter_mut()
+            .find(|other| other.name == bookmark.name)
+        {
+            Some(existing) => *existing = bookmark,
+            None => self.bookmarks.push(bookmark),
+        }
+    }
+
+    /// Remove the bookmark with the given name.
+    pub fn remove(&mut self, name: &str) -> Option<CameraBookmark> {
+        let index = self
+            .bookmarks
+            .iter()
+            .position(|bookmark| bookmark.name == name)?;
+        Some(self.bookmarks.remove(index))
+    }
+}
+
+/// A saved camera viewpoint.
+#[derive(Reflect, Default, Clone, Debug, PartialEq)]
+pub struct CameraBookmark {
+    /// The name of the bookmark.
+    pub name: String,
+    /// The number key (1-9) that recalls this bookmark, if it is bound to a key.
+    pub key: Option<u8>,
+    /// The position of the camera.
+    pub translation: Vec3,
+    /// The orientation of the camera.
+    pub rotation: Quat,
+    /// The distance from the camera to the point it orbits about, see
+    /// [`EditorCam::last_anchor_depth`].
+    pub anchor_depth: f64,
+    /// Whether the camera uses an orthographic projection.
+    pub orthographic: bool,
+    /// The vertical field of view of a perspective projection, in radians.
+    pub fov: f32,
+    /// The scale of an orthographic projection.
+    pub scale: f32,
+}
+
+impl CameraBookmark {
+    /// Create a bookmark from the current state of a viewport camera.
+    pub fn from_camera(
+        name: impl Into<String>,
+        key: Option<u8>,
+        transform: &Transform,
+        editor_cam: &EditorCam,
+        projection: &Projection,
+    ) -> Self {
+        let (orthographic, fov, scale) = match projection {
+            Projection::Orthographic(ortho) => {
+                (true, PerspectiveProjection::default().fov, ortho.scale)
+            }
+            Projection::Perspective(perspective) => (false, perspective.fov, 1.0),
+            Projection::Custom(_) => (false, PerspectiveProjection::default().fov, 1.0),
+        };
+        Self {
+            name: name.into(),
+            key,
+            translation: transform.translation,
+            rotation: transform.rotation,
+            anchor_depth: editor_cam.last_anchor_depth().abs(),
+            orthographic,
+            fov,
+            scale,
+        }
+    }
+
+    /// The view preset of a viewport showing the bookmark: perspective, or the orthographic preset
+    /// looking in the direction closest to the bookmarked camera.
+    pub fn view_preset(&self) -> ViewPreset {
+        if self.orthographic {
+            ViewPreset::closest_orthographic(self.rotation * Vec3::NEG_Z)
+        } else {
+            ViewPreset::Perspective
+        }
+    }
+
+    /// The projection of the bookmarked camera.
+    pub fn projection(&self) -> Projection {
+        if self.orthographic {
+            Projection::Orthographic(OrthographicProjection {
+                scale: self.scale,
+                ..OrthographicProjection::default_3d()
+            })
+        } else {
+            Projection::Perspective(PerspectiveProjection {
+                fov: self.fov,
+                ..default()
+            })
+        }
+    }
+}
+
+/// Send this event to save the current viewpoint of a viewport camera as a bookmark.
+#[derive(Event, Clone, Debug)]
+pub struct SaveCameraBookmark {
+    /// The viewport camera to save.
+    pub camera: Entity,
+    /// The name of the bookmark. An existing bookmark with this name is replaced.
+    pub name: String,
+    /// The number key (1-9) to bind the bookmark to, if any.
+    pub key: Option<u8>,
+}
+
+/// Send this event to smoothly move a viewport camera to a saved bookmark.
+#[derive(Event, Clone, Debug)]
+pub struct RecallCameraBookmark {
+    /// The viewport camera to move.
+    pub camera: Entity,
+    /// The name of the bookmark to recall.
+    pub name: String,
+}
+
+#[allow(clippy::too_many_arguments)]
+fn bookmark_hotkeys(
+    keys: Res<ButtonInput<KeyCode>>,
+    bookmarks: Res<CameraBookmarks>,
+    active_images: Query<Entity, (With<Active>, With<ImageNode>)>,
+    parent_query: Query<&ChildOf>,
+    viewports: Query<&Bevy3dViewport>,
+    focused: Query<(), With<Focus>>,
+    mut save: EventWriter<SaveCameraBookmark>,
+    mut recall: EventWriter<RecallCameraBookmark>,
+) {
+    // The digits are typed into the focused widget instead
+    if !focused.is_empty() {
+        return;
+    }
+
+    let Some(camera) = active_images.iter().find_map(|image| {
+        parent_query
+            .iter_ancestors(image)
+            .find_map(|entity| viewports.get(entity).ok())
+            .map(|viewport| viewport.camera_id)
+    }) else {
+        return;
+    };
+
+    let Some(key) = (1..)
+        .zip(BOOKMARK_KEYS)
+        .find(|(_, key_code)| keys.just_pressed(*key_code))
+        .map(|(key, _)| key)
+    else {
+        return;
+    };
+
+    let existing = bookmarks.get_by_key(key);
+    if Modifiers::pressed(&keys).primary() {
+        save.write(SaveCameraBookmark {
+            camera,
+            name: existing
+                .map(|bookmark| bookmark.name.clone())
+                .unwrap_or_else(|| format!("Bookmark {key}")),
+            key: Some(key),
+        });
+    } else if let Some(bookmark) = existing {
+        recall.write(RecallCameraBookmark {
+            camera,
+            name: bookmark.name.clone(),
+        });
+    }
+}
+
+fn save_bookmarks(
+    mut events: EventReader<SaveCameraBookmark>,
+    mut bookmarks: ResMut<CameraBookmarks>,
+    cameras: Query<(&Transform, &EditorCam, &Projection)>,
+    mut save_settings: EventWriter<SaveSettings>,
+) {
+    for event in events.read() {
+        let Ok((transform, editor_cam, projection)) = cameras.get(event.camera) else {
+            continue;
+        };
+        bookmarks.insert(CameraBookmark::from_camera(
+            event.name.clone(),
+            event.key,
+            transform,
+            editor_cam,
+            projection,
+        ));
+        save_settings.write(SaveSettings(SettingsType::Project));
+    }
+}
+
+fn recall_bookmarks(
+    mut events: EventReader<RecallCameraBookmark>,
+    bookmarks: Res<CameraBookmarks>,
+    mut cameras: Query<(&mut Projection, &mut EditorCam)>,
+    mut viewports: Query<(&Bevy3dViewport, &mut ViewPreset)>,
+    mut grids: Query<&mut Transform, With<InfiniteGrid>>,
+    mut fly_to: EventWriter<FlyToTrigger>,
+) {
+    for event in events.read() {
+        let Some(bookmark) = bookmarks.get(&event.name) else {
+            warn!("No camera bookmark named '{}'", event.name);
+            continue;
+        };
+        let Ok((mut projection, mut editor_cam)) = cameras.get_mut(event.camera) else {
+            continue;
+        };
+
+        // Keep the header and the grid of the viewport in sync with the recalled view
+        let preset = bookmark.view_preset();
+        if let Some((viewport, mut current_preset)) = viewports
+            .iter_mut()
+            .find(|(viewport, _)| viewport.camera_id == event.camera)
+        {
+            if *current_preset != preset {
+                *current_preset = preset;
+                editor_cam.enabled_motion = preset.enabled_motion();
+                if let Ok(mut grid_transform) = grids.get_mut(viewport.grid_id) {
+                    grid_transform.rotation = preset.grid_rotation();
+                }
+            }
+        }
+
+        // Projections are switched immediately, only the camera position is animated.
+        let same_kind = match &mut *projection {
+            Projection::Orthographic(ortho) if bookmark.orthographic => {
+                ortho.scale = bookmark.scale;
+                true
+            }
+            Projection::Perspective(perspective) if !bookmark.orthographic => {
+                perspective.fov = bookmark.fov;
+                true
+            }
+            _ => false,
+        };
+        if !same_kind {
+            *projection = bookmark.projection();
+        }
+
+        fly_to.write(FlyToTrigger {
+            target_transform: Transform::from_translation(bookmark.translation)
+                .with_rotation(bookmark.rotation),
+            target_anchor_depth: bookmark.anchor_depth,
+            camera: event.camera,
+        });
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn bookmark(name: &str, key: Option<u8>) -> CameraBookmark {
+        CameraBookmark {
+            name: name.to_string(),
+            key,
+            ..default()
+        }
+    }
+
+    #[test]
+    fn binding_a_key_unbinds_other_bookmarks() {
+        let mut bookmarks = CameraBookmarks::default();
+        bookmarks.insert(bookmark("Front", Some(1)));
+        bookmarks.insert(bookmark("Unbound", None));
+        bookmarks.insert(bookmark("Top", Some(1)));
+
+        assert_eq!(bookmarks.get_by_key(1).unwrap().name, "Top");
+        assert_eq!(bookmarks.get("Front").unwrap().key, None);
+        assert_eq!(bookmarks.get("Unbound").unwrap().key, None);
+    }
+
+    #[test]
+    fn recalling_an_orthographic_bookmark_switches_the_view_preset() {
+        let mut world = World::new();
+        world.init_resource::<Events<RecallCameraBookmark>>();
+        world.init_resource::<Events<FlyToTrigger>>();
+        let mut bookmarks = CameraBookmarks::default();
+        bookmarks.insert(CameraBookmark {
+            orthographic: true,
+            scale: 0.02,
+            // Looking down the Z axis
+            rotation: Quat::IDENTITY,
+            ..bookmark("Front", Some(1))
+        });
+        world.insert_resource(bookmarks);
+        let camera_id = world
+            .spawn((Projection::Perspective(default()), EditorCam::default()))
+            .id();
+        let grid_id = world.spawn((InfiniteGrid, Transform::default())).id();
+        let viewport = world
+            .spawn((
+                Bevy3dViewport {
+                    camera_id,
+                    grid_id,
+                    ..default()
+                },
+                ViewPreset::Perspective,
+            ))
+            .id();
+
+        world.send_event(RecallCameraBookmark {
+            camera: camera_id,
+            name: "Front".to_string(),
+        });
+        world.run_system_cached(recall_bookmarks).unwrap();
+
+        assert_eq!(world.get::<ViewPreset>(viewport), Some(&ViewPreset::Front));
+        assert!(matches!(
+            world.get::<Projection>(camera_id),
+            Some(Projection::Orthographic(_))
+        ));
+        assert!(
+            !world
+                .get::<EditorCam>(camera_id)
+                .unwrap()
+                .enabled_motion
+                .orbit
+        );
+        assert_eq!(
+            world.get::<Transform>(grid_id).unwrap().rotation,
+            ViewPreset::Front.grid_rotation()
+        );
+    }
+}
@@ -0,0 +1,65 @@
+//! Widgets in the pane header of 3D viewports.
+
+use bevy::prelude::*;
+use bevy_context_menu::{ContextMenu, ContextMenuOption};
+use bevy_editor_styles::Theme;
+
+/// Spawn a drop down button into the header of a viewport.
+///
+/// The `text` bundle is added to the label of the button, so the label can be found and updated
+/// when the selected option changes.
+pub(crate) fn spawn_header_dropdown(
+    commands: &mut Commands,
+    theme: &Theme,
+    header: Entity,
+    label: &str,
+    text: impl Bundle,
+    options: impl IntoIterator<Item = ContextMenuOption>,
+) {
+    commands
+        .spawn((
+            header_button(theme),
+            ContextMenu::new(options).with_button(PointerButton::Primary),
+            ChildOf(header),
+        ))
+        .with_child((header_label(theme, label), text));
+}
+
+/// Set the label of the drop down buttons marked with `T` in the header of `viewport`.
+pub(crate) fn set_header_dropdown_label<T: Component>(
+    viewport: Entity,
+    label: &str,
+    children_query: &Query<&Children>,
+    text_query: &mut Query<&mut Text, With<T>>,
+) {
+    for entity in children_query.iter_descendants(viewport) {
+        if let Ok(mut text) = text_query.get_mut(entity) {
+            text.0 = label.to_string();
+        }
+    }
+}
+
+fn header_button(theme: &Theme) -> impl Bundle {
+    (
+        Node {
+            padding: UiRect::axes(Val::Px(8.), Val::Px(2.)),
+            margin: UiRect::left(Val::Px(10.)),
+            align_items: AlignItems::Center,
+            ..default()
+        },
+        theme.button.background_color,
+        theme.button.border_radius,
+    )
+}
+
+fn header_label(theme: &Theme, label: &str) -> impl Bundle {
+    (
+        Text::new(label),
+        TextFont {
+            font: theme.text.font.clone(),
+            font_size: 12.,
+            ..default()
+        },
+        Pickable::IGNORE,
+    )
+}
@@ -11,29 +11,104 @@ use bevy::{
         view::RenderLayers,
     },
     ui::ui_layout_system,
+    window::PrimaryWindow,
 };
+use bevy_asset_preview::PREVIEW_RENDER_LAYER;
+use bevy_command_palette::CommandPalettePlugin;
 use bevy_editor_cam::prelude::{DefaultEditorCamPlugins, EditorCam};
 use bevy_editor_styles::Theme;
 use bevy_infinite_grid::{InfiniteGrid, InfiniteGridPlugin, InfiniteGridSettings};
 use bevy_pane_layout::prelude::*;
-use view_gizmo::{spawn_view_gizmo_target_texture, ViewGizmoPlugin};
+use bevy_transform_gizmos::snapping::Snapping;
+use shading::{spawn_shading_dropdown, ViewportShadingPlugin};
+use snapping::{spawn_snapping_toggle, ViewportSnappingPlugin};
+use view_gizmo::{spawn_view_gizmo_target_texture, ViewGizmoPlugin, VIEW_GIZMO_LAYER};
+use view_preset::{apply_view_preset, spawn_view_preset_dropdown, ViewPresetPlugin};
 
-use crate::outline_gizmo::OutlineGizmoPlugin;
+use crate::{
+    bookmarks::CameraBookmarksPlugin,
+    outline_gizmo::{spawn_outline_toggle, OutlineGizmoPlugin},
+};
+
+pub use bookmarks::{CameraBookmark, CameraBookmarks, RecallCameraBookmark, SaveCameraBookmark};
+pub use shading::{ShadingProxy, ViewportShading};
+pub use view_preset::ViewPreset;
 
+mod asset_drop;
+mod bookmarks;
+mod header;
 mod outline_gizmo;
+mod shading;
+mod snapping;
 mod view_gizmo;
+mod view_preset;
+
+/// The render layer of the gizmos drawn by the editor, like the outline of the selected entity.
+/// Viewport cameras always render it, even in the shading modes hiding the scene.
+const EDITOR_GIZMO_LAYER: usize = VIEW_GIZMO_LAYER - 1;
+
+/// The first render layer used by viewports.
+/// The layers below are used by the 2D viewport and the view gizmo.
+const FIRST_VIEWPORT_LAYER: usize = VIEW_GIZMO_LAYER + 1;
 
 /// The identifier for the 3D Viewport.
 /// This is present on any pane that is a 3D Viewport.
 #[derive(Component)]
+#[require(ViewPreset, ViewportShading)]
 pub struct Bevy3dViewport {
     camera_id: Entity,
+    grid_id: Entity,
+    layer: usize,
 }
 
 impl Default for Bevy3dViewport {
     fn default() -> Self {
         Bevy3dViewport {
             camera_id: Entity::PLACEHOLDER,
+            grid_id: Entity::PLACEHOLDER,
+            layer: FIRST_VIEWPORT_LAYER,
+        }
+    }
+}
+
+impl Bevy3dViewport {
+    /// The camera rendering this viewport.
+    pub fn camera(&self) -> Entity {
+        self.camera_id
+    }
+}
+
+/// The last render layer used by viewports.
+/// The layers above are reserved, like [`PREVIEW_RENDER_LAYER`] for asset previews.
+const LAST_VIEWPORT_LAYER: usize = PREVIEW_RENDER_LAYER - 1;
+
+/// Keeps track of the render layers used by viewports.
+/// Each viewport draws its own grid and [`ShadingProxy`]s on its own layer, so that every viewport
+/// can orient its grid to match its [`ViewPreset`] and have its own [`ViewportShading`].
+#[derive(Resource, Default)]
+struct ViewportLayers {
+    /// The layers in use, once for every viewport using it.
+    used: Vec<usize>,
+}
+
+impl ViewportLayers {
+    /// Allocate an unused layer, or `None` if all viewport layers are in use.
+    fn allocate(&mut self) -> Option<usize> {
+        let layer = (FIRST_VIEWPORT_LAYER..=LAST_VIEWPORT_LAYER)
+            .find(|layer| !self.used.contains(layer))?;
+        self.used.push(layer);
+        Some(layer)
+    }
+
+    /// Share the last viewport layer with the viewports already using it.
+    fn share_last(&mut self) -> usize {
+        self.used.push(LAST_VIEWPORT_LAYER);
+        LAST_VIEWPORT_LAYER
+    }
+
+    fn release(&mut self, layer: usize) {
+        if let Some(index) = self.used.iter().position(|used| *used == layer) {
+            self.used.swap_remove(index);
         }
     }
 }
@@ -46,27 +121,41 @@ impl Plugin for Viewport3dPanePlugin {
         if !app.is_plugin_added::<InfiniteGridPlugin>() {
             app.add_plugins(InfiniteGridPlugin);
         }
+        if !app.is_plugin_added::<CommandPalettePlugin>() {
+            app.add_plugins(CommandPalettePlugin);
+        }
 
-        app.add_plugins((DefaultEditorCamPlugins, ViewGizmoPlugin, OutlineGizmoPlugin))
-            .add_systems(Startup, setup)
-            .add_systems(
-                PreUpdate,
-                render_target_picking_passthrough.in_set(PickSet::Last),
-            )
-            .add_systems(
-                PostUpdate,
-                update_render_target_size.after(ui_layout_system),
-            )
-            .add_observer(
-                |trigger: Trigger<OnRemove, Bevy3dViewport>,
-                 mut commands: Commands,
-                 query: Query<&Bevy3dViewport>| {
-                    // Despawn the viewport camera
-                    commands
-                        .entity(query.get(trigger.target()).unwrap().camera_id)
-                        .despawn();
-                },
-            );
+        app.add_plugins((
+            DefaultEditorCamPlugins,
+            ViewGizmoPlugin,
+            OutlineGizmoPlugin,
+            CameraBookmarksPlugin,
+            ViewPresetPlugin,
+            ViewportShadingPlugin,
+            ViewportSnappingPlugin,
+        ))
+        .init_resource::<ViewportLayers>()
+        .register_type::<ViewPreset>()
+        .add_systems(
+            PreUpdate,
+            render_target_picking_passthrough.in_set(PickSet::Last),
+        )
+        .add_systems(
+            PostUpdate,
+            update_render_target_size.after(ui_layout_system),
+        )
+        .add_observer(
+            |trigger: Trigger<OnRemove, Bevy3dViewport>,
+             mut commands: Commands,
+             mut viewport_layers: ResMut<ViewportLayers>,
+             query: Query<&Bevy3dViewport>| {
+                let viewport = query.get(trigger.target()).unwrap();
+                // Despawn the viewport camera and grid
+                commands.entity(viewport.camera_id).despawn();
+                commands.entity(viewport.grid_id).despawn();
+                viewport_layers.release(viewport.layer);
+            },
+        );
 
         app.register_pane("Viewport 3D", on_pane_creation);
     }
@@ -75,17 +164,28 @@ impl Plugin for Viewport3dPanePlugin {
 #[derive(Component)]
 struct Active;
 
-// TODO This does not properly handle multiple windows.
 /// Copies picking events and moves pointers through render-targets.
+#[expect(clippy::too_many_arguments)]
 fn render_target_picking_passthrough(
     mut commands: Commands,
     viewports: Query<(Entity, &Bevy3dViewport)>,
     content: Query<&PaneContentNode>,
     children_query: Query<&Children>,
-    node_query: Query<(&ComputedNode, &GlobalTransform, &ImageNode), With<Active>>,
+    node_query: Query<
+        (
+            &ComputedNode,
+            &ComputedNodeTarget,
+            &GlobalTransform,
+            &ImageNode,
+        ),
+        With<Active>,
+    >,
+    ui_cameras: Query<&Camera>,
+    primary_window: Query<Entity, With<PrimaryWindow>>,
     mut pointers: Query<(&PointerId, &mut PointerLocation)>,
     mut pointer_input_reader: EventReader<PointerInput>,
 ) {
+    let primary_window = primary_window.single().ok();
     for event in pointer_input_reader.read() {
         // Ignore the events we send to the render-targets
         if !matches!(event.location.target, NormalizedRenderTarget::Window(..)) {
@@ -99,10 +199,22 @@ fn render_target_picking_passthrough(
 
             let image_id = children_query.get(content_node_id).unwrap()[0];
 
-            let Ok((computed_node, global_transform, ui_image)) = node_query.get(image_id) else {
+            let Ok((computed_node, node_target, global_transform, ui_image)) =
+                node_query.get(image_id)
+            else {
                 // Inactive viewport
                 continue;
             };
+
+            // Only relocate events from the window the viewport is displayed in
+            let viewport_window = node_target
+                .camera()
+                .and_then(|camera| ui_cameras.get(camera).ok())
+                .and_then(|camera| camera.target.normalize(primary_window));
+            if viewport_window.as_ref() != Some(&event.location.target) {
+                continue;
+            }
+
             let node_rect =
                 Rect::from_center_size(global_transform.translation().xy(), computed_node.size());
 
@@ -129,24 +241,11 @@ fn render_target_picking_passthrough(
     }
 }
 
-fn setup(mut commands: Commands, theme: Res<Theme>) {
-    commands.spawn((
-        InfiniteGrid,
-        InfiniteGridSettings {
-            x_axis_color: theme.viewport.x_axis_color,
-            z_axis_color: theme.viewport.z_axis_color,
-            major_line_color: theme.viewport.grid_major_line_color,
-            minor_line_color: theme.viewport.grid_minor_line_color,
-            ..default()
-        },
-        RenderLayers::layer(1),
-    ));
-}
-
 fn on_pane_creation(
     structure: In<PaneStructure>,
     mut commands: Commands,
     mut images: ResMut<Assets<Image>>,
+    mut viewport_layers: ResMut<ViewportLayers>,
     theme: Res<Theme>,
 ) {
     let mut image = Image::default();
@@ -177,8 +276,36 @@ fn on_pane_creation(
         })
         .observe(|trigger: Trigger<Pointer<Out>>, mut commands: Commands| {
             commands.entity(trigger.target()).remove::<Active>();
-        });
+        })
+        .observe(asset_drop::drop_asset);
 
+    let preset = ViewPreset::default();
+    let layer = viewport_layers.allocate().unwrap_or_else(|| {
+        warn!("All render layers of 3D viewports are in use, the new viewport shares its grid and shading with another one");
+        viewport_layers.share_last()
+    });
+
+    let grid_id = commands
+        .spawn((
+            InfiniteGrid,
+            InfiniteGridSettings {
+                x_axis_color: theme.viewport.x_axis_color,
+                z_axis_color: theme.viewport.z_axis_color,
+                major_line_color: theme.viewport.grid_major_line_color,
+                minor_line_color: theme.viewport.grid_minor_line_color,
+                ..default()
+            },
+            Transform::from_rotation(preset.grid_rotation()),
+            RenderLayers::layer(layer),
+        ))
+        .id();
+
+    let mut transform = Transform::from_translation(Vec3::ONE * 5.).looking_at(Vec3::ZERO, Vec3::Y);
+    let mut projection = Projection::default();
+    let mut editor_cam = EditorCam::default();
+    apply_view_preset(preset, &mut transform, &mut projection, &mut editor_cam);
+
+    let shading = ViewportShading::default();
     let camera_id = commands
         .spawn((
             Camera3d::default(),
@@ -187,15 +314,31 @@ fn on_pane_creation(
                 clear_color: ClearColorConfig::Custom(theme.viewport.background_color),
                 ..default()
             },
-            EditorCam::default(),
-            Transform::from_translation(Vec3::ONE * 5.).looking_at(Vec3::ZERO, Vec3::Y),
-            RenderLayers::from_layers(&[0, 1]),
+            editor_cam,
+            transform,
+            projection,
+            shading.camera_layers(layer),
+            Snapping {
+                enabled: false,
+                grid: Some(grid_id),
+            },
         ))
         .id();
 
-    commands
-        .entity(structure.root)
-        .insert(Bevy3dViewport { camera_id });
+    spawn_view_preset_dropdown(&mut commands, &theme, structure.header, preset);
+    spawn_shading_dropdown(&mut commands, &theme, structure.header, shading);
+    spawn_snapping_toggle(&mut commands, structure.header);
+    spawn_outline_toggle(&mut commands, structure.header);
+
+    commands.entity(structure.root).insert((
+        Bevy3dViewport {
+            camera_id,
+            grid_id,
+            layer,
+        },
+        preset,
+        shading,
+    ));
 }
 
 fn update_render_target_size(
@@ -229,3 +372,39 @@ fn update_render_target_size(
         images.get_mut(image_handle).unwrap().resize(size);
     }
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn viewport_layers_stop_below_reserved_layers() {
+        let mut layers = ViewportLayers::default();
+        let allocated: Vec<usize> = std::iter::from_fn(|| layers.allocate()).collect();
+        assert_eq!(
+            allocated,
+            (FIRST_VIEWPORT_LAYER..=LAST_VIEWPORT_LAYER).collect::<Vec<_>>()
+        );
+        assert!(!allocated.contains(&PREVIEW_RENDER_LAYER));
+        assert_eq!(layers.allocate(), None);
+    }
+
+    #[test]
+    fn released_viewport_layers_are_reused() {
+        let mut layers = ViewportLayers::default();
+        while layers.allocate().is_some() {}
+        layers.release(FIRST_VIEWPORT_LAYER + 1);
+        assert_eq!(layers.allocate(), Some(FIRST_VIEWPORT_LAYER + 1));
+    }
+
+    #[test]
+    fn shared_viewport_layer_is_released_once_unused() {
+        let mut layers = ViewportLayers::default();
+        while layers.allocate().is_some() {}
+        assert_eq!(layers.share_last(), LAST_VIEWPORT_LAYER);
+        layers.release(LAST_VIEWPORT_LAYER);
+        assert_eq!(layers.allocate(), None);
+        layers.release(LAST_VIEWPORT_LAYER);
+        assert_eq!(layers.allocate(), Some(LAST_VIEWPORT_LAYER));
+    }
+}
@@ -1,28 +1,46 @@
-use bevy::prelude::*;
+use bevy::{prelude::*, render::view::RenderLayers};
 use bevy_editor_core::SelectedEntity;
+use bevy_toolbar::{
+    binding::{ResourceToggle, ToolbarResourcePlugin},
+    Toolbar, ToolbarButton, ToolbarIcon, Tooltip,
+};
+
+use crate::EDITOR_GIZMO_LAYER;
 
 pub struct OutlineGizmoPlugin;
 impl Plugin for OutlineGizmoPlugin {
     fn build(&self, app: &mut App) {
-        app.init_resource::<ShowOutlines>()
-            .add_systems(Startup, spawn_gizmo_toggle_ui)
-            .add_systems(Update, outline_gizmo_system)
-            .add_systems(Update, update_gizmo_toggle_text);
+        app.insert_gizmo_config(
+            OutlineGizmos,
+            GizmoConfig {
+                render_layers: outline_gizmo_layers(),
+                ..default()
+            },
+        )
+        .init_resource::<ShowOutlines>()
+        .add_plugins(ToolbarResourcePlugin::<ShowOutlines>::default())
+        .add_systems(Update, outline_gizmo_system);
     }
 }
 
-#[derive(Resource, Default)]
+#[derive(Resource, Clone, Default, PartialEq)]
 pub struct ShowOutlines(pub bool);
 
-// Marker for the toggle button text
-#[derive(Component)]
-struct GizmoToggleText;
+/// The gizmos drawing the outline of the selected entity.
+#[derive(Default, Reflect, GizmoConfigGroup)]
+pub struct OutlineGizmos;
+
+/// The render layers of the [`OutlineGizmos`]: the scene layer, and the [`EDITOR_GIZMO_LAYER`] so
+/// that viewports hiding the scene for their shading mode still show the outline.
+pub(crate) fn outline_gizmo_layers() -> RenderLayers {
+    RenderLayers::from_layers(&[0, EDITOR_GIZMO_LAYER])
+}
 
 pub fn outline_gizmo_system(
     show: Res<ShowOutlines>,
     query: Query<&Transform>,
     selected_entity: Res<SelectedEntity>,
-    mut gizmos: Gizmos,
+    mut gizmos: Gizmos<OutlineGizmos>,
 ) {
     if !show.0 {
         return;
@@ -34,48 +52,20 @@ pub fn outline_gizmo_system(
     }
 }
 
-pub fn spawn_gizmo_toggle_ui(mut commands: Commands) {
-    info!("Spawning Gizmo Toggle UI");
+/// Spawn the toolbar with the button toggling the outlines into the pane header of a viewport.
+pub(crate) fn spawn_outline_toggle(commands: &mut Commands, header: Entity) {
     commands
         .spawn((
+            Toolbar,
             Node {
-                position_type: PositionType::Absolute,
-                top: Val::Px(20.0),
-                right: Val::Px(20.0),
-                width: Val::Px(100.0),
-                height: Val::Px(15.0),
-                align_items: AlignItems::Center,
-                justify_content: JustifyContent::Center,
+                margin: UiRect::left(Val::Px(10.)),
                 ..default()
             },
-            BackgroundColor(Color::srgb(0.2, 0.2, 0.2)),
+            ChildOf(header),
         ))
-        .with_children(|parent| {
-            parent.spawn((
-                Text::new("Show Outlines"),
-                TextFont::from_font_size(10.0),
-                GizmoToggleText,
-            ));
-        })
-        .observe(
-            |_trigger: Trigger<Pointer<Click>>, mut show_outlines: ResMut<ShowOutlines>| {
-                show_outlines.0 = !show_outlines.0;
-            },
-        );
-}
-
-// System to update the button text when ShowOutlines changes
-fn update_gizmo_toggle_text(
-    show_outlines: Res<ShowOutlines>,
-    mut query: Query<&mut Text, With<GizmoToggleText>>,
-) {
-    if show_outlines.is_changed() {
-        for mut text in &mut query {
-            text.0 = if show_outlines.0 {
-                "Hide Outlines".into()
-            } else {
-                "Show Outlines".into()
-            };
-        }
-    }
+        .with_child((
+            ToolbarButton::new(ToolbarIcon::text("Outlines")),
+            ResourceToggle::<ShowOutlines>::new(|show| show.0, |show, on| show.0 = on),
+            Tooltip::new("Show the outline of the selected entity"),
+        ));
 }
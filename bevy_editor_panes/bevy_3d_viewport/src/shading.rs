@@ -0,0 +1,638 @@
+//! Debug shading modes for 3D viewports.
+//!
+//! Every shading mode other than [`ViewportShading::Lit`] is drawn with proxy entities: for each
+//! mesh in the scene, the viewport spawns a [`ShadingProxy`] with the same mesh and transform on
+//! the render layer of its camera. This way the mode only affects a single viewport, and the
+//! materials of the scene are never modified.
+//!
+//! Modes showing only the proxies hide the scene layer from the camera of the viewport. The editor
+//! gizmos are also drawn on [`EDITOR_GIZMO_LAYER`], which viewport cameras always render, and the
+//! meshes of [`EditorEntity`]s don't get a proxy.
+//!
+//! Proxies don't follow skinning or morph target animations of their source mesh.
+
+use bevy::{
+    asset::embedded_asset,
+    pbr::wireframe::{Wireframe, WireframePlugin},
+    platform::collections::{HashMap, HashSet},
+    prelude::*,
+    render::{
+        render_resource::{AsBindGroup, ShaderRef},
+        view::RenderLayers,
+    },
+};
+use bevy_command_palette::{command_menu_option, CommandInfo, CommandPaletteAppExt, CommandTarget};
+use bevy_editor_core::EditorEntity;
+use bevy_editor_styles::Theme;
+
+use crate::{
+    header::{set_header_dropdown_label, spawn_header_dropdown},
+    Bevy3dViewport, EDITOR_GIZMO_LAYER,
+};
+
+pub struct ViewportShadingPlugin;
+
+impl Plugin for ViewportShadingPlugin {
+    fn build(&self, app: &mut App) {
+        embedded_asset!(app, "assets/debug_shading.wgsl");
+
+        if !app.is_plugin_added::<WireframePlugin>() {
+            app.add_plugins(WireframePlugin::default());
+        }
+
+        for shading in ViewportShading::ALL {
+            app.register_command(
+                CommandInfo::new(shading.command_id(), shading.name()).with_category("Shading"),
+                move |mut commands: Commands,
+                      target: Res<CommandTarget>,
+                      viewports: Query<Entity, With<Bevy3dViewport>>| {
+                    // From the command palette, switch the first viewport
+                    if let Some(target) = target.0.or_else(|| viewports.iter().next()) {
+                        commands.run_system_cached_with(set_viewport_shading, (target, shading));
+                    }
+                },
+            );
+        }
+
+        app.add_plugins(MaterialPlugin::<DebugShadingMaterial>::default())
+            .init_resource::<UnlitMaterials>()
+            .register_type::<ViewportShading>()
+            .add_systems(Startup, setup_debug_shading_materials)
+            .add_systems(Update, (update_shading_text, update_unlit_materials))
+            .add_systems(
+                PostUpdate,
+                (update_viewport_shading, sync_shading_proxies)
+                    .chain()
+                    .after(TransformSystem::TransformPropagate),
+            );
+    }
+}
+
+/// How the scene is shaded in a 3D viewport.
+#[derive(Component, Reflect, Clone, Copy, Default, Debug, PartialEq, Eq)]
+pub enum ViewportShading {
+    /// The scene as it looks in game, with full PBR lighting.
+    #[default]
+    Lit,
+    /// The base color of materials, without lighting.
+    Unlit,
+    /// The lit scene with the wireframe of every mesh drawn on top.
+    Wireframe,
+    /// World space normals, mapped to colors.
+    Normals,
+    /// A checker pattern showing the UV mapping of meshes.
+    UvChecker,
+}
+
+impl ViewportShading {
+    /// All shading modes, in the order they are listed in the viewport header.
+    pub const ALL: [ViewportShading; 5] = [
+        ViewportShading::Lit,
+        ViewportShading::Unlit,
+        ViewportShading::Wireframe,
+        ViewportShading::Normals,
+        ViewportShading::UvChecker,
+    ];
+
+    /// The name of the shading mode as shown in the viewport header.
+    pub fn name(self) -> &'static str {
+        match self {
+            ViewportShading::Lit => "Lit",
+            ViewportShading::Unlit => "Unlit",
+            ViewportShading::Wireframe => "Wireframe",
+            ViewportShading::Normals => "Normals",
+            ViewportShading::UvChecker => "UV Checker",
+        }
+    }
+
+    /// The id of the command switching a viewport to this shading mode.
+    fn command_id(self) -> String {
+        format!(
+            "viewport.shading.{}",
+            self.name().to_lowercase().replace(' ', "_")
+        )
+    }
+
+    /// Whether the scene itself is visible in this mode. If not, only the proxies are drawn.
+    fn shows_scene(self) -> bool {
+        matches!(self, ViewportShading::Lit | ViewportShading::Wireframe)
+    }
+
+    /// The render layers of the camera of a viewport drawing its grid and proxies on `layer`.
+    pub(crate) fn camera_layers(self, layer: usize) -> RenderLayers {
+        let layers = RenderLayers::from_layers(&[EDITOR_GIZMO_LAYER, layer]);
+        if self.shows_scene() {
+            layers.with(0)
+        } else {
+            layers
+        }
+    }
+}
+
+/// A copy of a scene mesh, drawn by a single viewport to display its [`ViewportShading`].
+#[derive(Component)]
+pub struct ShadingProxy {
+    source: Entity,
+    viewport: Entity,
+}
+
+impl ShadingProxy {
+    /// The scene entity this proxy is a copy of.
+    pub fn source(&self) -> Entity {
+        self.source
+    }
+}
+
+/// Material used for the [`ViewportShading::Normals`] and [`ViewportShading::UvChecker`] modes.
+#[derive(Asset, TypePath, AsBindGroup, Clone, Debug)]
+struct DebugShadingMaterial {
+    /// See `debug_shading.wgsl` for the meaning of each mode.
+    #[uniform(0)]
+    mode: u32,
+}
+
+impl Material for DebugShadingMaterial {
+    fn fragment_shader() -> ShaderRef {
+        "embedded://bevy_3d_viewport/assets/debug_shading.wgsl".into()
+    }
+}
+
+#[derive(Resource)]
+struct DebugShadingMaterials {
+    normals: Handle<DebugShadingMaterial>,
+    uv_checker: Handle<DebugShadingMaterial>,
+}
+
+/// Unlit copies of the [`StandardMaterial`]s used in the scene, keyed by the original material.
+#[derive(Resource, Default)]
+struct UnlitMaterials {
+    copies: HashMap<AssetId<StandardMaterial>, Handle<StandardMaterial>>,
+    /// Used for meshes that don't have a [`StandardMaterial`].
+    fallback: Option<Handle<StandardMaterial>>,
+}
+
+impl UnlitMaterials {
+    fn get_or_create(
+        &mut self,
+        source: Option<&MeshMaterial3d<StandardMaterial>>,
+        materials: &mut Assets<StandardMaterial>,
+    ) -> Handle<StandardMaterial> {
+        let Some(material) = source.and_then(|source| materials.get(&source.0)) else {
+            return self
+                .fallback
+                .get_or_insert_with(|| {
+                    materials.add(StandardMaterial {
+                        unlit: true,
+                        ..default()
+                    })
+                })
+                .clone();
+        };
+        let copy = StandardMaterial {
+            unlit: true,
+            ..material.clone()
+        };
+        self.copies
+            .entry(source.unwrap().id())
+            .or_insert_with(|| materials.add(copy))
+            .clone()
+    }
+}
+
+/// Keep the unlit copies of materials up to date when the original materials change, and drop them
+/// along with the original materials.
+fn update_unlit_materials(
+    mut events: EventReader<AssetEvent<StandardMaterial>>,
+    mut unlit_materials: ResMut<UnlitMaterials>,
+    mut materials: ResMut<Assets<StandardMaterial>>,
+) {
+    for event in events.read() {
+        let id = match event {
+            AssetEvent::Modified { id } => id,
+            AssetEvent::Removed { id } | AssetEvent::Unused { id } => {
+                unlit_materials.copies.remove(id);
+                continue;
+            }
+            _ => continue,
+        };
+        let Some(copy) = unlit_materials.copies.get(id) else {
+            continue;
+        };
+        let Some(material) = materials.get(*id) else {
+            continue;
+        };
+        let material = StandardMaterial {
+            unlit: true,
+            ..material.clone()
+        };
+        materials.insert(copy, material);
+    }
+}
+
+/// Marker for the text of the shading drop down button in the header of a viewport.
+#[derive(Component)]
+struct ViewportShadingText;
+
+/// Spawn the drop down button used to switch the shading mode of a viewport into its pane header.
+pub(crate) fn spawn_shading_dropdown(
+    commands: &mut Commands,
+    theme: &Theme,
+    header: Entity,
+    shading: ViewportShading,
+) {
+    let options = ViewportShading::ALL
+        .map(|shading| command_menu_option(shading.name(), shading.command_id()));
+
+    spawn_header_dropdown(
+        commands,
+        theme,
+        header,
+        shading.name(),
+        ViewportShadingText,
+        options,
+    );
+}
+
+/// Switch the viewport containing the `target` entity, like its header drop down button, to the
+/// given shading mode.
+fn set_viewport_shading(
+    In((target, shading)): In<(Entity, ViewportShading)>,
+    parent_query: Query<&ChildOf>,
+    mut viewports: Query<&mut ViewportShading>,
+) {
+    if let Some(mut current_shading) = std::iter::once(target)
+        .chain(parent_query.iter_ancestors(target))
+        .find(|entity| viewports.contains(*entity))
+        .and_then(|entity| viewports.get_mut(entity).ok())
+    {
+        *current_shading = shading;
+    }
+}
+
+fn setup_debug_shading_materials(
+    mut commands: Commands,
+    mut materials: ResMut<Assets<DebugShadingMaterial>>,
+) {
+    commands.insert_resource(DebugShadingMaterials {
+        normals: materials.add(DebugShadingMaterial { mode: 0 }),
+        uv_checker: materials.add(DebugShadingMaterial { mode: 1 }),
+    });
+}
+
+/// Show or hide the scene for viewport cameras, and remove the proxies of the previous shading
+/// mode when it changes.
+fn update_viewport_shading(
+    mut commands: Commands,
+    viewports: Query<(Entity, &Bevy3dViewport, &ViewportShading), Changed<ViewportShading>>,
+    mut cameras: Query<&mut RenderLayers, With<Camera>>,
+    proxies: Query<(Entity, &ShadingProxy)>,
+) {
+    for (viewport_entity, viewport, shading) in &viewports {
+        if let Ok(mut layers) = cameras.get_mut(viewport.camera_id) {
+            *layers = shading.camera_layers(viewport.layer);
+        }
+
+        for (proxy_entity, proxy) in &proxies {
+            if proxy.viewport == viewport_entity {
+                commands.entity(proxy_entity).despawn();
+            }
+        }
+    }
+}
+
+/// Spawn, update and despawn the [`ShadingProxy`]s of all viewports.
+///
+/// Only the proxies of scene meshes that changed or were removed are updated, and proxies are only
+/// spawned for the viewports whose shading mode changed or for the scene meshes that changed.
+#[allow(clippy::too_many_arguments)]
+fn sync_shading_proxies(
+    mut commands: Commands,
+    viewports: Query<(Entity, &Bevy3dViewport, Ref<ViewportShading>)>,
+    sources: Query<
+        (
+            Entity,
+            &Mesh3d,
+            &GlobalTransform,
+            &InheritedVisibility,
+            Option<&MeshMaterial3d<StandardMaterial>>,
+            Option<&RenderLayers>,
+        ),
+        Without<ShadingProxy>,
+    >,
+    changed_sources: Query<
+        Entity,
+        (
+            With<Mesh3d>,
+            Without<ShadingProxy>,
+            Or<(
+                Changed<Mesh3d>,
+                Changed<GlobalTransform>,
+                Changed<InheritedVisibility>,
+                Changed<RenderLayers>,
+            )>,
+        ),
+    >,
+    mut removed_meshes: RemovedComponents<Mesh3d>,
+    mut removed_layers: RemovedComponents<RenderLayers>,
+    mut removed_viewports: RemovedComponents<Bevy3dViewport>,
+    parent_query: Query<&ChildOf>,
+    editor_entities: Query<(), With<EditorEntity>>,
+    mut proxies: Query<(
+        Entity,
+        &ShadingProxy,
+        &mut Mesh3d,
+        &mut Transform,
+        &mut Visibility,
+    )>,
+    debug_materials: Res<DebugShadingMaterials>,
+    mut unlit_materials: ResMut<UnlitMaterials>,
+    mut standard_materials: ResMut<Assets<StandardMaterial>>,
+) {
+    let changed = changed_sources
+        .iter()
+        .chain(removed_layers.read())
+        .collect::<HashSet<_>>();
+    let removed = removed_meshes
+        .read()
+        .chain(removed_viewports.read())
+        .collect::<HashSet<_>>();
+    let shading_changed = viewports.iter().any(|(.., shading)| shading.is_changed());
+    if changed.is_empty() && removed.is_empty() && !shading_changed {
+        return;
+    }
+
+    // Only meshes of the scene that are visible to a viewport showing the scene get a proxy.
+    let scene_layers = RenderLayers::layer(0);
+    let is_scene_mesh = |entity: Entity, layers: Option<&RenderLayers>| {
+        layers.is_none_or(|layers| layers.intersects(&scene_layers))
+            && !std::iter::once(entity)
+                .chain(parent_query.iter_ancestors(entity))
+                .any(|entity| editor_entities.contains(entity))
+    };
+
+    let mut existing = HashSet::new();
+    for (entity, proxy, mut mesh, mut transform, mut visibility) in &mut proxies {
+        if !changed.contains(&proxy.source)
+            && !removed.contains(&proxy.source)
+            && !removed.contains(&proxy.viewport)
+        {
+            existing.insert((proxy.viewport, proxy.source));
+            continue;
+        }
+        let Some((_, source_mesh, global_transform, inherited_visibility, ..)) =
+            sources.get(proxy.source).ok().filter(|(.., layers)| {
+                is_scene_mesh(proxy.source, *layers) && viewports.contains(proxy.viewport)
+            })
+        else {
+            commands.entity(entity).despawn();
+            continue;
+        };
+
+        if mesh.0 != source_mesh.0 {
+            mesh.0 = source_mesh.0.clone();
+        }
+        transform.set_if_neq(global_transform.compute_transform());
+        visibility.set_if_neq(if inherited_visibility.get() {
+            Visibility::Visible
+        } else {
+            Visibility::Hidden
+        });
+        existing.insert((proxy.viewport, proxy.source));
+    }
+
+    for (viewport_entity, viewport, shading) in &viewports {
+        if *shading == ViewportShading::Lit {
+            continue;
+        }
+        for (source, mesh, global_transform, inherited_visibility, material, layers) in &sources {
+            // The proxies of a viewport whose mode changed were all despawned
+            if !shading.is_changed() && !changed.contains(&source) {
+                continue;
+            }
+            if !is_scene_mesh(source, layers) || existing.contains(&(viewport_entity, source)) {
+                continue;
+            }
+            let mut proxy = commands.spawn((
+                ShadingProxy {
+                    source,
+                    viewport: viewport_entity,
+                },
+                Mesh3d(mesh.0.clone()),
+                global_transform.compute_transform(),
+                if inherited_visibility.get() {
+                    Visibility::Visible
+                } else {
+                    Visibility::Hidden
+                },
+                RenderLayers::layer(viewport.layer),
+            ));
+            match *shading {
+                ViewportShading::Lit => unreachable!(),
+                ViewportShading::Unlit => {
+                    proxy.insert(MeshMaterial3d(
+                        unlit_materials.get_or_create(material, &mut standard_materials),
+                    ));
+                }
+                ViewportShading::Wireframe => {
+                    proxy.insert(Wireframe);
+                }
+                ViewportShading::Normals => {
+                    proxy.insert(MeshMaterial3d(debug_materials.normals.clone()));
+                }
+                ViewportShading::UvChecker => {
+                    proxy.insert(MeshMaterial3d(debug_materials.uv_checker.clone()));
+                }
+            }
+        }
+    }
+}
+
+fn update_shading_text(
+    viewports: Query<(Entity, &ViewportShading), Changed<ViewportShading>>,
+    children_query: Query<&Children>,
+    mut text_query: Query<&mut Text, With<ViewportShadingText>>,
+) {
+    for (viewport, shading) in &viewports {
+        set_header_dropdown_label(viewport, shading.name(), &children_query, &mut text_query);
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::outline_gizmo::outline_gizmo_layers;
+
+    fn proxy_count(world: &mut World) -> usize {
+        world.query::<&ShadingProxy>().iter(world).count()
+    }
+
+    fn setup() -> (World, Schedule) {
+        let mut world = World::new();
+        world.init_resource::<Assets<StandardMaterial>>();
+        world.init_resource::<UnlitMaterials>();
+        world.init_resource::<Events<AssetEvent<StandardMaterial>>>();
+        world.insert_resource(DebugShadingMaterials {
+            normals: Handle::default(),
+            uv_checker: Handle::default(),
+        });
+        let mut schedule = Schedule::default();
+        schedule.add_systems(
+            (
+                update_unlit_materials,
+                update_viewport_shading,
+                sync_shading_proxies,
+            )
+                .chain(),
+        );
+        (world, schedule)
+    }
+
+    #[test]
+    fn switching_modes_leaves_scene_materials_untouched() {
+        let (mut world, mut schedule) = setup();
+
+        let material = world
+            .resource_mut::<Assets<StandardMaterial>>()
+            .add(StandardMaterial::from_color(Color::WHITE));
+        let source = world
+            .spawn((
+                Mesh3d(Handle::default()),
+                MeshMaterial3d(material.clone()),
+                GlobalTransform::default(),
+                InheritedVisibility::VISIBLE,
+            ))
+            .id();
+        let viewport = world
+            .spawn((Bevy3dViewport::default(), ViewportShading::Lit))
+            .id();
+        schedule.run(&mut world);
+        assert_eq!(proxy_count(&mut world), 0);
+
+        for shading in [
+            ViewportShading::Unlit,
+            ViewportShading::Normals,
+            ViewportShading::Wireframe,
+            ViewportShading::Unlit,
+        ] {
+            *world.get_mut::<ViewportShading>(viewport).unwrap() = shading;
+            schedule.run(&mut world);
+            assert_eq!(proxy_count(&mut world), 1);
+        }
+
+        // Moving the mesh moves its proxy
+        *world.get_mut::<GlobalTransform>(source).unwrap() =
+            GlobalTransform::from_xyz(1.0, 2.0, 3.0);
+        schedule.run(&mut world);
+        let proxy_translation = world
+            .query::<(&ShadingProxy, &Transform)>()
+            .single(&world)
+            .unwrap()
+            .1
+            .translation;
+        assert_eq!(proxy_translation, Vec3::new(1.0, 2.0, 3.0));
+
+        *world.get_mut::<ViewportShading>(viewport).unwrap() = ViewportShading::Lit;
+        schedule.run(&mut world);
+        assert_eq!(proxy_count(&mut world), 0);
+
+        assert_eq!(
+            world
+                .get::<MeshMaterial3d<StandardMaterial>>(source)
+                .unwrap()
+                .0,
+            material
+        );
+        let materials = world.resource::<Assets<StandardMaterial>>();
+        assert!(!materials.get(&material).unwrap().unlit);
+        assert_eq!(materials.len(), 2, "only one unlit copy is created");
+
+        // Removing the mesh removes its proxies
+        *world.get_mut::<ViewportShading>(viewport).unwrap() = ViewportShading::Normals;
+        schedule.run(&mut world);
+        assert_eq!(proxy_count(&mut world), 1);
+        world.entity_mut(source).remove::<Mesh3d>();
+        schedule.run(&mut world);
+        assert_eq!(proxy_count(&mut world), 0);
+    }
+
+    #[test]
+    fn outline_renders_in_every_shading_mode() {
+        let (mut world, mut schedule) = setup();
+        let layer = crate::FIRST_VIEWPORT_LAYER;
+        let camera_id = world
+            .spawn((Camera::default(), RenderLayers::from_layers(&[0, layer])))
+            .id();
+        let viewport = world
+            .spawn(Bevy3dViewport {
+                camera_id,
+                grid_id: Entity::PLACEHOLDER,
+                layer,
+            })
+            .id();
+
+        for shading in ViewportShading::ALL {
+            *world.get_mut::<ViewportShading>(viewport).unwrap() = shading;
+            schedule.run(&mut world);
+            let layers = world.get::<RenderLayers>(camera_id).unwrap();
+            assert!(
+                layers.intersects(&outline_gizmo_layers()),
+                "no outline in {shading:?} mode"
+            );
+            assert!(layers.intersects(&RenderLayers::layer(layer)));
+            assert_eq!(
+                layers.intersects(&RenderLayers::layer(0)),
+                shading.shows_scene()
+            );
+        }
+    }
+
+    #[test]
+    fn editor_entities_get_no_proxy() {
+        let (mut world, mut schedule) = setup();
+        let mesh = (
+            Mesh3d(Handle::default()),
+            GlobalTransform::default(),
+            InheritedVisibility::VISIBLE,
+        );
+        let scene_mesh = world.spawn(mesh.clone()).id();
+        world.spawn((EditorEntity, mesh.clone()));
+        let editor_root = world.spawn(EditorEntity).id();
+        world.spawn((mesh, ChildOf(editor_root)));
+        world.spawn((Bevy3dViewport::default(), ViewportShading::Unlit));
+        schedule.run(&mut world);
+
+        let sources = world
+            .query::<&ShadingProxy>()
+            .iter(&world)
+            .map(ShadingProxy::source)
+            .collect::<Vec<_>>();
+        assert_eq!(sources, [scene_mesh]);
+    }
+
+    #[test]
+    fn unlit_copies_are_dropped_with_their_material() {
+        let (mut world, mut schedule) = setup();
+        let material = world
+            .resource_mut::<Assets<StandardMaterial>>()
+            .add(StandardMaterial::default());
+        let source = world
+            .spawn((
+                Mesh3d(Handle::default()),
+                MeshMaterial3d(material.clone()),
+                GlobalTransform::default(),
+                InheritedVisibility::VISIBLE,
+            ))
+            .id();
+        let viewport = world
+            .spawn((Bevy3dViewport::default(), ViewportShading::Unlit))
+            .id();
+        schedule.run(&mut world);
+        assert_eq!(world.resource::<UnlitMaterials>().copies.len(), 1);
+
+        *world.get_mut::<ViewportShading>(viewport).unwrap() = ViewportShading::Lit;
+        world.entity_mut(source).despawn();
+        world.send_event(AssetEvent::Unused { id: material.id() });
+        schedule.run(&mut world);
+        assert!(world.resource::<UnlitMaterials>().copies.is_empty());
+    }
+}
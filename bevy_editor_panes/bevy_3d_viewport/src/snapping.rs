@@ -0,0 +1,123 @@
+//! The snapping toggle of 3D viewports.
+//!
+//! Each viewport camera has a [`Snapping`] component that is linked to the grid of the viewport,
+//! so snapping to the grid uses the spacing and orientation of the grid that is visible in it.
+
+use bevy::prelude::*;
+use bevy_toolbar::{Toggled, Toolbar, ToolbarButton, ToolbarIcon, ToolbarToggle, Tooltip};
+use bevy_transform_gizmos::snapping::{Snapping, SnappingPlugin};
+
+use crate::Bevy3dViewport;
+
+pub struct ViewportSnappingPlugin;
+
+impl Plugin for ViewportSnappingPlugin {
+    fn build(&self, app: &mut App) {
+        if !app.is_plugin_added::<SnappingPlugin>() {
+            app.add_plugins(SnappingPlugin);
+        }
+
+        app.add_systems(Update, update_snapping_toggle);
+    }
+}
+
+/// Marker for the snapping toggle in the header of a viewport.
+#[derive(Component)]
+struct SnappingToggle;
+
+/// Spawn the toolbar with the button toggling snapping for a viewport into its pane header.
+///
+/// The toggle is controlled: it shows the [`Snapping`] of the viewport camera.
+pub(crate) fn spawn_snapping_toggle(commands: &mut Commands, header: Entity) {
+    commands
+        .spawn((
+            Toolbar,
+            Node {
+                margin: UiRect::left(Val::Px(10.)),
+                ..default()
+            },
+            ChildOf(header),
+        ))
+        .with_children(|toolbar| {
+            toolbar
+                .spawn((
+                    ToolbarButton::new(ToolbarIcon::text("Snap")),
+                    ToolbarToggle::new(false).with_controlled(true),
+                    SnappingToggle,
+                    Tooltip::new("Snap moved entities to the grid"),
+                ))
+                .observe(toggle_snapping);
+        });
+}
+
+/// Turn snapping on or off for the viewport containing the clicked toggle button.
+fn toggle_snapping(
+    trigger: Trigger<Toggled>,
+    parent_query: Query<&ChildOf>,
+    viewports: Query<&Bevy3dViewport>,
+    mut cameras: Query<&mut Snapping>,
+) {
+    let Some(viewport) = parent_query
+        .iter_ancestors(trigger.target())
+        .find_map(|entity| viewports.get(entity).ok())
+    else {
+        return;
+    };
+    if let Ok(mut snapping) = cameras.get_mut(viewport.camera_id) {
+        snapping.enabled = trigger.event().0;
+    }
+}
+
+fn update_snapping_toggle(
+    viewports: Query<(Entity, &Bevy3dViewport)>,
+    cameras: Query<&Snapping, Changed<Snapping>>,
+    children_query: Query<&Children>,
+    mut toggles: Query<&mut ToolbarToggle, With<SnappingToggle>>,
+) {
+    for (viewport_entity, viewport) in &viewports {
+        let Ok(snapping) = cameras.get(viewport.camera_id) else {
+            continue;
+        };
+        let mut toggles = toggles.iter_many_mut(children_query.iter_descendants(viewport_entity));
+        while let Some(mut toggle) = toggles.fetch_next() {
+            if toggle.on != snapping.enabled {
+                toggle.on = snapping.enabled;
+            }
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn toggle_follows_the_camera_snapping() {
+        let mut world = World::new();
+        let camera_id = world.spawn(Snapping::default()).id();
+        let viewport = world
+            .spawn(Bevy3dViewport {
+                camera_id,
+                ..default()
+            })
+            .id();
+        let header = world.spawn(ChildOf(viewport)).id();
+        spawn_snapping_toggle(&mut world.commands(), header);
+        world.flush();
+        let toggle = world
+            .query_filtered::<Entity, With<SnappingToggle>>()
+            .single(&world)
+            .unwrap();
+
+        world.trigger_targets(Toggled(true), toggle);
+        assert!(world.get::<Snapping>(camera_id).unwrap().enabled);
+        // Controlled, so only switched once the camera snapping changed
+        assert!(!world.get::<ToolbarToggle>(toggle).unwrap().on);
+        world.run_system_cached(update_snapping_toggle).unwrap();
+        assert!(world.get::<ToolbarToggle>(toggle).unwrap().on);
+
+        world.get_mut::<Snapping>(camera_id).unwrap().enabled = false;
+        world.run_system_cached(update_snapping_toggle).unwrap();
+        assert!(!world.get::<ToolbarToggle>(toggle).unwrap().on);
+    }
+}
@@ -12,6 +12,9 @@ use bevy::{
     },
 };
 use bevy_editor_cam::prelude::EditorCam;
+use bevy_editor_core::EditorEntity;
+
+use crate::Bevy3dViewport;
 
 // That value was picked arbitrarily
 pub const VIEW_GIZMO_TEXTURE_SIZE: u32 = 125;
@@ -28,8 +31,12 @@ impl Plugin for ViewGizmoPlugin {
     }
 }
 
+/// The camera rendering the view gizmo of a viewport.
 #[derive(Component)]
-pub struct ViewGizmoCamera;
+pub struct ViewGizmoCamera {
+    /// The camera of the viewport the gizmo belongs to.
+    viewport_camera: Entity,
+}
 
 #[derive(Component)]
 pub struct ViewGizmoCameraTarget(pub Handle<Image>);
@@ -114,6 +121,7 @@ fn setup_view_gizmo(
             })),
             Transform::from_translation(axis),
             view_gizmo_pass_layer.clone(),
+            EditorEntity,
         ));
     }
     // Use a sphere for the background
@@ -130,15 +138,24 @@ fn setup_view_gizmo(
         })),
         Transform::from_xyz(0.0, 0.0, 0.0),
         view_gizmo_pass_layer.clone(),
+        EditorEntity,
     ));
 }
 
 fn spawn_view_gizmo_camera(
     mut commands: Commands,
-    q: Query<&ViewGizmoCameraTarget, Added<ViewGizmoCameraTarget>>,
+    q: Query<(Entity, &ViewGizmoCameraTarget), Added<ViewGizmoCameraTarget>>,
+    parent_query: Query<&ChildOf>,
+    viewports: Query<&Bevy3dViewport>,
 ) {
     let view_gizmo_pass_layer = RenderLayers::layer(VIEW_GIZMO_LAYER);
-    for target in &q {
+    for (entity, target) in &q {
+        let Some(viewport) = parent_query
+            .iter_ancestors(entity)
+            .find_map(|ancestor| viewports.get(ancestor).ok())
+        else {
+            continue;
+        };
         commands.spawn((
             Camera3d::default(),
             Camera {
@@ -148,19 +165,26 @@ fn spawn_view_gizmo_camera(
             },
             Transform::from_translation(Vec3::new(0.0, 0.0, 0.0)).looking_at(Vec3::ZERO, Vec3::Y),
             view_gizmo_pass_layer.clone(),
-            ViewGizmoCamera,
+            ViewGizmoCamera {
+                viewport_camera: viewport.camera(),
+            },
         ));
     }
 }
 
 fn update_view_gizmo(
-    mut view_cube_camera: Query<&mut Transform, (With<ViewGizmoCamera>, With<Camera3d>)>,
+    mut commands: Commands,
+    mut view_cube_camera: Query<(Entity, &ViewGizmoCamera, &mut Transform), With<Camera3d>>,
     viewport_camera: Query<&Transform, (Without<ViewGizmoCamera>, With<Camera3d>, With<EditorCam>)>,
 ) {
-    for mut transform in &mut view_cube_camera {
-        if let Ok(viewport_camera_transform) = viewport_camera.single() {
-            transform.translation = viewport_camera_transform.back() * GIZMO_CAMERA_ZOOM;
-            transform.rotation = viewport_camera_transform.rotation;
-        }
+    for (entity, gizmo_camera, mut transform) in &mut view_cube_camera {
+        let Ok(viewport_camera_transform) = viewport_camera.get(gizmo_camera.viewport_camera)
+        else {
+            // The viewport was closed
+            commands.entity(entity).despawn();
+            continue;
+        };
+        transform.translation = viewport_camera_transform.back() * GIZMO_CAMERA_ZOOM;
+        transform.rotation = viewport_camera_transform.rotation;
     }
 }
@@ -0,0 +1,224 @@
+//! View presets for 3D viewports, used to set up views like the classic quad-view layout with
+//! top, front and side orthographic views next to a perspective view.
+//!
+//! The preset of a viewport can be switched with the drop down button in its pane header.
+
+use std::f32::consts::FRAC_PI_2;
+
+use bevy::prelude::*;
+use bevy_command_palette::{command_menu_option, CommandInfo, CommandPaletteAppExt, CommandTarget};
+use bevy_editor_cam::prelude::{motion::CurrentMotion, EditorCam, EnabledMotion};
+use bevy_editor_styles::Theme;
+use bevy_infinite_grid::InfiniteGrid;
+
+use crate::{
+    header::{set_header_dropdown_label, spawn_header_dropdown},
+    Bevy3dViewport,
+};
+
+/// The orthographic scale used when switching a viewport to an orthographic preset.
+const DEFAULT_ORTHOGRAPHIC_SCALE: f32 = 0.01;
+
+pub struct ViewPresetPlugin;
+
+impl Plugin for ViewPresetPlugin {
+    fn build(&self, app: &mut App) {
+        for preset in ViewPreset::ALL {
+            app.register_command(
+                CommandInfo::new(preset.command_id(), preset.name()).with_category("View"),
+                move |mut commands: Commands,
+                      target: Res<CommandTarget>,
+                      viewports: Query<Entity, With<Bevy3dViewport>>| {
+                    // From the command palette, switch the first viewport
+                    if let Some(target) = target.0.or_else(|| viewports.iter().next()) {
+                        commands.run_system_cached_with(set_view_preset, (target, preset));
+                    }
+                },
+            );
+        }
+        app.add_systems(Update, update_view_preset_text);
+    }
+}
+
+/// The view of a 3D viewport.
+#[derive(Component, Reflect, Clone, Copy, Default, Debug, PartialEq, Eq)]
+pub enum ViewPreset {
+    /// A freely orbiting perspective view.
+    #[default]
+    Perspective,
+    /// An orthographic view looking down the Y axis.
+    Top,
+    /// An orthographic view looking down the Z axis.
+    Front,
+    /// An orthographic view looking down the X axis.
+    Right,
+}
+
+impl ViewPreset {
+    /// All presets, in the order they are listed in the viewport header.
+    pub const ALL: [ViewPreset; 4] = [
+        ViewPreset::Perspective,
+        ViewPreset::Top,
+        ViewPreset::Front,
+        ViewPreset::Right,
+    ];
+
+    /// The name of the preset as shown in the viewport header.
+    pub fn name(self) -> &'static str {
+        match self {
+            ViewPreset::Perspective => "Perspective",
+            ViewPreset::Top => "Top",
+            ViewPreset::Front => "Front",
+            ViewPreset::Right => "Right",
+        }
+    }
+
+    /// Whether the preset uses an orthographic projection.
+    pub fn is_orthographic(self) -> bool {
+        self != ViewPreset::Perspective
+    }
+
+    /// The direction the camera looks in, and its up direction, or `None` if the preset does not
+    /// constrain the camera direction.
+    fn view_direction(self) -> Option<(Dir3, Dir3)> {
+        match self {
+            ViewPreset::Perspective => None,
+            ViewPreset::Top => Some((Dir3::NEG_Y, Dir3::NEG_Z)),
+            ViewPreset::Front => Some((Dir3::NEG_Z, Dir3::Y)),
+            ViewPreset::Right => Some((Dir3::NEG_X, Dir3::Y)),
+        }
+    }
+
+    /// The orthographic preset looking in the direction closest to `forward`.
+    pub(crate) fn closest_orthographic(forward: Vec3) -> ViewPreset {
+        ViewPreset::ALL
+            .into_iter()
+            .filter_map(|preset| {
+                let (direction, _) = preset.view_direction()?;
+                Some((preset, direction.dot(forward)))
+            })
+            .max_by(|(_, a), (_, b)| a.total_cmp(b))
+            .map_or(ViewPreset::Top, |(preset, _)| preset)
+    }
+
+    /// The orientation of the viewport grid, so that it lies in the plane facing the camera.
+    pub fn grid_rotation(self) -> Quat {
+        match self {
+            ViewPreset::Perspective | ViewPreset::Top => Quat::IDENTITY,
+            ViewPreset::Front => Quat::from_rotation_x(FRAC_PI_2),
+            ViewPreset::Right => Quat::from_rotation_z(-FRAC_PI_2),
+        }
+    }
+
+    /// The id of the command switching a viewport to this preset.
+    fn command_id(self) -> String {
+        format!("viewport.view.{}", self.name().to_lowercase())
+    }
+
+    /// The camera motions that are allowed in this preset. Orthographic presets can't be orbited,
+    /// so they keep looking along their axis.
+    pub fn enabled_motion(self) -> EnabledMotion {
+        EnabledMotion {
+            orbit: !self.is_orthographic(),
+            ..default()
+        }
+    }
+}
+
+/// Marker for the text of the view preset drop down button in the header of a viewport.
+#[derive(Component)]
+struct ViewPresetText;
+
+/// Spawn the drop down button used to switch the view preset of a viewport into its pane header.
+pub(crate) fn spawn_view_preset_dropdown(
+    commands: &mut Commands,
+    theme: &Theme,
+    header: Entity,
+    preset: ViewPreset,
+) {
+    let options =
+        ViewPreset::ALL.map(|preset| command_menu_option(preset.name(), preset.command_id()));
+
+    spawn_header_dropdown(
+        commands,
+        theme,
+        header,
+        preset.name(),
+        ViewPresetText,
+        options,
+    );
+}
+
+/// Switch the viewport containing the `target` entity, like its header drop down button, to the
+/// given preset.
+fn set_view_preset(
+    In((target, preset)): In<(Entity, ViewPreset)>,
+    parent_query: Query<&ChildOf>,
+    mut viewports: Query<(&Bevy3dViewport, &mut ViewPreset)>,
+    mut cameras: Query<(&mut Transform, &mut Projection, &mut EditorCam)>,
+    mut grids: Query<&mut Transform, (With<InfiniteGrid>, Without<EditorCam>)>,
+) {
+    let Some((viewport, mut current_preset)) = std::iter::once(target)
+        .chain(parent_query.iter_ancestors(target))
+        .find(|entity| viewports.contains(*entity))
+        .and_then(|entity| viewports.get_mut(entity).ok())
+    else {
+        return;
+    };
+    *current_preset = preset;
+
+    let Ok((mut transform, mut projection, mut editor_cam)) = cameras.get_mut(viewport.camera_id)
+    else {
+        return;
+    };
+    apply_view_preset(preset, &mut transform, &mut projection, &mut editor_cam);
+
+    if let Ok(mut grid_transform) = grids.get_mut(viewport.grid_id) {
+        grid_transform.rotation = preset.grid_rotation();
+    }
+}
+
+/// Set up a viewport camera for the given preset, keeping the point it orbits about in place.
+pub(crate) fn apply_view_preset(
+    preset: ViewPreset,
+    transform: &mut Transform,
+    projection: &mut Projection,
+    editor_cam: &mut EditorCam,
+) {
+    // Stop any ongoing motion or momentum, so the camera doesn't drift away from the new view.
+    editor_cam.current_motion = CurrentMotion::Stationary;
+    editor_cam.enabled_motion = preset.enabled_motion();
+
+    let anchor_depth = editor_cam.last_anchor_depth().abs() as f32;
+    let anchor = transform.translation + transform.forward() * anchor_depth;
+
+    let same_kind = matches!(
+        (preset.is_orthographic(), &*projection),
+        (true, Projection::Orthographic(_)) | (false, Projection::Perspective(_))
+    );
+    if !same_kind {
+        *projection = if preset.is_orthographic() {
+            Projection::Orthographic(OrthographicProjection {
+                scale: DEFAULT_ORTHOGRAPHIC_SCALE,
+                ..OrthographicProjection::default_3d()
+            })
+        } else {
+            Projection::Perspective(default())
+        };
+    }
+
+    if let Some((direction, up)) = preset.view_direction() {
+        transform.translation = anchor - direction * anchor_depth;
+        transform.look_to(direction, up);
+    }
+}
+
+fn update_view_preset_text(
+    viewports: Query<(Entity, &ViewPreset), Changed<ViewPreset>>,
+    children_query: Query<&Children>,
+    mut text_query: Query<&mut Text, With<ViewPresetText>>,
+) {
+    for (viewport, preset) in &viewports {
+        set_header_dropdown_label(viewport, preset.name(), &children_query, &mut text_query);
+    }
+}
@@ -0,0 +1,177 @@
+//! Per-folder defaults for the import settings of assets, stored in the project settings.
+//!
+//! A preset sets one loader setting of the `.meta` files of the assets in a folder and its
+//! subfolders, optionally only for one file extension. Presets are applied to new files that don't
+//! have a `.meta` file yet, and can be applied to the existing files of a folder.
+
+use std::path::Path;
+
+use bevy::prelude::*;
+use bevy_editor_core::meta::{apply_edits, meta_fields, MetaField};
+use bevy_editor_settings::{SettingKey, SettingsType};
+
+use crate::io::references::asset_path_string;
+
+/// The import presets of the project, stored in `Bevy.toml` like:
+///
+/// ```toml
+/// [[import_presets.presets]]
+/// folder = "ui"
+/// extension = "png"
+/// setting = "asset.settings.sampler"
+/// value = "Descriptor((mag_filter: Nearest, min_filter: Nearest))"
+/// ```
+#[derive(Resource, Reflect, Default, Clone, Debug)]
+#[reflect(Resource, @SettingsType::Project, @SettingKey("import_presets"))]
+pub struct ImportPresets {
+    /// All the presets, the presets of deeper folders take precedence
+    pub presets: Vec<ImportPreset>,
+}
+
+impl ImportPresets {
+    /// The presets applying to the asset at `path`, relative to the source root, from the least to
+    /// the most specific
+    pub fn presets_for(&self, path: &Path) -> Vec<&ImportPreset> {
+        let mut presets = self
+            .presets
+            .iter()
+            .filter(|preset| preset.applies_to(path))
+            .collect::<Vec<_>>();
+        // Stable, so the order of the presets of a folder is kept
+        presets.sort_by_key(|preset| match preset.folder() {
+            "" => 0,
+            folder => folder.matches('/').count() + 1,
+        });
+        presets
+    }
+}
+
+/// One import setting applied to the assets of a folder
+#[derive(Reflect, Default, Clone, Debug, PartialEq, Eq)]
+pub struct ImportPreset {
+    /// The folder the preset applies to, relative to the source root, with `/` separators
+    /// Empty for the whole asset source.
+    pub folder: String,
+    /// The extension of the files the preset applies to, like `png`, or empty for all files
+    pub extension: String,
+    /// The `.meta` field the preset sets, like `asset.settings.is_srgb`
+    pub setting: String,
+    /// The RON value of the setting, like `false`
+    pub value: String,
+}
+
+impl ImportPreset {
+    /// [`Self::folder`] without leading and trailing `/`
+    pub fn folder(&self) -> &str {
+        self.folder.trim_matches('/')
+    }
+
+    /// Whether the preset applies to the asset at `path`, relative to the source root
+    pub fn applies_to(&self, path: &Path) -> bool {
+        let directory = path.parent().map(asset_path_string).unwrap_or_default();
+        let folder = self.folder();
+        let in_folder = folder.is_empty()
+            || directory
+                .strip_prefix(folder)
+                .is_some_and(|rest| rest.is_empty() || rest.starts_with('/'));
+        let extension = self.extension.trim().trim_start_matches('.');
+        let has_extension = extension.is_empty()
+            || path
+                .extension()
+                .is_some_and(|file_extension| file_extension.eq_ignore_ascii_case(extension));
+        in_folder && has_extension && !self.setting.trim().is_empty()
+    }
+}
+
+/// Set the settings of `presets` in the `.meta` document `meta`, later presets overriding earlier
+/// ones
+///
+/// Returns `Err` with the settings that aren't in `meta`, for example because they belong to
+/// another loader.
+pub(crate) fn apply_presets(meta: &str, presets: &[ImportPreset]) -> Result<String, String> {
+    let fields = meta_fields(meta).ok_or_else(|| "The .meta file isn't valid RON".to_string())?;
+    let mut edits: Vec<(&MetaField, &str)> = Vec::new();
+    let mut missing = Vec::new();
+    for preset in presets {
+        let setting = preset.setting.trim();
+        match fields.iter().find(|field| field.key == setting) {
+            Some(field) => {
+                edits.retain(|(edited, _)| edited.key != field.key);
+                edits.push((field, preset.value.trim()));
+            }
+            None => missing.push(setting),
+        }
+    }
+    if !missing.is_empty() {
+        return Err(format!("Unknown settings: {}", missing.join(", ")));
+    }
+    Ok(apply_edits(meta, edits))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn preset(folder: &str, extension: &str, setting: &str, value: &str) -> ImportPreset {
+        ImportPreset {
+            folder: folder.to_string(),
+            extension: extension.to_string(),
+            setting: setting.to_string(),
+            value: value.to_string(),
+        }
+    }
+
+    #[test]
+    fn finds_presets_of_parent_folders() {
+        let presets = ImportPresets {
+            presets: vec![
+                preset("ui/icons", "", "asset.settings.is_srgb", "false"),
+                preset("ui/", "png", "asset.settings.is_srgb", "true"),
+                preset("", "PNG", "asset.settings.sampler", "Default"),
+                preset("ui", "jpg", "asset.settings.is_srgb", "true"),
+                preset("uis", "", "asset.settings.is_srgb", "true"),
+            ],
+        };
+        let found = presets.presets_for(Path::new("ui/icons/play.png"));
+        assert_eq!(
+            found,
+            [
+                &presets.presets[2],
+                &presets.presets[1],
+                &presets.presets[0]
+            ]
+        );
+        assert_eq!(presets.presets_for(Path::new("ui.png")).len(), 1);
+    }
+
+    #[test]
+    fn applies_presets_to_meta() {
+        let meta = r#"(
+    meta_format_version: "1.0",
+    asset: Load(
+        loader: "bevy_image::image_loader::ImageLoader",
+        settings: (
+            format: FromExtension,
+            is_srgb: true,
+            sampler: Default,
+        ),
+    ),
+)"#;
+        let presets = [
+            preset("", "", "asset.settings.is_srgb", "true"),
+            preset(
+                "ui",
+                "png",
+                "asset.settings.sampler",
+                "Descriptor((mag_filter: Nearest))",
+            ),
+            preset("ui", "png", "asset.settings.is_srgb", "false"),
+        ];
+        let applied = apply_presets(meta, &presets).unwrap();
+        assert!(applied.contains("is_srgb: false,"));
+        assert!(applied.contains("sampler: Descriptor((mag_filter: Nearest)),"));
+
+        let unknown = [preset("", "", "asset.settings.mipmaps", "false")];
+        assert!(apply_presets(meta, &unknown).is_err());
+    }
+}
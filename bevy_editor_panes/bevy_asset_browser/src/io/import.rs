@@ -0,0 +1,93 @@
+use std::path::PathBuf;
+
+use bevy::{
+    asset::{io::AssetSourceId, AssetPath},
+    prelude::*,
+    tasks::IoTaskPool,
+};
+
+use crate::import_presets::{apply_presets, ImportPresets};
+
+/// Write the [`ImportPresets`] of files of the default asset source to their `.meta` files, given
+/// as paths relative to the source root
+///
+/// Files that already have a `.meta` file are skipped, unless `overwrite` is set. In that case
+/// the settings of the presets are changed in the existing `.meta` file.
+///
+/// This is run for the files created, duplicated or moved by the asset browser, and for the files
+/// added from outside the editor when the `file_watcher` feature of Bevy is enabled.
+pub(crate) fn apply_import_presets(
+    In((paths, overwrite)): In<(Vec<PathBuf>, bool)>,
+    presets: Res<ImportPresets>,
+    asset_server: Res<AssetServer>,
+) {
+    let files = paths
+        .into_iter()
+        .filter_map(|path| {
+            let presets = presets
+                .presets_for(&path)
+                .into_iter()
+                .cloned()
+                .collect::<Vec<_>>();
+            (!presets.is_empty()).then_some((path, presets))
+        })
+        .collect::<Vec<_>>();
+    if files.is_empty() {
+        return;
+    }
+    let asset_server = asset_server.clone();
+    IoTaskPool::get()
+        .spawn(async move {
+            let Ok(source) = asset_server.get_source(AssetSourceId::Default) else {
+                return;
+            };
+            let Ok(writer) = source.writer() else {
+                warn!("Can't apply the import presets, the default asset source isn't writable");
+                return;
+            };
+            for (path, presets) in files {
+                let existing_meta = source.reader().read_meta_bytes(&path).await.ok();
+                if existing_meta.is_some() && !overwrite {
+                    continue;
+                }
+                let asset_path = AssetPath::from_path(&path).into_owned();
+                // Files without a loader, like scripts, have no import settings
+                let Ok(loader) = asset_server.get_path_asset_loader(&asset_path).await else {
+                    continue;
+                };
+                let meta = existing_meta.unwrap_or_else(|| loader.default_meta().serialize());
+                let meta = String::from_utf8_lossy(&meta);
+                let result = apply_presets(&meta, &presets).and_then(|meta| {
+                    loader
+                        .deserialize_meta(meta.as_bytes())
+                        .map(|_| meta)
+                        .map_err(|error| error.to_string())
+                });
+                let meta = match result {
+                    Ok(meta) => meta,
+                    Err(error) => {
+                        warn!(
+                            "Failed to apply the import presets to {}: {}",
+                            path.display(),
+                            error
+                        );
+                        continue;
+                    }
+                };
+                match writer.write_meta_bytes(&path, meta.as_bytes()).await {
+                    Ok(()) => {
+                        info!("Applied the import presets to {}", path.display());
+                        asset_server.reload(asset_path);
+                    }
+                    Err(error) => {
+                        warn!(
+                            "Failed to write the .meta file of {}: {}",
+                            path.display(),
+                            error
+                        );
+                    }
+                }
+            }
+        })
+        .detach();
+}
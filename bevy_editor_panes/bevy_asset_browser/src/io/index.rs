@@ -0,0 +1,133 @@
+use std::{
+    collections::{HashMap, HashSet},
+    path::{Path, PathBuf},
+};
+
+use crate::{
+    io::references::{self, asset_path_string},
+    ui::delete_confirmation::PendingDeletion,
+    view::AssetBrowserView,
+    AssetBrowserLocation,
+};
+use bevy::{
+    asset::io::{AssetSourceBuilders, AssetSourceId, Reader},
+    prelude::*,
+    tasks::{block_on, futures_lite::StreamExt, poll_once, IoTaskPool, Task},
+};
+
+/// All the files of an asset source, searched by the search field of the asset browser, and the
+/// assets they reference
+#[derive(Resource, Debug)]
+pub(crate) struct SourceIndex {
+    /// The indexed asset source
+    pub(crate) source_id: AssetSourceId<'static>,
+    /// The paths of the files, relative to the source root
+    pub(crate) files: Vec<PathBuf>,
+    /// The files referencing each asset, keyed by asset path without label
+    pub(crate) references: HashMap<String, Vec<PathBuf>>,
+    /// Set when files were added, removed, renamed or modified since the source was indexed
+    pub(crate) stale: bool,
+}
+
+impl SourceIndex {
+    /// The files referencing the asset at `path`, or any asset inside it if it's a folder
+    /// `path` is relative to the source root, with `/` separators.
+    pub(crate) fn referencing_files(&self, path: &str) -> Vec<&Path> {
+        let mut files = self
+            .references
+            .iter()
+            .filter(|(referenced, _)| references::points_into(referenced, path))
+            .flat_map(|(_, files)| files.iter().map(PathBuf::as_path))
+            .collect::<Vec<_>>();
+        files.sort();
+        files.dedup();
+        files
+    }
+}
+
+#[derive(Component)]
+/// The task that lists all the files of the asset source of the current [`AssetBrowserLocation`]
+pub(crate) struct IndexSourceTask(Task<SourceIndex>);
+
+/// Index the asset source of the current [`AssetBrowserLocation`], if it wasn't indexed yet
+/// A stale [`SourceIndex`] is only indexed again while it's used, by a search or by a
+/// [`PendingDeletion`] looking for the references to the deleted files.
+pub(crate) fn index_location_source(
+    mut commands: Commands,
+    mut asset_source_builders: ResMut<AssetSourceBuilders>,
+    location: Res<AssetBrowserLocation>,
+    view: Res<AssetBrowserView>,
+    index: Option<Res<SourceIndex>>,
+    pending_deletion: Option<Res<PendingDeletion>>,
+    task_query: Query<(), With<IndexSourceTask>>,
+) {
+    if !task_query.is_empty() {
+        return;
+    }
+    let Some(source_id) = location.source_id.clone() else {
+        return;
+    };
+    let used = !view.search.trim().is_empty() || pending_deletion.is_some();
+    if index.is_some_and(|index| index.source_id == source_id && (!index.stale || !used)) {
+        return;
+    }
+    let sources = asset_source_builders.build_sources(false, false);
+    let task = IoTaskPool::get().spawn(async move {
+        let mut files = Vec::new();
+        let mut references = HashMap::<String, Vec<PathBuf>>::new();
+        if let Ok(source) = sources.get(source_id.clone()) {
+            let reader = source.reader();
+            let mut directories = vec![PathBuf::new()];
+            while let Some(directory) = directories.pop() {
+                let Ok(mut dir_stream) = reader.read_directory(&directory).await else {
+                    continue;
+                };
+                while let Some(entry) = dir_stream.next().await {
+                    if reader.is_directory(&entry).await.unwrap_or(false) {
+                        directories.push(entry);
+                    } else {
+                        files.push(entry);
+                    }
+                }
+            }
+
+            let known_files = files
+                .iter()
+                .map(|file| asset_path_string(file))
+                .collect::<HashSet<_>>();
+            for file in files.iter().filter(|file| references::can_reference(file)) {
+                let mut content = Vec::new();
+                let Ok(mut file_reader) = reader.read(file).await else {
+                    continue;
+                };
+                if file_reader.read_to_end(&mut content).await.is_err() {
+                    continue;
+                }
+                for referenced in references::file_references(file, &content, &known_files) {
+                    references.entry(referenced).or_default().push(file.clone());
+                }
+            }
+        }
+        SourceIndex {
+            source_id,
+            files,
+            references,
+            stale: false,
+        }
+    });
+
+    commands.spawn(IndexSourceTask(task));
+}
+
+/// Poll the [`IndexSourceTask`] and insert the [`SourceIndex`] once it's done
+pub(crate) fn poll_index_task(
+    mut commands: Commands,
+    mut task_query: Query<(Entity, &mut IndexSourceTask)>,
+) {
+    for (task_entity, mut task) in &mut task_query {
+        if let Some(index) = block_on(poll_once(&mut task.0)) {
+            commands.entity(task_entity).despawn();
+            commands.insert_resource(index);
+        }
+    }
+}
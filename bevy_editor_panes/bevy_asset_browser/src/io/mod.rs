@@ -1,8 +1,16 @@
 //! this module encapsulate all the asset browser IO operations
 
+pub(crate) mod import;
+pub(crate) mod index;
+pub(crate) mod references;
 pub(crate) mod task;
+pub(crate) mod trash;
+pub(crate) mod watcher;
 
-use std::{fs::create_dir_all, path::PathBuf};
+use std::{
+    fs::create_dir_all,
+    path::{Path, PathBuf},
+};
 
 /// Create a new folder called "New Folder" in the parent directory
 /// If a folder with the same name already exists, it will increment the name until it's unique
@@ -70,13 +78,116 @@ pub fn open_in_file_manager(path: PathBuf) -> std::io::Result<()> {
     Ok(())
 }
 
-/// Delete a file
-pub fn delete_file(path: PathBuf) -> std::io::Result<()> {
-    std::fs::remove_file(path)?;
-    Ok(())
-}
 /// Delete a folder and all its content
 pub fn delete_folder(path: PathBuf) -> std::io::Result<()> {
     std::fs::remove_dir_all(path)?;
     Ok(())
 }
+
+/// The path of the `.meta` file of the asset at `path`
+fn meta_path(path: &Path) -> PathBuf {
+    let mut meta_path = path.as_os_str().to_owned();
+    meta_path.push(".meta");
+    PathBuf::from(meta_path)
+}
+
+/// Move or rename a file or folder, along with the `.meta` file of a file
+/// Fails if something already exists at the destination
+pub fn move_asset(from: PathBuf, to: PathBuf) -> std::io::Result<()> {
+    if to.exists() {
+        return Err(std::io::Error::new(
+            std::io::ErrorKind::AlreadyExists,
+            format!("{} already exists", to.display()),
+        ));
+    }
+    if let Some(parent) = to.parent() {
+        create_dir_all(parent)?;
+    }
+    std::fs::rename(&from, &to)?;
+    let from_meta = meta_path(&from);
+    if from_meta.is_file() {
+        std::fs::rename(from_meta, meta_path(&to))?;
+    }
+    Ok(())
+}
+
+/// Copy a file next to itself, along with its `.meta` file
+/// The copy is called like "image copy.png", with an index added until the name is unique
+pub fn duplicate_file(path: PathBuf) -> std::io::Result<String> {
+    let stem = path
+        .file_stem()
+        .unwrap_or_default()
+        .to_string_lossy()
+        .to_string();
+    let extension = path
+        .extension()
+        .map(|extension| format!(".{}", extension.to_string_lossy()))
+        .unwrap_or_default();
+    let mut copy = path.with_file_name(format!("{stem} copy{extension}"));
+    // increment name until it's unique
+    let mut index = 1;
+    while copy.exists() {
+        index += 1;
+        copy.set_file_name(format!("{stem} copy {index}{extension}"));
+    }
+    std::fs::copy(&path, &copy)?;
+    let meta = meta_path(&path);
+    if meta.is_file() {
+        std::fs::copy(meta, meta_path(&copy))?;
+    }
+    Ok(copy.file_name().unwrap().to_string_lossy().to_string())
+}
+
+/// All the files inside `directory` and its subdirectories
+pub fn files_recursive(directory: &Path) -> std::io::Result<Vec<PathBuf>> {
+    let mut files = Vec::new();
+    let mut directories = vec![directory.to_path_buf()];
+    while let Some(directory) = directories.pop() {
+        for entry in std::fs::read_dir(directory)? {
+            let path = entry?.path();
+            if path.is_dir() {
+                directories.push(path);
+            } else {
+                files.push(path);
+            }
+        }
+    }
+    Ok(files)
+}
+
+/// The asset files at `path`, relative to `source_root`: the file itself, or all the files inside
+/// it if it's a folder, without their `.meta` files
+pub fn asset_files(source_root: &Path, path: &Path) -> std::io::Result<Vec<PathBuf>> {
+    let absolute_path = source_root.join(path);
+    if !absolute_path.is_dir() {
+        return Ok(vec![path.to_path_buf()]);
+    }
+    Ok(files_recursive(&absolute_path)?
+        .into_iter()
+        .filter(|file| file.extension().is_none_or(|extension| extension != "meta"))
+        .filter_map(|file| Some(file.strip_prefix(source_root).ok()?.to_path_buf()))
+        .collect())
+}
+
+/// Rewrite the references to the asset or folder moved from `old` to `new` in all the `.bsn` files
+/// of the asset source at `source_root`
+/// `old` and `new` are asset paths relative to the source root, like `models/alien.glb`
+/// Returns the rewritten files
+pub fn rewrite_bsn_references(
+    source_root: &Path,
+    old: &str,
+    new: &str,
+) -> std::io::Result<Vec<PathBuf>> {
+    let mut rewritten_files = Vec::new();
+    for path in files_recursive(source_root)? {
+        if !references::is_bsn(&path) {
+            continue;
+        }
+        let bsn = std::fs::read_to_string(&path)?;
+        if let Some(rewritten) = references::rewrite_references(&bsn, old, new) {
+            std::fs::write(&path, rewritten)?;
+            rewritten_files.push(path);
+        }
+    }
+    Ok(rewritten_files)
+}
@@ -0,0 +1,436 @@
+//! Find the assets referenced by other assets, and rewrite the references of `.bsn` files
+//!
+//! `.bsn` files reference assets with `@"path"` props, which are loaded as handles. The same syntax
+//! names entities, like the entity observed by `On`, so only the props naming a file outside of
+//! those positions are asset references. glTF files reference their buffers and images by `uri`,
+//! and scene files by any string naming an asset.
+
+use std::{
+    collections::HashSet,
+    ops::Range,
+    path::{Component, Path},
+};
+
+/// The path of an asset relative to its source root, with `/` separators like in `.bsn` files
+pub(crate) fn asset_path_string(path: &Path) -> String {
+    path.components()
+        .map(|component| component.as_os_str().to_string_lossy())
+        .collect::<Vec<_>>()
+        .join("/")
+}
+
+/// Whether the file at `path` is a `.bsn` file
+pub(crate) fn is_bsn(path: &Path) -> bool {
+    path.extension()
+        .is_some_and(|extension| extension == "bsn" || extension == "proto_bsn")
+}
+
+/// Whether the file at `path` is a glTF file, either `.gltf` or binary `.glb`
+fn is_gltf(path: &Path) -> bool {
+    path.extension()
+        .is_some_and(|extension| extension == "gltf" || extension == "glb")
+}
+
+/// Whether the file at `path` is a Bevy scene, like `level.scn.ron`
+fn is_scene(path: &Path) -> bool {
+    path.file_name()
+        .is_some_and(|name| name.to_string_lossy().ends_with(".scn.ron"))
+        || path.extension().is_some_and(|extension| extension == "scn")
+}
+
+/// Whether the file at `path` can reference other assets, and should be read by
+/// [`file_references`]
+pub(crate) fn can_reference(path: &Path) -> bool {
+    is_bsn(path) || is_gltf(path) || is_scene(path)
+}
+
+/// The assets referenced by the file at `path`, relative to the source root and without label
+///
+/// `known_files` are the files of the asset source, scene files only reference those.
+pub(crate) fn file_references(
+    path: &Path,
+    content: &[u8],
+    known_files: &HashSet<String>,
+) -> Vec<String> {
+    let mut referenced = if is_bsn(path) {
+        let Ok(bsn) = std::str::from_utf8(content) else {
+            return Vec::new();
+        };
+        bsn_references(bsn)
+            .into_iter()
+            .map(|(_, reference)| without_label(&reference).to_string())
+            .collect()
+    } else if is_gltf(path) {
+        let json = if content.starts_with(b"glTF") {
+            glb_json(content)
+        } else {
+            std::str::from_utf8(content).ok()
+        };
+        let directory = path.parent().map(asset_path_string).unwrap_or_default();
+        json.map(gltf_uris)
+            .unwrap_or_default()
+            .into_iter()
+            .filter_map(|uri| resolve_uri(&directory, &uri))
+            .collect()
+    } else if is_scene(path) {
+        let Ok(scene) = std::str::from_utf8(content) else {
+            return Vec::new();
+        };
+        string_literals(scene)
+            .into_iter()
+            .map(|range| without_label(&scene[range]))
+            .filter(|reference| known_files.contains(*reference))
+            .map(str::to_string)
+            .collect()
+    } else {
+        Vec::new()
+    };
+    referenced.sort();
+    referenced.dedup();
+    referenced
+}
+
+/// The asset path without its label, like `models/alien.glb` for `models/alien.glb#Scene0`
+fn without_label(reference: &str) -> &str {
+    reference
+        .split_once('#')
+        .map_or(reference, |(path, _)| path)
+}
+
+/// The byte ranges of the double quoted string literals of `text`, quotes excluded
+/// Strings in `//` comments are skipped.
+fn string_literals(text: &str) -> Vec<Range<usize>> {
+    let bytes = text.as_bytes();
+    let mut literals = Vec::new();
+    let mut i = 0;
+    while i < bytes.len() {
+        match bytes[i] {
+            b'"' => {
+                let start = i + 1;
+                let end = string_end(text, start);
+                literals.push(start..end);
+                i = end + 1;
+            }
+            // Skip comments, they may contain quotes
+            b'/' if bytes.get(i + 1) == Some(&b'/') => {
+                i += text[i..].find('\n').unwrap_or(text.len() - i);
+            }
+            _ => i += 1,
+        }
+    }
+    literals
+}
+
+/// The calls whose `@"..."` props name entities instead of assets
+const ENTITY_REFERENCE_CALLS: &[&str] = &["On"];
+
+/// The asset paths referenced by the `.bsn` document `bsn`, unescaped, with the byte ranges of
+/// their literals, quotes excluded
+///
+/// `@"..."` props directly inside the parentheses of an [entity referencing
+/// call](ENTITY_REFERENCE_CALLS), and props that don't name a file, like `@"Player"`, are entity
+/// references and skipped.
+pub(crate) fn bsn_references(bsn: &str) -> Vec<(Range<usize>, String)> {
+    let bytes = bsn.as_bytes();
+    let mut references = Vec::new();
+    // The names of the calls of the open parentheses, brackets and braces
+    let mut groups = Vec::new();
+    let mut i = 0;
+    while i < bytes.len() {
+        match bytes[i] {
+            b'"' => {
+                let start = i + 1;
+                let end = string_end(bsn, start);
+                let is_prop = start >= 2 && bytes[start - 2] == b'@';
+                let names_entity = groups
+                    .last()
+                    .is_some_and(|call| ENTITY_REFERENCE_CALLS.contains(call));
+                if is_prop && !names_entity {
+                    let reference = unescape(&bsn[start..end]);
+                    if Path::new(without_label(&reference)).extension().is_some() {
+                        references.push((start..end, reference));
+                    }
+                }
+                i = end + 1;
+            }
+            b'/' if bytes.get(i + 1) == Some(&b'/') => {
+                i += bsn[i..].find('\n').unwrap_or(bsn.len() - i);
+            }
+            b'(' | b'[' | b'{' => {
+                groups.push(call_name(&bsn[..i]));
+                i += 1;
+            }
+            b')' | b']' | b'}' => {
+                groups.pop();
+                i += 1;
+            }
+            _ => i += 1,
+        }
+    }
+    references
+}
+
+/// The name of the call whose parenthesis follows `text`, like `On` for `bevy_proto_bsn::On`, or
+/// an empty string
+fn call_name(text: &str) -> &str {
+    let text = text.trim_end();
+    let start = text
+        .rfind(|c: char| !c.is_alphanumeric() && c != '_')
+        .map_or(0, |i| i + 1);
+    &text[start..]
+}
+
+/// The value of the string literal `literal`, quotes excluded, with its escapes replaced
+fn unescape(literal: &str) -> String {
+    let mut value = String::with_capacity(literal.len());
+    let mut chars = literal.chars();
+    while let Some(c) = chars.next() {
+        if c != '\\' {
+            value.push(c);
+            continue;
+        }
+        match chars.next() {
+            Some('n') => value.push('\n'),
+            Some('r') => value.push('\r'),
+            Some('t') => value.push('\t'),
+            Some('0') => value.push('\0'),
+            Some(escaped) => value.push(escaped),
+            None => {}
+        }
+    }
+    value
+}
+
+/// Escape `value` to be written between the quotes of a string literal
+fn escape(value: &str) -> String {
+    value.replace('\\', "\\\\").replace('"', "\\\"")
+}
+
+/// The JSON chunk of a binary `.glb` file
+fn glb_json(glb: &[u8]) -> Option<&str> {
+    let chunk_length = u32::from_le_bytes(glb.get(12..16)?.try_into().ok()?) as usize;
+    if glb.get(16..20)? != b"JSON" {
+        return None;
+    }
+    std::str::from_utf8(glb.get(20..20 + chunk_length)?).ok()
+}
+
+/// The `uri`s of the buffers and images of a glTF document, unescaped
+fn gltf_uris(json: &str) -> Vec<String> {
+    let literals = string_literals(json);
+    literals
+        .iter()
+        .zip(literals.iter().skip(1))
+        .filter(|(key, _)| {
+            &json[(*key).clone()] == "uri" && json[key.end + 1..].trim_start().starts_with(':')
+        })
+        .map(|(_, value)| json[value.clone()].replace("\\/", "/"))
+        .collect()
+}
+
+/// The asset path of a glTF `uri`, relative to the `directory` of the glTF file
+/// Embedded `data:` and remote URIs don't point to assets.
+fn resolve_uri(directory: &str, uri: &str) -> Option<String> {
+    if uri.contains(':') {
+        return None;
+    }
+    let mut segments = directory
+        .split('/')
+        .filter(|segment| !segment.is_empty())
+        .map(str::to_string)
+        .collect::<Vec<_>>();
+    for component in Path::new(&percent_decode(uri)).components() {
+        match component {
+            Component::ParentDir => {
+                segments.pop()?;
+            }
+            Component::Normal(segment) => segments.push(segment.to_string_lossy().into_owned()),
+            _ => {}
+        }
+    }
+    Some(segments.join("/"))
+}
+
+/// Decode the `%XX` escapes of a URI
+fn percent_decode(uri: &str) -> String {
+    let bytes = uri.as_bytes();
+    let mut decoded = Vec::with_capacity(bytes.len());
+    let mut i = 0;
+    while i < bytes.len() {
+        let escaped = (bytes[i] == b'%')
+            .then(|| uri.get(i + 1..i + 3))
+            .flatten()
+            .and_then(|hex| u8::from_str_radix(hex, 16).ok());
+        match escaped {
+            Some(byte) => {
+                decoded.push(byte);
+                i += 3;
+            }
+            None => {
+                decoded.push(bytes[i]);
+                i += 1;
+            }
+        }
+    }
+    String::from_utf8_lossy(&decoded).into_owned()
+}
+
+/// The byte index of the quote closing the string starting at `start`
+fn string_end(text: &str, start: usize) -> usize {
+    let mut escaped = false;
+    for (i, c) in text[start..].char_indices() {
+        match c {
+            '\\' if !escaped => escaped = true,
+            '"' if !escaped => return start + i,
+            _ => escaped = false,
+        }
+    }
+    text.len()
+}
+
+/// The path `reference` points to once the asset or folder at `old` is moved to `new`, or `None`
+/// if it doesn't point into `old`
+///
+/// References keep their label, like `#Scene0`.
+pub(crate) fn moved_reference(reference: &str, old: &str, new: &str) -> Option<String> {
+    points_into(reference, old).then(|| format!("{new}{}", &reference[old.len()..]))
+}
+
+/// Whether `reference` points to the asset at `path`, or into it if it's a folder
+pub(crate) fn points_into(reference: &str, path: &str) -> bool {
+    reference
+        .strip_prefix(path)
+        .is_some_and(|rest| rest.is_empty() || rest.starts_with('/') || rest.starts_with('#'))
+}
+
+/// Rewrite the references of `bsn` to the asset or folder at `old` so they point to `new`
+///
+/// Returns `None` if `bsn` doesn't reference `old`.
+pub(crate) fn rewrite_references(bsn: &str, old: &str, new: &str) -> Option<String> {
+    let mut rewritten = bsn.to_string();
+    let mut changed = false;
+    // Replace from the end so the ranges of the other references stay valid
+    for (range, reference) in bsn_references(bsn).into_iter().rev() {
+        if let Some(moved) = moved_reference(&reference, old, new) {
+            rewritten.replace_range(range, &escape(&moved));
+            changed = true;
+        }
+    }
+    changed.then_some(rewritten)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const BSN: &str = r#"(Transform, Visibility::Inherited) [
+    // SceneRoot(@"commented.glb")
+    SceneRoot(@"models/alien.glb#Scene0"),
+    Text("models/alien.glb"),
+    ConstructTextFont { font: @"fonts/Inter \"Regular\".ttf" },
+    Prefab(@"models/alien.glb.proto_bsn"),
+    On(|_: Trigger<Pointer<Click>>| { info!("Clicked models/alien.glb"); }, @"models"),
+    Follow { target: @"Player" },
+]"#;
+
+    #[test]
+    fn finds_references() {
+        let references = bsn_references(BSN)
+            .into_iter()
+            .map(|(_, reference)| reference)
+            .collect::<Vec<_>>();
+        assert_eq!(
+            references,
+            [
+                "models/alien.glb#Scene0",
+                r#"fonts/Inter "Regular".ttf"#,
+                "models/alien.glb.proto_bsn",
+            ]
+        );
+    }
+
+    #[test]
+    fn rewrites_moved_files_and_folders() {
+        let moved = rewrite_references(BSN, "models/alien.glb", "alien.glb").unwrap();
+        assert_eq!(moved, BSN.replace("@\"models/alien.glb#", "@\"alien.glb#"));
+
+        let moved = rewrite_references(BSN, "models", "characters").unwrap();
+        assert_eq!(moved, BSN.replace("@\"models/", "@\"characters/"));
+
+        assert_eq!(rewrite_references(BSN, "model", "characters"), None);
+    }
+
+    #[test]
+    fn leaves_entity_references_alone() {
+        assert_eq!(rewrite_references(BSN, "Player", "Hero"), None);
+
+        // `On` names the `models` entity, not the folder
+        let moved = rewrite_references(BSN, "models", "characters").unwrap();
+        assert!(moved.contains(r#"@"models")"#));
+    }
+
+    #[test]
+    fn rewrites_escaped_references() {
+        let moved = rewrite_references(
+            BSN,
+            r#"fonts/Inter "Regular".ttf"#,
+            r#"fonts/Inter "Bold".ttf"#,
+        )
+        .unwrap();
+        assert!(moved.contains(r#"font: @"fonts/Inter \"Bold\".ttf" }"#));
+        let references = bsn_references(&moved)
+            .into_iter()
+            .map(|(_, reference)| reference)
+            .collect::<Vec<_>>();
+        assert!(references.contains(&r#"fonts/Inter "Bold".ttf"#.to_string()));
+    }
+
+    #[test]
+    fn finds_file_references() {
+        let known_files = HashSet::from(["textures/grass.png".to_string()]);
+        assert_eq!(
+            file_references(
+                Path::new("levels/1.proto_bsn"),
+                BSN.as_bytes(),
+                &known_files
+            ),
+            [
+                r#"fonts/Inter "Regular".ttf"#,
+                "models/alien.glb",
+                "models/alien.glb.proto_bsn",
+            ]
+        );
+
+        let gltf = r#"{
+            "buffers": [{ "uri": "alien.bin", "byteLength": 1024 }],
+            "images": [
+                { "uri": "..\/textures/alien%20skin.png" },
+                { "uri": "data:image/png;base64,iVBORw0KGgo=" }
+            ]
+        }"#;
+        assert_eq!(
+            file_references(
+                Path::new("models/alien.gltf"),
+                gltf.as_bytes(),
+                &known_files
+            ),
+            ["models/alien.bin", "textures/alien skin.png"]
+        );
+        let mut glb = b"glTF\x02\0\0\0\0\0\0\0".to_vec();
+        glb.extend((gltf.len() as u32).to_le_bytes());
+        glb.extend(b"JSON");
+        glb.extend(gltf.as_bytes());
+        assert_eq!(
+            file_references(Path::new("models/alien.glb"), &glb, &known_files),
+            ["models/alien.bin", "textures/alien skin.png"]
+        );
+
+        let scene = r#"(entities: { 0: (components: {
+            "game::Ground": (texture: "textures/grass.png", name: "grass"),
+        }) })"#;
+        assert_eq!(
+            file_references(Path::new("level.scn.ron"), scene.as_bytes(), &known_files),
+            ["textures/grass.png"]
+        );
+    }
+}
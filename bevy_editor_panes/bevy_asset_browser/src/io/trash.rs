@@ -0,0 +1,231 @@
+//! Project-local trash for the files and folders deleted from the asset browser
+//!
+//! Every trashed item gets its own directory in the trash, named after the deletion time:
+//! `<trash>/<id>/origin` holds the path of the item relative to the source root, and
+//! `<trash>/<id>/content/` holds the item itself, along with its `.meta` file.
+
+use std::{
+    path::{Path, PathBuf},
+    time::{Duration, SystemTime},
+};
+
+use super::{delete_folder, move_asset, references::asset_path_string};
+
+/// The name of the file holding the original path of a trashed item
+const ORIGIN_FILE: &str = "origin";
+/// The name of the directory holding the trashed item
+const CONTENT_DIRECTORY: &str = "content";
+
+/// A file or folder in the trash
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct TrashedItem {
+    /// The name of the directory of the item in the trash
+    pub id: String,
+    /// The path of the item relative to the source root, before it was deleted
+    pub original_path: PathBuf,
+    /// When the item was deleted
+    pub deleted_at: SystemTime,
+}
+
+impl TrashedItem {
+    /// Create an item for `original_path`, with an id that isn't used in `trash` yet
+    pub fn new(trash: &Path, original_path: PathBuf, deleted_at: SystemTime) -> Self {
+        let millis = deleted_at
+            .duration_since(SystemTime::UNIX_EPOCH)
+            .unwrap_or_default()
+            .as_millis();
+        let mut id = millis.to_string();
+        // increment id until it's unique
+        let mut index = 0;
+        while trash.join(&id).exists() {
+            index += 1;
+            id = format!("{millis}-{index}");
+        }
+        Self {
+            id,
+            original_path,
+            deleted_at,
+        }
+    }
+
+    /// The file or folder name of the item
+    pub fn name(&self) -> String {
+        self.original_path
+            .file_name()
+            .unwrap_or_default()
+            .to_string_lossy()
+            .to_string()
+    }
+
+    fn content_path(&self, trash: &Path) -> PathBuf {
+        trash
+            .join(&self.id)
+            .join(CONTENT_DIRECTORY)
+            .join(self.name())
+    }
+}
+
+/// Move the item at its original path in `source_root` to `trash`
+pub fn move_to_trash(trash: &Path, source_root: &Path, item: &TrashedItem) -> std::io::Result<()> {
+    let directory = trash.join(&item.id);
+    std::fs::create_dir_all(directory.join(CONTENT_DIRECTORY))?;
+    std::fs::write(
+        directory.join(ORIGIN_FILE),
+        asset_path_string(&item.original_path),
+    )?;
+    if let Err(e) = move_asset(
+        source_root.join(&item.original_path),
+        item.content_path(trash),
+    ) {
+        // Don't leave an empty item behind
+        let _ = std::fs::remove_dir_all(directory);
+        return Err(e);
+    }
+    Ok(())
+}
+
+/// Move the item back from `trash` to its original path in `source_root`
+/// Fails if something was created at the original path in the meantime
+pub fn restore_from_trash(
+    trash: &Path,
+    source_root: &Path,
+    item: &TrashedItem,
+) -> std::io::Result<()> {
+    move_asset(
+        item.content_path(trash),
+        source_root.join(&item.original_path),
+    )?;
+    delete_folder(trash.join(&item.id))
+}
+
+/// Permanently delete an item of the trash
+pub fn delete_from_trash(trash: &Path, item: &TrashedItem) -> std::io::Result<()> {
+    delete_folder(trash.join(&item.id))
+}
+
+/// All the items in `trash`, from the oldest to the most recently deleted
+/// Returns an empty list if the trash doesn't exist yet
+pub fn trash_items(trash: &Path) -> std::io::Result<Vec<TrashedItem>> {
+    if !trash.is_dir() {
+        return Ok(Vec::new());
+    }
+    let mut items = Vec::new();
+    for entry in std::fs::read_dir(trash)? {
+        let entry = entry?;
+        let id = entry.file_name().to_string_lossy().to_string();
+        // Skip what wasn't created by the asset browser
+        let Some(millis) = id
+            .split('-')
+            .next()
+            .and_then(|millis| millis.parse::<u64>().ok())
+        else {
+            continue;
+        };
+        let Ok(origin) = std::fs::read_to_string(entry.path().join(ORIGIN_FILE)) else {
+            continue;
+        };
+        items.push(TrashedItem {
+            id,
+            original_path: PathBuf::from(origin.trim()),
+            deleted_at: SystemTime::UNIX_EPOCH + Duration::from_millis(millis),
+        });
+    }
+    items.sort_by(|a, b| a.deleted_at.cmp(&b.deleted_at).then(a.id.cmp(&b.id)));
+    Ok(items)
+}
+
+/// The items of `items`, sorted from the oldest, that must be deleted to keep the trash within
+/// `max_age` and `max_items`
+/// `None` keeps items forever and any number of items.
+pub fn expired_items(
+    items: &[TrashedItem],
+    max_age: Option<Duration>,
+    max_items: Option<usize>,
+    now: SystemTime,
+) -> Vec<TrashedItem> {
+    let over_count = max_items.map_or(0, |max_items| items.len().saturating_sub(max_items));
+    items
+        .iter()
+        .enumerate()
+        .filter(|(index, item)| {
+            let too_old = max_age.is_some_and(|max_age| {
+                now.duration_since(item.deleted_at)
+                    .is_ok_and(|age| age > max_age)
+            });
+            *index < over_count || too_old
+        })
+        .map(|(_, item)| item.clone())
+        .collect()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn temp_dir(name: &str) -> PathBuf {
+        let dir = std::env::temp_dir().join(format!(
+            "bevy_asset_browser_trash_{}_{}",
+            name,
+            std::process::id()
+        ));
+        let _ = std::fs::remove_dir_all(&dir);
+        std::fs::create_dir_all(&dir).unwrap();
+        dir
+    }
+
+    #[test]
+    fn trash_and_restore() {
+        let root = temp_dir("restore");
+        let source_root = root.join("assets");
+        let trash = root.join(".trash");
+        std::fs::create_dir_all(source_root.join("textures")).unwrap();
+        std::fs::write(source_root.join("textures/wall.png"), "png").unwrap();
+        std::fs::write(source_root.join("textures/wall.png.meta"), "meta").unwrap();
+
+        let now = SystemTime::now();
+        let item = TrashedItem::new(&trash, PathBuf::from("textures/wall.png"), now);
+        move_to_trash(&trash, &source_root, &item).unwrap();
+        assert!(!source_root.join("textures/wall.png").exists());
+        assert!(!source_root.join("textures/wall.png.meta").exists());
+
+        let other = TrashedItem::new(&trash, PathBuf::from("textures"), now);
+        assert_ne!(item.id, other.id);
+        move_to_trash(&trash, &source_root, &other).unwrap();
+
+        let items = trash_items(&trash).unwrap();
+        assert_eq!(items.len(), 2);
+        assert_eq!(items[0].original_path, PathBuf::from("textures/wall.png"));
+
+        restore_from_trash(&trash, &source_root, &items[1]).unwrap();
+        // Nothing is overwritten
+        std::fs::write(source_root.join("textures/wall.png"), "new png").unwrap();
+        assert!(restore_from_trash(&trash, &source_root, &items[0]).is_err());
+        std::fs::remove_file(source_root.join("textures/wall.png")).unwrap();
+        restore_from_trash(&trash, &source_root, &items[0]).unwrap();
+        assert!(source_root.join("textures/wall.png").is_file());
+        assert!(source_root.join("textures/wall.png.meta").is_file());
+        assert!(trash_items(&trash).unwrap().is_empty());
+
+        std::fs::remove_dir_all(root).unwrap();
+    }
+
+    #[test]
+    fn expires_old_items() {
+        let now = SystemTime::UNIX_EPOCH + Duration::from_secs(100 * 86400);
+        let item = |days: u64| TrashedItem {
+            id: days.to_string(),
+            original_path: PathBuf::from(format!("{days}.png")),
+            deleted_at: now - Duration::from_secs(days * 86400),
+        };
+        let items = [item(40), item(20), item(10), item(1)];
+        let day = Duration::from_secs(86400);
+
+        assert_eq!(expired_items(&items, Some(30 * day), None, now), [item(40)]);
+        assert_eq!(
+            expired_items(&items, Some(30 * day), Some(2), now),
+            [item(40), item(20)]
+        );
+        assert_eq!(expired_items(&items, Some(5 * day), Some(0), now).len(), 4);
+        assert!(expired_items(&items, None, None, now).is_empty());
+    }
+}
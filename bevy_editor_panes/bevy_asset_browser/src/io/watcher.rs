@@ -0,0 +1,385 @@
+use std::path::Path;
+
+use crate::{
+    io::{import, index::SourceIndex, references},
+    AssetBrowserLocation, DirectoryContent, Entry,
+};
+use bevy::{
+    asset::{
+        io::{AssetSource, AssetSourceBuilders, AssetSourceEvent, AssetSourceId},
+        AssetPath,
+    },
+    prelude::*,
+};
+use bevy_asset_preview::AssetPreviews;
+
+/// A watching instance of the asset source of the current [`AssetBrowserLocation`]
+/// The source stops being watched when this resource is removed
+#[derive(Resource)]
+pub(crate) struct SourceWatcher(AssetSource);
+
+/// Start watching the asset source of the current [`AssetBrowserLocation`] if it isn't already
+///
+/// Watching requires the `file_watcher` feature, without it the [`DirectoryContent`] is only
+/// updated when the location changes.
+pub(crate) fn watch_location_source(
+    mut commands: Commands,
+    mut asset_source_builders: ResMut<AssetSourceBuilders>,
+    location: Res<AssetBrowserLocation>,
+    watcher: Option<Res<SourceWatcher>>,
+) {
+    let Some(source_id) = location.source_id.clone() else {
+        commands.remove_resource::<SourceWatcher>();
+        return;
+    };
+    if watcher.is_some_and(|watcher| watcher.0.id() == source_id) {
+        return;
+    }
+    let source = asset_source_builders
+        .get_mut(source_id.clone())
+        .and_then(|builder| builder.build(source_id, true, false))
+        .filter(|source| source.event_receiver().is_some());
+    match source {
+        Some(source) => commands.insert_resource(SourceWatcher(source)),
+        None => commands.remove_resource::<SourceWatcher>(),
+    }
+}
+
+/// Apply the changes reported by the [`SourceWatcher`] to the [`DirectoryContent`]
+/// Only changes to the current directory are applied, and the content is only marked as changed
+/// if an entry was actually added, removed or renamed, or if a file was modified so its preview
+/// must be updated
+/// The [`SourceIndex`] is marked as stale when files are added, removed or renamed anywhere in the
+/// source, or when files that can reference other assets are modified
+/// The [`ImportPresets`](crate::import_presets::ImportPresets) are applied to the files added to
+/// the default source
+pub(crate) fn apply_source_events(
+    mut commands: Commands,
+    watcher: Option<Res<SourceWatcher>>,
+    location: Res<AssetBrowserLocation>,
+    mut directory_content: ResMut<DirectoryContent>,
+    mut index: Option<ResMut<SourceIndex>>,
+    mut previews: AssetPreviews,
+) {
+    let Some(watcher) = watcher else {
+        return;
+    };
+    let Some(receiver) = watcher.0.event_receiver() else {
+        return;
+    };
+    if location.source_id.as_ref() != Some(&watcher.0.id()) {
+        // Drop the events of the previous source
+        receiver.try_iter().for_each(drop);
+        return;
+    }
+    let mut changed = false;
+    let mut added_files = Vec::new();
+    for event in receiver.try_iter() {
+        if let AssetSourceEvent::AddedAsset(path) = &event {
+            if watcher.0.id() == AssetSourceId::Default {
+                added_files.push(path.clone());
+            }
+        }
+        if let AssetSourceEvent::ModifiedAsset(path) = &event {
+            previews.invalidate(AssetPath::from_path(path).with_source(watcher.0.id()));
+            // Refresh the UI to show the new preview
+            changed |= path.parent() == Some(location.path.as_path());
+        }
+        if let Some(index) = &mut index {
+            let lists_files = matches!(
+                event,
+                AssetSourceEvent::AddedAsset(_)
+                    | AssetSourceEvent::RemovedAsset(_)
+                    | AssetSourceEvent::RenamedAsset { .. }
+                    | AssetSourceEvent::AddedFolder(_)
+                    | AssetSourceEvent::RemovedFolder(_)
+                    | AssetSourceEvent::RenamedFolder { .. }
+                    | AssetSourceEvent::RemovedUnknown { is_meta: false, .. }
+            );
+            let changes_references = matches!(
+                &event,
+                AssetSourceEvent::ModifiedAsset(path) if references::can_reference(path)
+            );
+            if (lists_files || changes_references) && index.source_id == watcher.0.id() {
+                index.bypass_change_detection().stale = true;
+            }
+        }
+        changed |= apply_event(
+            &mut directory_content.bypass_change_detection().0,
+            &location.path,
+            event,
+        );
+    }
+    if changed {
+        directory_content.set_changed();
+    }
+    if !added_files.is_empty() {
+        commands.run_system_cached_with(import::apply_import_presets, (added_files, false));
+    }
+}
+
+/// Apply a single [`AssetSourceEvent`] to the entries of `directory`
+/// Returns `true` if the entries changed
+fn apply_event(entries: &mut Vec<Entry>, directory: &Path, event: AssetSourceEvent) -> bool {
+    match event {
+        AssetSourceEvent::AddedAsset(path) => add_entry(entries, directory, &path, Entry::File),
+        AssetSourceEvent::AddedFolder(path) => add_entry(entries, directory, &path, Entry::Folder),
+        AssetSourceEvent::RemovedAsset(path) => {
+            remove_entry(entries, directory, &path, Entry::File)
+        }
+        AssetSourceEvent::RemovedFolder(path) => {
+            if directory.starts_with(&path) {
+                // The current directory itself was removed
+                let changed = !entries.is_empty();
+                entries.clear();
+                return changed;
+            }
+            remove_entry(entries, directory, &path, Entry::Folder)
+        }
+        AssetSourceEvent::RemovedUnknown {
+            path,
+            is_meta: false,
+        } => {
+            remove_entry(entries, directory, &path, Entry::File)
+                | remove_entry(entries, directory, &path, Entry::Folder)
+        }
+        AssetSourceEvent::RenamedAsset { old, new } => {
+            rename_entry(entries, directory, &old, &new, Entry::File)
+        }
+        AssetSourceEvent::RenamedFolder { old, new } => {
+            rename_entry(entries, directory, &old, &new, Entry::Folder)
+        }
+        // Meta files are not listed, and modifications don't change the listing
+        _ => false,
+    }
+}
+
+/// The name of the entry at `path`, if it is directly inside `directory`
+fn entry_name(directory: &Path, path: &Path) -> Option<String> {
+    if path.parent()? != directory {
+        return None;
+    }
+    Some(path.file_name()?.to_string_lossy().to_string())
+}
+
+fn add_entry(
+    entries: &mut Vec<Entry>,
+    directory: &Path,
+    path: &Path,
+    entry: fn(String) -> Entry,
+) -> bool {
+    let Some(name) = entry_name(directory, path) else {
+        return false;
+    };
+    let entry = entry(name);
+    if entries.contains(&entry) {
+        // Already added, for example by the asset browser itself
+        return false;
+    }
+    entries.push(entry);
+    true
+}
+
+fn remove_entry(
+    entries: &mut Vec<Entry>,
+    directory: &Path,
+    path: &Path,
+    entry: fn(String) -> Entry,
+) -> bool {
+    let Some(name) = entry_name(directory, path) else {
+        return false;
+    };
+    let entry = entry(name);
+    let len = entries.len();
+    entries.retain(|other| *other != entry);
+    entries.len() != len
+}
+
+/// Rename an entry in place, so it keeps its position in the directory content
+fn rename_entry(
+    entries: &mut Vec<Entry>,
+    directory: &Path,
+    old: &Path,
+    new: &Path,
+    entry: fn(String) -> Entry,
+) -> bool {
+    let (Some(old_name), Some(new_name)) = (entry_name(directory, old), entry_name(directory, new))
+    else {
+        // Moved into or out of the current directory
+        return remove_entry(entries, directory, old, entry)
+            | add_entry(entries, directory, new, entry);
+    };
+    let old_entry = entry(old_name);
+    match entries.iter_mut().find(|other| **other == old_entry) {
+        Some(existing) => {
+            *existing = entry(new_name);
+            true
+        }
+        None => add_entry(entries, directory, new, entry),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use std::path::PathBuf;
+
+    use super::*;
+
+    fn models_entries() -> Vec<Entry> {
+        vec![
+            Entry::Folder("textures".to_string()),
+            Entry::File("ship.glb".to_string()),
+            Entry::File("alien.glb".to_string()),
+        ]
+    }
+
+    #[test]
+    fn adds_entries() {
+        let directory = Path::new("models");
+        let mut entries = models_entries();
+        assert!(apply_event(
+            &mut entries,
+            directory,
+            AssetSourceEvent::AddedAsset(PathBuf::from("models/car.glb"))
+        ));
+        assert!(apply_event(
+            &mut entries,
+            directory,
+            AssetSourceEvent::AddedFolder(PathBuf::from("models/props"))
+        ));
+        assert_eq!(
+            entries[3..],
+            [
+                Entry::File("car.glb".to_string()),
+                Entry::Folder("props".to_string())
+            ]
+        );
+        // Already listed
+        assert!(!apply_event(
+            &mut entries,
+            directory,
+            AssetSourceEvent::AddedAsset(PathBuf::from("models/ship.glb"))
+        ));
+        assert_eq!(entries.len(), 5);
+    }
+
+    #[test]
+    fn removes_entries() {
+        let directory = Path::new("models");
+        let mut entries = models_entries();
+        assert!(apply_event(
+            &mut entries,
+            directory,
+            AssetSourceEvent::RemovedAsset(PathBuf::from("models/ship.glb"))
+        ));
+        assert!(apply_event(
+            &mut entries,
+            directory,
+            AssetSourceEvent::RemovedUnknown {
+                path: PathBuf::from("models/textures"),
+                is_meta: false,
+            }
+        ));
+        assert_eq!(entries, [Entry::File("alien.glb".to_string())]);
+        assert!(!apply_event(
+            &mut entries,
+            directory,
+            AssetSourceEvent::RemovedUnknown {
+                path: PathBuf::from("models/alien.glb"),
+                is_meta: true,
+            }
+        ));
+
+        // Removing the current directory or one of its parents empties it
+        assert!(apply_event(
+            &mut entries,
+            Path::new("models/textures"),
+            AssetSourceEvent::RemovedFolder(PathBuf::from("models"))
+        ));
+        assert!(entries.is_empty());
+    }
+
+    #[test]
+    fn renames_entries_in_place() {
+        let directory = Path::new("models");
+        let mut entries = models_entries();
+        assert!(apply_event(
+            &mut entries,
+            directory,
+            AssetSourceEvent::RenamedAsset {
+                old: PathBuf::from("models/ship.glb"),
+                new: PathBuf::from("models/spaceship.glb"),
+            }
+        ));
+        assert!(apply_event(
+            &mut entries,
+            directory,
+            AssetSourceEvent::RenamedFolder {
+                old: PathBuf::from("models/textures"),
+                new: PathBuf::from("models/images"),
+            }
+        ));
+        assert_eq!(
+            entries,
+            [
+                Entry::Folder("images".to_string()),
+                Entry::File("spaceship.glb".to_string()),
+                Entry::File("alien.glb".to_string()),
+            ]
+        );
+    }
+
+    #[test]
+    fn renames_entries_across_directories() {
+        let directory = Path::new("models");
+        let mut entries = models_entries();
+        // Moved out of the current directory
+        assert!(apply_event(
+            &mut entries,
+            directory,
+            AssetSourceEvent::RenamedAsset {
+                old: PathBuf::from("models/ship.glb"),
+                new: PathBuf::from("archive/ship.glb"),
+            }
+        ));
+        // Moved into the current directory
+        assert!(apply_event(
+            &mut entries,
+            directory,
+            AssetSourceEvent::RenamedFolder {
+                old: PathBuf::from("archive/old"),
+                new: PathBuf::from("models/old"),
+            }
+        ));
+        assert_eq!(
+            entries,
+            [
+                Entry::Folder("textures".to_string()),
+                Entry::File("alien.glb".to_string()),
+                Entry::Folder("old".to_string()),
+            ]
+        );
+    }
+
+    #[test]
+    fn ignores_events_outside_the_directory() {
+        let directory = Path::new("models");
+        let mut entries = models_entries();
+        let events = [
+            AssetSourceEvent::AddedAsset(PathBuf::from("car.glb")),
+            AssetSourceEvent::AddedAsset(PathBuf::from("models/textures/metal.png")),
+            AssetSourceEvent::RemovedAsset(PathBuf::from("archive/ship.glb")),
+            AssetSourceEvent::RemovedFolder(PathBuf::from("models/textures/old")),
+            AssetSourceEvent::RenamedAsset {
+                old: PathBuf::from("archive/a.glb"),
+                new: PathBuf::from("archive/b.glb"),
+            },
+            AssetSourceEvent::ModifiedAsset(PathBuf::from("models/ship.glb")),
+            AssetSourceEvent::AddedMeta(PathBuf::from("models/car.glb")),
+        ];
+        for event in events {
+            assert!(!apply_event(&mut entries, directory, event));
+        }
+        assert_eq!(entries, models_entries());
+    }
+}
@@ -7,16 +7,29 @@ use bevy::{
     asset::{
         embedded_asset,
         io::{file::FileAssetReader, AssetSourceId},
-        AssetPlugin,
+        AssetPath, AssetPlugin,
     },
     prelude::*,
 };
+use bevy_asset_preview::AssetPreviewPlugin;
+use bevy_command_palette::CommandPalettePlugin;
+use bevy_editor_core::{OpenAsset, SelectedAsset};
+use bevy_editor_settings::EditorSettingsPlugin;
+use bevy_field_forms::FieldFormsPlugin;
 use bevy_pane_layout::prelude::*;
 use bevy_scroll_box::ScrollBoxPlugin;
+use bevy_undo::UndoPlugin;
+use import_presets::ImportPresets;
+use io::index::SourceIndex;
+use trash::{AssetTrash, TrashSettings};
 use ui::top_bar::location_as_changed;
+use view::AssetBrowserView;
 
+pub mod import_presets;
 mod io;
+pub mod trash;
 mod ui;
+pub mod view;
 
 /// The bevy asset browser plugin
 pub struct AssetBrowserPanePlugin;
@@ -43,12 +56,45 @@ impl Plugin for AssetBrowserPanePlugin {
             absolute_path.push(asset_plugin_file_path);
             absolute_path
         };
+        // The trash is in the project, outside of the asset source so it isn't browsed
+        let trash_path = FileAssetReader::get_base_path().join(AssetTrash::DIRECTORY_NAME);
+
+        if !app.is_plugin_added::<AssetPreviewPlugin>() {
+            app.add_plugins(AssetPreviewPlugin);
+        }
+        if !app.is_plugin_added::<FieldFormsPlugin>() {
+            app.add_plugins(FieldFormsPlugin);
+        }
+        if !app.is_plugin_added::<UndoPlugin>() {
+            app.add_plugins(UndoPlugin);
+        }
+        if !app.is_plugin_added::<CommandPalettePlugin>() {
+            app.add_plugins(CommandPalettePlugin);
+        }
+        if !app.is_plugin_added::<EditorSettingsPlugin>() {
+            app.add_plugins(EditorSettingsPlugin);
+        }
+        ui::commands::register_commands(app);
 
         app.add_plugins(ScrollBoxPlugin)
             .insert_resource(DefaultSourceFilePath(default_source_absolute_file_path))
             .insert_resource(AssetBrowserLocation::default())
             .insert_resource(DirectoryContent::default())
-            .add_systems(Startup, io::task::fetch_directory_content)
+            .insert_resource(AssetTrash::new(trash_path))
+            .init_resource::<AssetBrowserView>()
+            .init_resource::<AssetBrowserSelection>()
+            .init_resource::<ImportPresets>()
+            .register_type::<ImportPresets>()
+            .init_resource::<TrashSettings>()
+            .register_type::<TrashSettings>()
+            .add_event::<OpenAsset>()
+            .add_systems(
+                Startup,
+                (
+                    io::task::fetch_directory_content,
+                    io::watcher::watch_location_source,
+                ),
+            )
             // .add_systems(Update, button_interaction)
             .add_systems(
                 Update,
@@ -56,15 +102,61 @@ impl Plugin for AssetBrowserPanePlugin {
             )
             .add_systems(
                 Update,
-                ui::directory_content::refresh_ui
-                    .run_if(directory_content_as_changed)
+                (
+                    io::index::index_location_source,
+                    io::index::poll_index_task,
+                    ui::delete_confirmation::confirm_pending_deletion
+                        .run_if(resource_exists::<ui::delete_confirmation::PendingDeletion>),
+                )
+                    .chain(),
+            )
+            .add_systems(
+                Update,
+                (
+                    ui::nodes::show_file_previews,
+                    ui::nodes::highlight_selected_file,
+                    ui::directory_content::apply_content_layout,
+                    ui::top_bar::update_view_controls.run_if(resource_changed::<AssetBrowserView>),
+                    ui::file_operations::sync_selection.run_if(resource_changed::<SelectedAsset>),
+                    ui::file_operations::confirm_rename,
+                ),
+            )
+            .add_systems(
+                Update,
+                (
+                    trash::apply_trash_retention.run_if(
+                        resource_changed::<AssetTrash>.or(resource_changed::<TrashSettings>),
+                    ),
+                    ui::trash::refresh_trash_panel.run_if(
+                        resource_changed::<AssetTrash>.or(resource_changed::<TrashSettings>),
+                    ),
+                )
+                    .chain(),
+            )
+            .add_systems(
+                Update,
+                io::watcher::apply_source_events
+                    .run_if(not(io::task::fetch_task_is_running))
                     .after(io::task::poll_task),
             )
+            .add_systems(
+                Update,
+                ui::directory_content::refresh_ui
+                    .run_if(
+                        directory_content_as_changed
+                            .or(resource_changed::<AssetBrowserView>)
+                            .or(resource_exists_and_changed::<SourceIndex>),
+                    )
+                    .after(io::task::poll_task)
+                    .after(io::index::poll_index_task)
+                    .after(io::watcher::apply_source_events),
+            )
             .add_systems(
                 Update,
                 (
                     ui::top_bar::refresh_ui,
                     ui::directory_content::refresh_context_menu,
+                    io::watcher::watch_location_source,
                 )
                     .run_if(location_as_changed),
             );
@@ -91,6 +183,12 @@ pub(crate) fn directory_content_as_changed(directory_content: Res<DirectoryConte
     directory_content.is_changed()
 }
 
+/// The files selected in the asset browser
+///
+/// The [`SelectedAsset`] is the last file clicked, which is part of this selection.
+#[derive(Resource, Default, Debug, Clone, PartialEq, Eq)]
+pub struct AssetBrowserSelection(pub Vec<AssetPath<'static>>);
+
 #[derive(Resource)]
 struct DefaultSourceFilePath(pub PathBuf);
 
@@ -0,0 +1,332 @@
+//! The files and folders deleted from the asset browser are moved to a trash directory in the
+//! project, from which they can be restored, either from the trash panel or by undoing the
+//! deletion. Old items are deleted permanently according to the [`TrashSettings`].
+
+use std::{
+    path::{Path, PathBuf},
+    sync::{Arc, Weak},
+    time::{Duration, SystemTime},
+};
+
+use bevy::{platform::collections::HashMap, prelude::*};
+use bevy_editor_settings::{SettingKey, SettingsType};
+use bevy_undo::{ChangeResult, EditorChange, NewChange};
+
+use crate::{
+    io::{
+        self,
+        index::SourceIndex,
+        trash::{delete_from_trash, move_to_trash, restore_from_trash, trash_items},
+    },
+    DefaultSourceFilePath,
+};
+
+pub use crate::io::trash::TrashedItem;
+
+/// How long the deleted files are kept in the trash, stored in `Bevy.toml` like:
+///
+/// ```toml
+/// [asset_trash]
+/// retention_days = 30
+/// max_items = 500
+/// ```
+#[derive(Resource, Reflect, Clone, Debug, PartialEq, Eq)]
+#[reflect(Resource, @SettingsType::Project, @SettingKey("asset_trash"))]
+pub struct TrashSettings {
+    /// The number of days after which trashed items are deleted permanently, 0 to keep them
+    /// forever
+    pub retention_days: u32,
+    /// The maximum number of items in the trash, the oldest items are deleted permanently when
+    /// there are more, 0 for no limit
+    pub max_items: u32,
+}
+
+impl Default for TrashSettings {
+    fn default() -> Self {
+        Self {
+            retention_days: 30,
+            max_items: 500,
+        }
+    }
+}
+
+impl TrashSettings {
+    /// The age after which items are deleted permanently, if any
+    pub fn max_age(&self) -> Option<Duration> {
+        (self.retention_days > 0)
+            .then(|| Duration::from_secs(u64::from(self.retention_days) * 24 * 60 * 60))
+    }
+
+    /// The maximum number of items in the trash, if any
+    pub fn max_items(&self) -> Option<usize> {
+        (self.max_items > 0).then_some(self.max_items as usize)
+    }
+}
+
+/// The trash of the default asset source
+#[derive(Resource, Debug, Clone)]
+pub struct AssetTrash {
+    /// The trash directory, next to the default asset source
+    pub directory: PathBuf,
+    /// The items in the trash, from the oldest to the most recently deleted
+    pub items: Vec<TrashedItem>,
+    /// The items of the deletions that can still be undone, kept until they leave the undo history
+    undoable: Vec<Weak<Vec<TrashedItem>>>,
+}
+
+impl AssetTrash {
+    /// The name of the trash directory in the project
+    pub const DIRECTORY_NAME: &str = ".trash";
+
+    /// Create the trash in `directory`, reading the items already in it
+    pub fn new(directory: PathBuf) -> Self {
+        let mut trash = Self {
+            directory,
+            items: Vec::new(),
+            undoable: Vec::new(),
+        };
+        trash.reload();
+        trash
+    }
+
+    /// Move the files and folders at `paths`, relative to `source_root`, to the trash
+    /// The deletion can be undone, which restores the items.
+    /// Returns the paths that were moved.
+    pub(crate) fn trash(
+        &mut self,
+        source_root: &Path,
+        paths: Vec<PathBuf>,
+        new_changes: &mut EventWriter<NewChange>,
+    ) -> Vec<PathBuf> {
+        let now = SystemTime::now();
+        let mut trashed = Vec::new();
+        for path in paths {
+            let item = TrashedItem::new(&self.directory, path, now);
+            match move_to_trash(&self.directory, source_root, &item) {
+                Ok(()) => trashed.push(item),
+                Err(e) => eprintln!("Failed to delete {}: {}", item.original_path.display(), e),
+            }
+        }
+        if trashed.is_empty() {
+            return Vec::new();
+        }
+        self.reload();
+        let items = Arc::new(trashed);
+        self.undoable.retain(|items| items.strong_count() > 0);
+        self.undoable.push(Arc::downgrade(&items));
+        new_changes.write(NewChange::new(TrashedAssets {
+            items: items.clone(),
+            source_root: source_root.to_path_buf(),
+            trash: self.directory.clone(),
+            restore: true,
+        }));
+        items
+            .iter()
+            .map(|item| item.original_path.clone())
+            .collect()
+    }
+
+    /// The ids of the items that can be restored by undoing their deletion, which must not be
+    /// deleted permanently
+    pub fn undoable_ids(&self) -> Vec<String> {
+        self.undoable
+            .iter()
+            .filter_map(Weak::upgrade)
+            .flat_map(|items| items.iter().map(|item| item.id.clone()).collect::<Vec<_>>())
+            .collect()
+    }
+
+    /// Read the items of the trash directory again
+    pub fn reload(&mut self) {
+        self.items = trash_items(&self.directory).unwrap_or_else(|e| {
+            warn!("Failed to read the trash: {}", e);
+            Vec::new()
+        });
+    }
+}
+
+/// Restore an item of the [`AssetTrash`] to its original path
+pub(crate) fn restore_trashed_item(
+    In(item): In<TrashedItem>,
+    mut commands: Commands,
+    mut trash: ResMut<AssetTrash>,
+    default_source_file_path: Res<DefaultSourceFilePath>,
+    index: Option<ResMut<SourceIndex>>,
+) {
+    if let Err(e) = restore_from_trash(&trash.directory, &default_source_file_path.0, &item) {
+        eprintln!("Failed to restore {}: {}", item.original_path.display(), e);
+        return;
+    }
+    trash.reload();
+    if let Some(mut index) = index {
+        index.stale = true;
+    }
+    commands.run_system_cached(io::task::fetch_directory_content);
+}
+
+/// Permanently delete items of the [`AssetTrash`]
+pub(crate) fn delete_trashed_items(In(items): In<Vec<TrashedItem>>, mut trash: ResMut<AssetTrash>) {
+    for item in &items {
+        if let Err(e) = delete_from_trash(&trash.directory, item) {
+            eprintln!(
+                "Failed to delete {} from the trash: {}",
+                item.original_path.display(),
+                e
+            );
+        }
+    }
+    trash.reload();
+}
+
+/// Permanently delete the items of the [`AssetTrash`] that are too old, or over the maximum
+/// number of items, of the [`TrashSettings`]
+/// The items whose deletion can still be undone are kept.
+pub(crate) fn apply_trash_retention(
+    mut commands: Commands,
+    trash: Res<AssetTrash>,
+    settings: Res<TrashSettings>,
+) {
+    let undoable = trash.undoable_ids();
+    let items = trash
+        .items
+        .iter()
+        .filter(|item| !undoable.contains(&item.id))
+        .cloned()
+        .collect::<Vec<_>>();
+    let expired = io::trash::expired_items(
+        &items,
+        settings.max_age(),
+        settings.max_items(),
+        SystemTime::now(),
+    );
+    if !expired.is_empty() {
+        commands.run_system_cached_with(delete_trashed_items, expired);
+    }
+}
+
+/// The undoable deletion of assets, moved to the trash
+#[derive(Clone)]
+struct TrashedAssets {
+    /// Shared with the inverse change, and weakly referenced by the [`AssetTrash`]
+    items: Arc<Vec<TrashedItem>>,
+    source_root: PathBuf,
+    trash: PathBuf,
+    /// Whether reverting this change restores the items instead of trashing them again
+    restore: bool,
+}
+
+impl TrashedAssets {
+    fn apply(&self) -> std::io::Result<()> {
+        let (source_root, trash) = (&self.source_root, &self.trash);
+        // Restore in reverse order, so trashed folders are back before the items that were in them
+        if self.restore {
+            self.items
+                .iter()
+                .rev()
+                .try_for_each(|item| restore_from_trash(trash, source_root, item))
+        } else {
+            self.items
+                .iter()
+                .try_for_each(|item| move_to_trash(trash, source_root, item))
+        }
+    }
+}
+
+impl EditorChange for TrashedAssets {
+    fn revert(
+        &self,
+        world: &mut World,
+        _entity_remap: &HashMap<Entity, Entity>,
+    ) -> Result<ChangeResult, String> {
+        // The files may have been changed by other programs, or restored from the trash panel
+        let result = self.apply();
+        if let Some(mut trash) = world.get_resource_mut::<AssetTrash>() {
+            trash.reload();
+        }
+        if let Some(mut index) = world.get_resource_mut::<SourceIndex>() {
+            index.stale = true;
+        }
+        world
+            .run_system_cached(io::task::fetch_directory_content)
+            .map_err(|e| e.to_string())?;
+        result.map_err(|e| {
+            let action = if self.restore { "restore" } else { "trash" };
+            format!("Failed to {} the deleted assets: {}", action, e)
+        })?;
+        Ok(ChangeResult::Success)
+    }
+
+    fn debug_text(&self) -> String {
+        let paths = self
+            .items
+            .iter()
+            .map(|item| item.original_path.display().to_string())
+            .collect::<Vec<_>>();
+        format!("Deleted {}", paths.join(", "))
+    }
+
+    fn get_inverse(&self) -> Arc<dyn EditorChange + Send + Sync> {
+        Arc::new(TrashedAssets {
+            restore: !self.restore,
+            ..self.clone()
+        })
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use bevy::ecs::system::RunSystemOnce;
+
+    #[test]
+    fn keeps_the_items_whose_deletion_can_be_undone() {
+        let root = std::env::temp_dir().join(format!(
+            "bevy_asset_browser_retention_{}",
+            std::process::id()
+        ));
+        let _ = std::fs::remove_dir_all(&root);
+        let source_root = root.join("assets");
+        std::fs::create_dir_all(&source_root).unwrap();
+
+        let mut app = App::new();
+        app.add_event::<NewChange>()
+            .insert_resource(AssetTrash::new(root.join(AssetTrash::DIRECTORY_NAME)))
+            .insert_resource(TrashSettings {
+                retention_days: 0,
+                max_items: 1,
+            });
+        for name in ["a.png", "b.png", "c.png"] {
+            std::fs::write(source_root.join(name), name).unwrap();
+            let source_root = source_root.clone();
+            app.world_mut()
+                .run_system_once(
+                    move |mut trash: ResMut<AssetTrash>,
+                          mut new_changes: EventWriter<NewChange>| {
+                        trash.trash(&source_root, vec![PathBuf::from(name)], &mut new_changes);
+                    },
+                )
+                .unwrap();
+        }
+        let world = app.world_mut();
+        world.run_system_cached(apply_trash_retention).unwrap();
+        assert_eq!(world.resource::<AssetTrash>().items.len(), 3);
+
+        // Only the deletion of c.png can still be undone
+        let changes = world
+            .resource_mut::<Events<NewChange>>()
+            .drain()
+            .collect::<Vec<_>>();
+        let _undoable = changes[2].change.clone();
+        drop(changes);
+        world.run_system_cached(apply_trash_retention).unwrap();
+        let names = world
+            .resource::<AssetTrash>()
+            .items
+            .iter()
+            .map(TrashedItem::name)
+            .collect::<Vec<_>>();
+        assert_eq!(names, ["b.png", "c.png"]);
+
+        std::fs::remove_dir_all(root).unwrap();
+    }
+}
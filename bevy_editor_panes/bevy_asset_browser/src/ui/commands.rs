@@ -0,0 +1,145 @@
+//! The actions of the asset browser menus, registered as commands so they can also be run from the
+//! command palette
+//!
+//! Run from a context menu, the commands apply to the file or folder node the menu was opened on.
+//! Run from the palette, they apply to the file node of the [`SelectedAsset`].
+
+use bevy::{asset::io::AssetSourceId, prelude::*};
+use bevy_command_palette::{CommandInfo, CommandPaletteAppExt, CommandTarget};
+use bevy_editor_core::{AssetNode, SelectedAsset};
+
+use crate::AssetBrowserLocation;
+
+use super::{
+    directory_content::{
+        create_new_folder, create_new_script, delete_file, delete_folder, open_in_file_manager,
+    },
+    file_operations::{duplicate_files, start_rename, FolderPath},
+    import_presets::open_import_presets_editor,
+    nodes::find_references,
+    trash::open_trash_panel,
+    AssetBrowserNode,
+};
+
+/// Register the commands of the asset browser
+pub(crate) fn register_commands(app: &mut App) {
+    let info = |id: &'static str, label: &'static str| {
+        CommandInfo::new(id, label).with_category("Asset Browser")
+    };
+    app.register_command(
+        info("asset_browser.rename", "Rename"),
+        target_node.pipe(rename),
+    )
+    .register_command(
+        info("asset_browser.duplicate", "Duplicate"),
+        target_node.pipe(duplicate),
+    )
+    .register_command(
+        info("asset_browser.find_references", "Find references"),
+        target_node.pipe(references),
+    )
+    .register_command(
+        info("asset_browser.delete", "Delete"),
+        target_node.pipe(delete),
+    )
+    .register_command(
+        info("asset_browser.import_presets", "Import presets"),
+        target_node.pipe(import_presets),
+    )
+    .register_command(
+        info("asset_browser.create_folder", "Create Folder"),
+        in_default_source(create_new_folder),
+    )
+    .register_command(
+        info("asset_browser.create_script", "Create New Script"),
+        in_default_source(create_new_script),
+    )
+    .register_command(
+        info("asset_browser.open_in_file_manager", "Open in File Manager"),
+        in_default_source(open_in_file_manager),
+    )
+    .register_command(info("asset_browser.open_trash", "Open Trash"), open_trash);
+}
+
+/// The node a command applies to, if the files of the default source are shown, as the menus are
+/// only shown there
+fn target_node(
+    target: Res<CommandTarget>,
+    location: Res<AssetBrowserLocation>,
+    selected_asset: Res<SelectedAsset>,
+    nodes: Query<(Entity, &AssetNode)>,
+) -> Option<Entity> {
+    if location.source_id != Some(AssetSourceId::Default) {
+        return None;
+    }
+    target.0.or_else(|| {
+        let selected_asset = selected_asset.0.as_ref()?;
+        nodes
+            .iter()
+            .find(|(_, node)| node.0 == *selected_asset)
+            .map(|(entity, _)| entity)
+    })
+}
+
+/// Run `system` only if the files of the default source are shown
+fn in_default_source<M: 'static>(
+    system: impl IntoSystem<(), (), M> + Copy + Send + Sync + 'static,
+) -> impl FnMut(Commands, Res<AssetBrowserLocation>) {
+    move |mut commands, location| {
+        if location.source_id == Some(AssetSourceId::Default) {
+            commands.run_system_cached(system);
+        }
+    }
+}
+
+fn rename(In(node): In<Option<Entity>>, mut commands: Commands) {
+    if let Some(node) = node {
+        commands.run_system_cached_with(start_rename, node);
+    }
+}
+
+fn duplicate(In(node): In<Option<Entity>>, mut commands: Commands) {
+    if let Some(node) = node {
+        commands.run_system_cached_with(duplicate_files, node);
+    }
+}
+
+fn references(In(node): In<Option<Entity>>, mut commands: Commands) {
+    if let Some(node) = node {
+        commands.run_system_cached_with(find_references, node);
+    }
+}
+
+fn import_presets(In(node): In<Option<Entity>>, mut commands: Commands) {
+    if let Some(node) = node {
+        commands.run_system_cached_with(open_import_presets_editor, node);
+    }
+}
+
+fn delete(
+    In(node): In<Option<Entity>>,
+    mut commands: Commands,
+    files: Query<(), With<AssetNode>>,
+    folders: Query<(), With<FolderPath>>,
+) {
+    match node {
+        Some(node) if files.contains(node) => {
+            commands.run_system_cached_with(delete_file, node);
+        }
+        Some(node) if folders.contains(node) => {
+            commands.run_system_cached_with(delete_folder, node);
+        }
+        _ => {}
+    }
+}
+
+/// Open the trash over the asset browser of the context menu, or the first asset browser
+fn open_trash(
+    mut commands: Commands,
+    target: Res<CommandTarget>,
+    asset_browsers: Query<Entity, With<AssetBrowserNode>>,
+) {
+    if let Some(entity) = target.0.or_else(|| asset_browsers.iter().next()) {
+        commands.run_system_cached_with(open_trash_panel, entity);
+    }
+}
@@ -0,0 +1,223 @@
+//! Panel asking to confirm the deletion of assets referenced by other files, whose references
+//! would break
+
+use std::path::{Path, PathBuf};
+
+use bevy::{asset::io::AssetSourceId, prelude::*};
+use bevy_editor_styles::Theme;
+
+use crate::io::{index::SourceIndex, references::asset_path_string};
+
+use super::{directory_content::trash_assets, spawn_panel, spawn_panel_button, spawn_panel_label};
+
+/// Files and folders of the default source to move to the trash
+///
+/// The deletion waits for the [`SourceIndex`] to be up to date, then either happens right away,
+/// or once confirmed in a [`DeleteConfirmationPanel`] if other files reference the deleted assets.
+#[derive(Resource, Debug)]
+pub(crate) struct PendingDeletion {
+    /// The paths to delete, relative to the source root
+    targets: Vec<PathBuf>,
+    /// The asset browser to show the confirmation panel over
+    asset_browser: Option<Entity>,
+    /// The confirmation panel, once it's shown
+    panel: Option<Entity>,
+}
+
+impl PendingDeletion {
+    /// Delete `targets`, asking for confirmation over `asset_browser` if needed
+    pub(crate) fn new(targets: Vec<PathBuf>, asset_browser: Option<Entity>) -> Self {
+        Self {
+            targets,
+            asset_browser,
+            panel: None,
+        }
+    }
+}
+
+/// The panel listing the files referencing the assets of the [`PendingDeletion`]
+#[derive(Component)]
+pub(crate) struct DeleteConfirmationPanel;
+
+/// The files referencing each of `targets`, for the targets referenced by files that aren't
+/// deleted along with them
+pub(crate) fn broken_references(
+    index: &SourceIndex,
+    targets: &[PathBuf],
+) -> Vec<(PathBuf, Vec<PathBuf>)> {
+    targets
+        .iter()
+        .filter_map(|target| {
+            let referencing_files = index
+                .referencing_files(&asset_path_string(target))
+                .into_iter()
+                .filter(|file| !targets.iter().any(|target| file.starts_with(target)))
+                .map(Path::to_path_buf)
+                .collect::<Vec<_>>();
+            (!referencing_files.is_empty()).then(|| (target.clone(), referencing_files))
+        })
+        .collect()
+}
+
+/// Trash the assets of the [`PendingDeletion`] once the [`SourceIndex`] is up to date, or show a
+/// [`DeleteConfirmationPanel`] if other files reference them
+pub(crate) fn confirm_pending_deletion(
+    mut commands: Commands,
+    mut pending: ResMut<PendingDeletion>,
+    index: Option<Res<SourceIndex>>,
+    panels: Query<(), With<DeleteConfirmationPanel>>,
+    theme: Res<Theme>,
+) {
+    if let Some(panel) = pending.panel {
+        // The panel was replaced by another panel
+        if !panels.contains(panel) {
+            commands.remove_resource::<PendingDeletion>();
+        }
+        return;
+    }
+    let Some(index) = index.filter(|index| index.source_id == AssetSourceId::Default) else {
+        return;
+    };
+    if index.stale {
+        return;
+    }
+    let broken_references = broken_references(&index, &pending.targets);
+    if broken_references.is_empty() {
+        commands.remove_resource::<PendingDeletion>();
+        commands.run_system_cached_with(trash_assets, pending.targets.clone());
+        return;
+    }
+    let Some(asset_browser) = pending.asset_browser else {
+        warn!("Not deleting referenced assets without an asset browser to confirm it");
+        commands.remove_resource::<PendingDeletion>();
+        return;
+    };
+
+    let panel = spawn_panel(&mut commands, &theme, asset_browser)
+        .insert(DeleteConfirmationPanel)
+        .id();
+    pending.panel = Some(panel);
+    spawn_panel_label(
+        &mut commands,
+        "Delete referenced assets?".to_string(),
+        theme.text.text_color,
+        &theme,
+    )
+    .insert(ChildOf(panel));
+    let list = commands
+        .spawn((
+            Node {
+                flex_direction: FlexDirection::Column,
+                row_gap: Val::Px(2.0),
+                overflow: Overflow::scroll_y(),
+                ..default()
+            },
+            ChildOf(panel),
+        ))
+        .id();
+    for (target, referencing_files) in broken_references {
+        spawn_panel_label(
+            &mut commands,
+            format!("{} is referenced by:", asset_path_string(&target)),
+            theme.text.text_color,
+            &theme,
+        )
+        .insert(ChildOf(list));
+        for file in referencing_files {
+            spawn_panel_label(
+                &mut commands,
+                asset_path_string(&file),
+                theme.text.low_priority,
+                &theme,
+            )
+            .insert((
+                Node {
+                    margin: UiRect::left(Val::Px(12.0)),
+                    ..default()
+                },
+                ChildOf(list),
+            ));
+        }
+    }
+
+    let buttons = commands
+        .spawn((
+            Node {
+                column_gap: Val::Px(4.0),
+                margin: UiRect::top(Val::Px(4.0)),
+                ..default()
+            },
+            ChildOf(panel),
+        ))
+        .id();
+    spawn_panel_button(&mut commands, "Delete", &theme)
+        .insert(ChildOf(buttons))
+        .observe(
+            move |trigger: Trigger<Pointer<Click>>,
+                  mut commands: Commands,
+                  pending: Option<Res<PendingDeletion>>| {
+                if trigger.event().button != PointerButton::Primary {
+                    return;
+                }
+                if let Some(pending) = pending {
+                    commands.run_system_cached_with(trash_assets, pending.targets.clone());
+                }
+                commands.remove_resource::<PendingDeletion>();
+                commands.entity(panel).despawn();
+            },
+        );
+    spawn_panel_button(&mut commands, "Cancel", &theme)
+        .insert(ChildOf(buttons))
+        .observe(
+            move |trigger: Trigger<Pointer<Click>>, mut commands: Commands| {
+                if trigger.event().button == PointerButton::Primary {
+                    commands.remove_resource::<PendingDeletion>();
+                    commands.entity(panel).despawn();
+                }
+            },
+        );
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn ignores_references_from_deleted_files() {
+        let index = SourceIndex {
+            source_id: AssetSourceId::Default,
+            files: Vec::new(),
+            references: [
+                (
+                    "models/alien.glb".to_string(),
+                    vec![
+                        PathBuf::from("scenes/level.bsn"),
+                        PathBuf::from("models/alien.bsn"),
+                    ],
+                ),
+                (
+                    "textures/wall.png".to_string(),
+                    vec![PathBuf::from("models/wall.bsn")],
+                ),
+            ]
+            .into_iter()
+            .collect(),
+            stale: false,
+        };
+        assert_eq!(
+            broken_references(&index, &[PathBuf::from("models")]),
+            [(
+                PathBuf::from("models"),
+                vec![PathBuf::from("scenes/level.bsn")]
+            )]
+        );
+        assert!(broken_references(
+            &index,
+            &[
+                PathBuf::from("textures/wall.png"),
+                PathBuf::from("models/wall.bsn")
+            ]
+        )
+        .is_empty());
+    }
+}
@@ -1,23 +1,50 @@
+use std::{
+    path::{Path, PathBuf},
+    time::SystemTime,
+};
+
 use bevy::{asset::io::AssetSourceId, prelude::*};
-use bevy_context_menu::{ContextMenu, ContextMenuOption};
+use bevy_asset_preview::AssetPreviews;
+use bevy_command_palette::command_menu_option;
+use bevy_context_menu::ContextMenu;
+use bevy_editor_core::AssetNode;
 use bevy_editor_styles::Theme;
 use bevy_scroll_box::{spawn_scroll_box, ScrollBox, ScrollBoxContent};
+use bevy_undo::NewChange;
+
+use crate::{
+    io::{self, import::apply_import_presets, index::SourceIndex},
+    trash::AssetTrash,
+    view::{compare_by, AssetBrowserView, AssetType, SearchQuery, SortBy, ViewLayout},
+    AssetBrowserLocation, AssetBrowserSelection, DefaultSourceFilePath, DirectoryContent, Entry,
+};
 
-use crate::{io, AssetBrowserLocation, DefaultSourceFilePath, DirectoryContent, Entry};
+use crate::ui::{
+    delete_confirmation::PendingDeletion,
+    file_operations::action_targets,
+    nodes::{spawn_column_headers, spawn_file_node, spawn_folder_node, spawn_source_node},
+    source_id_to_string, AssetBrowserNode,
+};
 
-use crate::ui::nodes::{spawn_file_node, spawn_folder_node, spawn_source_node};
+/// The maximum number of search results shown, the best matches are kept
+const MAX_SEARCH_RESULTS: usize = 200;
 
 /// Tag for all the asset browser scroll boxes
 #[derive(Component)]
 pub(crate) struct AssetBrowserContent;
 
 /// Spawn the directory content UI
+#[allow(clippy::too_many_arguments)]
 pub(crate) fn spawn_directory_content<'a>(
     commands: &'a mut Commands,
     directory_content: &Res<DirectoryContent>,
     theme: &Res<Theme>,
     asset_server: &Res<AssetServer>,
     location: &Res<AssetBrowserLocation>,
+    view: &Res<AssetBrowserView>,
+    index: Option<&SourceIndex>,
+    default_source_file_path: &Res<DefaultSourceFilePath>,
+    previews: &mut AssetPreviews,
 ) -> EntityCommands<'a> {
     let root = commands
         .spawn(Node {
@@ -40,7 +67,11 @@ pub(crate) fn spawn_directory_content<'a>(
                 directory_content,
                 asset_server,
                 location,
+                view,
+                index,
+                default_source_file_path,
                 theme,
+                previews,
             );
         }),
     )
@@ -64,27 +95,29 @@ pub(crate) fn refresh_context_menu(
 
 fn asset_browser_context_menu() -> ContextMenu {
     ContextMenu::new([
-        ContextMenuOption::new("Create Folder", |mut commands, _entity| {
-            commands.run_system_cached(create_new_folder);
-        }),
-        ContextMenuOption::new("Create New Script", |mut commands, _entity| {
-            commands.run_system_cached(create_new_script);
-        }),
-        ContextMenuOption::new("Open in File Manager", |mut commands, _entity| {
-            commands.run_system_cached(open_in_file_manager);
-        }),
+        command_menu_option("Create Folder", "asset_browser.create_folder"),
+        command_menu_option("Create New Script", "asset_browser.create_script"),
+        command_menu_option("Open in File Manager", "asset_browser.open_in_file_manager"),
+        command_menu_option("Open Trash", "asset_browser.open_trash"),
     ])
 }
 
 /// Refresh the UI with the content of the current [`AssetBrowserLocation`]
+/// The scroll position is kept when the content of the same location is refreshed
+#[allow(clippy::too_many_arguments)]
 pub(crate) fn refresh_ui(
     mut commands: Commands,
+    mut displayed_location: Local<Option<AssetBrowserLocation>>,
     content_list_query: Query<(Entity, Option<&Children>), With<ScrollBoxContent>>,
     theme: Res<Theme>,
     asset_server: Res<AssetServer>,
     location: Res<AssetBrowserLocation>,
     directory_content: Res<DirectoryContent>,
+    view: Res<AssetBrowserView>,
+    index: Option<Res<SourceIndex>>,
+    default_source_file_path: Res<DefaultSourceFilePath>,
     mut query_scrollbox: Query<&mut ScrollBox, With<AssetBrowserContent>>,
+    mut previews: AssetPreviews,
 ) {
     for (content_list_entity, content_list_children) in content_list_query.iter() {
         despawn_content_entries(&mut commands, content_list_entity, content_list_children);
@@ -94,9 +127,17 @@ pub(crate) fn refresh_ui(
             &directory_content,
             &asset_server,
             &location,
+            &view,
+            index.as_deref(),
+            &default_source_file_path,
             &theme,
+            &mut previews,
         );
     }
+    if displayed_location.as_ref() == Some(&*location) {
+        return;
+    }
+    *displayed_location = Some(location.clone());
     // Reset scroll boxes
     for mut scrollbox in query_scrollbox.iter_mut() {
         scrollbox.scroll_to_top();
@@ -113,32 +154,254 @@ fn despawn_content_entries(commands: &mut Commands, container: Entity, entries:
     }
 }
 
-/// Spawn all the content [entries](Entry) based on [`DirectoryContent`]
+/// Lay out the content list as a grid or a list, following the [`AssetBrowserView`]
+pub(crate) fn apply_content_layout(
+    mut query: Query<(&mut Node, Ref<AssetBrowserContent>)>,
+    view: Res<AssetBrowserView>,
+) {
+    for (mut node, content) in &mut query {
+        if !view.is_changed() && !content.is_added() {
+            continue;
+        }
+        let (flex_direction, flex_wrap) = match view.layout {
+            ViewLayout::Grid => (FlexDirection::Row, FlexWrap::Wrap),
+            ViewLayout::List => (FlexDirection::Column, FlexWrap::NoWrap),
+        };
+        node.flex_direction = flex_direction;
+        node.flex_wrap = flex_wrap;
+    }
+}
+
+/// An [`Entry`] as shown in the asset browser, with the details shown by the list view
+pub(crate) struct ShownEntry {
+    pub(crate) entry: Entry,
+    /// The path of the entry relative to the source root, empty for sources
+    pub(crate) path: PathBuf,
+    /// The size of files, only known for the default source
+    pub(crate) size: Option<u64>,
+    /// The last modification date, only known for the default source
+    pub(crate) modified: Option<SystemTime>,
+}
+
+impl ShownEntry {
+    fn new(entry: Entry, path: PathBuf, source_root: Option<&Path>) -> Self {
+        let metadata = source_root.and_then(|root| std::fs::metadata(root.join(&path)).ok());
+        Self {
+            entry,
+            size: metadata
+                .as_ref()
+                .filter(|metadata| metadata.is_file())
+                .map(std::fs::Metadata::len),
+            modified: metadata.and_then(|metadata| metadata.modified().ok()),
+            path,
+        }
+    }
+
+    fn name(&self) -> String {
+        match &self.entry {
+            Entry::Source(id) => source_id_to_string(id),
+            Entry::Folder(name) | Entry::File(name) => name.clone(),
+        }
+    }
+}
+
+/// The entries to show: the [`DirectoryContent`], or the files of the [`SourceIndex`] matching
+/// the search of the [`AssetBrowserView`], filtered and sorted
+///
+/// Returns `None` while the asset source is being indexed for the search
+fn shown_entries(
+    directory_content: &DirectoryContent,
+    location: &AssetBrowserLocation,
+    view: &AssetBrowserView,
+    index: Option<&SourceIndex>,
+    default_source_file_path: &DefaultSourceFilePath,
+) -> Option<Vec<ShownEntry>> {
+    let query = SearchQuery::parse(&view.search);
+    let searching = !query.is_empty() && location.source_id.is_some();
+    // Only the files of the default source can be read directly for their size and date
+    let source_root = (location.source_id == Some(AssetSourceId::Default))
+        .then_some(default_source_file_path.0.as_path());
+
+    let mut entries = if searching {
+        let index = index.filter(|index| location.source_id.as_ref() == Some(&index.source_id))?;
+        let result = |path: &Path, score| {
+            // The list view has room to show where the results are
+            let name = match view.layout {
+                ViewLayout::Grid => path.file_name()?.to_string_lossy().into_owned(),
+                ViewLayout::List => path.to_string_lossy().into_owned(),
+            };
+            Some((
+                ShownEntry::new(Entry::File(name), path.to_path_buf(), None),
+                score,
+            ))
+        };
+        match &query.references_of {
+            Some(referenced) => index
+                .referencing_files(referenced)
+                .into_iter()
+                .filter_map(|path| result(path, 0))
+                .collect::<Vec<_>>(),
+            None => index
+                .files
+                .iter()
+                .filter_map(|path| {
+                    let score = query.score(&path.file_name()?.to_string_lossy())?;
+                    result(path, score)
+                })
+                .collect::<Vec<_>>(),
+        }
+    } else {
+        directory_content
+            .0
+            .iter()
+            .map(|entry| {
+                let path = match entry {
+                    Entry::Source(_) => PathBuf::new(),
+                    Entry::Folder(name) | Entry::File(name) => location.path.join(name),
+                };
+                (ShownEntry::new(entry.clone(), path, source_root), 0)
+            })
+            .collect()
+    };
+
+    if let Some(type_filter) = view.type_filter {
+        entries.retain(|(shown, _)| {
+            !matches!(shown.entry, Entry::File(_)) || AssetType::of(&shown.path) == type_filter
+        });
+    }
+    if searching {
+        // Keep the best matches, and only read the details of the files that are shown
+        entries.sort_by(|(_, score), (_, other_score)| other_score.cmp(score));
+        entries.truncate(MAX_SEARCH_RESULTS);
+        for (shown, _) in &mut entries {
+            *shown = ShownEntry::new(
+                shown.entry.clone(),
+                std::mem::take(&mut shown.path),
+                source_root,
+            );
+        }
+    }
+
+    let kind_order = |entry: &Entry| match entry {
+        Entry::Source(_) => 0,
+        Entry::Folder(_) => 1,
+        Entry::File(_) => 2,
+    };
+    entries.sort_by(|(a, a_score), (b, b_score)| {
+        // Search results are sorted by relevance, unless another order was chosen
+        let ordering = if searching && view.sort == SortBy::Name {
+            b_score.cmp(a_score)
+        } else {
+            compare_by(
+                view.sort,
+                (&a.name(), a.modified, a.size),
+                (&b.name(), b.modified, b.size),
+            )
+        };
+        let ordering = if view.descending {
+            ordering.reverse()
+        } else {
+            ordering
+        };
+        kind_order(&a.entry)
+            .cmp(&kind_order(&b.entry))
+            .then(ordering)
+    });
+    Some(entries.into_iter().map(|(shown, _)| shown).collect())
+}
+
+/// Spawn all the content [entries](Entry) based on [`DirectoryContent`] and [`AssetBrowserView`]
+#[allow(clippy::too_many_arguments)]
 fn populate_directory_content(
     commands: &mut Commands,
     parent_entity: Entity,
     directory_content: &Res<DirectoryContent>,
     asset_server: &Res<AssetServer>,
     location: &Res<AssetBrowserLocation>,
+    view: &Res<AssetBrowserView>,
+    index: Option<&SourceIndex>,
+    default_source_file_path: &Res<DefaultSourceFilePath>,
     theme: &Res<Theme>,
+    previews: &mut AssetPreviews,
 ) {
-    for entry in &directory_content.0 {
-        match entry {
+    if view.layout == ViewLayout::List {
+        spawn_column_headers(commands, theme).insert(ChildOf(parent_entity));
+    }
+    let Some(entries) = shown_entries(
+        directory_content,
+        location,
+        view,
+        index,
+        default_source_file_path,
+    ) else {
+        spawn_message(commands, "Searching...", theme).insert(ChildOf(parent_entity));
+        return;
+    };
+    if entries.is_empty() && !view.search.trim().is_empty() {
+        let message = if SearchQuery::parse(&view.search).references_of.is_some() {
+            "No references"
+        } else {
+            "No results"
+        };
+        spawn_message(commands, message, theme).insert(ChildOf(parent_entity));
+    }
+
+    for shown_entry in &entries {
+        match &shown_entry.entry {
             Entry::Source(id) => {
-                spawn_source_node(commands, id, asset_server, theme).insert(ChildOf(parent_entity));
+                spawn_source_node(commands, id, asset_server, theme, view.layout)
+                    .insert(ChildOf(parent_entity));
             }
             Entry::Folder(name) => {
-                spawn_folder_node(commands, name.clone(), asset_server, location, theme)
-                    .insert(ChildOf(parent_entity));
+                spawn_folder_node(
+                    commands,
+                    name.clone(),
+                    shown_entry,
+                    asset_server,
+                    location,
+                    theme,
+                    view.layout,
+                )
+                .insert(ChildOf(parent_entity));
             }
             Entry::File(name) => {
-                spawn_file_node(commands, name.clone(), asset_server, location, theme)
-                    .insert(ChildOf(parent_entity));
+                spawn_file_node(
+                    commands,
+                    name.clone(),
+                    shown_entry,
+                    asset_server,
+                    location,
+                    theme,
+                    previews,
+                    view.layout,
+                )
+                .insert(ChildOf(parent_entity));
             }
         }
     }
 }
 
+/// Spawn a message shown in place of the entries
+fn spawn_message<'a>(
+    commands: &'a mut Commands,
+    message: &str,
+    theme: &Theme,
+) -> EntityCommands<'a> {
+    commands.spawn((
+        Text::new(message),
+        TextFont {
+            font: theme.text.font.clone(),
+            font_size: 10.0,
+            ..default()
+        },
+        TextColor(theme.text.text_color),
+        Node {
+            margin: UiRect::all(Val::Px(10.0)),
+            ..default()
+        },
+    ))
+}
+
 pub(crate) fn create_new_folder(
     mut commands: Commands,
     default_source_file_path: Res<DefaultSourceFilePath>,
@@ -160,14 +423,16 @@ pub(crate) fn create_new_folder(
     }
 }
 
+/// Move the folder of the node to the [`AssetTrash`], once the deletion is confirmed if
+/// files reference the assets in it
 pub(crate) fn delete_folder(
     folder_entity: In<Entity>,
     query_children: Query<&Children>,
     query_text: Query<&Text>,
+    query_parent: Query<&ChildOf>,
+    query_asset_browser: Query<(), With<AssetBrowserNode>>,
     mut commands: Commands,
-    default_source_file_path: Res<DefaultSourceFilePath>,
     location: Res<AssetBrowserLocation>,
-    directory_content: Res<DirectoryContent>,
 ) {
     if location.source_id != Some(AssetSourceId::Default) {
         panic!("Cannot delete folder: Invalid source id, make sure your inside the Default source");
@@ -178,20 +443,12 @@ pub(crate) fn delete_folder(
         .unwrap()
         .0
         .clone();
-    let mut path = default_source_file_path.0.clone();
-    path.push(location.path.as_path());
-    path.push(folder_name.clone());
-    match io::delete_folder(path) {
-        Ok(_) => {
-            let mut updated_content = directory_content.0.clone();
-            updated_content.retain(|entry| match entry {
-                Entry::Folder(name) => name != &folder_name,
-                _ => true,
-            });
-            commands.insert_resource(DirectoryContent(updated_content));
-        }
-        Err(e) => eprintln!("Failed to delete directory: {}", e),
-    }
+    commands.insert_resource(PendingDeletion::new(
+        vec![location.path.join(&folder_name)],
+        query_parent
+            .iter_ancestors(*folder_entity)
+            .find(|entity| query_asset_browser.contains(*entity)),
+    ));
 }
 
 pub(crate) fn create_new_script(
@@ -207,6 +464,10 @@ pub(crate) fn create_new_script(
     path.push(location.path.as_path());
     match io::create_new_script(path) {
         Ok(file_name) => {
+            commands.run_system_cached_with(
+                apply_import_presets,
+                (vec![location.path.join(&file_name)], false),
+            );
             let mut updated_content = directory_content.0.clone();
             updated_content.push(Entry::File(file_name));
             commands.insert_resource(DirectoryContent(updated_content));
@@ -257,36 +518,69 @@ pub(crate) fn reveal_in_file_manager(
     }
 }
 
+/// Move the file of the node to the [`AssetTrash`], or all the selected files if it's one of them,
+/// once the deletion is confirmed if other files reference them
 pub(crate) fn delete_file(
     file_entity: In<Entity>,
-    query_children: Query<&Children>,
-    query_text: Query<&Text>,
+    query_asset_node: Query<&AssetNode>,
+    query_parent: Query<&ChildOf>,
+    query_asset_browser: Query<(), With<AssetBrowserNode>>,
     mut commands: Commands,
-    default_source_file_path: Res<DefaultSourceFilePath>,
     location: Res<AssetBrowserLocation>,
-    directory_content: Res<DirectoryContent>,
+    selection: Res<AssetBrowserSelection>,
 ) {
     if location.source_id != Some(AssetSourceId::Default) {
         panic!("Cannot delete file: Invalid source id, make sure your inside the Default source");
     }
-    let file_children = query_children.get(*file_entity).unwrap();
-    let file_name = query_text
-        .get(*file_children.get(1).unwrap())
-        .unwrap()
-        .0
-        .clone();
-    let mut path = default_source_file_path.0.clone();
-    path.push(location.path.as_path());
-    path.push(file_name.clone());
-    match io::delete_file(path) {
-        Ok(_) => {
-            let mut updated_content = directory_content.0.clone();
-            updated_content.retain(|entry| match entry {
-                Entry::File(name) => name != &file_name,
-                _ => true,
-            });
-            commands.insert_resource(DirectoryContent(updated_content));
-        }
-        Err(e) => eprintln!("Failed to delete file: {}", e),
+    let file_node = query_asset_node.get(*file_entity).unwrap();
+    let targets = action_targets(&file_node.0, &selection)
+        .into_iter()
+        .filter(|asset_path| asset_path.source() == &AssetSourceId::Default)
+        .map(|asset_path| asset_path.path().to_path_buf())
+        .collect::<Vec<_>>();
+    commands.insert_resource(PendingDeletion::new(
+        targets,
+        query_parent
+            .iter_ancestors(*file_entity)
+            .find(|entity| query_asset_browser.contains(*entity)),
+    ));
+}
+
+/// Move the files and folders at `paths`, relative to the source root, to the [`AssetTrash`],
+/// and remove them from the [`DirectoryContent`]
+#[allow(clippy::too_many_arguments)]
+pub(crate) fn trash_assets(
+    paths: In<Vec<PathBuf>>,
+    mut commands: Commands,
+    default_source_file_path: Res<DefaultSourceFilePath>,
+    location: Res<AssetBrowserLocation>,
+    directory_content: Res<DirectoryContent>,
+    index: Option<ResMut<SourceIndex>>,
+    mut trash: ResMut<AssetTrash>,
+    mut new_changes: EventWriter<NewChange>,
+) {
+    let deleted_names = trash
+        .trash(&default_source_file_path.0, paths.0, &mut new_changes)
+        .into_iter()
+        // The file may be a search result from another directory
+        .filter(|asset_path| asset_path.parent() == Some(location.path.as_path()))
+        .map(|asset_path| {
+            asset_path
+                .file_name()
+                .unwrap()
+                .to_string_lossy()
+                .to_string()
+        })
+        .collect::<Vec<_>>();
+    if let Some(mut index) = index {
+        index.stale = true;
+    }
+    let mut updated_content = directory_content.0.clone();
+    updated_content.retain(|entry| match entry {
+        Entry::File(name) | Entry::Folder(name) => !deleted_names.contains(name),
+        Entry::Source(_) => true,
+    });
+    if updated_content != directory_content.0 {
+        commands.insert_resource(DirectoryContent(updated_content));
     }
 }
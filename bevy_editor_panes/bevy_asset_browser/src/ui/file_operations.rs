@@ -0,0 +1,320 @@
+//! Renaming, moving and duplicating the files of the asset browser, and selecting several of them
+
+use std::path::{Path, PathBuf};
+
+use bevy::{
+    asset::{io::AssetSourceId, AssetPath},
+    prelude::*,
+};
+use bevy_editor_core::{AssetNode, SelectedAsset};
+use bevy_editor_styles::Theme;
+use bevy_focus::{Focus, LostFocus, SetFocus};
+use bevy_text_editing::{CharPosition, EditableTextLine};
+
+use crate::{
+    io::{
+        self,
+        import::apply_import_presets,
+        index::SourceIndex,
+        references::{asset_path_string, moved_reference},
+    },
+    AssetBrowserLocation, AssetBrowserSelection, DefaultSourceFilePath,
+};
+
+use super::top_bar::PATH_SEGMENT_BACKGROUND_COLOR;
+
+/// The folder a folder node or a location path segment stands for, relative to the source root
+/// Files dropped onto the node are moved into this folder
+#[derive(Component, Clone, Debug)]
+pub(crate) struct FolderPath(pub(crate) PathBuf);
+
+/// The field replacing the name of a file or folder node while it's being renamed
+#[derive(Component)]
+pub(crate) struct RenameField {
+    /// The file or folder node being renamed
+    node: Entity,
+    /// The text showing the name of the node, hidden while renaming
+    name_text: Entity,
+}
+
+/// The files an action on the file at `path` applies to: all the selected files if it's selected,
+/// or only this file
+pub(crate) fn action_targets(
+    path: &AssetPath<'static>,
+    selection: &AssetBrowserSelection,
+) -> Vec<AssetPath<'static>> {
+    if selection.0.contains(path) {
+        selection.0.clone()
+    } else {
+        vec![path.clone()]
+    }
+}
+
+/// Keep the [`AssetBrowserSelection`] in sync with the [`SelectedAsset`] when it's changed
+/// elsewhere, for example when an entity gets selected
+pub(crate) fn sync_selection(
+    selected_asset: Res<SelectedAsset>,
+    mut selection: ResMut<AssetBrowserSelection>,
+) {
+    match &selected_asset.0 {
+        None if !selection.0.is_empty() => selection.0.clear(),
+        Some(path) if !selection.0.contains(path) => selection.0 = vec![path.clone()],
+        _ => {}
+    }
+}
+
+/// Move files and folders of the default source, given as paths relative to the source root
+/// Their `.meta` files are moved along, and the references to them in `.bsn` files are updated
+pub(crate) fn move_assets(
+    moves: In<Vec<(PathBuf, PathBuf)>>,
+    mut commands: Commands,
+    default_source_file_path: Res<DefaultSourceFilePath>,
+    mut selection: ResMut<AssetBrowserSelection>,
+    mut selected_asset: ResMut<SelectedAsset>,
+    index: Option<ResMut<SourceIndex>>,
+) {
+    let source_root = &default_source_file_path.0;
+    let mut moved_selection = selection.0.clone();
+    let mut moved_selected_asset = selected_asset.0.clone();
+    let mut moved_files = Vec::new();
+    for (from, to) in moves.0 {
+        if from == to {
+            continue;
+        }
+        if let Err(e) = io::move_asset(source_root.join(&from), source_root.join(&to)) {
+            eprintln!("Failed to move {}: {}", from.display(), e);
+            continue;
+        }
+        match io::asset_files(source_root, &to) {
+            Ok(files) => moved_files.extend(files),
+            Err(e) => eprintln!("Failed to list the files of {}: {}", to.display(), e),
+        }
+        let (old, new) = (asset_path_string(&from), asset_path_string(&to));
+        match io::rewrite_bsn_references(source_root, &old, &new) {
+            Ok(rewritten_files) => {
+                for file in rewritten_files {
+                    info!("Updated the references to {} in {}", old, file.display());
+                }
+            }
+            Err(e) => eprintln!("Failed to update the references to {}: {}", old, e),
+        }
+        // Keep the moved files selected
+        for path in moved_selection
+            .iter_mut()
+            .chain(moved_selected_asset.as_mut())
+        {
+            if path.source() != &AssetSourceId::Default {
+                continue;
+            }
+            if let Some(moved) = moved_reference(&asset_path_string(path.path()), &old, &new) {
+                *path = AssetPath::from_path(Path::new(&moved)).into_owned();
+            }
+        }
+    }
+    selection.set_if_neq(AssetBrowserSelection(moved_selection));
+    if selected_asset.0 != moved_selected_asset {
+        selected_asset.0 = moved_selected_asset;
+    }
+    if let Some(mut index) = index {
+        index.stale = true;
+    }
+    // The files keep their `.meta` files, only the files without one get the presets of their new
+    // folder
+    commands.run_system_cached_with(apply_import_presets, (moved_files, false));
+    commands.run_system_cached(io::task::fetch_directory_content);
+}
+
+/// Move the files dropped onto a node with a [`FolderPath`] into its folder
+/// All the selected files are moved if the dropped file is one of them
+pub(crate) fn drop_files_on_folder(
+    trigger: Trigger<Pointer<DragDrop>>,
+    mut commands: Commands,
+    query_asset_node: Query<&AssetNode>,
+    query_folder_path: Query<&FolderPath>,
+    location: Res<AssetBrowserLocation>,
+    selection: Res<AssetBrowserSelection>,
+) {
+    if location.source_id != Some(AssetSourceId::Default) {
+        return;
+    }
+    let (Ok(dropped), Ok(folder)) = (
+        query_asset_node.get(trigger.dropped),
+        query_folder_path.get(trigger.target()),
+    ) else {
+        return;
+    };
+    let moves = action_targets(&dropped.0, &selection)
+        .into_iter()
+        .filter(|path| path.source() == &AssetSourceId::Default)
+        .filter_map(|path| {
+            let from = path.path().to_path_buf();
+            let to = folder.0.join(from.file_name()?);
+            (from != to).then_some((from, to))
+        })
+        .collect::<Vec<_>>();
+    if !moves.is_empty() {
+        commands.run_system_cached_with(move_assets, moves);
+    }
+}
+
+/// Duplicate the file of the node, or all the selected files if it's one of them
+pub(crate) fn duplicate_files(
+    file_entity: In<Entity>,
+    mut commands: Commands,
+    query_asset_node: Query<&AssetNode>,
+    default_source_file_path: Res<DefaultSourceFilePath>,
+    selection: Res<AssetBrowserSelection>,
+    index: Option<ResMut<SourceIndex>>,
+) {
+    let Ok(file_node) = query_asset_node.get(*file_entity) else {
+        return;
+    };
+    let mut copies = Vec::new();
+    for path in action_targets(&file_node.0, &selection) {
+        if path.source() != &AssetSourceId::Default {
+            continue;
+        }
+        match io::duplicate_file(default_source_file_path.0.join(path.path())) {
+            Ok(copy_name) => copies.push(path.path().with_file_name(copy_name)),
+            Err(e) => eprintln!("Failed to duplicate {}: {}", path, e),
+        }
+    }
+    if let Some(mut index) = index {
+        index.stale = true;
+    }
+    // Copies of files without a `.meta` file get the presets of their folder
+    commands.run_system_cached_with(apply_import_presets, (copies, false));
+    commands.run_system_cached(io::task::fetch_directory_content);
+}
+
+/// Replace the name of a file or folder node by a text field to rename it
+/// The new name is applied with `Enter` or when the field loses focus, and `Escape` cancels
+pub(crate) fn start_rename(
+    node: In<Entity>,
+    mut commands: Commands,
+    query_children: Query<&Children>,
+    mut query_name: Query<(&Text, &mut Node)>,
+    query_rename_field: Query<(), With<RenameField>>,
+    theme: Res<Theme>,
+) {
+    let Ok(children) = query_children.get(*node) else {
+        return;
+    };
+    if children
+        .iter()
+        .any(|child| query_rename_field.contains(child))
+    {
+        return;
+    }
+    // Child 1 of file and folder nodes is their name
+    let name_text = children[1];
+    let Ok((name, mut name_node)) = query_name.get_mut(name_text) else {
+        return;
+    };
+    name_node.display = Display::None;
+
+    // Select the name without the extension, so it's replaced when typing
+    let name = name.0.clone();
+    let stem_length = match name.rfind('.') {
+        Some(dot) if dot > 0 => name[..dot].chars().count(),
+        _ => name.chars().count(),
+    };
+    let rename_field = commands
+        .spawn((
+            RenameField {
+                node: *node,
+                name_text,
+            },
+            EditableTextLine {
+                text: name,
+                cursor_position: Some(CharPosition(stem_length)),
+                selection_start: Some(CharPosition(0)),
+                ..default()
+            },
+            TextFont {
+                font: theme.text.font.clone(),
+                font_size: 10.0,
+                ..default()
+            },
+            Node {
+                width: Val::Percent(100.0),
+                height: Val::Px(14.0),
+                flex_grow: 1.0,
+                ..default()
+            },
+            BackgroundColor(PATH_SEGMENT_BACKGROUND_COLOR),
+        ))
+        .observe(
+            |trigger: Trigger<LostFocus>,
+             mut commands: Commands,
+             query: Query<(&RenameField, &EditableTextLine)>| {
+                if let Ok((field, line)) = query.get(trigger.target()) {
+                    end_rename(&mut commands, trigger.target(), field, Some(&line.text));
+                }
+            },
+        )
+        .id();
+    commands.entity(*node).insert_children(2, &[rename_field]);
+    commands.trigger_targets(SetFocus, rename_field);
+}
+
+/// Apply the new name of the focused [`RenameField`] with `Enter`, or cancel with `Escape`
+pub(crate) fn confirm_rename(
+    mut commands: Commands,
+    query: Query<(Entity, &RenameField, &EditableTextLine), With<Focus>>,
+    keyboard_input: Res<ButtonInput<KeyCode>>,
+) {
+    for (entity, field, line) in &query {
+        if keyboard_input.just_pressed(KeyCode::Escape) {
+            end_rename(&mut commands, entity, field, None);
+        } else if keyboard_input.any_just_pressed([KeyCode::Enter, KeyCode::NumpadEnter]) {
+            end_rename(&mut commands, entity, field, Some(&line.text));
+        }
+    }
+}
+
+/// Remove the [`RenameField`] and show the name again, renaming the node to `new_name` if given
+fn end_rename(
+    commands: &mut Commands,
+    rename_field: Entity,
+    field: &RenameField,
+    new_name: Option<&str>,
+) {
+    commands.entity(rename_field).despawn();
+    let (node, name_text) = (field.node, field.name_text);
+    commands.queue(move |world: &mut World| {
+        if let Some(mut name_node) = world.get_mut::<Node>(name_text) {
+            name_node.display = Display::Flex;
+        }
+    });
+    if let Some(new_name) = new_name {
+        commands.run_system_cached_with(rename_asset, (node, new_name.trim().to_string()));
+    }
+}
+
+/// Rename the file or folder of a node
+fn rename_asset(
+    In((node, new_name)): In<(Entity, String)>,
+    mut commands: Commands,
+    query_asset_node: Query<&AssetNode>,
+    query_folder_path: Query<&FolderPath>,
+) {
+    let from = if let Ok(file_node) = query_asset_node.get(node) {
+        if file_node.0.source() != &AssetSourceId::Default {
+            return;
+        }
+        file_node.0.path().to_path_buf()
+    } else if let Ok(folder) = query_folder_path.get(node) {
+        folder.0.clone()
+    } else {
+        return;
+    };
+    if new_name.is_empty() || new_name.contains(['/', '\\']) {
+        warn!("Invalid name: {:?}", new_name);
+        return;
+    }
+    let to = from.with_file_name(new_name);
+    if to != from {
+        commands.run_system_cached_with(move_assets, vec![(from, to)]);
+    }
+}
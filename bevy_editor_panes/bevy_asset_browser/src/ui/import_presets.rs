@@ -0,0 +1,252 @@
+//! Panel editing the [`ImportPresets`] of a folder, opened from the folder context menu
+
+use std::path::Path;
+
+use bevy::prelude::*;
+use bevy_editor_settings::{SaveSettings, SettingsType};
+use bevy_editor_styles::Theme;
+use bevy_field_forms::{
+    input_field::{InputField, ValueChanged},
+    validate_highlight::SimpleBorderHighlight,
+};
+
+use crate::{
+    import_presets::{ImportPreset, ImportPresets},
+    io::{self, import::apply_import_presets, references::asset_path_string},
+    DefaultSourceFilePath,
+};
+
+use super::{
+    file_operations::FolderPath, spawn_panel, spawn_panel_button, spawn_panel_label,
+    top_bar::PATH_SEGMENT_BACKGROUND_COLOR, AssetBrowserNode,
+};
+
+/// The panel editing the [`ImportPresets`] of a folder
+#[derive(Component)]
+pub(crate) struct ImportPresetsEditor {
+    /// The folder whose presets are edited, relative to the source root with `/` separators
+    folder: String,
+}
+
+/// A field of the [`ImportPresetsEditor`] editing a column of a preset
+#[derive(Component, Clone, Copy)]
+struct PresetField {
+    /// The index of the preset in [`ImportPresets::presets`]
+    index: usize,
+    /// The edited column
+    column: PresetColumn,
+}
+
+#[derive(Clone, Copy)]
+enum PresetColumn {
+    Extension,
+    Setting,
+    Value,
+}
+
+/// Open the [`ImportPresetsEditor`] for the folder of a node with a [`FolderPath`]
+pub(crate) fn open_import_presets_editor(
+    folder_entity: In<Entity>,
+    mut commands: Commands,
+    query_folder_path: Query<&FolderPath>,
+    query_parent: Query<&ChildOf>,
+    query_asset_browser: Query<(), With<AssetBrowserNode>>,
+    theme: Res<Theme>,
+) {
+    let Ok(folder_path) = query_folder_path.get(*folder_entity) else {
+        return;
+    };
+    let Some(asset_browser) = query_parent
+        .iter_ancestors(*folder_entity)
+        .find(|entity| query_asset_browser.contains(*entity))
+    else {
+        return;
+    };
+    spawn_panel(&mut commands, &theme, asset_browser).insert(ImportPresetsEditor {
+        folder: asset_path_string(&folder_path.0),
+    });
+    commands.run_system_cached(refresh_import_presets_editor);
+}
+
+/// Rebuild the content of the [`ImportPresetsEditor`], when presets are added or removed
+fn refresh_import_presets_editor(
+    mut commands: Commands,
+    editors: Query<(Entity, &ImportPresetsEditor, Option<&Children>)>,
+    presets: Res<ImportPresets>,
+    theme: Res<Theme>,
+) {
+    for (editor, presets_editor, children) in &editors {
+        if let Some(children) = children {
+            for child in children.iter() {
+                commands.entity(child).despawn();
+            }
+        }
+        let title = match presets_editor.folder.as_str() {
+            "" => "Import presets of all assets".to_string(),
+            folder => format!("Import presets of {folder}/"),
+        };
+        spawn_panel_label(&mut commands, title, theme.text.text_color, &theme)
+            .insert(ChildOf(editor));
+        spawn_panel_label(
+            &mut commands,
+            "Extension (empty for all files), .meta setting and value, like asset.settings.is_srgb"
+                .to_string(),
+            theme.text.low_priority,
+            &theme,
+        )
+        .insert(ChildOf(editor));
+
+        for (index, preset) in presets.presets.iter().enumerate() {
+            if preset.folder() != presets_editor.folder {
+                continue;
+            }
+            let row = commands
+                .spawn((
+                    Node {
+                        column_gap: Val::Px(4.0),
+                        align_items: AlignItems::Center,
+                        ..default()
+                    },
+                    ChildOf(editor),
+                ))
+                .id();
+            for (column, text, width) in [
+                (PresetColumn::Extension, &preset.extension, Val::Px(50.0)),
+                (PresetColumn::Setting, &preset.setting, Val::Px(170.0)),
+                (PresetColumn::Value, &preset.value, Val::Auto),
+            ] {
+                spawn_preset_field(&mut commands, PresetField { index, column }, text, width)
+                    .insert(ChildOf(row));
+            }
+            spawn_panel_button(&mut commands, "Remove", &theme)
+                .insert(ChildOf(row))
+                .observe(
+                    move |trigger: Trigger<Pointer<Click>>,
+                          mut commands: Commands,
+                          mut presets: ResMut<ImportPresets>| {
+                        if trigger.event().button != PointerButton::Primary {
+                            return;
+                        }
+                        if index < presets.presets.len() {
+                            presets.presets.remove(index);
+                        }
+                        commands.run_system_cached(refresh_import_presets_editor);
+                    },
+                );
+        }
+
+        let buttons = commands
+            .spawn((
+                Node {
+                    column_gap: Val::Px(4.0),
+                    margin: UiRect::top(Val::Px(4.0)),
+                    ..default()
+                },
+                ChildOf(editor),
+            ))
+            .id();
+        spawn_panel_button(&mut commands, "Add preset", &theme)
+            .insert(ChildOf(buttons))
+            .observe(
+                move |trigger: Trigger<Pointer<Click>>,
+                      mut commands: Commands,
+                      editors: Query<&ImportPresetsEditor>,
+                      mut presets: ResMut<ImportPresets>| {
+                    if trigger.event().button != PointerButton::Primary {
+                        return;
+                    }
+                    let Ok(presets_editor) = editors.get(editor) else {
+                        return;
+                    };
+                    presets.presets.push(ImportPreset {
+                        folder: presets_editor.folder.clone(),
+                        ..default()
+                    });
+                    commands.run_system_cached(refresh_import_presets_editor);
+                },
+            );
+        spawn_panel_button(&mut commands, "Apply to existing files", &theme)
+            .insert(ChildOf(buttons))
+            .observe(
+                move |trigger: Trigger<Pointer<Click>>,
+                      mut commands: Commands,
+                      editors: Query<&ImportPresetsEditor>,
+                      default_source_file_path: Res<DefaultSourceFilePath>,
+                      mut save_settings: EventWriter<SaveSettings>| {
+                    if trigger.event().button != PointerButton::Primary {
+                        return;
+                    }
+                    let Ok(presets_editor) = editors.get(editor) else {
+                        return;
+                    };
+                    save_settings.write(SaveSettings(SettingsType::Project));
+                    let folder = Path::new(&presets_editor.folder);
+                    match io::asset_files(&default_source_file_path.0, folder) {
+                        Ok(paths) => {
+                            commands.run_system_cached_with(apply_import_presets, (paths, true));
+                        }
+                        Err(e) => {
+                            eprintln!(
+                                "Failed to list the files of {}: {}",
+                                presets_editor.folder, e
+                            );
+                        }
+                    }
+                },
+            );
+        spawn_panel_button(&mut commands, "Close", &theme)
+            .insert(ChildOf(buttons))
+            .observe(
+                move |trigger: Trigger<Pointer<Click>>,
+                      mut commands: Commands,
+                      mut save_settings: EventWriter<SaveSettings>| {
+                    if trigger.event().button != PointerButton::Primary {
+                        return;
+                    }
+                    save_settings.write(SaveSettings(SettingsType::Project));
+                    commands.entity(editor).despawn();
+                },
+            );
+    }
+}
+
+/// Spawn a text field editing a column of a preset
+fn spawn_preset_field<'a>(
+    commands: &'a mut Commands,
+    field: PresetField,
+    text: &str,
+    width: Val,
+) -> EntityCommands<'a> {
+    let mut field_ec = commands.spawn((
+        Node {
+            width,
+            flex_grow: if width == Val::Auto { 1.0 } else { 0.0 },
+            height: Val::Px(20.0),
+            border: UiRect::all(Val::Px(1.0)),
+            ..default()
+        },
+        BackgroundColor(PATH_SEGMENT_BACKGROUND_COLOR),
+        InputField::new(text.to_string()),
+        SimpleBorderHighlight::default(),
+        field,
+    ));
+    field_ec.observe(
+        |trigger: Trigger<ValueChanged<String>>,
+         query: Query<&PresetField>,
+         mut presets: ResMut<ImportPresets>| {
+            let Ok(field) = query.get(trigger.target()) else {
+                return;
+            };
+            let Some(preset) = presets.presets.get_mut(field.index) else {
+                return;
+            };
+            let value = trigger.event().0.clone();
+            match field.column {
+                PresetColumn::Extension => preset.extension = value,
+                PresetColumn::Setting => preset.setting = value,
+                PresetColumn::Value => preset.value = value,
+            }
+        },
+    );
+    field_ec
+}
@@ -1,14 +1,23 @@
 //! Module for all the UI components of the Asset Browser
 
 use bevy::prelude::*;
+use bevy_asset_preview::AssetPreviews;
 use bevy_editor_styles::Theme;
 use bevy_pane_layout::prelude::*;
 
-use crate::{AssetBrowserLocation, DirectoryContent};
+use crate::{
+    io::index::SourceIndex, view::AssetBrowserView, AssetBrowserLocation, DefaultSourceFilePath,
+    DirectoryContent,
+};
 
+pub(crate) mod commands;
+pub(crate) mod delete_confirmation;
 pub mod directory_content;
-mod nodes;
+pub(crate) mod file_operations;
+pub(crate) mod import_presets;
+pub(crate) mod nodes;
 pub mod top_bar;
+pub(crate) mod trash;
 
 /// The root node for the asset browser.
 #[derive(Component)]
@@ -23,6 +32,10 @@ pub fn on_pane_creation(
     location: Res<AssetBrowserLocation>,
     asset_server: Res<AssetServer>,
     directory_content: Res<DirectoryContent>,
+    view: Res<AssetBrowserView>,
+    index: Option<Res<SourceIndex>>,
+    default_source_file_path: Res<DefaultSourceFilePath>,
+    mut previews: AssetPreviews,
 ) {
     let asset_browser = commands
         .entity(structure.content)
@@ -35,13 +48,17 @@ pub fn on_pane_creation(
         })
         .id();
 
-    top_bar::spawn_top_bar(&mut commands, &theme, &location).insert(ChildOf(asset_browser));
+    top_bar::spawn_top_bar(&mut commands, &theme, &location, &view).insert(ChildOf(asset_browser));
     directory_content::spawn_directory_content(
         &mut commands,
         &directory_content,
         &theme,
         &asset_server,
         &location,
+        &view,
+        index.as_deref(),
+        &default_source_file_path,
+        &mut previews,
     )
     .insert(ChildOf(asset_browser));
 
@@ -56,3 +73,91 @@ pub(crate) fn source_id_to_string(source_id: &crate::AssetSourceId) -> String {
         crate::AssetSourceId::Name(name) => name.to_string(),
     }
 }
+
+/// Spawn a floating panel over the asset browser, like the trash or the import presets editor
+/// Other panels are closed, as they all take the same place.
+pub(crate) fn spawn_panel<'a>(
+    commands: &'a mut Commands,
+    theme: &Theme,
+    asset_browser: Entity,
+) -> EntityCommands<'a> {
+    commands.queue(move |world: &mut World| {
+        let panels = world
+            .query_filtered::<(Entity, &ChildOf), With<AssetBrowserPanel>>()
+            .iter(world)
+            .filter(|(_, child_of)| child_of.parent() == asset_browser)
+            .map(|(panel, _)| panel)
+            .collect::<Vec<_>>();
+        for panel in panels {
+            world.despawn(panel);
+        }
+    });
+    commands.spawn((
+        AssetBrowserPanel,
+        Node {
+            position_type: PositionType::Absolute,
+            top: Val::Px(40.0),
+            right: Val::Px(10.0),
+            width: Val::Px(480.0),
+            max_height: Val::Percent(80.0),
+            flex_direction: FlexDirection::Column,
+            row_gap: Val::Px(4.0),
+            padding: UiRect::all(Val::Px(8.0)),
+            overflow: Overflow::clip_y(),
+            ..default()
+        },
+        theme.pane.area_background_color,
+        theme.general.border_radius,
+        ZIndex(10),
+        ChildOf(asset_browser),
+    ))
+}
+
+/// Tag for the floating panels spawned by [`spawn_panel`]
+#[derive(Component)]
+pub(crate) struct AssetBrowserPanel;
+
+/// Spawn a line of text for a panel
+pub(crate) fn spawn_panel_label<'a>(
+    commands: &'a mut Commands,
+    text: String,
+    color: Color,
+    theme: &Theme,
+) -> EntityCommands<'a> {
+    commands.spawn((
+        Text(text),
+        TextFont {
+            font: theme.text.font.clone(),
+            font_size: 10.0,
+            ..default()
+        },
+        TextColor(color),
+    ))
+}
+
+/// Spawn a button for a panel
+pub(crate) fn spawn_panel_button<'a>(
+    commands: &'a mut Commands,
+    label: &str,
+    theme: &Theme,
+) -> EntityCommands<'a> {
+    let mut button_ec = commands.spawn((
+        Button,
+        Node {
+            padding: UiRect::axes(Val::Px(10.0), Val::Px(2.0)),
+            ..default()
+        },
+        BackgroundColor(top_bar::PATH_SEGMENT_BACKGROUND_COLOR),
+        theme.general.border_radius,
+    ));
+    button_ec.with_child((
+        Text(label.to_string()),
+        TextFont {
+            font: theme.text.font.clone(),
+            font_size: 10.0,
+            ..default()
+        },
+        TextColor(theme.text.text_color),
+    ));
+    button_ec
+}
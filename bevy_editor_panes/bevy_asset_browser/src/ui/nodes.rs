@@ -2,28 +2,50 @@
 
 use atomicow::CowArc;
 use bevy::{
-    asset::io::{AssetSource, AssetSourceBuilders, AssetSourceId},
+    asset::{
+        io::{AssetSource, AssetSourceBuilders, AssetSourceId},
+        AssetPath,
+    },
     prelude::*,
     window::SystemCursorIcon,
     winit::cursor::CursorIcon,
 };
-use bevy_context_menu::{ContextMenu, ContextMenuOption};
+use bevy_asset_preview::AssetPreviews;
+use bevy_command_palette::command_menu_option;
+use bevy_context_menu::ContextMenu;
+use bevy_editor_core::{
+    format::format_file_size, AssetNode, OpenAsset, SelectedAsset, SelectedEntity,
+};
 use bevy_editor_styles::Theme;
 
-use crate::{io, ui::source_id_to_string, AssetBrowserLocation};
+use crate::{
+    io::{self, references::asset_path_string},
+    ui::source_id_to_string,
+    view::{format_modified, AssetBrowserView, AssetType, SortBy, ViewLayout, REFERENCES_PREFIX},
+    AssetBrowserLocation, AssetBrowserSelection, Entry,
+};
 
 use super::{
-    directory_content::{delete_file, delete_folder},
+    directory_content::ShownEntry,
+    file_operations::{drop_files_on_folder, FolderPath, RenameField},
     DEFAULT_SOURCE_ID_NAME,
 };
 
+/// Width of the type column of the list view
+const TYPE_COLUMN_WIDTH: f32 = 60.0;
+/// Width of the size column of the list view
+const SIZE_COLUMN_WIDTH: f32 = 70.0;
+/// Width of the modification date column of the list view
+const MODIFIED_COLUMN_WIDTH: f32 = 110.0;
+
 pub(crate) fn spawn_source_node<'a>(
     commands: &'a mut Commands,
     source_id: &AssetSourceId,
     asset_server: &Res<AssetServer>,
     theme: &Res<Theme>,
+    layout: ViewLayout,
 ) -> EntityCommands<'a> {
-    let base_node = spawn_base_node(commands, theme)
+    let base_node = spawn_base_node(commands, theme, layout)
         .observe(
             move |trigger: Trigger<Pointer<Released>>,
                   mut commands: Commands,
@@ -60,23 +82,17 @@ pub(crate) fn spawn_source_node<'a>(
     // Icon
     commands.spawn((
         ImageNode::new(asset_server.load("embedded://bevy_asset_browser/assets/source_icon.png")),
-        Node {
-            height: Val::Px(50.0),
-            ..default()
-        },
+        icon_node(layout),
         ChildOf(base_node),
     ));
     // Source Name
-    commands.spawn((
-        Text::new(source_id_to_string(source_id)),
-        TextFont {
-            font: theme.text.font.clone(),
-            font_size: 10.0,
-            ..default()
-        },
-        TextColor(theme.text.text_color),
-        ChildOf(base_node),
-    ));
+    spawn_name(
+        commands,
+        base_node,
+        source_id_to_string(source_id),
+        theme,
+        layout,
+    );
 
     commands.entity(base_node)
 }
@@ -84,39 +100,44 @@ pub(crate) fn spawn_source_node<'a>(
 pub(crate) fn spawn_folder_node<'a>(
     commands: &'a mut Commands,
     folder_name: String,
+    shown_entry: &ShownEntry,
     asset_server: &Res<AssetServer>,
     location: &Res<AssetBrowserLocation>,
     theme: &Res<Theme>,
+    layout: ViewLayout,
 ) -> EntityCommands<'a> {
     let base_node = {
-        let mut ec = spawn_base_node(commands, theme);
-        ec.observe(
-            |trigger: Trigger<Pointer<Released>>,
-             mut commands: Commands,
-             mut location: ResMut<AssetBrowserLocation>,
-             query_text: Query<&Text>,
-             query_children: Query<&Children>| {
-                if trigger.event().button != PointerButton::Primary {
-                    return;
-                }
-                let button = trigger.target();
-                let button_children = query_children.get(button).unwrap();
-                let folder_name = &query_text
-                    .get(button_children[1])
-                    .expect("Child 1 of folder node to have a text component")
-                    .0;
-                location.path.push(folder_name.clone());
-                commands.run_system_cached(io::task::fetch_directory_content);
-            },
-        );
+        let mut ec = spawn_base_node(commands, theme, layout);
+        ec.insert(FolderPath(shown_entry.path.clone()))
+            .observe(drop_files_on_folder)
+            .observe(
+                // Not on release, so dropping files onto the folder doesn't open it
+                |trigger: Trigger<Pointer<Click>>,
+                 mut commands: Commands,
+                 mut location: ResMut<AssetBrowserLocation>,
+                 query_text: Query<&Text>,
+                 query_children: Query<&Children>,
+                 query_rename_field: Query<(), With<RenameField>>| {
+                    if trigger.event().button != PointerButton::Primary
+                        || !query_rename_field.is_empty()
+                    {
+                        return;
+                    }
+                    let button = trigger.target();
+                    let button_children = query_children.get(button).unwrap();
+                    let folder_name = &query_text
+                        .get(button_children[1])
+                        .expect("Child 1 of folder node to have a text component")
+                        .0;
+                    location.path.push(folder_name.clone());
+                    commands.run_system_cached(io::task::fetch_directory_content);
+                },
+            );
         if location.source_id == Some(AssetSourceId::Default) {
             ec.insert(ContextMenu::new([
-                // ContextMenuOption::new("Rename", |mut commands, entity| {
-                //     commands.run_system_cached_with(rename_asset, entity);
-                // }),
-                ContextMenuOption::new("Delete", |mut commands, entity| {
-                    commands.run_system_cached_with(delete_folder, entity);
-                }),
+                command_menu_option("Rename", "asset_browser.rename"),
+                command_menu_option("Import presets", "asset_browser.import_presets"),
+                command_menu_option("Delete", "asset_browser.delete"),
             ]));
         }
         ec.id()
@@ -127,44 +148,49 @@ pub(crate) fn spawn_folder_node<'a>(
         ImageNode::new(
             asset_server.load("embedded://bevy_asset_browser/assets/directory_icon.png"),
         ),
-        Node {
-            height: Val::Px(50.0),
-            ..default()
-        },
+        icon_node(layout),
         ChildOf(base_node),
     ));
     // Folder Name
-    commands.spawn((
-        Text::new(folder_name),
-        TextFont {
-            font: theme.text.font.clone(),
-            font_size: 10.0,
-            ..default()
-        },
-        TextColor(theme.text.text_color),
-        ChildOf(base_node),
-    ));
+    spawn_name(commands, base_node, folder_name, theme, layout);
+    if layout == ViewLayout::List {
+        spawn_detail_columns(commands, base_node, shown_entry, theme);
+    }
 
     commands.entity(base_node)
 }
 
+#[allow(clippy::too_many_arguments)]
 pub(crate) fn spawn_file_node<'a>(
     commands: &'a mut Commands,
     file_name: String,
+    shown_entry: &ShownEntry,
     asset_server: &Res<AssetServer>,
     location: &Res<AssetBrowserLocation>,
     theme: &Res<Theme>,
+    previews: &mut AssetPreviews,
+    layout: ViewLayout,
 ) -> EntityCommands<'a> {
+    let path = location.source_id.clone().map(|source_id| {
+        AssetPath::from_path(&shown_entry.path)
+            .with_source(source_id)
+            .into_owned()
+    });
+    let preview = path.clone().and_then(|path| previews.get(path));
+
     let base_node = {
-        let mut ec = spawn_base_node(commands, theme);
+        let mut ec = spawn_base_node(commands, theme, layout);
+        if let Some(path) = path {
+            ec.insert(AssetNode(path))
+                .observe(select_file)
+                .observe(open_file);
+        }
         if location.source_id == Some(AssetSourceId::Default) {
             ec.insert(ContextMenu::new([
-                // ContextMenuOption::new("Rename", |mut commands, entity| {
-                //     commands.run_system_cached_with(rename_asset, entity);
-                // }),
-                ContextMenuOption::new("Delete", |mut commands, entity| {
-                    commands.run_system_cached_with(delete_file, entity);
-                }),
+                command_menu_option("Rename", "asset_browser.rename"),
+                command_menu_option("Duplicate", "asset_browser.duplicate"),
+                command_menu_option("Find references", "asset_browser.find_references"),
+                command_menu_option("Delete", "asset_browser.delete"),
                 // TODO: add this to the folders as well
                 // TODO: fix this, doesn't yet work, it opens the file instead of revealing it in the file manager (at least on linux)
                 // ContextMenuOption::new("Reveal in File Manager", |mut commands, entity| {
@@ -175,18 +201,50 @@ pub(crate) fn spawn_file_node<'a>(
         ec.id()
     };
 
-    // Icon
-    commands.spawn((
+    // Icon, replaced by the preview of the file once it's ready
+    let mut icon = commands.spawn((
         ImageNode::new(asset_server.load("embedded://bevy_asset_browser/assets/file_icon.png")),
-        Node {
+        icon_node(layout),
+        ChildOf(base_node),
+    ));
+    if let Some(preview) = preview {
+        icon.insert(FilePreview(preview));
+    }
+    // File Name
+    spawn_name(commands, base_node, file_name, theme, layout);
+    if layout == ViewLayout::List {
+        spawn_detail_columns(commands, base_node, shown_entry, theme);
+    }
+
+    commands.entity(base_node)
+}
+
+/// The node of the icon of an entry, the first child of every entry node
+fn icon_node(layout: ViewLayout) -> Node {
+    match layout {
+        ViewLayout::Grid => Node {
             height: Val::Px(50.0),
             ..default()
         },
-        ChildOf(base_node),
-    ));
-    // Folder Name
-    commands.spawn((
-        Text::new(file_name),
+        ViewLayout::List => Node {
+            height: Val::Px(16.0),
+            width: Val::Px(16.0),
+            flex_shrink: 0.0,
+            ..default()
+        },
+    }
+}
+
+/// Spawn the name of an entry, the second child of every entry node
+fn spawn_name(
+    commands: &mut Commands,
+    base_node: Entity,
+    name: String,
+    theme: &Theme,
+    layout: ViewLayout,
+) {
+    let mut name_ec = commands.spawn((
+        Text::new(name),
         TextFont {
             font: theme.text.font.clone(),
             font_size: 10.0,
@@ -195,14 +253,293 @@ pub(crate) fn spawn_file_node<'a>(
         TextColor(theme.text.text_color),
         ChildOf(base_node),
     ));
+    if layout == ViewLayout::List {
+        name_ec.insert(Node {
+            flex_grow: 1.0,
+            overflow: Overflow::clip_x(),
+            ..default()
+        });
+    }
+}
 
-    commands.entity(base_node)
+/// Spawn the type, size and modification date columns of an entry of the list view
+fn spawn_detail_columns(
+    commands: &mut Commands,
+    base_node: Entity,
+    shown_entry: &ShownEntry,
+    theme: &Theme,
+) {
+    let asset_type = match shown_entry.entry {
+        Entry::Source(_) => "Source",
+        Entry::Folder(_) => "Folder",
+        Entry::File(_) => AssetType::of(&shown_entry.path).name(),
+    };
+    let columns = [
+        (asset_type.to_string(), TYPE_COLUMN_WIDTH),
+        (
+            shown_entry.size.map(format_file_size).unwrap_or_default(),
+            SIZE_COLUMN_WIDTH,
+        ),
+        (
+            shown_entry
+                .modified
+                .map(format_modified)
+                .unwrap_or_default(),
+            MODIFIED_COLUMN_WIDTH,
+        ),
+    ];
+    for (text, width) in columns {
+        commands.spawn((
+            Text::new(text),
+            TextFont {
+                font: theme.text.font.clone(),
+                font_size: 10.0,
+                ..default()
+            },
+            TextColor(theme.text.text_color),
+            Node {
+                width: Val::Px(width),
+                flex_shrink: 0.0,
+                ..default()
+            },
+            ChildOf(base_node),
+        ));
+    }
+}
+
+/// A header of the list view, sorting the entries by its column when clicked
+#[derive(Component, Clone, Copy, Debug)]
+pub(crate) struct ColumnHeader(SortBy);
+
+/// Spawn the headers of the columns of the list view
+pub(crate) fn spawn_column_headers<'a>(
+    commands: &'a mut Commands,
+    theme: &Res<Theme>,
+) -> EntityCommands<'a> {
+    let headers = commands
+        .spawn((
+            Node {
+                width: Val::Percent(100.0),
+                height: Val::Px(20.0),
+                flex_direction: FlexDirection::Row,
+                align_items: AlignItems::Center,
+                padding: UiRect::horizontal(Val::Px(6.0)),
+                column_gap: Val::Px(8.0),
+                ..default()
+            },
+            theme.pane.header_background_color,
+        ))
+        .id();
+    let columns = [
+        ("Name", Some(SortBy::Name), None),
+        ("Type", None, Some(TYPE_COLUMN_WIDTH)),
+        ("Size", Some(SortBy::Size), Some(SIZE_COLUMN_WIDTH)),
+        (
+            "Modified",
+            Some(SortBy::Modified),
+            Some(MODIFIED_COLUMN_WIDTH),
+        ),
+    ];
+    for (name, sort, width) in columns {
+        let node = match width {
+            Some(width) => Node {
+                width: Val::Px(width),
+                flex_shrink: 0.0,
+                ..default()
+            },
+            // The name column also spans the icon column
+            None => Node {
+                flex_grow: 1.0,
+                ..default()
+            },
+        };
+        let mut header = commands.spawn((
+            Text::new(name),
+            TextFont {
+                font: theme.text.font.clone(),
+                font_size: 10.0,
+                ..default()
+            },
+            TextColor(theme.text.text_color),
+            node,
+            ChildOf(headers),
+        ));
+        if let Some(sort) = sort {
+            header.insert(ColumnHeader(sort)).observe(sort_by_column);
+        }
+    }
+    commands.entity(headers)
 }
 
-fn spawn_base_node<'a>(commands: &'a mut Commands, theme: &Res<Theme>) -> EntityCommands<'a> {
-    let mut base_node_ec = commands.spawn((
-        Button,
-        Node {
+/// Sort by the clicked column, or reverse the order if the entries are already sorted by it
+fn sort_by_column(
+    trigger: Trigger<Pointer<Released>>,
+    query: Query<&ColumnHeader>,
+    mut view: ResMut<AssetBrowserView>,
+) {
+    if trigger.event().button != PointerButton::Primary {
+        return;
+    }
+    let Ok(header) = query.get(trigger.target()) else {
+        return;
+    };
+    if view.sort == header.0 {
+        view.descending = !view.descending;
+    } else {
+        view.sort = header.0;
+        view.descending = false;
+    }
+}
+
+/// Search the files referencing the asset of the file node
+pub(crate) fn find_references(
+    file_entity: In<Entity>,
+    query: Query<&AssetNode>,
+    mut view: ResMut<AssetBrowserView>,
+) {
+    if let Ok(file_node) = query.get(*file_entity) {
+        view.search = format!(
+            "{REFERENCES_PREFIX}{}",
+            asset_path_string(file_node.0.path())
+        );
+    }
+}
+
+/// Select the asset of the clicked file node
+/// With ctrl the file is added to or removed from the [`AssetBrowserSelection`], and with shift all
+/// the files shown between the [`SelectedAsset`] and the clicked file are selected
+#[allow(clippy::too_many_arguments)]
+fn select_file(
+    trigger: Trigger<Pointer<Released>>,
+    query: Query<&AssetNode>,
+    query_parent: Query<&ChildOf>,
+    query_children: Query<&Children>,
+    keyboard_input: Res<ButtonInput<KeyCode>>,
+    mut selection: ResMut<AssetBrowserSelection>,
+    mut selected_asset: ResMut<SelectedAsset>,
+    mut selected_entity: ResMut<SelectedEntity>,
+) {
+    if trigger.event().button != PointerButton::Primary {
+        return;
+    }
+    let file_entity = trigger.target();
+    let Ok(file_node) = query.get(file_entity) else {
+        return;
+    };
+    let path = file_node.0.clone();
+    selected_entity.0 = None;
+
+    if keyboard_input.any_pressed([KeyCode::ShiftLeft, KeyCode::ShiftRight]) {
+        let shown_files = query_parent
+            .get(file_entity)
+            .and_then(|parent| query_children.get(parent.parent()))
+            .map(|siblings| {
+                siblings
+                    .iter()
+                    .filter_map(|sibling| query.get(sibling).ok())
+                    .map(|file_node| file_node.0.clone())
+                    .collect::<Vec<_>>()
+            })
+            .unwrap_or_default();
+        let anchor = selected_asset
+            .0
+            .as_ref()
+            .and_then(|anchor| shown_files.iter().position(|file| file == anchor));
+        let clicked = shown_files.iter().position(|file| *file == path);
+        if let (Some(anchor), Some(clicked)) = (anchor, clicked) {
+            // The anchor stays the selected asset, so the range can be changed
+            selection.0 = shown_files[anchor.min(clicked)..=anchor.max(clicked)].to_vec();
+            return;
+        }
+    }
+
+    if keyboard_input.any_pressed([
+        KeyCode::ControlLeft,
+        KeyCode::ControlRight,
+        KeyCode::SuperLeft,
+        KeyCode::SuperRight,
+    ]) {
+        match selection.0.iter().position(|selected| *selected == path) {
+            Some(index) => {
+                selection.0.remove(index);
+            }
+            None => selection.0.push(path),
+        }
+        selected_asset.0 = selection.0.last().cloned();
+    } else {
+        selection.0 = vec![path.clone()];
+        selected_asset.0 = Some(path);
+    }
+}
+
+/// The longest time between the two clicks of a double-click, in seconds
+const DOUBLE_CLICK_TIME: f64 = 0.5;
+
+/// Send an [`OpenAsset`] event when a file node is double-clicked
+fn open_file(
+    trigger: Trigger<Pointer<Click>>,
+    query: Query<&AssetNode>,
+    time: Res<Time<Real>>,
+    mut last_click: Local<Option<(AssetPath<'static>, f64)>>,
+    mut open_asset: EventWriter<OpenAsset>,
+) {
+    if trigger.event().button != PointerButton::Primary {
+        return;
+    }
+    let Ok(file_node) = query.get(trigger.target()) else {
+        return;
+    };
+    let now = time.elapsed_secs_f64();
+    // Compare the paths, as the node may be spawned again between the clicks
+    match last_click.take() {
+        Some((path, clicked_at)) if path == file_node.0 && now - clicked_at < DOUBLE_CLICK_TIME => {
+            open_asset.write(OpenAsset(path));
+        }
+        _ => *last_click = Some((file_node.0.clone(), now)),
+    }
+}
+
+/// Highlight the border of the file nodes of the [`AssetBrowserSelection`]
+pub(crate) fn highlight_selected_file(
+    mut query: Query<(&AssetNode, &mut BorderColor)>,
+    selection: Res<AssetBrowserSelection>,
+    theme: Res<Theme>,
+) {
+    for (file_node, mut border_color) in &mut query {
+        let color = if selection.0.contains(&file_node.0) {
+            theme.button.hover_color
+        } else {
+            Color::NONE
+        };
+        border_color.set_if_neq(BorderColor(color));
+    }
+}
+
+/// The preview of a file, shown in place of the file icon once it's ready
+#[derive(Component)]
+pub(crate) struct FilePreview(Handle<Image>);
+
+/// Show the previews of files that finished rendering
+pub(crate) fn show_file_previews(
+    mut commands: Commands,
+    mut query: Query<(Entity, &FilePreview, &mut ImageNode)>,
+    images: Res<Assets<Image>>,
+) {
+    for (entity, preview, mut image_node) in &mut query {
+        if images.contains(&preview.0) {
+            image_node.image = preview.0.clone();
+            commands.entity(entity).remove::<FilePreview>();
+        }
+    }
+}
+
+fn spawn_base_node<'a>(
+    commands: &'a mut Commands,
+    theme: &Res<Theme>,
+    layout: ViewLayout,
+) -> EntityCommands<'a> {
+    let node = match layout {
+        ViewLayout::Grid => Node {
             margin: UiRect::all(Val::Px(5.0)),
             padding: UiRect::all(Val::Px(5.0)),
             height: Val::Px(100.0),
@@ -213,9 +550,18 @@ fn spawn_base_node<'a>(commands: &'a mut Commands, theme: &Res<Theme>) -> Entity
             justify_content: JustifyContent::SpaceBetween,
             ..default()
         },
-        ZIndex(1),
-        theme.general.border_radius,
-    ));
+        ViewLayout::List => Node {
+            padding: UiRect::horizontal(Val::Px(5.0)),
+            height: Val::Px(22.0),
+            width: Val::Percent(100.0),
+            align_items: AlignItems::Center,
+            flex_direction: FlexDirection::Row,
+            border: UiRect::all(Val::Px(1.0)),
+            column_gap: Val::Px(8.0),
+            ..default()
+        },
+    };
+    let mut base_node_ec = commands.spawn((Button, node, ZIndex(1), theme.general.border_radius));
 
     // Hover effect
     base_node_ec
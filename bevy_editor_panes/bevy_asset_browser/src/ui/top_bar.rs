@@ -1,9 +1,24 @@
-use bevy::{prelude::*, window::SystemCursorIcon, winit::cursor::CursorIcon};
+use std::path::PathBuf;
+
+use bevy::{
+    asset::io::AssetSourceId, prelude::*, window::SystemCursorIcon, winit::cursor::CursorIcon,
+};
 use bevy_editor_styles::Theme;
+use bevy_field_forms::{
+    input_field::{InputField, ValueChanged},
+    validate_highlight::SimpleBorderHighlight,
+};
 
-use crate::{io, AssetBrowserLocation};
+use crate::{
+    io,
+    view::{AssetBrowserView, AssetType, ViewLayout},
+    AssetBrowserLocation,
+};
 
-use super::source_id_to_string;
+use super::{
+    file_operations::{drop_files_on_folder, FolderPath},
+    source_id_to_string,
+};
 
 /// Color of the path segment background when idle
 pub const PATH_SEGMENT_BACKGROUND_COLOR: Color = Color::srgb(0.2, 0.2, 0.2);
@@ -12,11 +27,72 @@ pub const PATH_SEGMENT_BACKGROUND_COLOR: Color = Color::srgb(0.2, 0.2, 0.2);
 #[derive(Component)]
 pub struct TopBarNode;
 
+/// The node containing the path segments of the [`AssetBrowserLocation`]
+#[derive(Component)]
+pub struct LocationPathNode;
+
+/// The search field of the top bar, setting [`AssetBrowserView::search`]
+#[derive(Component)]
+pub struct SearchField;
+
+/// A button of the top bar changing the [`AssetBrowserView`]
+#[derive(Component, Clone, Copy, PartialEq, Eq, Debug)]
+pub enum ViewControl {
+    /// Cycle through the [`AssetType`] filters
+    TypeFilter,
+    /// Cycle through the sort orders
+    Sort,
+    /// Toggle between ascending and descending order
+    Direction,
+    /// Toggle between the grid and the list view
+    Layout,
+}
+
+impl ViewControl {
+    const ALL: [ViewControl; 4] = [
+        ViewControl::TypeFilter,
+        ViewControl::Sort,
+        ViewControl::Direction,
+        ViewControl::Layout,
+    ];
+
+    fn label(self, view: &AssetBrowserView) -> String {
+        match self {
+            ViewControl::TypeFilter => view
+                .type_filter
+                .map_or("All types", AssetType::name)
+                .to_string(),
+            ViewControl::Sort => format!("Sort: {}", view.sort.name()),
+            ViewControl::Direction if view.descending => "Descending".to_string(),
+            ViewControl::Direction => "Ascending".to_string(),
+            ViewControl::Layout => match view.layout {
+                ViewLayout::Grid => "Grid".to_string(),
+                ViewLayout::List => "List".to_string(),
+            },
+        }
+    }
+
+    fn apply(self, view: &mut AssetBrowserView) {
+        match self {
+            ViewControl::TypeFilter => view.type_filter = AssetType::next_filter(view.type_filter),
+            ViewControl::Sort => view.sort = view.sort.next(),
+            ViewControl::Direction => view.descending = !view.descending,
+            ViewControl::Layout => {
+                view.layout = match view.layout {
+                    ViewLayout::Grid => ViewLayout::List,
+                    ViewLayout::List => ViewLayout::Grid,
+                }
+            }
+        }
+    }
+}
+
 /// Spawn the top bar of the asset browser
 pub(crate) fn spawn_top_bar<'a>(
     commands: &'a mut Commands,
     theme: &Res<Theme>,
     location: &Res<AssetBrowserLocation>,
+    view: &Res<AssetBrowserView>,
 ) -> EntityCommands<'a> {
     let top_bar = commands
         .spawn((
@@ -33,10 +109,110 @@ pub(crate) fn spawn_top_bar<'a>(
         ))
         .id();
     spawn_location_path_ui(commands, theme, location).insert(ChildOf(top_bar));
+    // Push the view controls to the right
+    commands.spawn((
+        Node {
+            flex_grow: 1.0,
+            ..default()
+        },
+        ChildOf(top_bar),
+    ));
+    commands
+        .spawn((
+            Node {
+                width: Val::Px(160.0),
+                height: Val::Px(20.0),
+                margin: UiRect::horizontal(Val::Px(5.0)),
+                border: UiRect::all(Val::Px(1.0)),
+                ..default()
+            },
+            BackgroundColor(PATH_SEGMENT_BACKGROUND_COLOR),
+            InputField::new(view.search.clone()),
+            SimpleBorderHighlight::default(),
+            SearchField,
+            ChildOf(top_bar),
+        ))
+        .observe(
+            |trigger: Trigger<ValueChanged<String>>, mut view: ResMut<AssetBrowserView>| {
+                view.search = trigger.event().0.clone();
+            },
+        );
+    for control in ViewControl::ALL {
+        spawn_view_control(commands, control, theme, view).insert(ChildOf(top_bar));
+    }
 
     commands.entity(top_bar)
 }
 
+/// Spawn a button changing the [`AssetBrowserView`]
+fn spawn_view_control<'a>(
+    commands: &'a mut Commands,
+    control: ViewControl,
+    theme: &Theme,
+    view: &AssetBrowserView,
+) -> EntityCommands<'a> {
+    let mut control_ec = commands.spawn((
+        Button,
+        Node {
+            padding: UiRect::axes(Val::Px(10.0), Val::Px(1.0)),
+            margin: UiRect::horizontal(Val::Px(2.0)),
+            ..default()
+        },
+        BackgroundColor(PATH_SEGMENT_BACKGROUND_COLOR),
+        theme.general.border_radius,
+        control,
+    ));
+    control_ec
+        .with_children(|parent| {
+            parent.spawn((
+                Text(control.label(view)),
+                TextFont {
+                    font: theme.text.font.clone(),
+                    font_size: 10.0,
+                    ..default()
+                },
+                TextColor(theme.text.text_color),
+            ));
+        })
+        .observe(
+            |trigger: Trigger<Pointer<Released>>,
+             query: Query<&ViewControl>,
+             mut view: ResMut<AssetBrowserView>| {
+                if trigger.event().button != PointerButton::Primary {
+                    return;
+                }
+                if let Ok(control) = query.get(trigger.target()) {
+                    control.apply(&mut view);
+                }
+            },
+        );
+    control_ec
+}
+
+/// Update the labels of the [`ViewControl`] buttons, and the [`SearchField`] when the search was
+/// changed elsewhere, for example to find the references of an asset
+pub(crate) fn update_view_controls(
+    query: Query<(&ViewControl, &Children)>,
+    mut query_text: Query<&mut Text>,
+    mut query_search_field: Query<&mut InputField<String>, With<SearchField>>,
+    view: Res<AssetBrowserView>,
+) {
+    for (control, children) in &query {
+        let Some(mut text) = children
+            .first()
+            .and_then(|child| query_text.get_mut(*child).ok())
+        else {
+            continue;
+        };
+        text.0 = control.label(&view);
+    }
+    for mut search_field in &mut query_search_field {
+        if search_field.value != view.search {
+            search_field.value = view.search.clone();
+        }
+    }
+}
+
 pub fn location_as_changed(location: Res<AssetBrowserLocation>) -> bool {
     location.is_changed()
 }
@@ -44,20 +220,20 @@ pub fn location_as_changed(location: Res<AssetBrowserLocation>) -> bool {
 /// Clear and regenerate the location path UI
 pub fn refresh_ui(
     mut commands: Commands,
-    root: Query<(Entity, Option<&Children>), With<TopBarNode>>,
+    root: Query<(Entity, Option<&Children>), With<LocationPathNode>>,
     theme: Res<Theme>,
     location: Res<AssetBrowserLocation>,
 ) {
-    for (top_bar_entity, top_bar_childrens) in root.iter() {
+    for (location_path_entity, location_path_childrens) in root.iter() {
         // Clear location path UI
-        if let Some(childrens) = top_bar_childrens {
+        if let Some(childrens) = location_path_childrens {
             for child in childrens.iter() {
                 commands.entity(child).despawn();
             }
-            commands.entity(top_bar_entity).remove::<Children>();
+            commands.entity(location_path_entity).remove::<Children>();
         }
         // Regenerate location path UI
-        spawn_location_path_ui(&mut commands, &theme, &location).insert(ChildOf(top_bar_entity));
+        spawn_location_path_segments(&mut commands, &theme, &location, location_path_entity);
     }
 }
 
@@ -68,34 +244,50 @@ pub fn spawn_location_path_ui<'a>(
     location: &Res<AssetBrowserLocation>,
 ) -> EntityCommands<'a> {
     let location_path = commands
-        .spawn(Node {
-            flex_direction: FlexDirection::Row,
-            align_items: AlignItems::Center,
-            ..default()
-        })
+        .spawn((
+            LocationPathNode,
+            Node {
+                flex_direction: FlexDirection::Row,
+                align_items: AlignItems::Center,
+                ..default()
+            },
+        ))
         .id();
+    spawn_location_path_segments(commands, theme, location, location_path);
+    commands.entity(location_path)
+}
 
+/// Spawn the segments of the location path, separated by [`path_separator_ui`]
+fn spawn_location_path_segments(
+    commands: &mut Commands,
+    theme: &Res<Theme>,
+    location: &Res<AssetBrowserLocation>,
+    location_path: Entity,
+) {
     spawn_path_segment_ui(
         commands,
         "Sources".to_string(),
         theme.as_ref(),
         LocationSegmentType::Root,
+        None,
     )
     .insert(ChildOf(location_path));
 
-    if location.source_id.is_some() {
+    if let Some(source_id) = &location.source_id {
         commands
             .spawn(path_separator_ui(theme.as_ref()))
             .insert(ChildOf(location_path));
-        let source_id = location.source_id.as_ref().unwrap();
         spawn_path_segment_ui(
             commands,
             source_id_to_string(source_id),
             theme.as_ref(),
             LocationSegmentType::Source,
+            (*source_id == AssetSourceId::Default).then(PathBuf::new),
         )
         .insert(ChildOf(location_path));
+        let mut folder_path = PathBuf::new();
         location.path.iter().for_each(|directory_name| {
+            folder_path.push(directory_name);
             commands
                 .spawn(path_separator_ui(theme.as_ref()))
                 .insert(ChildOf(location_path));
@@ -104,21 +296,23 @@ pub fn spawn_location_path_ui<'a>(
                 directory_name.to_str().unwrap().to_string(),
                 theme.as_ref(),
                 LocationSegmentType::Directory,
+                (*source_id == AssetSourceId::Default).then(|| folder_path.clone()),
             )
             .insert(ChildOf(location_path));
         });
     }
-    commands.entity(location_path)
 }
 
 /// Spawn a path segment UI element
 /// This segment represent a component of the [`AssetBrowserLocation`] path
 /// When clicked, it changes the [`AssetBrowserLocation`] to the corresponding path
+/// Files can be dropped onto segments with a `folder_path` to move them into this folder
 fn spawn_path_segment_ui<'a>(
     commands: &'a mut Commands,
     directory_name: String,
     theme: &Theme,
     segment_type: LocationSegmentType,
+    folder_path: Option<PathBuf>,
 ) -> EntityCommands<'a> {
     let mut segment_ec = commands.spawn((
         Button,
@@ -131,6 +325,11 @@ fn spawn_path_segment_ui<'a>(
         theme.general.border_radius,
         segment_type,
     ));
+    if let Some(folder_path) = folder_path {
+        segment_ec
+            .insert(FolderPath(folder_path))
+            .observe(drop_files_on_folder);
+    }
     segment_ec
         .with_children(|parent| {
             parent.spawn((
@@ -144,7 +343,8 @@ fn spawn_path_segment_ui<'a>(
             ));
         })
         .observe(
-            move |trigger: Trigger<Pointer<Released>>,
+            // Not on release, so dropping files onto the segment doesn't open its folder
+            move |trigger: Trigger<Pointer<Click>>,
                   mut commands: Commands,
                   mut location: ResMut<AssetBrowserLocation>,
                   query_children: Query<&Children>,
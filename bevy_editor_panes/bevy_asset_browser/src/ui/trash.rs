@@ -0,0 +1,176 @@
+//! Panel listing the [`AssetTrash`], opened from the context menu of the directory content
+
+use bevy::prelude::*;
+use bevy_editor_styles::Theme;
+
+use crate::{
+    io::references::asset_path_string,
+    trash::{delete_trashed_items, restore_trashed_item, AssetTrash, TrashSettings},
+    view::format_modified,
+};
+
+use super::{spawn_panel, spawn_panel_button, spawn_panel_label, AssetBrowserNode};
+
+/// The panel listing the items of the [`AssetTrash`]
+#[derive(Component)]
+pub(crate) struct TrashPanel;
+
+/// Open the [`TrashPanel`] over the asset browser containing `entity`, or over `entity` if it's an
+/// asset browser
+pub(crate) fn open_trash_panel(
+    entity: In<Entity>,
+    mut commands: Commands,
+    query_parent: Query<&ChildOf>,
+    query_asset_browser: Query<(), With<AssetBrowserNode>>,
+    theme: Res<Theme>,
+) {
+    let Some(asset_browser) = std::iter::once(*entity)
+        .chain(query_parent.iter_ancestors(*entity))
+        .find(|entity| query_asset_browser.contains(*entity))
+    else {
+        return;
+    };
+    spawn_panel(&mut commands, &theme, asset_browser).insert(TrashPanel);
+    commands.run_system_cached(refresh_trash_panel);
+}
+
+/// Rebuild the content of the [`TrashPanel`], when the [`AssetTrash`] changes
+pub(crate) fn refresh_trash_panel(
+    mut commands: Commands,
+    panels: Query<(Entity, Option<&Children>), With<TrashPanel>>,
+    trash: Res<AssetTrash>,
+    settings: Res<TrashSettings>,
+    theme: Res<Theme>,
+) {
+    for (panel, children) in &panels {
+        if let Some(children) = children {
+            for child in children.iter() {
+                commands.entity(child).despawn();
+            }
+        }
+        spawn_panel_label(
+            &mut commands,
+            "Trash".to_string(),
+            theme.text.text_color,
+            &theme,
+        )
+        .insert(ChildOf(panel));
+        let retention = match settings.retention_days {
+            0 => "Deleted files are kept until the trash is emptied".to_string(),
+            days => format!("Deleted files are kept for {days} days"),
+        };
+        spawn_panel_label(&mut commands, retention, theme.text.low_priority, &theme)
+            .insert(ChildOf(panel));
+        if trash.items.is_empty() {
+            spawn_panel_label(
+                &mut commands,
+                "The trash is empty".to_string(),
+                theme.text.low_priority,
+                &theme,
+            )
+            .insert(ChildOf(panel));
+        }
+
+        let list = commands
+            .spawn((
+                Node {
+                    flex_direction: FlexDirection::Column,
+                    row_gap: Val::Px(2.0),
+                    overflow: Overflow::scroll_y(),
+                    ..default()
+                },
+                ChildOf(panel),
+            ))
+            .id();
+        // Most recently deleted first
+        for item in trash.items.iter().rev() {
+            let row = commands
+                .spawn((
+                    Node {
+                        column_gap: Val::Px(4.0),
+                        align_items: AlignItems::Center,
+                        ..default()
+                    },
+                    ChildOf(list),
+                ))
+                .id();
+            spawn_panel_label(
+                &mut commands,
+                asset_path_string(&item.original_path),
+                theme.text.text_color,
+                &theme,
+            )
+            .insert((
+                Node {
+                    flex_grow: 1.0,
+                    ..default()
+                },
+                ChildOf(row),
+            ));
+            spawn_panel_label(
+                &mut commands,
+                format_modified(item.deleted_at),
+                theme.text.low_priority,
+                &theme,
+            )
+            .insert(ChildOf(row));
+            let restored_item = item.clone();
+            spawn_panel_button(&mut commands, "Restore", &theme)
+                .insert(ChildOf(row))
+                .observe(
+                    move |trigger: Trigger<Pointer<Click>>, mut commands: Commands| {
+                        if trigger.event().button == PointerButton::Primary {
+                            commands.run_system_cached_with(
+                                restore_trashed_item,
+                                restored_item.clone(),
+                            );
+                        }
+                    },
+                );
+            let deleted_item = item.clone();
+            spawn_panel_button(&mut commands, "Delete", &theme)
+                .insert(ChildOf(row))
+                .observe(
+                    move |trigger: Trigger<Pointer<Click>>, mut commands: Commands| {
+                        if trigger.event().button == PointerButton::Primary {
+                            commands.run_system_cached_with(
+                                delete_trashed_items,
+                                vec![deleted_item.clone()],
+                            );
+                        }
+                    },
+                );
+        }
+
+        let buttons = commands
+            .spawn((
+                Node {
+                    column_gap: Val::Px(4.0),
+                    margin: UiRect::top(Val::Px(4.0)),
+                    ..default()
+                },
+                ChildOf(panel),
+            ))
+            .id();
+        spawn_panel_button(&mut commands, "Empty trash", &theme)
+            .insert(ChildOf(buttons))
+            .observe(
+                |trigger: Trigger<Pointer<Click>>,
+                 mut commands: Commands,
+                 trash: Res<AssetTrash>| {
+                    if trigger.event().button == PointerButton::Primary {
+                        commands.run_system_cached_with(delete_trashed_items, trash.items.clone());
+                    }
+                },
+            );
+        spawn_panel_button(&mut commands, "Close", &theme)
+            .insert(ChildOf(buttons))
+            .observe(
+                move |trigger: Trigger<Pointer<Click>>, mut commands: Commands| {
+                    if trigger.event().button == PointerButton::Primary {
+                        commands.entity(panel).despawn();
+                    }
+                },
+            );
+    }
+}
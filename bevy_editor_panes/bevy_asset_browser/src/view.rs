@@ -0,0 +1,339 @@
+//! Searching, filtering, sorting and laying out the content of the asset browser.
+
+use std::{cmp::Ordering, path::Path, time::SystemTime};
+
+use bevy::{image::ImageLoader, prelude::*};
+use bevy_editor_core::fuzzy::fuzzy_score;
+
+/// How the content of the asset browser is shown
+#[derive(Resource, Debug, Clone, Default, PartialEq, Eq)]
+pub struct AssetBrowserView {
+    /// The search query. When it isn't empty, the files of the whole asset source of the
+    /// [`AssetBrowserLocation`](crate::AssetBrowserLocation) are searched recursively, instead of
+    /// showing the current directory.
+    ///
+    /// Words are fuzzy matched against the file names, and words starting with a `.`, like `.png`,
+    /// only keep the files with that extension.
+    ///
+    /// A query starting with `references:`, like `references:models/alien.glb`, shows the files
+    /// referencing this asset or the assets inside this folder instead.
+    pub search: String,
+    /// Only show the files of this type, folders are always shown
+    pub type_filter: Option<AssetType>,
+    /// The order of the entries, folders always come before files
+    pub sort: SortBy,
+    /// Reverse the order of the entries
+    pub descending: bool,
+    /// Show the entries as a grid of tiles or as a list with columns
+    pub layout: ViewLayout,
+}
+
+/// What the entries of the asset browser are sorted by
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+pub enum SortBy {
+    /// Sort by name, or by relevance when searching
+    #[default]
+    Name,
+    /// Sort by last modification date
+    Modified,
+    /// Sort by file size
+    Size,
+}
+
+impl SortBy {
+    /// The next sort order, to cycle through them
+    pub fn next(self) -> SortBy {
+        match self {
+            SortBy::Name => SortBy::Modified,
+            SortBy::Modified => SortBy::Size,
+            SortBy::Size => SortBy::Name,
+        }
+    }
+
+    /// A short name to show in the UI
+    pub fn name(self) -> &'static str {
+        match self {
+            SortBy::Name => "Name",
+            SortBy::Modified => "Modified",
+            SortBy::Size => "Size",
+        }
+    }
+}
+
+/// The layout of the entries of the asset browser
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+pub enum ViewLayout {
+    /// Tiles with large icons
+    #[default]
+    Grid,
+    /// One row per entry, with columns for the type, size and modification date
+    List,
+}
+
+/// The type of an asset file, based on its extension
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub enum AssetType {
+    /// Images and textures
+    Image,
+    /// 3D models, like glTF files
+    Model,
+    /// Scenes and prefabs
+    Scene,
+    /// Sounds and music
+    Audio,
+    /// Shaders
+    Shader,
+    /// Fonts
+    Font,
+    /// Rust source files
+    Script,
+    /// Any other file
+    Other,
+}
+
+impl AssetType {
+    /// All the asset types, in the order they are cycled through by the type filter
+    pub const ALL: [AssetType; 8] = [
+        AssetType::Image,
+        AssetType::Model,
+        AssetType::Scene,
+        AssetType::Audio,
+        AssetType::Shader,
+        AssetType::Font,
+        AssetType::Script,
+        AssetType::Other,
+    ];
+
+    /// The type of the file at `path`
+    pub fn of(path: &Path) -> AssetType {
+        let Some(file_name) = path.file_name() else {
+            return AssetType::Other;
+        };
+        let file_name = file_name.to_string_lossy().to_ascii_lowercase();
+        if file_name.ends_with(".scn.ron") {
+            return AssetType::Scene;
+        }
+        let Some((_, extension)) = file_name.rsplit_once('.') else {
+            return AssetType::Other;
+        };
+        match extension {
+            "gltf" | "glb" | "obj" | "fbx" => AssetType::Model,
+            "bsn" | "proto_bsn" | "scn" => AssetType::Scene,
+            "ogg" | "oga" | "spx" | "wav" | "mp3" | "flac" => AssetType::Audio,
+            "wgsl" | "glsl" | "vert" | "frag" | "comp" | "spv" => AssetType::Shader,
+            "ttf" | "otf" => AssetType::Font,
+            "rs" => AssetType::Script,
+            extension if ImageLoader::SUPPORTED_FILE_EXTENSIONS.contains(&extension) => {
+                AssetType::Image
+            }
+            _ => AssetType::Other,
+        }
+    }
+
+    /// A short name to show in the UI
+    pub fn name(self) -> &'static str {
+        match self {
+            AssetType::Image => "Image",
+            AssetType::Model => "Model",
+            AssetType::Scene => "Scene",
+            AssetType::Audio => "Audio",
+            AssetType::Shader => "Shader",
+            AssetType::Font => "Font",
+            AssetType::Script => "Script",
+            AssetType::Other => "Other",
+        }
+    }
+
+    /// The filter after `filter`, to cycle through all the types and back to no filter
+    pub fn next_filter(filter: Option<AssetType>) -> Option<AssetType> {
+        match filter {
+            None => Some(AssetType::ALL[0]),
+            Some(asset_type) => {
+                let index = AssetType::ALL
+                    .iter()
+                    .position(|other| *other == asset_type)?;
+                AssetType::ALL.get(index + 1).copied()
+            }
+        }
+    }
+}
+
+/// The prefix of the [`AssetBrowserView::search`] queries looking for the files referencing an
+/// asset
+pub(crate) const REFERENCES_PREFIX: &str = "references:";
+
+/// A parsed [`AssetBrowserView::search`] query
+#[derive(Debug, Default, PartialEq, Eq)]
+pub(crate) struct SearchQuery {
+    /// Lowercase words that are fuzzy matched against the file names
+    words: Vec<String>,
+    /// Lowercase extensions, without the leading `.`
+    extensions: Vec<String>,
+    /// The asset path whose referencing files are searched, relative to the source root
+    pub(crate) references_of: Option<String>,
+}
+
+impl SearchQuery {
+    pub(crate) fn parse(search: &str) -> SearchQuery {
+        let mut query = SearchQuery::default();
+        // Asset paths may contain spaces, the whole query is the path
+        if let Some(path) = search.trim().strip_prefix(REFERENCES_PREFIX) {
+            query.references_of = Some(path.trim().to_string());
+            return query;
+        }
+        for word in search.split_whitespace() {
+            let word = word.to_lowercase();
+            match word.strip_prefix("*.").or_else(|| word.strip_prefix('.')) {
+                Some(extension) if !extension.is_empty() => {
+                    query.extensions.push(extension.to_string());
+                }
+                _ => query.words.push(word),
+            }
+        }
+        query
+    }
+
+    pub(crate) fn is_empty(&self) -> bool {
+        self.words.is_empty() && self.extensions.is_empty() && self.references_of.is_none()
+    }
+
+    /// How well the file name matches the query, or `None` if it doesn't
+    pub(crate) fn score(&self, file_name: &str) -> Option<i32> {
+        let lowercase_name = file_name.to_lowercase();
+        if !self.extensions.is_empty()
+            && !self
+                .extensions
+                .iter()
+                .any(|extension| lowercase_name.ends_with(&format!(".{extension}")))
+        {
+            return None;
+        }
+        self.words
+            .iter()
+            .map(|word| fuzzy_score(word, file_name))
+            .sum()
+    }
+}
+
+/// Compare two entries by `sort`, entries without a date or size come last
+pub(crate) fn compare_by(
+    sort: SortBy,
+    (name, modified, size): (&str, Option<SystemTime>, Option<u64>),
+    (other_name, other_modified, other_size): (&str, Option<SystemTime>, Option<u64>),
+) -> Ordering {
+    let by_name = || name.to_lowercase().cmp(&other_name.to_lowercase());
+    let by_option = |a: Option<Ordering>| a.unwrap_or(Ordering::Equal);
+    match sort {
+        SortBy::Name => by_name(),
+        SortBy::Modified => match (modified, other_modified) {
+            (Some(modified), Some(other)) => by_option(modified.partial_cmp(&other)),
+            (Some(_), None) => Ordering::Less,
+            (None, Some(_)) => Ordering::Greater,
+            (None, None) => Ordering::Equal,
+        }
+        .then_with(by_name),
+        SortBy::Size => match (size, other_size) {
+            (Some(size), Some(other)) => size.cmp(&other),
+            (Some(_), None) => Ordering::Less,
+            (None, Some(_)) => Ordering::Greater,
+            (None, None) => Ordering::Equal,
+        }
+        .then_with(by_name),
+    }
+}
+
+/// Format a modification date as `YYYY-MM-DD hh:mm`, in UTC
+pub(crate) fn format_modified(modified: SystemTime) -> String {
+    let Ok(since_epoch) = modified.duration_since(SystemTime::UNIX_EPOCH) else {
+        return String::new();
+    };
+    let seconds = since_epoch.as_secs();
+    let (hours, minutes) = ((seconds / 3600) % 24, (seconds / 60) % 60);
+
+    // Convert the days since the epoch to a civil date, see
+    // https://howardhinnant.github.io/date_algorithms.html#civil_from_days
+    let days = (seconds / 86400) as i64 + 719_468;
+    let era = days.div_euclid(146_097);
+    let day_of_era = days.rem_euclid(146_097);
+    let year_of_era =
+        (day_of_era - day_of_era / 1460 + day_of_era / 36524 - day_of_era / 146_096) / 365;
+    let day_of_year = day_of_era - (365 * year_of_era + year_of_era / 4 - year_of_era / 100);
+    let shifted_month = (5 * day_of_year + 2) / 153;
+    let day = day_of_year - (153 * shifted_month + 2) / 5 + 1;
+    let month = if shifted_month < 10 {
+        shifted_month + 3
+    } else {
+        shifted_month - 9
+    };
+    let year = year_of_era + era * 400 + i64::from(month <= 2);
+    format!("{year:04}-{month:02}-{day:02} {hours:02}:{minutes:02}")
+}
+
+#[cfg(test)]
+mod tests {
+    use std::time::Duration;
+
+    use super::*;
+
+    #[test]
+    fn parses_extensions() {
+        let query = SearchQuery::parse("Player .PNG *.jpg");
+        assert_eq!(
+            query,
+            SearchQuery {
+                words: vec!["player".to_string()],
+                extensions: vec!["png".to_string(), "jpg".to_string()],
+                references_of: None,
+            }
+        );
+        assert!(query.score("player_idle.png").is_some());
+        assert!(query.score("player_idle.ogg").is_none());
+        assert!(query.score("enemy.png").is_none());
+        assert!(SearchQuery::parse(".png").score("enemy.png").is_some());
+        assert_eq!(
+            SearchQuery::parse(" references:My Models/ship.glb").references_of,
+            Some("My Models/ship.glb".to_string())
+        );
+    }
+
+    #[test]
+    fn detects_asset_types() {
+        assert_eq!(
+            AssetType::of(Path::new("models/ship.glb")),
+            AssetType::Model
+        );
+        assert_eq!(AssetType::of(Path::new("level.scn.ron")), AssetType::Scene);
+        assert_eq!(AssetType::of(Path::new("icon.PNG")), AssetType::Image);
+        assert_eq!(AssetType::of(Path::new("README")), AssetType::Other);
+        assert_eq!(AssetType::next_filter(Some(AssetType::Other)), None);
+        assert_eq!(AssetType::next_filter(None), Some(AssetType::Image));
+    }
+
+    #[test]
+    fn sorts_missing_details_last() {
+        let now = SystemTime::now();
+        let earlier = now - Duration::from_secs(60);
+        assert_eq!(
+            compare_by(
+                SortBy::Modified,
+                ("b", Some(earlier), None),
+                ("a", Some(now), None)
+            ),
+            Ordering::Less
+        );
+        assert_eq!(
+            compare_by(SortBy::Size, ("a", None, None), ("b", None, Some(1))),
+            Ordering::Greater
+        );
+        assert_eq!(
+            compare_by(SortBy::Size, ("a", None, Some(1)), ("B", None, Some(1))),
+            Ordering::Less
+        );
+    }
+
+    #[test]
+    fn formats_modification_dates() {
+        let date = SystemTime::UNIX_EPOCH + Duration::from_secs(1_792_331_580);
+        assert_eq!(format_modified(date), "2026-10-18 13:53");
+    }
+}
@@ -0,0 +1,253 @@
+//! The code editor of each pane, and the text files opened in its tabs
+//!
+//! Each opened file is a [`Document`] entity with an [`EditableText`], which edits, undoes and
+//! lays out the text. The document keeps the [`TextHighlights`] of the text up to date, from the
+//! [`TextChanged`] events of the edits.
+
+use bevy::{asset::AssetPath, prelude::*};
+use bevy_editor_styles::{CodeStyles, Theme};
+use bevy_text_editing::{
+    history::TextEditHistory, text_change::TextChange, EditableText, Indentation, TextChanged,
+    TextHighlights, TextMarks,
+};
+
+use crate::{
+    find::FindState,
+    highlight::{Highlighter, Language, LineTokens},
+};
+
+/// The state of a code editor pane, on its content entity
+#[derive(Component, Default)]
+pub struct CodeEditor {
+    /// The documents opened in the tabs of the pane, in the order of the tabs
+    pub documents: Vec<Entity>,
+    /// The document shown in the pane, if any
+    pub active: Option<Entity>,
+    /// The find and replace bar
+    pub find: FindState,
+    /// The error of the last load or save, shown in the header until the next one succeeds
+    pub error: Option<String>,
+    /// The edited document being closed, waiting for the user to save or discard its edits
+    pub closing: Option<Entity>,
+}
+
+impl CodeEditor {
+    /// Show `document` in the pane
+    pub fn activate(&mut self, document: Entity) {
+        if self.active != Some(document) {
+            self.active = Some(document);
+        }
+    }
+
+    /// Remove the tab of `document`, showing the next tab if it was shown
+    pub fn remove(&mut self, document: Entity) {
+        let Some(index) = self.documents.iter().position(|&d| d == document) else {
+            return;
+        };
+        self.documents.remove(index);
+        if self.closing == Some(document) {
+            self.closing = None;
+        }
+        if self.active == Some(document) {
+            self.active = self.documents.get(index).or(self.documents.last()).copied();
+        }
+    }
+}
+
+/// A text file opened in a tab of a code editor, on an entity with the [`EditableText`] editing it
+#[derive(Component)]
+#[require(EditableText, TextHighlights, TextMarks, Indentation)]
+pub struct Document {
+    /// The path of the file
+    pub path: AssetPath<'static>,
+    /// The language of the file, used for highlighting
+    pub language: Language,
+    /// Whether the text was changed since it was loaded or saved
+    pub dirty: bool,
+    /// Whether the file had Windows line breaks, restored when saving
+    pub crlf: bool,
+    highlighter: Highlighter,
+}
+
+impl Document {
+    /// Create a document for the file at `path` with `text`, whose line breaks must be `\n`,
+    /// returning it with the tokens of every line
+    pub fn new(
+        path: AssetPath<'static>,
+        language: Language,
+        text: &str,
+        crlf: bool,
+    ) -> (Self, Vec<LineTokens>) {
+        let (highlighter, tokens) = Highlighter::new(language, text);
+        let document = Self {
+            path,
+            language,
+            dirty: false,
+            crlf,
+            highlighter,
+        };
+        (document, tokens)
+    }
+
+    /// The name of the file
+    pub fn file_name(&self) -> String {
+        self.path
+            .path()
+            .file_name()
+            .unwrap_or_default()
+            .to_string_lossy()
+            .to_string()
+    }
+
+    /// The text to write to the file
+    pub fn text_to_save(&self, text: &str) -> String {
+        if self.crlf {
+            text.replace('\n', "\r\n")
+        } else {
+            text.to_string()
+        }
+    }
+}
+
+/// The colors of highlighted tokens, as [`TextHighlights`] lines
+pub(crate) fn token_colors(
+    tokens: Vec<LineTokens>,
+    styles: &CodeStyles,
+) -> Vec<Vec<(std::ops::Range<usize>, Color)>> {
+    tokens
+        .into_iter()
+        .map(|line| {
+            line.into_iter()
+                .map(|(range, kind)| (range, kind.color(styles)))
+                .collect()
+        })
+        .collect()
+}
+
+/// Highlight again the lines changed in a document, and mark it as edited
+pub(crate) fn on_text_changed(
+    mut trigger: Trigger<TextChanged>,
+    mut documents: Query<(&mut Document, &mut TextHighlights)>,
+    theme: Res<Theme>,
+) {
+    let Ok((mut document, mut highlights)) = documents.get_mut(trigger.target()) else {
+        return;
+    };
+    trigger.propagate(false);
+    let event = trigger.event();
+    let update = document.highlighter.edit(&event.change, &event.new_text);
+    highlights
+        .0
+        .splice(update.replaced, token_colors(update.tokens, &theme.code));
+    document.dirty = true;
+}
+
+/// Apply `change` to the text of a document as an edit of the user, which can be undone, putting
+/// the cursor after the inserted text
+pub(crate) fn apply_change(
+    commands: &mut Commands,
+    entity: Entity,
+    editable_text: &mut EditableText,
+    history: &mut TextEditHistory,
+    change: TextChange,
+) {
+    let cursor = change.range.0 + change.new_text.chars().count();
+    history.record(
+        &editable_text.text,
+        &change,
+        editable_text.cursor_position,
+        editable_text.selection_start,
+        cursor,
+    );
+    let old_cursor_position = editable_text.cursor_position;
+    change.apply(&mut editable_text.text);
+    editable_text.cursor_position = Some(cursor);
+    editable_text.selection_start = None;
+    commands.trigger_targets(
+        TextChanged {
+            change,
+            new_text: editable_text.text.clone(),
+            old_cursor_position,
+            new_cursor_position: Some(cursor),
+        },
+        entity,
+    );
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use bevy::ecs::system::RunSystemOnce;
+    use bevy_text_editing::CharPosition;
+
+    #[test]
+    fn closing_a_tab_shows_the_next_one() {
+        let mut world = World::new();
+        let [a, b, c] = [(); 3].map(|()| world.spawn_empty().id());
+        let mut editor = CodeEditor {
+            documents: vec![a, b, c],
+            active: Some(b),
+            closing: Some(b),
+            ..default()
+        };
+        editor.remove(b);
+        assert_eq!(editor.documents, [a, c]);
+        assert_eq!(editor.active, Some(c));
+        assert_eq!(editor.closing, None);
+        editor.remove(c);
+        assert_eq!(editor.active, Some(a));
+        editor.remove(a);
+        assert_eq!(editor.active, None);
+    }
+
+    #[test]
+    fn edits_highlight_the_changed_lines() {
+        let mut app = App::new();
+        app.add_plugins((MinimalPlugins, AssetPlugin::default()))
+            .init_asset::<Font>()
+            .init_resource::<Theme>()
+            .add_observer(on_text_changed);
+        let text = "fn a() {}\nlet b = 1;";
+        let (document, tokens) = Document::new(AssetPath::from("a.rs"), Language::Rust, text, true);
+        let highlights = token_colors(tokens, &app.world().resource::<Theme>().code);
+        let entity = app
+            .world_mut()
+            .spawn((
+                document,
+                EditableText::new(text),
+                TextHighlights(highlights),
+            ))
+            .id();
+
+        app.world_mut()
+            .run_system_once(
+                move |mut commands: Commands,
+                      mut q: Query<(&mut EditableText, &mut TextEditHistory)>| {
+                    let (mut editable_text, mut history) = q.get_mut(entity).unwrap();
+                    let change = TextChange::new((CharPosition(14), CharPosition(15)), "// b");
+                    apply_change(
+                        &mut commands,
+                        entity,
+                        &mut editable_text,
+                        &mut history,
+                        change,
+                    );
+                },
+            )
+            .unwrap();
+
+        let world = app.world();
+        assert_eq!(
+            world.get::<EditableText>(entity).unwrap().text,
+            "fn a() {}\nlet // b = 1;"
+        );
+        let document = world.get::<Document>(entity).unwrap();
+        assert!(document.dirty);
+        assert_eq!(document.text_to_save("a\nb"), "a\r\nb");
+        let (_, tokens) = Highlighter::new(Language::Rust, "fn a() {}\nlet // b = 1;");
+        assert_eq!(
+            world.get::<TextHighlights>(entity).unwrap().0,
+            token_colors(tokens, &world.resource::<Theme>().code)
+        );
+    }
+}
@@ -0,0 +1,205 @@
+//! Finding and replacing text in a document
+
+use bevy::prelude::*;
+use bevy_text_editing::{text_change::TextChange, CharPosition, EditableText};
+
+/// The start and end of a match, in characters
+pub type Match = (CharPosition, CharPosition);
+
+/// The state of the find and replace bar of a code editor pane
+#[derive(Default, Debug)]
+pub struct FindState {
+    /// Whether the bar is shown
+    pub open: bool,
+    /// The searched text
+    pub query: String,
+    /// The text replacing the matches
+    pub replacement: String,
+    /// Whether the search matches the case of the query
+    pub case_sensitive: bool,
+    /// The matches in the shown document
+    pub matches: Vec<Match>,
+}
+
+impl FindState {
+    /// Search `text` again, after the query or the text changed
+    pub fn update_matches(&mut self, text: &str) {
+        self.matches = if self.open {
+            find_matches(text, &self.query, self.case_sensitive)
+        } else {
+            Vec::new()
+        };
+    }
+
+    /// The index of the selected match, if a match is selected
+    pub fn selected_match(&self, editable_text: &EditableText) -> Option<usize> {
+        let selection = editable_text.selection_range()?;
+        self.matches.iter().position(|m| *m == selection)
+    }
+
+    /// Select the next match after the cursor, or the previous one when `backwards`
+    pub fn select_match(&self, editable_text: &mut EditableText, backwards: bool) {
+        let cursor = editable_text.cursor_position.unwrap_or_default();
+        let index = if backwards {
+            let start = editable_text
+                .selection_range()
+                .map_or(cursor, |(start, _)| start);
+            previous_match(&self.matches, start)
+        } else {
+            next_match(&self.matches, cursor)
+        };
+        let Some((start, end)) = index.map(|index| self.matches[index]) else {
+            return;
+        };
+        editable_text.selection_start = Some(start);
+        editable_text.cursor_position = Some(end);
+    }
+
+    /// The change replacing the selected match by the replacement text, if a match is selected
+    pub fn replace_match(&self, editable_text: &EditableText) -> Option<TextChange> {
+        let index = self.selected_match(editable_text)?;
+        Some(TextChange::new(
+            self.matches[index],
+            self.replacement.clone(),
+        ))
+    }
+
+    /// The change replacing all the matches in `text` by the replacement text.
+    /// All the matches are replaced by a single change, from the start of the first match to the
+    /// end of the last one, so that they are undone at once.
+    pub fn replace_all(&self, text: &str) -> Option<TextChange> {
+        let (first, last) = (self.matches.first()?, self.matches.last()?);
+        let mut replaced = String::new();
+        let mut chars = text.chars().skip(first.0 .0);
+        let mut position = first.0;
+        for &(start, end) in &self.matches {
+            replaced.extend(chars.by_ref().take(start.0 - position.0));
+            replaced.push_str(&self.replacement);
+            chars.by_ref().take(end.0 - start.0).for_each(drop);
+            position = end;
+        }
+        Some(TextChange::new((first.0, last.1), replaced))
+    }
+}
+
+/// The start and end of the matches of `query` in `text`, in reading order.
+/// Matches don't overlap and don't span lines, so a query containing a line break finds nothing.
+pub fn find_matches(text: &str, query: &str, case_sensitive: bool) -> Vec<Match> {
+    if query.is_empty() || query.contains('\n') {
+        return Vec::new();
+    }
+    let query = query.chars().collect::<Vec<_>>();
+    let mut matches = Vec::new();
+    let mut line_start = 0;
+    for line in text.split('\n') {
+        let chars = line.chars().collect::<Vec<_>>();
+        let mut column = 0;
+        while column + query.len() <= chars.len() {
+            let found = chars[column..column + query.len()]
+                .iter()
+                .zip(&query)
+                .all(|(a, b)| chars_match(*a, *b, case_sensitive));
+            if found {
+                matches.push((
+                    CharPosition(line_start + column),
+                    CharPosition(line_start + column + query.len()),
+                ));
+                column += query.len();
+            } else {
+                column += 1;
+            }
+        }
+        line_start += chars.len() + 1;
+    }
+    matches
+}
+
+fn chars_match(a: char, b: char, case_sensitive: bool) -> bool {
+    if case_sensitive {
+        a == b
+    } else {
+        a.to_lowercase().eq(b.to_lowercase())
+    }
+}
+
+/// The index of the first match starting at or after `position`, wrapping around to the first
+/// match
+pub fn next_match(matches: &[Match], position: CharPosition) -> Option<usize> {
+    if matches.is_empty() {
+        return None;
+    }
+    Some(
+        matches
+            .iter()
+            .position(|(start, _)| *start >= position)
+            .unwrap_or(0),
+    )
+}
+
+/// The index of the last match starting before `position`, wrapping around to the last match
+pub fn previous_match(matches: &[Match], position: CharPosition) -> Option<usize> {
+    if matches.is_empty() {
+        return None;
+    }
+    Some(
+        matches
+            .iter()
+            .rposition(|(start, _)| *start < position)
+            .unwrap_or(matches.len() - 1),
+    )
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn m(start: usize, end: usize) -> Match {
+        (CharPosition(start), CharPosition(end))
+    }
+
+    #[test]
+    fn finds_and_cycles_through_matches() {
+        let text = "let Foo = foo;\nfoofoo";
+        let matches = find_matches(text, "foo", false);
+        assert_eq!(matches, [m(4, 7), m(10, 13), m(15, 18), m(18, 21)]);
+        assert_eq!(find_matches(text, "foo", true).len(), 3);
+        assert!(find_matches(text, "o\nf", false).is_empty());
+
+        assert_eq!(next_match(&matches, CharPosition(5)), Some(1));
+        assert_eq!(next_match(&matches, CharPosition(19)), Some(0));
+        assert_eq!(previous_match(&matches, CharPosition(10)), Some(0));
+        assert_eq!(previous_match(&matches, CharPosition(4)), Some(3));
+        assert_eq!(next_match(&[], CharPosition(0)), None);
+    }
+
+    #[test]
+    fn selects_and_replaces_matches() {
+        let mut editable_text = EditableText::new("a b a\na");
+        let mut find = FindState {
+            open: true,
+            query: "a".to_string(),
+            replacement: "cc".to_string(),
+            ..default()
+        };
+        find.update_matches(&editable_text.text);
+        assert_eq!(find.matches.len(), 3);
+        assert!(find.replace_match(&editable_text).is_none());
+
+        editable_text.cursor_position = Some(CharPosition(1));
+        find.select_match(&mut editable_text, false);
+        assert_eq!(find.selected_match(&editable_text), Some(1));
+        let change = find.replace_match(&editable_text).unwrap();
+        change.apply(&mut editable_text.text);
+        assert_eq!(editable_text.text, "a b cc\na");
+
+        find.update_matches(&editable_text.text);
+        let change = find.replace_all(&editable_text.text).unwrap();
+        assert_eq!(change.range, m(0, 8));
+        change.apply(&mut editable_text.text);
+        assert_eq!(editable_text.text, "cc b cc\ncc");
+
+        find.query = "x".to_string();
+        find.update_matches(&editable_text.text);
+        assert!(find.replace_all(&editable_text.text).is_none());
+    }
+}
@@ -0,0 +1,669 @@
+//! Syntax highlighting of the supported languages, line by line
+//!
+//! The highlighting is lexical only: it finds comments, strings, numbers, keywords and the like,
+//! which is enough for the small files edited in the editor. The [`Highlighter`] of a document
+//! highlights again only the lines changed by an edit, and the lines after them whose start state
+//! changed, like the rest of a block comment being opened.
+
+use std::ops::Range;
+
+use bevy::prelude::*;
+use bevy_editor_styles::CodeStyles;
+use bevy_text_editing::text_change::TextChange;
+
+/// The highlighted tokens of a line, as byte ranges in the line
+pub type LineTokens = Vec<(Range<usize>, TokenKind)>;
+
+/// The languages that are highlighted
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq)]
+pub enum Language {
+    /// Rust scripts
+    Rust,
+    /// RON files, like scenes and `.meta` files
+    Ron,
+    /// TOML files, like `Cargo.toml` and `Bevy.toml`
+    Toml,
+    /// BSN files, describing entities with their components
+    Bsn,
+    /// Text without highlighting
+    #[default]
+    Plain,
+}
+
+impl Language {
+    /// The language of a file, from its extension
+    /// Returns `None` for the files that aren't text.
+    pub fn from_extension(extension: &str) -> Option<Self> {
+        match extension.to_ascii_lowercase().as_str() {
+            "rs" => Some(Language::Rust),
+            "ron" | "meta" => Some(Language::Ron),
+            "toml" => Some(Language::Toml),
+            "bsn" | "proto_bsn" => Some(Language::Bsn),
+            "txt" | "md" | "json" | "wgsl" | "glsl" | "vert" | "frag" | "csv" | "yaml" | "yml"
+            | "ftl" | "js" | "html" | "css" | "xml" | "svg" => Some(Language::Plain),
+            _ => None,
+        }
+    }
+
+    fn keywords(self) -> &'static [&'static str] {
+        match self {
+            Language::Rust => &[
+                "as", "async", "await", "break", "const", "continue", "crate", "dyn", "else",
+                "enum", "extern", "false", "fn", "for", "if", "impl", "in", "let", "loop", "match",
+                "mod", "move", "mut", "pub", "ref", "return", "self", "Self", "static", "struct",
+                "super", "trait", "true", "type", "unsafe", "use", "where", "while",
+            ],
+            Language::Ron | Language::Bsn => &["true", "false", "Some", "None"],
+            Language::Toml => &["true", "false"],
+            Language::Plain => &[],
+        }
+    }
+
+    fn line_comment(self) -> Option<&'static str> {
+        match self {
+            Language::Rust | Language::Ron | Language::Bsn => Some("//"),
+            Language::Toml => Some("#"),
+            Language::Plain => None,
+        }
+    }
+
+    fn has_block_comments(self) -> bool {
+        matches!(self, Language::Rust | Language::Ron | Language::Bsn)
+    }
+}
+
+/// The kind of a highlighted part of a line
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum TokenKind {
+    /// Anything else, like identifiers and punctuation
+    Plain,
+    /// Keywords, and constants like `true`
+    Keyword,
+    /// Type names, and RON struct and enum names
+    Type,
+    /// Functions and macros
+    Function,
+    /// String and character literals
+    String,
+    /// Number literals
+    Number,
+    /// Comments
+    Comment,
+    /// Attributes like `#[derive(...)]`, TOML tables, and BSN asset paths
+    Attribute,
+    /// Struct fields in RON and BSN, and keys in TOML
+    Field,
+}
+
+impl TokenKind {
+    /// The color of the token in the theme
+    pub fn color(self, styles: &CodeStyles) -> Color {
+        match self {
+            TokenKind::Plain => styles.text_color,
+            TokenKind::Keyword => styles.keyword_color,
+            TokenKind::Type => styles.type_color,
+            TokenKind::Function => styles.function_color,
+            TokenKind::String => styles.string_color,
+            TokenKind::Number => styles.number_color,
+            TokenKind::Comment => styles.comment_color,
+            TokenKind::Attribute => styles.attribute_color,
+            TokenKind::Field => styles.field_color,
+        }
+    }
+}
+
+/// The state carried from a line to the next
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq)]
+pub enum LineState {
+    /// The line starts with normal code
+    #[default]
+    Code,
+    /// The line starts inside a block comment, with its nesting depth
+    BlockComment(usize),
+    /// The line starts inside a string literal
+    String,
+    /// The line starts inside a TOML multi-line string, delimited by `"""` or `'''`
+    MultiLineString(char),
+}
+
+/// The tokens of a line, as byte ranges, with the state at the start of the next line
+/// Parts of the line that aren't covered by a token are [`TokenKind::Plain`].
+pub fn highlight_line(language: Language, line: &str, state: LineState) -> (LineTokens, LineState) {
+    let mut tokens = Vec::new();
+    if language == Language::Plain {
+        return (tokens, LineState::Code);
+    }
+    let bytes = line.as_bytes();
+    let mut index = 0;
+    let mut state = state;
+
+    // Finish what was started on the previous lines
+    match state {
+        LineState::Code => {}
+        LineState::BlockComment(depth) => {
+            let (end, depth) = block_comment_end(line, 0, depth);
+            tokens.push((0..end, TokenKind::Comment));
+            index = end;
+            state = if depth == 0 {
+                LineState::Code
+            } else {
+                LineState::BlockComment(depth)
+            };
+        }
+        LineState::String => {
+            let end = string_end(line, 0, b'"');
+            tokens.push((0..end.unwrap_or(line.len()), TokenKind::String));
+            index = end.unwrap_or(line.len());
+            state = if end.is_some() {
+                LineState::Code
+            } else {
+                LineState::String
+            };
+        }
+        LineState::MultiLineString(quote) => {
+            let delimiter = quote.to_string().repeat(3);
+            match line.find(&delimiter) {
+                Some(end) => {
+                    tokens.push((0..end + 3, TokenKind::String));
+                    index = end + 3;
+                    state = LineState::Code;
+                }
+                None => tokens.push((0..line.len(), TokenKind::String)),
+            }
+        }
+    }
+    if state != LineState::Code {
+        return (tokens, state);
+    }
+
+    // TOML lines are mostly `key = value` or `[table]`
+    if language == Language::Toml && index == 0 {
+        let trimmed = line.trim_start();
+        let start = line.len() - trimmed.len();
+        if trimmed.starts_with('[') {
+            let end = line.rfind(']').map_or(line.len(), |end| end + 1);
+            tokens.push((start..end, TokenKind::Attribute));
+            index = end;
+        } else if let Some(equal) = line.find('=').filter(|_| {
+            !trimmed.starts_with('#') && !trimmed.starts_with('"') && !trimmed.starts_with('\'')
+        }) {
+            tokens.push((start..line[..equal].trim_end().len(), TokenKind::Field));
+            index = equal;
+        }
+    }
+
+    while index < bytes.len() {
+        let rest = &line[index..];
+        let c = rest.chars().next().unwrap();
+
+        if let Some(comment) = language.line_comment() {
+            if rest.starts_with(comment) {
+                tokens.push((index..line.len(), TokenKind::Comment));
+                break;
+            }
+        }
+        if language.has_block_comments() && rest.starts_with("/*") {
+            let (end, depth) = block_comment_end(line, index + 2, 1);
+            tokens.push((index..end, TokenKind::Comment));
+            if depth > 0 {
+                return (tokens, LineState::BlockComment(depth));
+            }
+            index = end;
+            continue;
+        }
+        if language == Language::Toml && (rest.starts_with("\"\"\"") || rest.starts_with("'''")) {
+            let quote = c;
+            let delimiter = &rest[..3];
+            match rest[3..].find(delimiter) {
+                Some(end) => {
+                    tokens.push((index..index + 3 + end + 3, TokenKind::String));
+                    index += 3 + end + 3;
+                    continue;
+                }
+                None => {
+                    tokens.push((index..line.len(), TokenKind::String));
+                    return (tokens, LineState::MultiLineString(quote));
+                }
+            }
+        }
+        if c == '"' || (c == '\'' && language == Language::Toml) {
+            match string_end(line, index + 1, c as u8) {
+                Some(end) => {
+                    tokens.push((index..end, TokenKind::String));
+                    index = end;
+                    continue;
+                }
+                None => {
+                    tokens.push((index..line.len(), TokenKind::String));
+                    // Only Rust and RON strings can span lines
+                    let state = if language == Language::Toml || c == '\'' {
+                        LineState::Code
+                    } else {
+                        LineState::String
+                    };
+                    return (tokens, state);
+                }
+            }
+        }
+        if c == '\'' && language != Language::Toml {
+            // A character literal, not a lifetime
+            if let Some(end) = char_literal_end(line, index) {
+                tokens.push((index..end, TokenKind::String));
+                index = end;
+                continue;
+            }
+        }
+        if c == '@' && language == Language::Bsn && rest[1..].starts_with('"') {
+            let end = string_end(line, index + 2, b'"').unwrap_or(line.len());
+            tokens.push((index..end, TokenKind::Attribute));
+            index = end;
+            continue;
+        }
+        if c == '#'
+            && language == Language::Rust
+            && (rest[1..].starts_with('[') || rest[1..].starts_with("!["))
+        {
+            let end = attribute_end(line, index);
+            tokens.push((index..end, TokenKind::Attribute));
+            index = end;
+            continue;
+        }
+        if c.is_ascii_digit() {
+            let end = index
+                + rest
+                    .find(|c: char| !(is_word_char(c) || c == '.'))
+                    .unwrap_or(rest.len());
+            // `1..2` is a range, not a number
+            let end = line[index..end]
+                .find("..")
+                .map_or(end, |range| index + range);
+            tokens.push((index..end, TokenKind::Number));
+            index = end;
+            continue;
+        }
+        if is_word_char(c) {
+            let end = index + rest.find(|c: char| !is_word_char(c)).unwrap_or(rest.len());
+            let word = &line[index..end];
+            let next = line[end..].trim_start();
+            let kind = if language.keywords().contains(&word) {
+                Some(TokenKind::Keyword)
+            } else if next.starts_with('!') && language == Language::Rust && !next.starts_with("!=")
+            {
+                Some(TokenKind::Function)
+            } else if word.starts_with(char::is_uppercase) {
+                Some(TokenKind::Type)
+            } else if next.starts_with('(') && language == Language::Rust {
+                Some(TokenKind::Function)
+            } else if next.starts_with(':')
+                && !next.starts_with("::")
+                && matches!(language, Language::Ron | Language::Bsn)
+            {
+                Some(TokenKind::Field)
+            } else {
+                None
+            };
+            if let Some(kind) = kind {
+                tokens.push((index..end, kind));
+            }
+            index = end;
+            continue;
+        }
+        index += c.len_utf8();
+    }
+    (tokens, LineState::Code)
+}
+
+/// Highlight all the lines of a text, each line starting in the state the previous line ended in
+pub fn highlight_lines(language: Language, text: &str) -> Vec<LineTokens> {
+    let mut state = LineState::Code;
+    text.split('\n')
+        .map(|line| {
+            let (tokens, next_state) = highlight_line(language, line, state);
+            state = next_state;
+            tokens
+        })
+        .collect()
+}
+
+/// What the highlighter knows of a line of the text
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+struct LineInfo {
+    /// The length of the line in bytes, without its line break
+    bytes: usize,
+    /// The length of the line in characters, without its line break
+    chars: usize,
+    /// The state at the start of the next line
+    end_state: LineState,
+}
+
+/// The lines highlighted again after an edit
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct HighlightUpdate {
+    /// The lines of the text before the edit that were replaced
+    pub replaced: Range<usize>,
+    /// The tokens of the lines replacing them
+    pub tokens: Vec<LineTokens>,
+}
+
+/// Highlights a text as it is edited, see the [module docs](self)
+#[derive(Clone, Debug)]
+pub struct Highlighter {
+    language: Language,
+    lines: Vec<LineInfo>,
+}
+
+impl Highlighter {
+    /// Highlight `text`, returning the highlighter and the tokens of every line
+    pub fn new(language: Language, text: &str) -> (Self, Vec<LineTokens>) {
+        let mut highlighter = Self {
+            language,
+            lines: Vec::new(),
+        };
+        let mut state = LineState::Code;
+        let tokens = text
+            .split('\n')
+            .map(|line| {
+                let (tokens, next_state) = highlight_line(language, line, state);
+                highlighter.lines.push(LineInfo {
+                    bytes: line.len(),
+                    chars: line.chars().count(),
+                    end_state: next_state,
+                });
+                state = next_state;
+                tokens
+            })
+            .collect();
+        (highlighter, tokens)
+    }
+
+    /// Highlight the lines changed by `change`, which turned the text into `new_text`
+    pub fn edit(&mut self, change: &TextChange, new_text: &str) -> HighlightUpdate {
+        let first = self.line_of(change.range.0 .0);
+        let last = self.line_of(change.range.1 .0);
+        let inserted_lines = change.new_text.matches('\n').count();
+        let mut state = first
+            .checked_sub(1)
+            .map_or(LineState::Code, |line| self.lines[line].end_state);
+        let start_byte = self.lines[..first]
+            .iter()
+            .map(|line| line.bytes + 1)
+            .sum::<usize>();
+
+        let mut new_lines = Vec::new();
+        let mut tokens = Vec::new();
+        let mut replaced_end = last + 1;
+        for (index, line) in new_text[start_byte.min(new_text.len())..]
+            .split('\n')
+            .enumerate()
+        {
+            // Past the edited lines, the lines are unchanged: stop once one of them starts in the
+            // state it started in before the edit
+            if index > inserted_lines {
+                let old_line = last + index - inserted_lines;
+                if old_line >= self.lines.len() || self.lines[old_line - 1].end_state == state {
+                    break;
+                }
+                replaced_end = old_line + 1;
+            }
+            let (line_tokens, next_state) = highlight_line(self.language, line, state);
+            new_lines.push(LineInfo {
+                bytes: line.len(),
+                chars: line.chars().count(),
+                end_state: next_state,
+            });
+            tokens.push(line_tokens);
+            state = next_state;
+        }
+
+        self.lines.splice(first..replaced_end, new_lines);
+        HighlightUpdate {
+            replaced: first..replaced_end,
+            tokens,
+        }
+    }
+
+    /// The line of the character at `position`
+    fn line_of(&self, position: usize) -> usize {
+        let mut start = 0;
+        for (index, line) in self.lines.iter().enumerate() {
+            if position <= start + line.chars {
+                return index;
+            }
+            start += line.chars + 1;
+        }
+        self.lines.len() - 1
+    }
+}
+
+/// Whether a character is part of identifiers and numbers
+fn is_word_char(c: char) -> bool {
+    c.is_alphanumeric() || c == '_'
+}
+
+/// The end of the block comment whose content starts at `start`, with the nesting depth left if
+/// it doesn't end on this line
+fn block_comment_end(line: &str, start: usize, mut depth: usize) -> (usize, usize) {
+    let mut index = start;
+    while index < line.len() {
+        if line[index..].starts_with("/*") {
+            depth += 1;
+            index += 2;
+        } else if line[index..].starts_with("*/") {
+            depth -= 1;
+            index += 2;
+            if depth == 0 {
+                return (index, 0);
+            }
+        } else {
+            index += line[index..].chars().next().unwrap().len_utf8();
+        }
+    }
+    (line.len(), depth)
+}
+
+/// The end of the string whose content starts at `start`, after the closing `quote`
+fn string_end(line: &str, start: usize, quote: u8) -> Option<usize> {
+    let bytes = line.as_bytes();
+    let mut index = start;
+    while index < bytes.len() {
+        match bytes[index] {
+            b'\\' if quote != b'\'' => index += 2,
+            byte if byte == quote => return Some(index + 1),
+            _ => index += 1,
+        }
+    }
+    None
+}
+
+/// The end of the character literal at `start`, or `None` if it's a lifetime
+fn char_literal_end(line: &str, start: usize) -> Option<usize> {
+    let rest = &line[start + 1..];
+    let mut chars = rest.char_indices();
+    let (_, first) = chars.next()?;
+    let content = if first == '\\' {
+        // Escapes like '\n' and '\u{1F600}'
+        rest.find('\'').filter(|end| *end > 1)?
+    } else {
+        first.len_utf8()
+    };
+    rest[content..]
+        .starts_with('\'')
+        .then_some(start + 1 + content + 1)
+}
+
+/// The end of the attribute starting at `start`, after its closing bracket
+fn attribute_end(line: &str, start: usize) -> usize {
+    let mut depth = 0;
+    for (index, c) in line[start..].char_indices() {
+        match c {
+            '[' => depth += 1,
+            ']' => {
+                depth -= 1;
+                if depth == 0 {
+                    return start + index + 1;
+                }
+            }
+            _ => {}
+        }
+    }
+    line.len()
+}
+
+#[cfg(test)]
+mod tests {
+    use bevy_text_editing::CharPosition;
+
+    use super::*;
+
+    fn tokens(language: Language, line: &str) -> Vec<(&str, TokenKind)> {
+        highlight_line(language, line, LineState::Code)
+            .0
+            .into_iter()
+            .map(|(range, kind)| (&line[range], kind))
+            .collect()
+    }
+
+    #[test]
+    fn highlights_rust() {
+        assert_eq!(
+            tokens(
+                Language::Rust,
+                r#"pub fn new_system(mut commands: Commands) { info!("a\"b"); } // done"#
+            ),
+            [
+                ("pub", TokenKind::Keyword),
+                ("fn", TokenKind::Keyword),
+                ("new_system", TokenKind::Function),
+                ("mut", TokenKind::Keyword),
+                ("Commands", TokenKind::Type),
+                ("info", TokenKind::Function),
+                (r#""a\"b""#, TokenKind::String),
+                ("// done", TokenKind::Comment),
+            ]
+        );
+        assert_eq!(
+            tokens(
+                Language::Rust,
+                "#[derive(Component)] let c = 'x'; 1..10 &'a"
+            ),
+            [
+                ("#[derive(Component)]", TokenKind::Attribute),
+                ("let", TokenKind::Keyword),
+                ("'x'", TokenKind::String),
+                ("1", TokenKind::Number),
+                ("10", TokenKind::Number),
+            ]
+        );
+    }
+
+    #[test]
+    fn carries_state_across_lines() {
+        let highlighted = highlight_lines(Language::Rust, "let a = /* one\ntwo */ 2;");
+        assert_eq!(highlighted[0][1], (8..14, TokenKind::Comment));
+        assert_eq!(highlighted[1][0], (0..6, TokenKind::Comment));
+        assert_eq!(highlighted[1][1], (7..8, TokenKind::Number));
+
+        let highlighted = highlight_lines(Language::Toml, "text = \"\"\"\ninside\n\"\"\" # end");
+        assert_eq!(highlighted[1], [(0..6, TokenKind::String)]);
+        assert_eq!(highlighted[2][1], (4..9, TokenKind::Comment));
+    }
+
+    #[test]
+    fn highlights_data_languages() {
+        assert_eq!(
+            tokens(Language::Ron, "(is_srgb: true, sampler: Default)"),
+            [
+                ("is_srgb", TokenKind::Field),
+                ("true", TokenKind::Keyword),
+                ("sampler", TokenKind::Field),
+                ("Default", TokenKind::Type),
+            ]
+        );
+        assert_eq!(
+            tokens(Language::Toml, "[[import_presets.presets]]"),
+            [("[[import_presets.presets]]", TokenKind::Attribute)]
+        );
+        assert_eq!(
+            tokens(Language::Toml, "folder = \"ui\" # comment"),
+            [
+                ("folder", TokenKind::Field),
+                ("\"ui\"", TokenKind::String),
+                ("# comment", TokenKind::Comment),
+            ]
+        );
+        assert_eq!(
+            tokens(Language::Bsn, "Sprite { image: @\"player.png\" }"),
+            [
+                ("Sprite", TokenKind::Type),
+                ("image", TokenKind::Field),
+                ("@\"player.png\"", TokenKind::Attribute),
+            ]
+        );
+    }
+
+    /// Apply `change` to `text` and check that the highlighter highlights it like the whole text
+    /// highlighted again
+    fn check_edit(
+        highlighter: &mut Highlighter,
+        tokens: &mut Vec<LineTokens>,
+        text: &mut String,
+        change: TextChange,
+    ) -> HighlightUpdate {
+        change.apply(text);
+        let update = highlighter.edit(&change, text);
+        tokens.splice(update.replaced.clone(), update.tokens.clone());
+        assert_eq!(*tokens, highlight_lines(Language::Rust, text), "{text:?}");
+        assert_eq!(
+            highlighter.lines,
+            Highlighter::new(Language::Rust, text).0.lines
+        );
+        update
+    }
+
+    #[test]
+    fn highlights_only_the_edited_lines() {
+        let mut text = "fn a() {}\nfn b() {}\nfn c() {}\nfn d() {}".to_string();
+        let (mut highlighter, mut tokens) = Highlighter::new(Language::Rust, &text);
+
+        // Typing in a line
+        let change = TextChange::insert_change(CharPosition(13), "x");
+        let update = check_edit(&mut highlighter, &mut tokens, &mut text, change);
+        assert_eq!(update.replaced, 1..2);
+        assert_eq!(update.tokens.len(), 1);
+
+        // Joining and splitting lines
+        let change = TextChange::remove_change((CharPosition(9), CharPosition(10)));
+        let update = check_edit(&mut highlighter, &mut tokens, &mut text, change);
+        assert_eq!(update.replaced, 0..2);
+        assert_eq!(update.tokens.len(), 1);
+        let change = TextChange::insert_change(CharPosition(9), "\n\n");
+        let update = check_edit(&mut highlighter, &mut tokens, &mut text, change);
+        assert_eq!(update.replaced, 0..1);
+        assert_eq!(update.tokens.len(), 3);
+    }
+
+    #[test]
+    fn highlights_the_lines_whose_state_changed() {
+        let mut text = "let a = 1;\nlet b = 2;\nlet c = 3; */\nlet d = 4;".to_string();
+        let (mut highlighter, mut tokens) = Highlighter::new(Language::Rust, &text);
+
+        // Opening a block comment comments the lines up to its end
+        let change = TextChange::insert_change(CharPosition(10), " /*");
+        let update = check_edit(&mut highlighter, &mut tokens, &mut text, change);
+        assert_eq!(update.replaced, 0..3);
+
+        // Closing it again
+        let change = TextChange::remove_change((CharPosition(10), CharPosition(13)));
+        let update = check_edit(&mut highlighter, &mut tokens, &mut text, change);
+        assert_eq!(update.replaced, 0..3);
+
+        // Replacing everything
+        let len = text.chars().count();
+        let change = TextChange::new((CharPosition(0), CharPosition(len)), "\"\n\"");
+        let update = check_edit(&mut highlighter, &mut tokens, &mut text, change);
+        assert_eq!(update.replaced, 0..4);
+        let change = TextChange::remove_change((CharPosition(0), CharPosition(3)));
+        check_edit(&mut highlighter, &mut tokens, &mut text, change);
+        assert_eq!(text, "");
+    }
+}
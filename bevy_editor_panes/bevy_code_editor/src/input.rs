@@ -0,0 +1,245 @@
+//! The shortcuts of the code editor, and the actions of its find bar and save prompt
+//!
+//! The documents are edited by their [`EditableText`], with the shortcuts of the
+//! [`TextEditKeymap`](bevy_text_editing::keymap::TextEditKeymap).
+
+use bevy::{
+    input::keyboard::{Key, KeyboardInput},
+    prelude::*,
+};
+use bevy_field_forms::input_field::InputField;
+use bevy_focus::{Focus, SetFocus};
+use bevy_text_editing::{
+    history::TextEditHistory, keymap::Modifiers, CharPosition, EditableText, SetCursorPosition,
+};
+
+use crate::{
+    document::{apply_change, CodeEditor, Document},
+    io::{save_document, LastCodeEditor},
+    ui::{CodeEditorPane, FindField},
+};
+
+/// An action of the find bar
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub(crate) enum FindAction {
+    /// Toggle matching the case of the query
+    ToggleCase,
+    /// Select the next match
+    Next,
+    /// Select the previous match
+    Previous,
+    /// Replace the selected match, then select the next one
+    Replace,
+    /// Replace all the matches
+    ReplaceAll,
+    /// Close the find bar
+    Close,
+}
+
+/// An answer to the prompt shown when closing an edited document
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub(crate) enum PromptAction {
+    /// Save the document, then close it
+    Save,
+    /// Close the document without saving it
+    Discard,
+    /// Keep the document open
+    Cancel,
+}
+
+/// Save the focused document with Ctrl+S (Cmd+S on macOS), and open the find bar with Ctrl+F,
+/// searching the selected text
+#[expect(clippy::too_many_arguments)]
+pub(crate) fn document_shortcuts(
+    mut commands: Commands,
+    mut documents: Query<(Entity, &mut Document, &EditableText), With<Focus>>,
+    parents: Query<&ChildOf>,
+    mut panes: Query<(&mut CodeEditor, &CodeEditorPane)>,
+    mut query_fields: Query<&mut InputField<String>>,
+    mut events: EventReader<KeyboardInput>,
+    keys: Res<ButtonInput<KeyCode>>,
+    asset_server: Res<AssetServer>,
+) {
+    let Ok((entity, mut document, editable_text)) = documents.single_mut() else {
+        events.clear();
+        return;
+    };
+    if !Modifiers::pressed(&keys).primary() {
+        events.clear();
+        return;
+    }
+    for event in events.read() {
+        if !event.state.is_pressed() {
+            continue;
+        }
+        match event.key_code {
+            KeyCode::KeyS => save_document(
+                &mut commands,
+                &asset_server,
+                entity,
+                &mut document,
+                &editable_text.text,
+                false,
+            ),
+            KeyCode::KeyF => {
+                let Some((mut editor, pane)) = parents
+                    .iter_ancestors(entity)
+                    .find(|&ancestor| panes.contains(ancestor))
+                    .and_then(|ancestor| panes.get_mut(ancestor).ok())
+                else {
+                    continue;
+                };
+                editor.find.open = true;
+                if let Some(text) = editable_text
+                    .get_selected_text()
+                    .filter(|text| !text.contains('\n'))
+                {
+                    editor.find.query.clone_from(&text);
+                    if let Ok(mut field) = query_fields.get_mut(pane.query_field) {
+                        field.value = text;
+                    }
+                }
+                commands.trigger_targets(SetFocus, pane.query_field);
+                commands.trigger_targets(
+                    SetCursorPosition(CharPosition(editor.find.query.chars().count())),
+                    pane.query_field,
+                );
+            }
+            _ => {}
+        }
+    }
+}
+
+/// Search with Enter and close the find bar with Escape, while a field of the find bar is focused
+pub(crate) fn find_bar_input(
+    mut commands: Commands,
+    focused_fields: Query<Entity, (With<FindField>, With<Focus>)>,
+    parents: Query<&ChildOf>,
+    panes: Query<(), With<CodeEditorPane>>,
+    mut events: EventReader<KeyboardInput>,
+    keys: Res<ButtonInput<KeyCode>>,
+) {
+    let Some(pane) = focused_fields.single().ok().and_then(|field| {
+        parents
+            .iter_ancestors(field)
+            .find(|&ancestor| panes.contains(ancestor))
+    }) else {
+        events.clear();
+        return;
+    };
+    let shift = keys.any_pressed([KeyCode::ShiftLeft, KeyCode::ShiftRight]);
+    for event in events.read() {
+        if !event.state.is_pressed() {
+            continue;
+        }
+        let action = match event.logical_key {
+            Key::Enter if shift => FindAction::Previous,
+            Key::Enter => FindAction::Next,
+            Key::Escape => FindAction::Close,
+            _ => continue,
+        };
+        commands.run_system_cached_with(run_find_action, (pane, action));
+    }
+}
+
+/// Run an action of the find bar of the pane `root` on its active document
+pub(crate) fn run_find_action(
+    In((root, action)): In<(Entity, FindAction)>,
+    mut commands: Commands,
+    mut editors: Query<&mut CodeEditor>,
+    mut documents: Query<(&mut EditableText, &mut TextEditHistory), With<Document>>,
+) {
+    let Ok(mut editor) = editors.get_mut(root) else {
+        return;
+    };
+    let active = editor.active;
+    let mut document = active.and_then(|active| documents.get_mut(active).ok());
+    match action {
+        FindAction::ToggleCase => editor.find.case_sensitive = !editor.find.case_sensitive,
+        FindAction::Close => {
+            editor.find.open = false;
+            if let Some(active) = active {
+                commands.trigger_targets(SetFocus, active);
+            }
+        }
+        FindAction::Next | FindAction::Previous => {
+            if let Some((editable_text, _)) = &mut document {
+                editor
+                    .find
+                    .select_match(editable_text, action == FindAction::Previous);
+            }
+        }
+        FindAction::Replace => {
+            let (Some(active), Some((editable_text, history))) = (active, &mut document) else {
+                return;
+            };
+            if let Some(change) = editor.find.replace_match(editable_text) {
+                apply_change(&mut commands, active, editable_text, history, change);
+                editor.find.update_matches(&editable_text.text);
+            }
+            editor.find.select_match(editable_text, false);
+        }
+        FindAction::ReplaceAll => {
+            let (Some(active), Some((editable_text, history))) = (active, &mut document) else {
+                return;
+            };
+            if let Some(change) = editor.find.replace_all(&editable_text.text) {
+                apply_change(&mut commands, active, editable_text, history, change);
+            }
+        }
+    }
+}
+
+/// Answer the prompt shown when closing the edited document of the pane `root`
+pub(crate) fn run_prompt_action(
+    In((root, action)): In<(Entity, PromptAction)>,
+    mut commands: Commands,
+    mut editors: Query<&mut CodeEditor>,
+    mut documents: Query<(&mut Document, &EditableText)>,
+    asset_server: Res<AssetServer>,
+) {
+    let Ok(mut editor) = editors.get_mut(root) else {
+        return;
+    };
+    let Some(entity) = editor.closing.take() else {
+        return;
+    };
+    match action {
+        PromptAction::Save => {
+            if let Ok((mut document, editable_text)) = documents.get_mut(entity) {
+                save_document(
+                    &mut commands,
+                    &asset_server,
+                    entity,
+                    &mut document,
+                    &editable_text.text,
+                    true,
+                );
+            }
+        }
+        PromptAction::Discard => {
+            editor.remove(entity);
+            commands.entity(entity).despawn();
+        }
+        PromptAction::Cancel => {}
+    }
+}
+
+/// Open the next files in the pane of the focused document
+pub(crate) fn track_last_editor(
+    focused: Query<Entity, (With<Document>, With<Focus>)>,
+    parents: Query<&ChildOf>,
+    editors: Query<(), With<CodeEditor>>,
+    mut last_editor: ResMut<LastCodeEditor>,
+) {
+    let Some(editor) = focused.single().ok().and_then(|document| {
+        parents
+            .iter_ancestors(document)
+            .find(|&ancestor| editors.contains(ancestor))
+    }) else {
+        return;
+    };
+    if last_editor.0 != Some(editor) {
+        last_editor.0 = Some(editor);
+    }
+}
@@ -0,0 +1,237 @@
+//! Loading and saving the edited files through their asset source
+
+use bevy::{
+    asset::AssetPath,
+    prelude::*,
+    tasks::{block_on, poll_once, IoTaskPool, Task},
+};
+use bevy_editor_core::OpenAsset;
+use bevy_editor_styles::Theme;
+use bevy_focus::SetFocus;
+use bevy_pane_layout::prelude::*;
+use bevy_text_editing::{EditableText, TextHighlights};
+
+use crate::{
+    document::{token_colors, CodeEditor, Document},
+    highlight::Language,
+    ui::{spawn_document, CodeEditorPane},
+    CODE_EDITOR_PANE_NAME,
+};
+
+/// The code editor pane the files are opened in: the pane last used, if it's still open
+#[derive(Resource, Default)]
+pub(crate) struct LastCodeEditor(pub Option<Entity>);
+
+/// The task reading a file to open it in a new tab
+#[derive(Component)]
+pub(crate) struct LoadDocumentTask {
+    path: AssetPath<'static>,
+    language: Language,
+    task: Task<Result<String, String>>,
+}
+
+/// The task writing an edited file
+#[derive(Component)]
+pub(crate) struct SaveDocumentTask {
+    document: Entity,
+    path: AssetPath<'static>,
+    /// Close the document once it's saved
+    close: bool,
+    task: Task<Result<(), String>>,
+}
+
+/// Open the text files requested by [`OpenAsset`] events in a new tab, or show their tab if they
+/// are already open, and open the code editor pane to show them
+pub(crate) fn open_assets(
+    mut events: EventReader<OpenAsset>,
+    mut commands: Commands,
+    mut open_pane: EventWriter<OpenPane>,
+    mut editors: Query<&mut CodeEditor>,
+    documents: Query<(Entity, &Document)>,
+    loading: Query<&LoadDocumentTask>,
+    asset_server: Res<AssetServer>,
+) {
+    for OpenAsset(path) in events.read() {
+        let Some(language) = path
+            .path()
+            .extension()
+            .and_then(|extension| Language::from_extension(&extension.to_string_lossy()))
+        else {
+            continue;
+        };
+        open_pane.write(OpenPane(CODE_EDITOR_PANE_NAME.to_string()));
+        // Keep the edits of a file opened again
+        if let Some((entity, _)) = documents
+            .iter()
+            .find(|(_, document)| document.path == *path)
+        {
+            for mut editor in &mut editors {
+                if editor.documents.contains(&entity) {
+                    editor.activate(entity);
+                }
+            }
+            continue;
+        }
+        if loading.iter().any(|task| task.path == *path) {
+            continue;
+        }
+
+        let asset_server = asset_server.clone();
+        let task_path = path.clone();
+        let task = IoTaskPool::get().spawn(async move {
+            let source = asset_server
+                .get_source(task_path.source().clone())
+                .map_err(|error| error.to_string())?;
+            let mut reader = source
+                .reader()
+                .read(task_path.path())
+                .await
+                .map_err(|error| error.to_string())?;
+            let mut bytes = Vec::new();
+            reader
+                .read_to_end(&mut bytes)
+                .await
+                .map_err(|error| error.to_string())?;
+            String::from_utf8(bytes).map_err(|_| "The file isn't valid UTF-8 text".to_string())
+        });
+        commands.spawn(LoadDocumentTask {
+            path: path.clone(),
+            language,
+            task,
+        });
+    }
+}
+
+/// Open the loaded files in a new tab of the last used code editor pane.
+/// The files wait for a code editor pane to be opened.
+pub(crate) fn poll_load_tasks(
+    mut commands: Commands,
+    mut tasks: Query<(Entity, &mut LoadDocumentTask)>,
+    mut editors: Query<(Entity, &mut CodeEditor, &CodeEditorPane)>,
+    mut last_editor: ResMut<LastCodeEditor>,
+    theme: Res<Theme>,
+) {
+    if last_editor.0.is_none_or(|entity| !editors.contains(entity)) {
+        last_editor.0 = editors.iter().next().map(|(entity, ..)| entity);
+    }
+    let Some(Ok((_, mut editor, pane))) = last_editor.0.map(|entity| editors.get_mut(entity))
+    else {
+        return;
+    };
+    for (entity, mut task) in &mut tasks {
+        let Some(result) = block_on(poll_once(&mut task.task)) else {
+            continue;
+        };
+        commands.entity(entity).despawn();
+        match result {
+            Ok(text) => {
+                // The editable text and the highlighter split lines on `\n` only
+                let crlf = text.contains("\r\n");
+                let text = if crlf {
+                    text.replace("\r\n", "\n")
+                } else {
+                    text
+                };
+                let (document, tokens) =
+                    Document::new(task.path.clone(), task.language, &text, crlf);
+                let document = spawn_document(&mut commands, pane, &theme)
+                    .insert((
+                        document,
+                        EditableText::new(text),
+                        TextHighlights(token_colors(tokens, &theme.code)),
+                    ))
+                    .id();
+                editor.documents.push(document);
+                editor.activate(document);
+                editor.error = None;
+                commands.trigger_targets(SetFocus, document);
+            }
+            Err(error) => editor.error = Some(format!("Failed to open {}: {}", task.path, error)),
+        }
+    }
+}
+
+/// Write the text of a document to its asset source, closing the document once it's written if
+/// `close`
+pub(crate) fn save_document(
+    commands: &mut Commands,
+    asset_server: &AssetServer,
+    entity: Entity,
+    document: &mut Document,
+    text: &str,
+    close: bool,
+) {
+    let asset_server = asset_server.clone();
+    let path = document.path.clone();
+    let text = document.text_to_save(text);
+    // Edits made while saving make the document dirty again
+    document.dirty = false;
+    let task_path = path.clone();
+    let task = IoTaskPool::get().spawn(async move {
+        let source = asset_server
+            .get_source(task_path.source().clone())
+            .map_err(|error| error.to_string())?;
+        let writer = source.writer().map_err(|error| error.to_string())?;
+        writer
+            .write_bytes(task_path.path(), text.as_bytes())
+            .await
+            .map_err(|error| error.to_string())
+    });
+    commands.spawn(SaveDocumentTask {
+        document: entity,
+        path,
+        close,
+        task,
+    });
+}
+
+/// Report the errors of the saved files, and close the documents saved before closing
+pub(crate) fn poll_save_tasks(
+    mut commands: Commands,
+    mut tasks: Query<(Entity, &mut SaveDocumentTask)>,
+    mut editors: Query<&mut CodeEditor>,
+    mut documents: Query<&mut Document>,
+) {
+    for (entity, mut task) in &mut tasks {
+        let Some(result) = block_on(poll_once(&mut task.task)) else {
+            continue;
+        };
+        commands.entity(entity).despawn();
+        let Some(mut editor) = editors
+            .iter_mut()
+            .find(|editor| editor.documents.contains(&task.document))
+        else {
+            continue;
+        };
+        match result {
+            Ok(()) => {
+                editor.error = None;
+                if task.close && documents.get(task.document).is_ok_and(|d| !d.dirty) {
+                    editor.remove(task.document);
+                    commands.entity(task.document).despawn();
+                }
+            }
+            Err(error) => {
+                editor.error = Some(format!("Failed to save {}: {}", task.path, error));
+                if let Ok(mut document) = documents.get_mut(task.document) {
+                    document.dirty = true;
+                }
+            }
+        }
+    }
+}
+
+/// Close a document, or ask whether to save its edits first
+pub(crate) fn close_document(
+    commands: &mut Commands,
+    editor: &mut CodeEditor,
+    entity: Entity,
+    document: &Document,
+) {
+    if document.dirty {
+        editor.closing = Some(entity);
+    } else {
+        editor.remove(entity);
+        commands.entity(entity).despawn();
+    }
+}
@@ -0,0 +1,70 @@
+//! A pane editing the text files of the project, like scripts, scenes and settings.
+//!
+//! Double-clicking a text file in the asset browser opens it in a new tab of the code editor,
+//! which highlights Rust, RON, TOML and BSN files. The edits are saved with Ctrl+S (Cmd+S on
+//! macOS), through the asset source of the file, and closing an edited tab asks whether to save
+//! it. Ctrl+F opens a bar to find and replace text. The documents are
+//! [`EditableText`](bevy_text_editing::EditableText)s, so the other shortcuts, like undo and word
+//! motions, are those of the [`TextEditKeymap`](bevy_text_editing::keymap::TextEditKeymap) of the
+//! text fields.
+
+use bevy::prelude::*;
+use bevy_clipboard::ClipboardPlugin;
+use bevy_editor_core::OpenAsset;
+use bevy_field_forms::FieldFormsPlugin;
+use bevy_focus::FocusPlugin;
+use bevy_pane_layout::{prelude::*, PaneLayoutSet};
+use bevy_text_editing::EditableTextPlugin;
+
+pub mod document;
+pub mod find;
+pub mod highlight;
+mod input;
+mod io;
+mod ui;
+
+pub use document::CodeEditor;
+
+/// The name the code editor pane is registered with
+pub const CODE_EDITOR_PANE_NAME: &str = "Code Editor";
+
+/// Plugin for the code editor pane
+pub struct CodeEditorPanePlugin;
+
+impl Plugin for CodeEditorPanePlugin {
+    fn build(&self, app: &mut App) {
+        if !app.is_plugin_added::<FocusPlugin>() {
+            app.add_plugins(FocusPlugin);
+        }
+        if !app.is_plugin_added::<ClipboardPlugin>() {
+            app.add_plugins(ClipboardPlugin);
+        }
+        if !app.is_plugin_added::<FieldFormsPlugin>() {
+            app.add_plugins(FieldFormsPlugin);
+        }
+        if !app.is_plugin_added::<EditableTextPlugin>() {
+            app.add_plugins(EditableTextPlugin);
+        }
+
+        app.register_pane(CODE_EDITOR_PANE_NAME, ui::on_pane_creation);
+
+        app.init_resource::<io::LastCodeEditor>()
+            .add_event::<OpenAsset>()
+            .add_observer(document::on_text_changed)
+            .add_systems(
+                Update,
+                (
+                    io::open_assets.before(PaneLayoutSet),
+                    io::poll_load_tasks,
+                    io::poll_save_tasks,
+                    input::track_last_editor,
+                    input::document_shortcuts,
+                    input::find_bar_input,
+                    ui::update_matches,
+                    ui::render_code_editors,
+                    ui::update_gutters,
+                )
+                    .chain(),
+            );
+    }
+}
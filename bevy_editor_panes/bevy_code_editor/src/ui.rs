@@ -0,0 +1,653 @@
+//! The code editor pane: the tabs in the header, the prompt to save a closed document, the find
+//! and replace bar, and the documents with their line numbers
+//!
+//! The documents are [`EditableText`]s, which lay out and scroll their text. The line numbers of
+//! the gutter are placed on the laid out lines, and spawned again only when the document scrolls
+//! or its lines change.
+
+use bevy::{color::palettes::tailwind, prelude::*};
+use bevy_editor_styles::Theme;
+use bevy_field_forms::{
+    input_field::{InputField, ValueChanged},
+    validate_highlight::SimpleBorderHighlight,
+};
+use bevy_pane_layout::prelude::*;
+use bevy_text_editing::{EditableText, EditableTextLayout, TextMarks};
+
+use crate::{
+    document::{CodeEditor, Document},
+    input::{run_find_action, run_prompt_action, FindAction, PromptAction},
+    io::{close_document, LastCodeEditor},
+};
+
+/// The font size of the code
+pub const FONT_SIZE: f32 = 13.0;
+/// The width of a character of the monospace code font
+pub const CHAR_WIDTH: f32 = FONT_SIZE * 0.6;
+/// The space between the gutter and the code
+pub const TEXT_PADDING: f32 = 6.0;
+
+/// The entities of a code editor pane, on its content entity with its [`CodeEditor`]
+#[derive(Component)]
+#[require(CodeEditor)]
+pub(crate) struct CodeEditorPane {
+    title: Entity,
+    tab_bar: Entity,
+    prompt_bar: Entity,
+    prompt_text: Entity,
+    find_bar: Entity,
+    find_count: Entity,
+    case_toggle: Entity,
+    pub(crate) query_field: Entity,
+    gutter: Entity,
+    documents: Entity,
+    placeholder: Entity,
+    /// The labels of the shown tabs, and whether they are active
+    tabs: Vec<(Entity, String, bool)>,
+    /// The document, scroll, number of lines, cursor line and height the gutter was spawned for
+    gutter_state: Option<(Entity, f32, usize, Option<usize>, f32)>,
+}
+
+/// A text field of the find bar
+#[derive(Component, Clone, Copy, PartialEq, Eq)]
+pub(crate) enum FindField {
+    /// The searched text
+    Query,
+    /// The replacement text
+    Replacement,
+}
+
+/// Spawn the content of a code editor pane
+pub(crate) fn on_pane_creation(
+    structure: In<PaneStructure>,
+    mut commands: Commands,
+    theme: Res<Theme>,
+) {
+    let root = commands
+        .entity(structure.content)
+        .insert(Node {
+            width: Val::Percent(100.0),
+            height: Val::Percent(100.0),
+            flex_direction: FlexDirection::Column,
+            ..default()
+        })
+        .id();
+
+    let tab_bar = commands
+        .spawn((
+            Node {
+                margin: UiRect::left(Val::Px(10.)),
+                column_gap: Val::Px(2.0),
+                overflow: Overflow::clip(),
+                ..default()
+            },
+            ChildOf(structure.header),
+        ))
+        .id();
+    let title = commands
+        .spawn((
+            Text::default(),
+            TextFont {
+                font: theme.text.font.clone(),
+                font_size: 12.,
+                ..default()
+            },
+            TextColor(theme.text.low_priority),
+            Node {
+                margin: UiRect::left(Val::Px(10.)),
+                ..default()
+            },
+            ChildOf(structure.header),
+        ))
+        .id();
+
+    let prompt_bar = spawn_bar(&mut commands, root);
+    let prompt_text = commands
+        .spawn((
+            Text::default(),
+            TextFont {
+                font: theme.text.font.clone(),
+                font_size: 11.,
+                ..default()
+            },
+            TextColor(theme.text.text_color),
+            ChildOf(prompt_bar),
+        ))
+        .id();
+    for (label, action) in [
+        ("Save", PromptAction::Save),
+        ("Discard", PromptAction::Discard),
+        ("Cancel", PromptAction::Cancel),
+    ] {
+        spawn_button(&mut commands, label, &theme)
+            .insert(ChildOf(prompt_bar))
+            .observe(on_click(move |mut commands: Commands| {
+                commands.run_system_cached_with(run_prompt_action, (root, action));
+            }));
+    }
+
+    let find_bar = spawn_bar(&mut commands, root);
+    let query_field = spawn_find_field(&mut commands, &theme, root, FindField::Query)
+        .insert(ChildOf(find_bar))
+        .id();
+    let case_toggle = spawn_find_button(&mut commands, "Aa", &theme, root, FindAction::ToggleCase)
+        .insert(ChildOf(find_bar))
+        .id();
+    let find_count = commands
+        .spawn((
+            Text::default(),
+            TextFont {
+                font: theme.text.font.clone(),
+                font_size: 11.,
+                ..default()
+            },
+            TextColor(theme.text.low_priority),
+            Node {
+                min_width: Val::Px(60.0),
+                ..default()
+            },
+            ChildOf(find_bar),
+        ))
+        .id();
+    spawn_find_button(
+        &mut commands,
+        "Previous",
+        &theme,
+        root,
+        FindAction::Previous,
+    )
+    .insert(ChildOf(find_bar));
+    spawn_find_button(&mut commands, "Next", &theme, root, FindAction::Next)
+        .insert(ChildOf(find_bar));
+    spawn_find_field(&mut commands, &theme, root, FindField::Replacement).insert(ChildOf(find_bar));
+    spawn_find_button(&mut commands, "Replace", &theme, root, FindAction::Replace)
+        .insert(ChildOf(find_bar));
+    spawn_find_button(
+        &mut commands,
+        "Replace all",
+        &theme,
+        root,
+        FindAction::ReplaceAll,
+    )
+    .insert(ChildOf(find_bar));
+    spawn_find_button(&mut commands, "Close", &theme, root, FindAction::Close)
+        .insert(ChildOf(find_bar));
+
+    let area = commands
+        .spawn((
+            Node {
+                flex_grow: 1.0,
+                width: Val::Percent(100.0),
+                overflow: Overflow::clip(),
+                ..default()
+            },
+            theme.code.background_color,
+            ChildOf(root),
+        ))
+        .id();
+    let gutter = commands
+        .spawn((
+            Node {
+                height: Val::Percent(100.0),
+                flex_shrink: 0.0,
+                overflow: Overflow::clip(),
+                ..default()
+            },
+            Pickable::IGNORE,
+            ChildOf(area),
+        ))
+        .id();
+    let documents = commands
+        .spawn((
+            Node {
+                flex_grow: 1.0,
+                height: Val::Percent(100.0),
+                padding: UiRect::left(Val::Px(TEXT_PADDING)),
+                ..default()
+            },
+            ChildOf(area),
+        ))
+        .id();
+    let placeholder = commands
+        .spawn((
+            Text::new("Double-click a text file in the asset browser to edit it"),
+            TextFont {
+                font: theme.text.font.clone(),
+                font_size: 12.,
+                ..default()
+            },
+            TextColor(theme.text.low_priority),
+            Node {
+                margin: UiRect::all(Val::Px(TEXT_PADDING)),
+                ..default()
+            },
+            Pickable::IGNORE,
+            ChildOf(documents),
+        ))
+        .id();
+
+    commands.entity(root).insert(CodeEditorPane {
+        title,
+        tab_bar,
+        prompt_bar,
+        prompt_text,
+        find_bar,
+        find_count,
+        case_toggle,
+        query_field,
+        gutter,
+        documents,
+        placeholder,
+        tabs: Vec::new(),
+        gutter_state: None,
+    });
+}
+
+/// Spawn the node of a document in a code editor pane, hidden until it's the active document
+pub(crate) fn spawn_document<'a>(
+    commands: &'a mut Commands,
+    pane: &CodeEditorPane,
+    theme: &Theme,
+) -> EntityCommands<'a> {
+    commands.spawn((
+        Node {
+            display: Display::None,
+            width: Val::Percent(100.0),
+            height: Val::Percent(100.0),
+            ..default()
+        },
+        code_font(),
+        TextColor(theme.code.text_color),
+        ChildOf(pane.documents),
+    ))
+}
+
+/// Spawn a hidden bar of buttons above the code
+fn spawn_bar(commands: &mut Commands, root: Entity) -> Entity {
+    commands
+        .spawn((
+            Node {
+                display: Display::None,
+                column_gap: Val::Px(4.0),
+                row_gap: Val::Px(4.0),
+                padding: UiRect::all(Val::Px(4.0)),
+                align_items: AlignItems::Center,
+                flex_wrap: FlexWrap::Wrap,
+                flex_shrink: 0.0,
+                ..default()
+            },
+            ChildOf(root),
+        ))
+        .id()
+}
+
+/// An observer running `action` when the target is clicked with the primary button
+fn on_click(
+    action: impl Fn(Commands) + Send + Sync + 'static,
+) -> impl Fn(Trigger<Pointer<Click>>, Commands) {
+    move |trigger: Trigger<Pointer<Click>>, commands: Commands| {
+        if trigger.event().button == PointerButton::Primary {
+            action(commands);
+        }
+    }
+}
+
+/// Spawn a button
+fn spawn_button<'a>(commands: &'a mut Commands, label: &str, theme: &Theme) -> EntityCommands<'a> {
+    let mut button_ec = commands.spawn((
+        Button,
+        Node {
+            padding: UiRect::axes(Val::Px(8.0), Val::Px(2.0)),
+            align_items: AlignItems::Center,
+            ..default()
+        },
+        theme.button.background_color,
+        theme.button.border_radius,
+    ));
+    button_ec.with_child((
+        Text(label.to_string()),
+        TextFont {
+            font: theme.text.font.clone(),
+            font_size: 11.0,
+            ..default()
+        },
+        TextColor(theme.text.text_color),
+        Pickable::IGNORE,
+    ));
+    button_ec
+}
+
+/// Spawn a button of the find bar running `action` on the code editor of the pane `root`
+fn spawn_find_button<'a>(
+    commands: &'a mut Commands,
+    label: &str,
+    theme: &Theme,
+    root: Entity,
+    action: FindAction,
+) -> EntityCommands<'a> {
+    let mut button_ec = spawn_button(commands, label, theme);
+    button_ec.observe(on_click(move |mut commands: Commands| {
+        commands.run_system_cached_with(run_find_action, (root, action));
+    }));
+    button_ec
+}
+
+/// Spawn a text field of the find bar of the pane `root`
+fn spawn_find_field<'a>(
+    commands: &'a mut Commands,
+    theme: &Theme,
+    root: Entity,
+    field: FindField,
+) -> EntityCommands<'a> {
+    let mut field_ec = commands.spawn((
+        Node {
+            width: Val::Px(160.0),
+            height: Val::Px(20.0),
+            border: UiRect::all(Val::Px(1.0)),
+            ..default()
+        },
+        theme.code.background_color,
+        InputField::new(String::new()),
+        SimpleBorderHighlight::default(),
+        field,
+    ));
+    field_ec.observe(
+        move |trigger: Trigger<ValueChanged<String>>, mut editors: Query<&mut CodeEditor>| {
+            let Ok(mut editor) = editors.get_mut(root) else {
+                return;
+            };
+            let value = trigger.event().0.clone();
+            match field {
+                FindField::Query => editor.find.query = value,
+                FindField::Replacement => editor.find.replacement = value,
+            }
+        },
+    );
+    field_ec
+}
+
+/// Search the active documents again when their text or the query changed, and mark the matches
+pub(crate) fn update_matches(
+    mut editors: Query<&mut CodeEditor>,
+    mut documents: Query<(Ref<Document>, &EditableText, &mut TextMarks)>,
+    theme: Res<Theme>,
+) {
+    for mut editor in &mut editors {
+        let Some(Ok((document, editable_text, mut marks))) =
+            editor.active.map(|active| documents.get_mut(active))
+        else {
+            continue;
+        };
+        if !editor.is_changed() && !document.is_changed() {
+            continue;
+        }
+        // The matches are part of the state the editor shows, not a change of it
+        let find = &mut editor.bypass_change_detection().find;
+        find.update_matches(&editable_text.text);
+        let color = theme.code.search_match_color;
+        let new_marks = find
+            .matches
+            .iter()
+            .map(|&(start, end)| (start, end, color))
+            .collect();
+        marks.set_if_neq(TextMarks(new_marks));
+    }
+}
+
+/// Show the state of the code editors in their panes: the tabs, the bars and the active document
+pub(crate) fn render_code_editors(
+    mut commands: Commands,
+    mut panes: Query<(Entity, Ref<CodeEditor>, &mut CodeEditorPane)>,
+    documents: Query<(Ref<Document>, Ref<EditableText>)>,
+    mut texts: Query<(&mut Text, &mut TextColor)>,
+    mut nodes: Query<&mut Node>,
+    mut backgrounds: Query<&mut BackgroundColor>,
+    theme: Res<Theme>,
+) {
+    for (root, editor, mut pane) in &mut panes {
+        let tabs = editor
+            .documents
+            .iter()
+            .filter_map(|&entity| {
+                let (document, _) = documents.get(entity).ok()?;
+                let label = if document.dirty {
+                    format!("{} \u{25CF}", document.file_name())
+                } else {
+                    document.file_name()
+                };
+                Some((entity, label, editor.active == Some(entity)))
+            })
+            .collect::<Vec<_>>();
+        if tabs != pane.tabs {
+            commands.entity(pane.tab_bar).despawn_related::<Children>();
+            for (document, label, active) in &tabs {
+                spawn_tab(&mut commands, root, *document, label, *active, &theme)
+                    .insert(ChildOf(pane.tab_bar));
+            }
+            pane.tabs = tabs;
+        }
+
+        let active = editor.active.and_then(|active| documents.get(active).ok());
+        let active_changed = active.as_ref().is_some_and(|(document, editable_text)| {
+            document.is_changed() || editable_text.is_changed()
+        });
+        if !editor.is_changed() && !pane.is_added() && !active_changed {
+            continue;
+        }
+
+        if let Ok((mut title, mut color)) = texts.get_mut(pane.title) {
+            (title.0, color.0) = match (&editor.error, &active) {
+                (Some(error), _) => (error.clone(), tailwind::RED_400.into()),
+                (None, Some((document, _))) => (document.path.to_string(), theme.text.low_priority),
+                (None, None) => (String::new(), theme.text.low_priority),
+            };
+        }
+
+        let closing = editor
+            .closing
+            .and_then(|closing| documents.get(closing).ok());
+        if let Some((document, _)) = &closing {
+            if let Ok((mut text, _)) = texts.get_mut(pane.prompt_text) {
+                text.0 = format!("Save the changes to {}?", document.file_name());
+            }
+        }
+        set_display(&mut nodes, pane.prompt_bar, closing.is_some());
+        set_display(&mut nodes, pane.find_bar, editor.find.open);
+        set_display(&mut nodes, pane.placeholder, active.is_none());
+        for &document in &editor.documents {
+            set_display(&mut nodes, document, editor.active == Some(document));
+        }
+
+        if let Ok(mut background) = backgrounds.get_mut(pane.case_toggle) {
+            *background = if editor.find.case_sensitive {
+                BackgroundColor(theme.code.selection_color)
+            } else {
+                theme.button.background_color
+            };
+        }
+        if let Ok((mut count, _)) = texts.get_mut(pane.find_count) {
+            let matches = editor.find.matches.len();
+            count.0 = match active
+                .as_ref()
+                .and_then(|(_, editable_text)| editor.find.selected_match(editable_text))
+            {
+                _ if editor.find.query.is_empty() => String::new(),
+                Some(index) => format!("{} of {}", index + 1, matches),
+                None if matches == 0 => "No results".to_string(),
+                None => format!("{matches} results"),
+            };
+        }
+    }
+}
+
+fn set_display(nodes: &mut Query<&mut Node>, entity: Entity, shown: bool) {
+    let Ok(mut node) = nodes.get_mut(entity) else {
+        return;
+    };
+    let display = if shown { Display::Flex } else { Display::None };
+    if node.display != display {
+        node.display = display;
+    }
+}
+
+/// Spawn the tab of a document, showing it when clicked, with a button closing it
+fn spawn_tab<'a>(
+    commands: &'a mut Commands,
+    root: Entity,
+    document: Entity,
+    label: &str,
+    active: bool,
+    theme: &Theme,
+) -> EntityCommands<'a> {
+    let mut tab_ec = spawn_button(commands, label, theme);
+    if !active {
+        tab_ec.insert(BackgroundColor(Color::NONE));
+    }
+    tab_ec.observe(on_click(move |mut commands: Commands| {
+        commands.run_system_cached_with(activate_tab, (root, document));
+    }));
+    tab_ec.with_children(|tab| {
+        tab.spawn((
+            Button,
+            Text::new("\u{00D7}"),
+            TextFont {
+                font: theme.text.font.clone(),
+                font_size: 12.0,
+                ..default()
+            },
+            TextColor(theme.text.low_priority),
+            Node {
+                margin: UiRect::left(Val::Px(6.0)),
+                ..default()
+            },
+        ))
+        .observe(
+            move |mut trigger: Trigger<Pointer<Click>>, mut commands: Commands| {
+                trigger.propagate(false);
+                if trigger.event().button == PointerButton::Primary {
+                    commands.run_system_cached_with(close_tab, (root, document));
+                }
+            },
+        );
+    });
+    tab_ec
+}
+
+fn activate_tab(
+    In((root, document)): In<(Entity, Entity)>,
+    mut editors: Query<&mut CodeEditor>,
+    mut last_editor: ResMut<LastCodeEditor>,
+) {
+    if let Ok(mut editor) = editors.get_mut(root) {
+        editor.activate(document);
+        last_editor.0 = Some(root);
+    }
+}
+
+fn close_tab(
+    In((root, entity)): In<(Entity, Entity)>,
+    mut commands: Commands,
+    mut editors: Query<&mut CodeEditor>,
+    documents: Query<&Document>,
+) {
+    if let (Ok(mut editor), Ok(document)) = (editors.get_mut(root), documents.get(entity)) {
+        close_document(&mut commands, &mut editor, entity, document);
+    }
+}
+
+/// Number the lines of the active documents in the gutter, from the laid out lines.
+/// A line wrapped over several visual lines is numbered on its first visual line.
+pub(crate) fn update_gutters(
+    mut commands: Commands,
+    mut panes: Query<(&CodeEditor, &mut CodeEditorPane)>,
+    documents: Query<(&EditableText, &EditableTextLayout)>,
+    nodes: Query<&ComputedNode>,
+    theme: Res<Theme>,
+) {
+    for (editor, mut pane) in &mut panes {
+        let Some((entity, (editable_text, layout))) = editor
+            .active
+            .and_then(|active| Some((active, documents.get(active).ok()?)))
+        else {
+            if pane.gutter_state.take().is_some() {
+                commands.entity(pane.gutter).despawn_related::<Children>();
+            }
+            continue;
+        };
+        let height = nodes
+            .get(pane.gutter)
+            .map_or(0.0, |node| node.size().y * node.inverse_scale_factor());
+        let lines = &layout.lines;
+        let cursor_line = editable_text
+            .cursor_position
+            .map(|position| lines.line_index(position));
+        let state = (entity, layout.scroll(), lines.0.len(), cursor_line, height);
+        if pane.gutter_state == Some(state) {
+            continue;
+        }
+        pane.gutter_state = Some(state);
+
+        commands.entity(pane.gutter).despawn_related::<Children>();
+        let line_count = editable_text.text.split('\n').count();
+        let digits = line_count.to_string().len().max(3);
+        commands.entity(pane.gutter).insert(Node {
+            width: Val::Px(digits as f32 * CHAR_WIDTH + 2.0 * TEXT_PADDING),
+            height: Val::Percent(100.0),
+            flex_shrink: 0.0,
+            overflow: Overflow::clip(),
+            ..default()
+        });
+
+        let mut number = 0;
+        let mut cursor_number = None;
+        let mut visible_numbers = Vec::new();
+        for (index, line) in lines.0.iter().enumerate() {
+            if index == 0 || !lines.is_wrapped(index - 1) {
+                number += 1;
+                let top = line.top - layout.scroll();
+                if top + line.height >= 0.0 && top <= height {
+                    visible_numbers.push((number, top, line.height));
+                }
+            }
+            if cursor_line == Some(index) {
+                cursor_number = Some(number);
+            }
+        }
+        for (number, top, line_height) in visible_numbers {
+            let is_cursor_line = cursor_number == Some(number);
+            let mut number_ec = commands.spawn((
+                Text::new(number.to_string()),
+                code_font(),
+                TextColor(if is_cursor_line {
+                    theme.code.text_color
+                } else {
+                    theme.code.line_number_color
+                }),
+                TextLayout::new_with_justify(JustifyText::Right),
+                Node {
+                    position_type: PositionType::Absolute,
+                    top: Val::Px(top),
+                    left: Val::Px(0.0),
+                    right: Val::Px(0.0),
+                    height: Val::Px(line_height),
+                    padding: UiRect::horizontal(Val::Px(TEXT_PADDING)),
+                    justify_content: JustifyContent::FlexEnd,
+                    ..default()
+                },
+                Pickable::IGNORE,
+                ChildOf(pane.gutter),
+            ));
+            if is_cursor_line {
+                number_ec.insert(BackgroundColor(theme.code.current_line_color));
+            }
+        }
+    }
+}
+
+/// The monospace font of the code
+fn code_font() -> TextFont {
+    TextFont {
+        font_size: FONT_SIZE,
+        ..default()
+    }
+}
@@ -0,0 +1,678 @@
+//! Inspector for the [`SelectedAsset`].
+//!
+//! It shows the loader of the asset, its file size and its dependencies, and lets the loader
+//! settings stored in the `.meta` file of the asset be edited. Saving the settings writes the
+//! `.meta` file and reimports the asset.
+
+use std::sync::Arc;
+
+use bevy::{
+    asset::{AssetPath, ErasedAssetLoader, LoadedUntypedAsset, ReflectAsset, ReflectHandle},
+    color::palettes::tailwind,
+    platform::collections::HashMap,
+    prelude::*,
+    reflect::{ReflectRef, TypeRegistry},
+    tasks::{block_on, poll_once, IoTaskPool, Task},
+};
+use bevy_editor_core::{format::format_file_size, SelectedAsset};
+use bevy_field_forms::{
+    input_field::{InputField, ValueChanged},
+    validate_highlight::SimpleBorderHighlight,
+    FieldFormsPlugin,
+};
+use bevy_i_cant_believe_its_not_bsn::Receipt;
+
+use crate::{
+    meta::{apply_edits, meta_fields, MetaField},
+    PropertiesPaneRoot,
+};
+
+/// Meta fields that are not loader settings, and must not be edited.
+const HIDDEN_META_FIELDS: [&str; 3] = ["meta_format_version", "asset.loader", "asset.processor"];
+
+/// Plugin for the asset inspector of the properties pane.
+pub struct AssetInspectorPlugin;
+
+impl Plugin for AssetInspectorPlugin {
+    fn build(&self, app: &mut App) {
+        if !app.is_plugin_added::<FieldFormsPlugin>() {
+            app.add_plugins(FieldFormsPlugin);
+        }
+
+        app.init_resource::<AssetInspection>().add_systems(
+            Update,
+            (
+                inspect_selected_asset.run_if(resource_changed::<SelectedAsset>),
+                poll_inspect_task,
+                poll_save_task,
+                find_dependencies,
+            )
+                .chain(),
+        );
+    }
+}
+
+/// What is known about the [`SelectedAsset`], or `None` while it is being inspected.
+#[derive(Resource, Default)]
+pub struct AssetInspection(pub Option<AssetDetails>);
+
+/// Details about an asset file and how it is loaded.
+pub struct AssetDetails {
+    /// The path of the asset.
+    pub path: AssetPath<'static>,
+    /// The size of the asset file in bytes, or `None` if the file couldn't be read.
+    pub file_size: Option<usize>,
+    /// The loader used for the asset, or `None` if no loader supports the file.
+    pub loader: Option<Arc<dyn ErasedAssetLoader>>,
+    /// The content of the `.meta` file of the asset, or the default meta of its loader if the
+    /// asset has no `.meta` file.
+    pub meta: String,
+    /// Whether the asset has a `.meta` file.
+    pub has_meta_file: bool,
+    /// The loader settings found in [`Self::meta`].
+    pub fields: Vec<MetaField>,
+    /// The new values of the edited [`Self::fields`], by index.
+    pub edits: HashMap<usize, String>,
+    /// The assets this asset depends on.
+    pub dependencies: Dependencies,
+    /// Why the `.meta` file couldn't be saved the last time.
+    pub save_error: Option<String>,
+    /// Keeps the asset loaded so its dependencies can be found.
+    handle: Option<Handle<LoadedUntypedAsset>>,
+}
+
+impl AssetDetails {
+    /// The `.meta` file content with the edits applied.
+    pub fn edited_meta(&self) -> String {
+        apply_edits(
+            &self.meta,
+            self.edits
+                .iter()
+                .map(|(index, value)| (&self.fields[*index], value.as_str())),
+        )
+    }
+}
+
+/// The dependencies of an asset.
+///
+/// Dependencies are found by looking for handles in the loaded asset, so they are only known for
+/// asset types that are reflected and registered with [`ReflectAsset`].
+pub enum Dependencies {
+    /// The asset is still loading.
+    Loading,
+    /// The asset failed to load, or its type is not reflected.
+    Unknown,
+    /// The paths of the dependencies.
+    Found(Vec<AssetPath<'static>>),
+}
+
+#[derive(Component)]
+struct InspectAssetTask(Task<AssetDetails>);
+
+#[derive(Component)]
+struct SaveMetaTask(Task<Result<(), String>>);
+
+/// Start inspecting the [`SelectedAsset`].
+fn inspect_selected_asset(
+    mut commands: Commands,
+    selected_asset: Res<SelectedAsset>,
+    mut inspection: ResMut<AssetInspection>,
+    tasks: Query<Entity, With<InspectAssetTask>>,
+    asset_server: Res<AssetServer>,
+) {
+    for task in &tasks {
+        commands.entity(task).despawn();
+    }
+    // Keep showing the previous details while the same asset is inspected again
+    if inspection.0.as_ref().map(|details| &details.path) != selected_asset.0.as_ref() {
+        inspection.0 = None;
+    }
+    let Some(path) = selected_asset.0.clone() else {
+        return;
+    };
+
+    let asset_server = asset_server.clone();
+    let task = IoTaskPool::get().spawn(async move { inspect(&asset_server, path).await });
+    commands.spawn(InspectAssetTask(task));
+}
+
+async fn inspect(asset_server: &AssetServer, path: AssetPath<'static>) -> AssetDetails {
+    let loader = asset_server.get_path_asset_loader(&path).await.ok();
+
+    let (file_size, meta_bytes) = match asset_server.get_source(path.source().clone()) {
+        Ok(source) => {
+            let reader = source.reader();
+            let mut bytes = Vec::new();
+            let file_size = match reader.read(path.path()).await {
+                Ok(mut file) => file.read_to_end(&mut bytes).await.ok(),
+                Err(_) => None,
+            };
+            (file_size, reader.read_meta_bytes(path.path()).await.ok())
+        }
+        Err(_) => (None, None),
+    };
+
+    let has_meta_file = meta_bytes.is_some();
+    let meta = meta_bytes
+        .or_else(|| {
+            loader
+                .as_ref()
+                .map(|loader| loader.default_meta().serialize())
+        })
+        .map(|bytes| String::from_utf8_lossy(&bytes).into_owned())
+        .unwrap_or_default();
+    let fields = meta_fields(&meta)
+        .unwrap_or_default()
+        .into_iter()
+        .filter(|field| !HIDDEN_META_FIELDS.contains(&field.key.as_str()))
+        .collect();
+
+    AssetDetails {
+        path,
+        file_size,
+        loader,
+        meta,
+        has_meta_file,
+        fields,
+        edits: HashMap::default(),
+        dependencies: Dependencies::Loading,
+        save_error: None,
+        handle: None,
+    }
+}
+
+fn poll_inspect_task(
+    mut commands: Commands,
+    mut tasks: Query<(Entity, &mut InspectAssetTask)>,
+    mut inspection: ResMut<AssetInspection>,
+    asset_server: Res<AssetServer>,
+) {
+    for (entity, mut task) in &mut tasks {
+        let Some(mut details) = block_on(poll_once(&mut task.0)) else {
+            continue;
+        };
+        commands.entity(entity).despawn();
+        if details.loader.is_some() {
+            details.handle = Some(asset_server.load_untyped(&details.path));
+        } else {
+            details.dependencies = Dependencies::Unknown;
+        }
+        inspection.0 = Some(details);
+    }
+}
+
+/// Find the dependencies of the inspected asset once it is loaded.
+fn find_dependencies(world: &mut World) {
+    world.resource_scope(|world, mut inspection: Mut<AssetInspection>| {
+        let Some(details) = &inspection.0 else {
+            return;
+        };
+        let Some(handle) = &details.handle else {
+            return;
+        };
+        if !matches!(details.dependencies, Dependencies::Loading) {
+            return;
+        }
+
+        let asset_server = world.resource::<AssetServer>();
+        let dependencies = if asset_server.load_state(handle).is_failed() {
+            Dependencies::Unknown
+        } else {
+            let Some(loaded) = world.resource::<Assets<LoadedUntypedAsset>>().get(handle) else {
+                return;
+            };
+            let registry = world.resource::<AppTypeRegistry>().read();
+            match registry.get_type_data::<ReflectAsset>(loaded.handle.type_id()) {
+                Some(reflect_asset) => {
+                    let Some(asset) = reflect_asset.get(world, loaded.handle.clone()) else {
+                        return;
+                    };
+                    let mut dependencies = Vec::new();
+                    visit_handles(asset.as_partial_reflect(), &registry, &mut |handle| {
+                        let Some(path) = asset_server.get_path(handle.id()) else {
+                            return;
+                        };
+                        let path = path.into_owned();
+                        if path.without_label() != details.path.without_label()
+                            && !dependencies.contains(&path)
+                        {
+                            dependencies.push(path);
+                        }
+                    });
+                    Dependencies::Found(dependencies)
+                }
+                None => Dependencies::Unknown,
+            }
+        };
+
+        if let Some(details) = &mut inspection.0 {
+            details.dependencies = dependencies;
+        }
+    });
+}
+
+/// Call `visit` for every asset handle in `value`.
+fn visit_handles(
+    value: &dyn PartialReflect,
+    registry: &TypeRegistry,
+    visit: &mut impl FnMut(UntypedHandle),
+) {
+    let reflect_handle = value
+        .get_represented_type_info()
+        .and_then(|info| registry.get_type_data::<ReflectHandle>(info.type_id()));
+    if let Some(reflect_handle) = reflect_handle {
+        if let Some(handle) = value
+            .try_as_reflect()
+            .and_then(|value| reflect_handle.downcast_handle_untyped(value.as_any()))
+        {
+            visit(handle);
+        }
+        return;
+    }
+
+    match value.reflect_ref() {
+        ReflectRef::Struct(value) => {
+            for field in value.iter_fields() {
+                visit_handles(field, registry, visit);
+            }
+        }
+        ReflectRef::TupleStruct(value) => {
+            for field in value.iter_fields() {
+                visit_handles(field, registry, visit);
+            }
+        }
+        ReflectRef::Tuple(value) => {
+            for field in value.iter_fields() {
+                visit_handles(field, registry, visit);
+            }
+        }
+        ReflectRef::List(value) => {
+            for item in value.iter() {
+                visit_handles(item, registry, visit);
+            }
+        }
+        ReflectRef::Array(value) => {
+            for item in value.iter() {
+                visit_handles(item, registry, visit);
+            }
+        }
+        ReflectRef::Map(value) => {
+            for (_, item) in value.iter() {
+                visit_handles(item, registry, visit);
+            }
+        }
+        ReflectRef::Set(value) => {
+            for item in value.iter() {
+                visit_handles(item, registry, visit);
+            }
+        }
+        ReflectRef::Enum(value) => {
+            for field in value.iter_fields() {
+                visit_handles(field.value(), registry, visit);
+            }
+        }
+        _ => {}
+    }
+}
+
+/// Validate and write the edited `.meta` file of the inspected asset.
+fn save_meta(
+    mut commands: Commands,
+    mut inspection: ResMut<AssetInspection>,
+    asset_server: Res<AssetServer>,
+) {
+    let Some(details) = &mut inspection.0 else {
+        return;
+    };
+    let meta = details.edited_meta();
+    if let Some(loader) = &details.loader {
+        if let Err(error) = loader.deserialize_meta(meta.as_bytes()) {
+            details.save_error = Some(error.to_string());
+            return;
+        }
+    }
+    details.save_error = None;
+
+    let asset_server = asset_server.clone();
+    let path = details.path.clone();
+    let task = IoTaskPool::get().spawn(async move {
+        let source = asset_server
+            .get_source(path.source().clone())
+            .map_err(|error| error.to_string())?;
+        let writer = source.writer().map_err(|error| error.to_string())?;
+        writer
+            .write_meta_bytes(path.path(), meta.as_bytes())
+            .await
+            .map_err(|error| error.to_string())?;
+        asset_server.reload(path);
+        Ok::<_, String>(())
+    });
+    commands.spawn(SaveMetaTask(task));
+}
+
+fn poll_save_task(
+    mut commands: Commands,
+    mut tasks: Query<(Entity, &mut SaveMetaTask)>,
+    mut inspection: ResMut<AssetInspection>,
+    mut selected_asset: ResMut<SelectedAsset>,
+) {
+    for (entity, mut task) in &mut tasks {
+        let Some(result) = block_on(poll_once(&mut task.0)) else {
+            continue;
+        };
+        commands.entity(entity).despawn();
+        match result {
+            // Inspect the asset again to show the saved settings
+            Ok(()) => selected_asset.set_changed(),
+            Err(error) => {
+                if let Some(details) = &mut inspection.0 {
+                    details.save_error = Some(error);
+                }
+            }
+        }
+    }
+}
+
+/// Discard the edits of the inspected asset.
+fn revert_meta(mut inspection: ResMut<AssetInspection>) {
+    if let Some(details) = &mut inspection.0 {
+        details.edits.clear();
+        details.save_error = None;
+    }
+}
+
+/// Root UI node of the asset inspector in a properties pane.
+#[derive(Component)]
+pub(crate) struct AssetInspectorView;
+
+/// The input field of the [`MetaField`] with this index.
+#[derive(Component)]
+struct MetaFieldInput(usize);
+
+/// Show the inspector in the properties panes while an asset is selected.
+///
+/// The inspector is rebuilt when the inspection changes. Edits of the fields don't count as
+/// changes, so the fields keep their focus while typing.
+pub(crate) fn update_asset_inspector(
+    mut commands: Commands,
+    panes: Query<(Entity, Option<&Children>), With<PropertiesPaneRoot>>,
+    views: Query<Entity, With<AssetInspectorView>>,
+    selected_asset: Res<SelectedAsset>,
+    inspection: Res<AssetInspection>,
+) {
+    let Some(path) = &selected_asset.0 else {
+        if selected_asset.is_changed() {
+            for view in &views {
+                commands.entity(view).despawn();
+            }
+        }
+        return;
+    };
+
+    let changed = selected_asset.is_changed() || inspection.is_changed();
+    for (pane, children) in &panes {
+        let has_view = children.is_some_and(|children| views.iter_many(children).next().is_some());
+        if has_view && !changed {
+            continue;
+        }
+        // Replace the entity inspector, which is rebuilt from scratch when an entity is selected
+        commands
+            .entity(pane)
+            .despawn_related::<Children>()
+            .remove::<Receipt>();
+        let view = commands
+            .spawn((
+                AssetInspectorView,
+                Node {
+                    flex_direction: FlexDirection::Column,
+                    row_gap: Val::Px(4.0),
+                    ..Default::default()
+                },
+                ChildOf(pane),
+            ))
+            .id();
+        spawn_asset_inspector(&mut commands, view, path, inspection.0.as_ref());
+    }
+}
+
+fn spawn_asset_inspector(
+    commands: &mut Commands,
+    view: Entity,
+    path: &AssetPath,
+    details: Option<&AssetDetails>,
+) {
+    let file_name = path
+        .path()
+        .file_name()
+        .map(|name| name.to_string_lossy().into_owned())
+        .unwrap_or_default();
+    commands.spawn((
+        Text(file_name),
+        TextFont::from_font_size(14.0),
+        TextColor(Color::WHITE),
+        ChildOf(view),
+    ));
+
+    let Some(details) = details else {
+        spawn_note(commands, view, "Loading...");
+        return;
+    };
+
+    spawn_property(commands, view, "Path", &path.to_string());
+    spawn_property(
+        commands,
+        view,
+        "File size",
+        &details
+            .file_size
+            .map_or("<unavailable>".to_string(), |size| {
+                format_file_size(size as u64)
+            }),
+    );
+    if let Some(loader) = &details.loader {
+        spawn_property(
+            commands,
+            view,
+            "Loader",
+            short_type_name(loader.type_name()),
+        );
+        spawn_property(
+            commands,
+            view,
+            "Asset type",
+            short_type_name(loader.asset_type_name()),
+        );
+    }
+
+    spawn_header(commands, view, "Import Settings");
+    if details.loader.is_none() {
+        spawn_note(commands, view, "No loader supports this file");
+    } else if details.fields.is_empty() {
+        spawn_note(commands, view, "No settings");
+    } else {
+        if !details.has_meta_file {
+            spawn_note(
+                commands,
+                view,
+                "Default settings, the asset has no .meta file",
+            );
+        }
+        for (index, field) in details.fields.iter().enumerate() {
+            let value = details.edits.get(&index).cloned().unwrap_or_else(|| {
+                // Fields are edited on a single line
+                details.meta[field.span.clone()]
+                    .lines()
+                    .map(str::trim)
+                    .collect::<Vec<_>>()
+                    .join(" ")
+            });
+            spawn_meta_field(commands, view, index, field, value);
+        }
+        if let Some(error) = &details.save_error {
+            commands.spawn((
+                Text(error.clone()),
+                TextFont::from_font_size(10.0),
+                TextColor(tailwind::RED_400.into()),
+                ChildOf(view),
+            ));
+        }
+        let buttons = commands
+            .spawn((
+                Node {
+                    flex_direction: FlexDirection::Row,
+                    column_gap: Val::Px(4.0),
+                    margin: UiRect::vertical(Val::Px(4.0)),
+                    ..Default::default()
+                },
+                ChildOf(view),
+            ))
+            .id();
+        spawn_button(commands, buttons, "Save & Reimport").observe(
+            |_trigger: Trigger<Pointer<Click>>, mut commands: Commands| {
+                commands.run_system_cached(save_meta);
+            },
+        );
+        spawn_button(commands, buttons, "Revert").observe(
+            |_trigger: Trigger<Pointer<Click>>, mut commands: Commands| {
+                commands.run_system_cached(revert_meta);
+            },
+        );
+    }
+
+    spawn_header(commands, view, "Dependencies");
+    match &details.dependencies {
+        Dependencies::Loading => spawn_note(commands, view, "Loading..."),
+        Dependencies::Unknown => spawn_note(commands, view, "Unknown for this asset type"),
+        Dependencies::Found(dependencies) if dependencies.is_empty() => {
+            spawn_note(commands, view, "None");
+        }
+        Dependencies::Found(dependencies) => {
+            for dependency in dependencies {
+                commands.spawn((
+                    Text(dependency.to_string()),
+                    TextFont::from_font_size(10.0),
+                    TextColor(Color::WHITE),
+                    ChildOf(view),
+                ));
+            }
+        }
+    }
+}
+
+fn spawn_header(commands: &mut Commands, parent: Entity, title: &str) {
+    commands.spawn((
+        Text(format!("⯆ {title}")),
+        TextFont::from_font_size(14.0),
+        TextColor(Color::WHITE),
+        Node {
+            margin: UiRect::top(Val::Px(8.0)),
+            ..Default::default()
+        },
+        ChildOf(parent),
+    ));
+}
+
+fn spawn_note(commands: &mut Commands, parent: Entity, note: &str) {
+    commands.spawn((
+        Text(note.to_string()),
+        TextFont::from_font_size(10.0),
+        TextColor(Color::srgb(0.8, 0.8, 0.8)),
+        ChildOf(parent),
+    ));
+}
+
+fn spawn_property(commands: &mut Commands, parent: Entity, name: &str, value: &str) {
+    commands
+        .spawn((
+            Node {
+                flex_direction: FlexDirection::Row,
+                column_gap: Val::Px(8.0),
+                ..Default::default()
+            },
+            ChildOf(parent),
+        ))
+        .with_children(|row| {
+            row.spawn((
+                Text(name.to_string()),
+                TextFont::from_font_size(12.0),
+                TextColor(Color::srgb(0.8, 0.8, 0.8)),
+            ));
+            row.spawn((
+                Text(value.to_string()),
+                TextFont::from_font_size(12.0),
+                TextColor(Color::WHITE),
+            ));
+        });
+}
+
+fn spawn_meta_field(
+    commands: &mut Commands,
+    parent: Entity,
+    index: usize,
+    field: &MetaField,
+    value: String,
+) {
+    let name = field
+        .key
+        .strip_prefix("asset.settings.")
+        .unwrap_or(&field.key);
+    commands.spawn((
+        Text(name.to_string()),
+        TextFont::from_font_size(12.0),
+        TextColor(Color::srgb(0.8, 0.8, 0.8)),
+        ChildOf(parent),
+    ));
+    commands
+        .spawn((
+            Node {
+                height: Val::Px(22.0),
+                border: UiRect::all(Val::Px(1.0)),
+                ..Default::default()
+            },
+            BackgroundColor(tailwind::NEUTRAL_700.into()),
+            InputField::new(value),
+            SimpleBorderHighlight::default(),
+            MetaFieldInput(index),
+            ChildOf(parent),
+        ))
+        .observe(on_meta_field_changed);
+}
+
+/// Store the new value of an edited field, without rebuilding the inspector.
+fn on_meta_field_changed(
+    trigger: Trigger<ValueChanged<String>>,
+    inputs: Query<&MetaFieldInput>,
+    mut inspection: ResMut<AssetInspection>,
+) {
+    let Ok(input) = inputs.get(trigger.target()) else {
+        return;
+    };
+    if let Some(details) = &mut inspection.bypass_change_detection().0 {
+        details.edits.insert(input.0, trigger.event().0.clone());
+    }
+}
+
+fn spawn_button<'a>(commands: &'a mut Commands, parent: Entity, label: &str) -> EntityCommands<'a> {
+    let mut button = commands.spawn((
+        Button,
+        Node {
+            padding: UiRect::axes(Val::Px(8.0), Val::Px(4.0)),
+            ..Default::default()
+        },
+        BackgroundColor(tailwind::NEUTRAL_700.into()),
+        BorderRadius::all(Val::Px(3.0)),
+        ChildOf(parent),
+    ));
+    button.with_child((
+        Text(label.to_string()),
+        TextFont::from_font_size(12.0),
+        TextColor(Color::WHITE),
+        Pickable::IGNORE,
+    ));
+    button
+}
+
+/// The name of a type without its module path.
+fn short_type_name(type_name: &str) -> &str {
+    type_name.rsplit("::").next().unwrap_or(type_name)
+}
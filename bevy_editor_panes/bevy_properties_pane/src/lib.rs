@@ -3,17 +3,29 @@
 //! Data can be viewed and modified in real-time, with changes being reflected in the application.
 
 use bevy::{color::palettes::tailwind, prelude::*, reflect::*};
-use bevy_editor_core::SelectedEntity;
+use bevy_editor_core::{SelectedAsset, SelectedEntity};
 use bevy_i_cant_believe_its_not_bsn::{template, Template, TemplateEntityCommandsExt};
 use bevy_pane_layout::prelude::{PaneAppExt, PaneStructure};
 
+pub mod asset_inspector;
+
+pub use bevy_editor_core::meta;
+
 /// Plugin for the editor properties pane.
 pub struct PropertiesPanePlugin;
 
 impl Plugin for PropertiesPanePlugin {
     fn build(&self, app: &mut App) {
-        app.register_pane("Properties", setup_pane)
-            .add_systems(PostUpdate, update_properties_pane);
+        app.add_plugins(asset_inspector::AssetInspectorPlugin)
+            .register_pane("Properties", setup_pane)
+            .add_systems(
+                PostUpdate,
+                (
+                    asset_inspector::update_asset_inspector,
+                    update_properties_pane,
+                )
+                    .chain(),
+            );
     }
 }
 
@@ -38,9 +50,14 @@ fn setup_pane(pane: In<PaneStructure>, mut commands: Commands) {
 fn update_properties_pane(
     panes: Query<Entity, With<PropertiesPaneRoot>>,
     selected_entity: Res<SelectedEntity>,
+    selected_asset: Res<SelectedAsset>,
     world: &World,
     mut commands: Commands,
 ) {
+    // The asset inspector is shown instead
+    if selected_asset.0.is_some() {
+        return;
+    }
     for pane in &panes {
         commands
             .entity(pane)
@@ -57,7 +74,7 @@ fn properties_pane(selected_entity: &SelectedEntity, world: &World) -> Template
                 ..Default::default()
             } => [
                 (
-                    Text("Select an entity to inspect".into()),
+                    Text("Select an entity or asset to inspect".into()),
                     TextFont::from_font_size(14.0),
                 );
             ];
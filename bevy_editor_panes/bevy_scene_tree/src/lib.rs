@@ -1,7 +1,7 @@
 //! An interactive, collapsible tree view for hierarchical ECS data in Bevy.
 
 use bevy::{app::Plugin, color::palettes::tailwind, prelude::*};
-use bevy_editor_core::{SceneRootMarker, SelectedEntity};
+use bevy_editor_core::{AssetNode, SceneRootMarker, SelectedEntity, SpawnAsset};
 use bevy_i_cant_believe_its_not_bsn::{on, template, Template, TemplateEntityCommandsExt};
 use bevy_pane_layout::prelude::{PaneAppExt, PaneStructure};
 
@@ -158,6 +158,7 @@ fn expansion_tile(
                 ..Default::default()
             }) => [
                 on(toggle_expansion_tile);
+                on(drop_asset);
                 // on(select_entity);
                 (Text(title), TextFont::from_font_size(11.0), Pickable::IGNORE);
             ];
@@ -190,6 +191,31 @@ fn toggle_expansion_tile(
     folded.0 = !folded.0;
 }
 
+/// Spawn the asset of an [`AssetNode`] dropped onto a node as a child of its entity.
+fn drop_asset(
+    trigger: Trigger<Pointer<DragDrop>>,
+    parent_query: Query<&ChildOf>,
+    node_query: Query<&SceneTreeNode>,
+    asset_nodes: Query<&AssetNode>,
+    mut spawn_asset: EventWriter<SpawnAsset>,
+) {
+    let Ok(asset_node) = asset_nodes.get(trigger.dropped) else {
+        return;
+    };
+    let Some(node) = parent_query
+        .iter_ancestors(trigger.target())
+        .find_map(|entity| node_query.get(entity).ok())
+    else {
+        return;
+    };
+    spawn_asset.write(SpawnAsset {
+        path: asset_node.0.clone(),
+        transform: Transform::default(),
+        parent: Some(node.0),
+        in_2d: false,
+    });
+}
+
 fn update_expansion_tile(
     query: Query<(&ExpansionTileFolded, &Children), Changed<ExpansionTileFolded>>,
     etc_query: Query<(), With<ExpansionTileChildren>>,
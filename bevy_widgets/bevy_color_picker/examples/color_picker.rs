@@ -0,0 +1,66 @@
+//! This example demonstrates a color picker editing the color of a square.
+
+use bevy::prelude::*;
+use bevy_color_picker::{ColorCommitted, ColorPicker, ColorPickerPlugin, ValueChanged};
+
+fn main() {
+    App::new()
+        .add_plugins(DefaultPlugins)
+        .add_plugins(ColorPickerPlugin)
+        .add_systems(Startup, setup)
+        .run();
+}
+
+/// The square showing the color of the picker
+#[derive(Component)]
+struct Preview;
+
+fn setup(mut commands: Commands) {
+    commands.spawn(Camera2d);
+
+    commands
+        .spawn(Node {
+            width: Val::Percent(100.0),
+            height: Val::Percent(100.0),
+            align_items: AlignItems::Center,
+            justify_content: JustifyContent::Center,
+            column_gap: Val::Px(20.0),
+            ..default()
+        })
+        .with_children(|cmd| {
+            cmd.spawn((
+                Node {
+                    width: Val::Px(260.0),
+                    padding: UiRect::all(Val::Px(8.0)),
+                    ..default()
+                },
+                BackgroundColor(Color::srgb(0.12, 0.12, 0.12)),
+                ColorPicker::new(Color::srgb(0.2, 0.5, 0.9)),
+            ))
+            .observe(
+                |trigger: Trigger<ValueChanged<Color>>,
+                 mut q_previews: Query<&mut BackgroundColor, With<Preview>>| {
+                    for mut background in q_previews.iter_mut() {
+                        background.0 = trigger.event().0;
+                    }
+                },
+            )
+            .observe(|trigger: Trigger<ColorCommitted>| {
+                let event = trigger.event();
+                info!(
+                    "Color changed from {:?} to {:?}",
+                    event.previous, event.color
+                );
+            });
+
+            cmd.spawn((
+                Node {
+                    width: Val::Px(120.0),
+                    height: Val::Px(120.0),
+                    ..default()
+                },
+                BackgroundColor(Color::srgb(0.2, 0.5, 0.9)),
+                Preview,
+            ));
+        });
+}
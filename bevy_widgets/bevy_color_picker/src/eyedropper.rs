@@ -0,0 +1,102 @@
+//! The eyedropper, picking a color from the window
+
+use bevy::{
+    prelude::*,
+    render::view::screenshot::{Screenshot, ScreenshotCaptured},
+    window::PrimaryWindow,
+};
+
+use crate::{ColorPicker, ColorSwatches};
+
+/// The button of a picker starting the eyedropper
+#[derive(Component, Clone, Copy)]
+pub(crate) struct EyedropperButton {
+    pub picker: Entity,
+}
+
+/// A node over the whole window while the eyedropper is active, catching the click picking the
+/// color so that it doesn't reach what is under it
+#[derive(Component, Clone, Copy)]
+pub(crate) struct EyedropperOverlay {
+    picker: Entity,
+}
+
+pub(crate) fn on_eyedropper_click(
+    trigger: Trigger<Pointer<Click>>,
+    mut commands: Commands,
+    q_buttons: Query<&EyedropperButton>,
+) {
+    let Ok(button) = q_buttons.get(trigger.target()) else {
+        return;
+    };
+    commands.spawn((
+        Node {
+            position_type: PositionType::Absolute,
+            width: Val::Percent(100.0),
+            height: Val::Percent(100.0),
+            ..default()
+        },
+        GlobalZIndex(i32::MAX),
+        EyedropperOverlay {
+            picker: button.picker,
+        },
+    ));
+}
+
+/// Take a screenshot of the window, and pick the color of the pixel which was clicked in it
+pub(crate) fn on_overlay_pressed(
+    trigger: Trigger<Pointer<Pressed>>,
+    mut commands: Commands,
+    q_overlays: Query<&EyedropperOverlay>,
+    q_windows: Query<&Window, With<PrimaryWindow>>,
+) {
+    let entity = trigger.target();
+    let Ok(overlay) = q_overlays.get(entity) else {
+        return;
+    };
+    commands.entity(entity).despawn();
+    if trigger.event().button != PointerButton::Primary {
+        return;
+    }
+    let Ok(window) = q_windows.single() else {
+        return;
+    };
+
+    let position = (trigger.pointer_location.position * window.scale_factor()).as_uvec2();
+    let picker_entity = overlay.picker;
+    commands.spawn(Screenshot::primary_window()).observe(
+        move |trigger: Trigger<ScreenshotCaptured>,
+              mut commands: Commands,
+              mut q_pickers: Query<&mut ColorPicker>,
+              mut swatches: ResMut<ColorSwatches>| {
+            let color = match trigger.event().0.get_color_at(position.x, position.y) {
+                Ok(color) => color,
+                Err(error) => {
+                    warn!("Could not pick the color of the window: {}", error);
+                    return;
+                }
+            };
+            let Ok(mut picker) = q_pickers.get_mut(picker_entity) else {
+                return;
+            };
+            // The window is opaque, keep the alpha of the picker
+            let hsva = Hsva::from(color).with_alpha(picker.hsva().alpha);
+            let hsva = crate::keep_hue(hsva, picker.hsva());
+            picker.edit(&mut commands, picker_entity, hsva);
+            picker.commit(&mut commands, picker_entity, &mut swatches);
+        },
+    );
+}
+
+/// Cancel the eyedropper with Escape
+pub(crate) fn cancel_eyedropper(
+    mut commands: Commands,
+    keyboard_input: Res<ButtonInput<KeyCode>>,
+    q_overlays: Query<Entity, With<EyedropperOverlay>>,
+) {
+    if keyboard_input.just_pressed(KeyCode::Escape) {
+        for entity in q_overlays.iter() {
+            commands.entity(entity).despawn();
+        }
+    }
+}
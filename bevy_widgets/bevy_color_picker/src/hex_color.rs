@@ -0,0 +1,49 @@
+//! This module provides the value of the hex field of the color picker
+
+use std::fmt;
+
+use bevy::prelude::*;
+use bevy_field_forms::input_field::Validable;
+
+/// A color written in hexadecimal, like `#FF8000` or `#FF800080` with alpha, as edited in an
+/// [`InputField`](bevy_field_forms::input_field::InputField)
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub struct HexColor(pub Srgba);
+
+impl Default for HexColor {
+    fn default() -> Self {
+        Self(Srgba::WHITE)
+    }
+}
+
+impl fmt::Display for HexColor {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "{}", self.0.to_hex())
+    }
+}
+
+impl Validable for HexColor {
+    fn validate(text: &str) -> Result<Self, String> {
+        Srgba::hex(text.trim())
+            .map(HexColor)
+            .map_err(|_| "Invalid hex color".to_string())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn parses_and_formats_hex_colors() {
+        let color = HexColor::validate(" #ff8000 ").unwrap();
+        assert_eq!(color, HexColor(Srgba::rgb_u8(255, 128, 0)));
+        assert_eq!(color.to_string(), "#FF8000");
+        assert_eq!(HexColor::validate("f80").unwrap().to_string(), "#FF8800");
+
+        let transparent = HexColor::validate("#FF800080").unwrap();
+        assert_eq!(transparent.to_string(), "#FF800080");
+        assert!(HexColor::validate("#FF80F").is_err());
+        assert!(HexColor::validate("orange").is_err());
+    }
+}
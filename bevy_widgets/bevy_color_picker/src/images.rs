@@ -0,0 +1,93 @@
+//! Images of the color areas of the picker, painted on the CPU
+
+use bevy::{
+    asset::RenderAssetUsages,
+    image::ImageSampler,
+    prelude::*,
+    render::render_resource::{Extent3d, TextureDimension, TextureFormat},
+};
+
+/// Size of the saturation/value square image, scaled up with linear filtering
+const SQUARE_SIZE: u32 = 32;
+/// Width of the slider images
+const SLIDER_WIDTH: u32 = 128;
+/// Height of the alpha slider image, with a checkerboard of two rows
+const ALPHA_HEIGHT: u32 = 8;
+/// Size of the checkerboard squares behind transparent colors
+const CHECKER_SIZE: u32 = 4;
+
+fn new_image(width: u32, height: u32) -> Image {
+    Image::new_fill(
+        Extent3d {
+            width,
+            height,
+            depth_or_array_layers: 1,
+        },
+        TextureDimension::D2,
+        &[0, 0, 0, 255],
+        TextureFormat::Rgba8UnormSrgb,
+        RenderAssetUsages::MAIN_WORLD | RenderAssetUsages::RENDER_WORLD,
+    )
+}
+
+/// Fill the image with the color of each pixel
+fn paint(image: &mut Image, color_at: impl Fn(u32, u32) -> Srgba) {
+    let size = image.size();
+    let mut data = Vec::with_capacity((size.x * size.y * 4) as usize);
+    for y in 0..size.y {
+        for x in 0..size.x {
+            data.extend_from_slice(&color_at(x, y).to_u8_array());
+        }
+    }
+    image.data = Some(data);
+}
+
+/// The fraction of the way across `size` pixels of pixel `i`
+fn fraction(i: u32, size: u32) -> f32 {
+    i as f32 / (size - 1) as f32
+}
+
+/// The saturation/value square of a hue, saturation growing to the right and value to the top
+pub(crate) fn saturation_value_image(hue: f32) -> Image {
+    let mut image = new_image(SQUARE_SIZE, SQUARE_SIZE);
+    paint_saturation_value(&mut image, hue);
+    image
+}
+
+/// Repaint the saturation/value square for another hue
+pub(crate) fn paint_saturation_value(image: &mut Image, hue: f32) {
+    paint(image, |x, y| {
+        let saturation = fraction(x, SQUARE_SIZE);
+        let value = 1.0 - fraction(y, SQUARE_SIZE);
+        Hsva::new(hue, saturation, value, 1.0).into()
+    });
+}
+
+/// The hue slider, from red to red through the whole color wheel
+pub(crate) fn hue_image() -> Image {
+    let mut image = new_image(SLIDER_WIDTH, 1);
+    paint(&mut image, |x, _| {
+        Hsva::new(fraction(x, SLIDER_WIDTH) * 360.0, 1.0, 1.0, 1.0).into()
+    });
+    image
+}
+
+/// The alpha slider of a color, over a checkerboard
+pub(crate) fn alpha_image(color: Srgba) -> Image {
+    let mut image = new_image(SLIDER_WIDTH, ALPHA_HEIGHT);
+    image.sampler = ImageSampler::nearest();
+    paint_alpha(&mut image, color);
+    image
+}
+
+/// Repaint the alpha slider for another color
+pub(crate) fn paint_alpha(image: &mut Image, color: Srgba) {
+    paint(image, |x, y| {
+        let checker = if (x / CHECKER_SIZE + y / CHECKER_SIZE).is_multiple_of(2) {
+            Srgba::gray(0.8)
+        } else {
+            Srgba::gray(0.5)
+        };
+        checker.mix(&color.with_alpha(1.0), fraction(x, SLIDER_WIDTH))
+    });
+}
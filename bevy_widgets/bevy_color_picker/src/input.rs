@@ -0,0 +1,185 @@
+//! Editing the color of the pickers with the color areas and the fields
+
+use bevy::prelude::*;
+use bevy_field_forms::input_field::ValueChanged;
+use bevy_focus::{Focus, LostFocus};
+
+use crate::{
+    ui::{ColorArea, ColorAreaKind, PickerField},
+    ColorPicker, ColorSwatches, HexColor,
+};
+
+pub(crate) fn on_area_pressed(
+    trigger: Trigger<Pointer<Pressed>>,
+    mut commands: Commands,
+    q_areas: Query<(&ColorArea, &ComputedNode, &GlobalTransform)>,
+    mut q_pickers: Query<&mut ColorPicker>,
+) {
+    if trigger.event().button != PointerButton::Primary {
+        return;
+    }
+    let position = trigger.pointer_location.position;
+    edit_in_area(
+        &mut commands,
+        trigger.target(),
+        position,
+        &q_areas,
+        &mut q_pickers,
+    );
+}
+
+pub(crate) fn on_area_dragged(
+    trigger: Trigger<Pointer<Drag>>,
+    mut commands: Commands,
+    q_areas: Query<(&ColorArea, &ComputedNode, &GlobalTransform)>,
+    mut q_pickers: Query<&mut ColorPicker>,
+) {
+    if trigger.event().button != PointerButton::Primary {
+        return;
+    }
+    let position = trigger.pointer_location.position;
+    edit_in_area(
+        &mut commands,
+        trigger.target(),
+        position,
+        &q_areas,
+        &mut q_pickers,
+    );
+}
+
+/// Set the color from the position of the pointer in a color area
+fn edit_in_area(
+    commands: &mut Commands,
+    entity: Entity,
+    pointer_position: Vec2,
+    q_areas: &Query<(&ColorArea, &ComputedNode, &GlobalTransform)>,
+    q_pickers: &mut Query<&mut ColorPicker>,
+) {
+    let Ok((area, node, global_transform)) = q_areas.get(entity) else {
+        return;
+    };
+    let Ok(mut picker) = q_pickers.get_mut(area.picker) else {
+        return;
+    };
+
+    let scale = node.inverse_scale_factor();
+    let size = node.size() * scale;
+    let top_left = global_transform.translation().truncate() * scale - size / 2.0;
+    let fraction =
+        ((pointer_position - top_left) / size.max(Vec2::ONE)).clamp(Vec2::ZERO, Vec2::ONE);
+
+    let hsva = picker.hsva();
+    let hsva = match area.kind {
+        ColorAreaKind::SaturationValue => Hsva {
+            saturation: fraction.x,
+            value: 1.0 - fraction.y,
+            ..hsva
+        },
+        ColorAreaKind::Hue => hsva.with_hue(fraction.x * 360.0),
+        ColorAreaKind::Alpha => hsva.with_alpha(fraction.x),
+    };
+    picker.edit(commands, area.picker, hsva);
+}
+
+/// Commit a click in a color area without dragging
+pub(crate) fn on_area_released(
+    trigger: Trigger<Pointer<Released>>,
+    mut commands: Commands,
+    q_areas: Query<&ColorArea>,
+    mut q_pickers: Query<&mut ColorPicker>,
+    mut swatches: ResMut<ColorSwatches>,
+) {
+    let Ok(area) = q_areas.get(trigger.target()) else {
+        return;
+    };
+    if let Ok(mut picker) = q_pickers.get_mut(area.picker) {
+        picker.commit(&mut commands, area.picker, &mut swatches);
+    }
+}
+
+/// Commit the drags in the color areas and in the fields, which may end outside of them
+pub(crate) fn on_drag_end(
+    trigger: Trigger<Pointer<DragEnd>>,
+    mut commands: Commands,
+    q_areas: Query<&ColorArea>,
+    q_fields: Query<&PickerField>,
+    mut q_pickers: Query<&mut ColorPicker>,
+    mut swatches: ResMut<ColorSwatches>,
+) {
+    let entity = trigger.target();
+    let picker_entity = match (q_areas.get(entity), q_fields.get(entity)) {
+        (Ok(area), _) => area.picker,
+        (_, Ok(field)) => field.picker,
+        _ => return,
+    };
+    if let Ok(mut picker) = q_pickers.get_mut(picker_entity) {
+        picker.commit(&mut commands, picker_entity, &mut swatches);
+    }
+}
+
+pub(crate) fn on_channel_changed<T: Copy + Into<f64> + Send + Sync + 'static>(
+    trigger: Trigger<ValueChanged<T>>,
+    mut commands: Commands,
+    q_fields: Query<&PickerField>,
+    mut q_pickers: Query<&mut ColorPicker>,
+) {
+    let Ok(field) = q_fields.get(trigger.target()) else {
+        return;
+    };
+    let Ok(mut picker) = q_pickers.get_mut(field.picker) else {
+        return;
+    };
+    let value: f64 = trigger.event().0.into();
+    let hsva = field.channel.set(picker.hsva(), value as f32);
+    picker.edit(&mut commands, field.picker, hsva);
+}
+
+pub(crate) fn on_hex_changed(
+    trigger: Trigger<ValueChanged<HexColor>>,
+    mut commands: Commands,
+    q_fields: Query<&PickerField>,
+    mut q_pickers: Query<&mut ColorPicker>,
+) {
+    let Ok(field) = q_fields.get(trigger.target()) else {
+        return;
+    };
+    let Ok(mut picker) = q_pickers.get_mut(field.picker) else {
+        return;
+    };
+    let hsva = crate::keep_hue(trigger.event().0 .0.into(), picker.hsva());
+    picker.edit(&mut commands, field.picker, hsva);
+}
+
+/// Commit the edits typed in the fields when leaving them
+pub(crate) fn on_field_focus_lost(
+    trigger: Trigger<LostFocus>,
+    mut commands: Commands,
+    q_fields: Query<&PickerField>,
+    mut q_pickers: Query<&mut ColorPicker>,
+    mut swatches: ResMut<ColorSwatches>,
+) {
+    let Ok(field) = q_fields.get(trigger.target()) else {
+        return;
+    };
+    if let Ok(mut picker) = q_pickers.get_mut(field.picker) {
+        picker.commit(&mut commands, field.picker, &mut swatches);
+    }
+}
+
+/// Commit the edits typed in the fields with Enter
+pub(crate) fn commit_on_enter(
+    mut commands: Commands,
+    keyboard_input: Res<ButtonInput<KeyCode>>,
+    q_focused_fields: Query<&PickerField, With<Focus>>,
+    mut q_pickers: Query<&mut ColorPicker>,
+    mut swatches: ResMut<ColorSwatches>,
+) {
+    if !keyboard_input.any_just_pressed([KeyCode::Enter, KeyCode::NumpadEnter]) {
+        return;
+    }
+    for field in q_focused_fields.iter() {
+        if let Ok(mut picker) = q_pickers.get_mut(field.picker) {
+            picker.commit(&mut commands, field.picker, &mut swatches);
+        }
+    }
+}
@@ -1,8 +1,189 @@
 //! A color picker widget for Bevy applications.
+//!
+//! A [`ColorPicker`] edits a color with a saturation/value square, hue and alpha sliders, hex, RGB
+//! and HSL fields built on `bevy_field_forms`, an eyedropper sampling the window, and swatches of
+//! the recent and saved colors.
+//!
+//! Editing emits [`ValueChanged<Color>`] continuously, and [`ColorCommitted`] once an edit is
+//! done, at the end of a drag or when leaving a field, with the color from before the edit so
+//! that it can be recorded as a single undo step.
 
-/// an add function that adds two numbers
-pub fn add(left: u64, right: u64) -> u64 {
-    left + right
+mod eyedropper;
+pub mod hex_color;
+mod images;
+mod input;
+pub mod swatches;
+mod ui;
+
+use bevy::prelude::*;
+use bevy_field_forms::{input_field::InputFieldPlugin, FieldFormsPlugin};
+
+pub use bevy_field_forms::input_field::ValueChanged;
+pub use hex_color::HexColor;
+pub use swatches::ColorSwatches;
+
+/// Plugin for the color picker widget
+pub struct ColorPickerPlugin;
+
+impl Plugin for ColorPickerPlugin {
+    fn build(&self, app: &mut App) {
+        if !app.is_plugin_added::<FieldFormsPlugin>() {
+            app.add_plugins(FieldFormsPlugin);
+        }
+
+        app.add_plugins(InputFieldPlugin::<HexColor>::default());
+
+        app.add_event::<ValueChanged<Color>>();
+        app.add_event::<ColorCommitted>();
+        app.init_resource::<ColorSwatches>();
+
+        app.add_systems(PreUpdate, (ui::spawn_color_pickers, input::commit_on_enter));
+        app.add_systems(
+            PostUpdate,
+            (ui::update_color_pickers, swatches::update_swatch_rows),
+        );
+        app.add_systems(Update, eyedropper::cancel_eyedropper);
+
+        app.add_observer(input::on_area_pressed)
+            .add_observer(input::on_area_dragged)
+            .add_observer(input::on_area_released)
+            .add_observer(input::on_drag_end)
+            .add_observer(input::on_channel_changed::<u8>)
+            .add_observer(input::on_channel_changed::<f32>)
+            .add_observer(input::on_hex_changed)
+            .add_observer(input::on_field_focus_lost)
+            .add_observer(eyedropper::on_eyedropper_click)
+            .add_observer(eyedropper::on_overlay_pressed)
+            .add_observer(swatches::on_swatch_click)
+            .add_observer(swatches::on_save_click);
+    }
+}
+
+/// A widget editing a color, spawned as children of the picker's [`Node`]
+#[derive(Component, Clone, Debug)]
+#[require(Node)]
+pub struct ColorPicker {
+    /// The current color
+    pub color: Color,
+    /// If true, this picker will not update its color automatically
+    /// and will require an external update call to update the color.
+    pub controlled: bool,
+    /// The color being edited, in HSVA so that the hue and saturation of grays are kept
+    hsva: Hsva,
+    /// The color at the end of the last edit
+    committed: Color,
+}
+
+impl Default for ColorPicker {
+    fn default() -> Self {
+        Self::new(Color::WHITE)
+    }
+}
+
+impl ColorPicker {
+    /// Create a new color picker with the given color
+    pub fn new(color: impl Into<Color>) -> Self {
+        let color = color.into();
+        Self {
+            color,
+            controlled: false,
+            hsva: color.into(),
+            committed: color,
+        }
+    }
+
+    /// Make the picker controlled, see [`ColorPicker::controlled`]
+    pub fn with_controlled(mut self, controlled: bool) -> Self {
+        self.controlled = controlled;
+        self
+    }
+
+    /// The color being edited, in HSVA
+    pub fn hsva(&self) -> Hsva {
+        self.hsva
+    }
+
+    /// The edited color, as emitted in the events
+    fn edited_color(&self) -> Color {
+        Color::Srgba(self.hsva.into())
+    }
+
+    /// Edit the color, emitting [`ValueChanged<Color>`]
+    pub(crate) fn edit(&mut self, commands: &mut Commands, entity: Entity, hsva: Hsva) {
+        if hsva == self.hsva {
+            return;
+        }
+        self.hsva = hsva;
+        let color = self.edited_color();
+        commands.trigger_targets(ValueChanged(color), entity);
+        if !self.controlled {
+            self.color = color;
+        }
+    }
+
+    /// End the current edit, emitting [`ColorCommitted`] if the color changed since the last one
+    pub(crate) fn commit(
+        &mut self,
+        commands: &mut Commands,
+        entity: Entity,
+        swatches: &mut ColorSwatches,
+    ) {
+        let color = self.edited_color();
+        if color == self.committed {
+            return;
+        }
+        commands.trigger_targets(
+            ColorCommitted {
+                previous: self.committed,
+                color,
+            },
+            entity,
+        );
+        self.committed = color;
+        swatches.add_recent(color);
+    }
+
+    /// Whether the color was set from outside, and differs from the edited one by more than the
+    /// noise of the conversions
+    fn is_set_from_outside(&self) -> bool {
+        let current = Srgba::from(self.hsva);
+        let color = self.color.to_srgba();
+        let difference = [
+            current.red - color.red,
+            current.green - color.green,
+            current.blue - color.blue,
+        ]
+        .map(f32::abs);
+        difference.into_iter().any(|difference| difference > 1e-4) || current.alpha != color.alpha
+    }
+
+    /// Follow a color set from outside, keeping the hue and saturation where the new color
+    /// doesn't define them
+    fn sync_color(&mut self) {
+        self.hsva = keep_hue(self.color.into(), self.hsva);
+        self.committed = self.color;
+    }
+}
+
+/// Event emitted when an edit of the color of a [`ColorPicker`] is done, like at the end of a drag
+#[derive(Event, Clone, Debug, PartialEq)]
+pub struct ColorCommitted {
+    /// The color before the edit
+    pub previous: Color,
+    /// The color after the edit
+    pub color: Color,
+}
+
+/// Keep the hue of `previous` when `hsva` is a gray, and its saturation too when it is black, as
+/// converting from RGB loses them
+pub(crate) fn keep_hue(mut hsva: Hsva, previous: Hsva) -> Hsva {
+    if hsva.saturation == 0.0 || hsva.value == 0.0 {
+        hsva.hue = previous.hue;
+    }
+    if hsva.value == 0.0 {
+        hsva.saturation = previous.saturation;
+    }
+    hsva
 }
 
 #[cfg(test)]
@@ -10,8 +191,25 @@ mod tests {
     use super::*;
 
     #[test]
-    fn it_works() {
-        let result = add(2, 2);
-        assert_eq!(result, 4);
+    fn keeps_the_hue_of_grays() {
+        let previous = Hsva::new(120.0, 0.5, 0.5, 1.0);
+        let gray = keep_hue(Srgba::gray(0.3).into(), previous);
+        assert_eq!(gray.hue, 120.0);
+        assert_eq!(gray.saturation, 0.0);
+
+        let black = keep_hue(Srgba::BLACK.into(), previous);
+        assert_eq!((black.hue, black.saturation), (120.0, 0.5));
+
+        let red = keep_hue(Srgba::RED.into(), previous);
+        assert_eq!(red.hue, 0.0);
+    }
+
+    #[test]
+    fn follows_colors_set_from_outside() {
+        let mut picker = ColorPicker::new(Hsva::new(200.0, 1.0, 1.0, 1.0));
+        picker.color = Color::BLACK;
+        picker.sync_color();
+        assert_eq!(picker.hsva(), Hsva::new(200.0, 1.0, 0.0, 1.0));
+        assert_eq!(picker.committed, Color::BLACK);
     }
 }
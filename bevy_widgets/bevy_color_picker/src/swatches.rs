@@ -0,0 +1,157 @@
+//! Swatches of the recent and saved colors, shared by all the pickers
+
+use bevy::prelude::*;
+
+use crate::ColorPicker;
+
+/// Number of recent colors kept in [`ColorSwatches`]
+pub const MAX_RECENT_COLORS: usize = 10;
+
+/// The colors shown as swatches under the pickers: the last committed ones, and the ones saved
+/// with the `+` button. A saved swatch is removed by clicking it with the secondary button.
+#[derive(Resource, Clone, Debug, Default, PartialEq)]
+pub struct ColorSwatches {
+    /// The last committed colors, the latest first
+    pub recent: Vec<Color>,
+    /// The saved colors
+    pub saved: Vec<Color>,
+}
+
+impl ColorSwatches {
+    /// Add a color to the recent ones, moving it first if it was already there
+    pub fn add_recent(&mut self, color: Color) {
+        self.recent.retain(|recent| *recent != color);
+        self.recent.insert(0, color);
+        self.recent.truncate(MAX_RECENT_COLORS);
+    }
+
+    /// Save a color, unless it is already saved
+    pub fn save(&mut self, color: Color) {
+        if !self.saved.contains(&color) {
+            self.saved.push(color);
+        }
+    }
+}
+
+/// Which swatches a [`SwatchRow`] shows
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub(crate) enum SwatchRowKind {
+    Recent,
+    Saved,
+}
+
+/// A row of swatches of a picker
+#[derive(Component, Clone, Copy)]
+pub(crate) struct SwatchRow {
+    pub picker: Entity,
+    pub kind: SwatchRowKind,
+}
+
+/// A swatch setting the color of a picker when clicked
+#[derive(Component, Clone, Copy)]
+pub(crate) struct Swatch {
+    picker: Entity,
+    color: Color,
+    kind: SwatchRowKind,
+}
+
+/// The button saving the color of a picker in the swatches
+#[derive(Component, Clone, Copy)]
+pub(crate) struct SaveSwatchButton {
+    pub picker: Entity,
+}
+
+pub(crate) fn update_swatch_rows(
+    mut commands: Commands,
+    swatches: Res<ColorSwatches>,
+    q_rows: Query<(Entity, &SwatchRow, Ref<SwatchRow>)>,
+) {
+    for (entity, row, row_ref) in q_rows.iter() {
+        if !swatches.is_changed() && !row_ref.is_added() {
+            continue;
+        }
+        let colors = match row.kind {
+            SwatchRowKind::Recent => &swatches.recent,
+            SwatchRowKind::Saved => &swatches.saved,
+        };
+        commands.entity(entity).despawn_related::<Children>();
+        for color in colors {
+            commands.spawn((
+                Node {
+                    width: Val::Px(16.0),
+                    height: Val::Px(16.0),
+                    ..default()
+                },
+                BackgroundColor(*color),
+                BorderRadius::all(Val::Px(2.0)),
+                Swatch {
+                    picker: row.picker,
+                    color: *color,
+                    kind: row.kind,
+                },
+                ChildOf(entity),
+            ));
+        }
+    }
+}
+
+pub(crate) fn on_swatch_click(
+    trigger: Trigger<Pointer<Click>>,
+    mut commands: Commands,
+    q_swatches: Query<&Swatch>,
+    mut q_pickers: Query<&mut ColorPicker>,
+    mut swatches: ResMut<ColorSwatches>,
+) {
+    let Ok(swatch) = q_swatches.get(trigger.target()) else {
+        return;
+    };
+    match trigger.event().button {
+        PointerButton::Primary => {
+            let Ok(mut picker) = q_pickers.get_mut(swatch.picker) else {
+                return;
+            };
+            let hsva = crate::keep_hue(swatch.color.into(), picker.hsva());
+            picker.edit(&mut commands, swatch.picker, hsva);
+            picker.commit(&mut commands, swatch.picker, &mut swatches);
+        }
+        PointerButton::Secondary if swatch.kind == SwatchRowKind::Saved => {
+            swatches.saved.retain(|saved| *saved != swatch.color);
+        }
+        _ => {}
+    }
+}
+
+pub(crate) fn on_save_click(
+    trigger: Trigger<Pointer<Click>>,
+    q_buttons: Query<&SaveSwatchButton>,
+    q_pickers: Query<&ColorPicker>,
+    mut swatches: ResMut<ColorSwatches>,
+) {
+    let Ok(button) = q_buttons.get(trigger.target()) else {
+        return;
+    };
+    if let Ok(picker) = q_pickers.get(button.picker) {
+        swatches.save(picker.edited_color());
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn keeps_the_latest_recent_colors() {
+        let mut swatches = ColorSwatches::default();
+        for i in 0..12 {
+            swatches.add_recent(Color::srgb(i as f32 / 12.0, 0.0, 0.0));
+        }
+        swatches.add_recent(Color::srgb(5.0 / 12.0, 0.0, 0.0));
+        assert_eq!(swatches.recent.len(), MAX_RECENT_COLORS);
+        assert_eq!(swatches.recent[0], Color::srgb(5.0 / 12.0, 0.0, 0.0));
+        assert_eq!(swatches.recent[1], Color::srgb(11.0 / 12.0, 0.0, 0.0));
+
+        swatches.save(Color::WHITE);
+        swatches.save(Color::WHITE);
+        assert_eq!(swatches.saved, vec![Color::WHITE]);
+    }
+}
@@ -0,0 +1,512 @@
+//! Spawning the parts of the color pickers, and showing their color in them
+
+use bevy::prelude::*;
+use bevy_field_forms::{
+    drag_input::DragInput,
+    input_field::{InputField, Validable},
+    numeric_format::NumericFormat,
+    validate_highlight::SimpleBorderHighlight,
+};
+
+use crate::{
+    eyedropper::EyedropperButton,
+    images,
+    swatches::{SaveSwatchButton, SwatchRow, SwatchRowKind},
+    ColorPicker, HexColor,
+};
+
+/// Size of the handles of the color areas
+const HANDLE_SIZE: f32 = 10.0;
+/// Background of the fields and buttons
+const FIELD_COLOR: Color = Color::srgb(0.2, 0.2, 0.2);
+
+/// The entities and images of the parts of a picker showing its color
+#[derive(Component)]
+pub(crate) struct ColorPickerParts {
+    square_handle: Entity,
+    square_image: Handle<Image>,
+    hue_handle: Entity,
+    alpha_handle: Entity,
+    alpha_image: Handle<Image>,
+    preview: Entity,
+    /// The color the images are painted for
+    painted: Option<Hsva>,
+}
+
+/// An area of a picker edited by pressing and dragging in it
+#[derive(Component, Clone, Copy)]
+pub(crate) struct ColorArea {
+    pub picker: Entity,
+    pub kind: ColorAreaKind,
+}
+
+/// What a [`ColorArea`] edits
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub(crate) enum ColorAreaKind {
+    /// Saturation to the right and value to the top
+    SaturationValue,
+    /// Hue to the right
+    Hue,
+    /// Alpha to the right
+    Alpha,
+}
+
+/// A field editing a channel of the color of a picker
+#[derive(Component, Clone, Copy)]
+pub(crate) struct PickerField {
+    pub picker: Entity,
+    pub channel: Channel,
+}
+
+/// A channel of a color edited in a field
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub(crate) enum Channel {
+    /// Red, from 0 to 255
+    Red,
+    /// Green, from 0 to 255
+    Green,
+    /// Blue, from 0 to 255
+    Blue,
+    /// HSL hue, in degrees
+    Hue,
+    /// HSL saturation, in percent
+    Saturation,
+    /// HSL lightness, in percent
+    Lightness,
+    /// Alpha, in percent
+    Alpha,
+    /// The whole color in hexadecimal
+    Hex,
+}
+
+impl Channel {
+    fn label(self) -> &'static str {
+        match self {
+            Channel::Red => "R",
+            Channel::Green => "G",
+            Channel::Blue => "B",
+            Channel::Hue => "H",
+            Channel::Saturation => "S",
+            Channel::Lightness => "L",
+            Channel::Alpha => "A",
+            Channel::Hex => "Hex",
+        }
+    }
+
+    /// The value of the channel in a color, rounded as shown in the field
+    pub(crate) fn get(self, hsva: Hsva) -> f32 {
+        let srgba = Srgba::from(hsva);
+        let hsla = Hsla::from(hsva);
+        let value = match self {
+            Channel::Red => srgba.red * 255.0,
+            Channel::Green => srgba.green * 255.0,
+            Channel::Blue => srgba.blue * 255.0,
+            Channel::Hue => hsva.hue,
+            Channel::Saturation => hsla.saturation * 100.0,
+            Channel::Lightness => hsla.lightness * 100.0,
+            Channel::Alpha | Channel::Hex => hsva.alpha * 100.0,
+        };
+        // One decimal is enough, and avoids showing the noise of the conversions
+        (value * 10.0).round() / 10.0
+    }
+
+    /// The color with the channel set to `value`
+    pub(crate) fn set(self, hsva: Hsva, value: f32) -> Hsva {
+        let mut srgba = Srgba::from(hsva);
+        let mut hsla = Hsla::from(hsva);
+        let edited = match self {
+            Channel::Red | Channel::Green | Channel::Blue => {
+                let value = value / 255.0;
+                match self {
+                    Channel::Red => srgba.red = value,
+                    Channel::Green => srgba.green = value,
+                    _ => srgba.blue = value,
+                }
+                Hsva::from(srgba)
+            }
+            Channel::Hue => return hsva.with_hue(value),
+            Channel::Saturation | Channel::Lightness => {
+                if self == Channel::Saturation {
+                    hsla.saturation = value / 100.0;
+                } else {
+                    hsla.lightness = value / 100.0;
+                }
+                Hsva::from(hsla)
+            }
+            Channel::Alpha | Channel::Hex => return hsva.with_alpha(value / 100.0),
+        };
+        crate::keep_hue(edited, hsva)
+    }
+}
+
+pub(crate) fn spawn_color_pickers(
+    mut commands: Commands,
+    q_created_pickers: Query<(Entity, &ColorPicker), Added<ColorPicker>>,
+    mut images: ResMut<Assets<Image>>,
+) {
+    for (entity, picker) in q_created_pickers.iter() {
+        let hsva = picker.hsva();
+        let square_image = images.add(images::saturation_value_image(hsva.hue));
+        let hue_image = images.add(images::hue_image());
+        let alpha_image = images.add(images::alpha_image(hsva.into()));
+
+        let root = commands
+            .spawn((
+                Node {
+                    width: Val::Percent(100.0),
+                    flex_direction: FlexDirection::Column,
+                    row_gap: Val::Px(6.0),
+                    ..default()
+                },
+                ChildOf(entity),
+            ))
+            .id();
+
+        let (_, square_handle) = spawn_area(
+            &mut commands,
+            root,
+            ColorArea {
+                picker: entity,
+                kind: ColorAreaKind::SaturationValue,
+            },
+            square_image.clone(),
+            120.0,
+        );
+        let (_, hue_handle) = spawn_area(
+            &mut commands,
+            root,
+            ColorArea {
+                picker: entity,
+                kind: ColorAreaKind::Hue,
+            },
+            hue_image,
+            12.0,
+        );
+        let (_, alpha_handle) = spawn_area(
+            &mut commands,
+            root,
+            ColorArea {
+                picker: entity,
+                kind: ColorAreaKind::Alpha,
+            },
+            alpha_image.clone(),
+            12.0,
+        );
+
+        // Preview, eyedropper and hex field
+        let row = spawn_row(&mut commands, root);
+        let preview = commands
+            .spawn((
+                Node {
+                    width: Val::Px(22.0),
+                    height: Val::Px(22.0),
+                    flex_shrink: 0.0,
+                    ..default()
+                },
+                BackgroundColor(hsva.into()),
+                BorderRadius::all(Val::Px(3.0)),
+                ChildOf(row),
+            ))
+            .id();
+        spawn_button(&mut commands, row, "Pick").insert(EyedropperButton { picker: entity });
+        spawn_field::<HexColor>(
+            &mut commands,
+            row,
+            PickerField {
+                picker: entity,
+                channel: Channel::Hex,
+            },
+            NumericFormat::default(),
+        );
+
+        let row = spawn_row(&mut commands, root);
+        for channel in [Channel::Red, Channel::Green, Channel::Blue] {
+            spawn_field::<u8>(
+                &mut commands,
+                row,
+                PickerField {
+                    picker: entity,
+                    channel,
+                },
+                NumericFormat::default(),
+            )
+            .insert(DragInput::<u8>::default());
+        }
+
+        let row = spawn_row(&mut commands, root);
+        let percent = NumericFormat::default()
+            .with_range(0.0, 100.0)
+            .with_unit("%");
+        for (channel, format) in [
+            (
+                Channel::Hue,
+                NumericFormat::default()
+                    .with_range(0.0, 360.0)
+                    .with_unit("deg"),
+            ),
+            (Channel::Saturation, percent.clone()),
+            (Channel::Lightness, percent.clone()),
+        ] {
+            spawn_field::<f32>(
+                &mut commands,
+                row,
+                PickerField {
+                    picker: entity,
+                    channel,
+                },
+                format,
+            )
+            .insert(DragInput::<f32>::default());
+        }
+
+        let row = spawn_row(&mut commands, root);
+        spawn_field::<f32>(
+            &mut commands,
+            row,
+            PickerField {
+                picker: entity,
+                channel: Channel::Alpha,
+            },
+            percent,
+        )
+        .insert(DragInput::<f32>::default());
+
+        // Swatches of the recent colors, and of the saved ones with a button saving the color
+        for kind in [SwatchRowKind::Recent, SwatchRowKind::Saved] {
+            let row = spawn_row(&mut commands, root);
+            commands.spawn((
+                Node {
+                    flex_grow: 1.0,
+                    flex_wrap: FlexWrap::Wrap,
+                    column_gap: Val::Px(3.0),
+                    row_gap: Val::Px(3.0),
+                    ..default()
+                },
+                SwatchRow {
+                    picker: entity,
+                    kind,
+                },
+                ChildOf(row),
+            ));
+            if kind == SwatchRowKind::Saved {
+                spawn_button(&mut commands, row, "+").insert(SaveSwatchButton { picker: entity });
+            }
+        }
+
+        commands.entity(entity).insert(ColorPickerParts {
+            square_handle,
+            square_image,
+            hue_handle,
+            alpha_handle,
+            alpha_image,
+            preview,
+            painted: None,
+        });
+    }
+}
+
+/// Spawn a color area showing `image`, returning it and its handle
+fn spawn_area(
+    commands: &mut Commands,
+    parent: Entity,
+    area: ColorArea,
+    image: Handle<Image>,
+    height: f32,
+) -> (Entity, Entity) {
+    let area_entity = commands
+        .spawn((
+            Node {
+                width: Val::Percent(100.0),
+                height: Val::Px(height),
+                ..default()
+            },
+            ImageNode::new(image),
+            area,
+            ChildOf(parent),
+        ))
+        .id();
+    let handle = commands
+        .spawn((
+            Node {
+                position_type: PositionType::Absolute,
+                width: Val::Px(HANDLE_SIZE),
+                height: Val::Px(HANDLE_SIZE),
+                margin: UiRect::all(Val::Px(-HANDLE_SIZE / 2.0)),
+                border: UiRect::all(Val::Px(2.0)),
+                ..default()
+            },
+            BorderColor(Color::WHITE),
+            BorderRadius::MAX,
+            Outline::new(Val::Px(1.0), Val::ZERO, Color::BLACK),
+            Pickable::IGNORE,
+            ChildOf(area_entity),
+        ))
+        .id();
+    (area_entity, handle)
+}
+
+fn spawn_row(commands: &mut Commands, parent: Entity) -> Entity {
+    commands
+        .spawn((
+            Node {
+                width: Val::Percent(100.0),
+                align_items: AlignItems::Center,
+                column_gap: Val::Px(4.0),
+                ..default()
+            },
+            ChildOf(parent),
+        ))
+        .id()
+}
+
+fn spawn_button<'a>(commands: &'a mut Commands, parent: Entity, label: &str) -> EntityCommands<'a> {
+    let mut button = commands.spawn((
+        Button,
+        Node {
+            height: Val::Px(22.0),
+            padding: UiRect::horizontal(Val::Px(6.0)),
+            align_items: AlignItems::Center,
+            flex_shrink: 0.0,
+            ..default()
+        },
+        BackgroundColor(FIELD_COLOR),
+        BorderRadius::all(Val::Px(3.0)),
+        ChildOf(parent),
+    ));
+    button.with_child((
+        Text::new(label),
+        TextFont::from_font_size(12.0),
+        Pickable::IGNORE,
+    ));
+    button
+}
+
+fn spawn_field<'a, T: Validable>(
+    commands: &'a mut Commands,
+    parent: Entity,
+    field: PickerField,
+    format: NumericFormat,
+) -> EntityCommands<'a> {
+    commands.spawn((
+        Text::new(field.channel.label()),
+        TextFont::from_font_size(12.0),
+        ChildOf(parent),
+    ));
+    commands.spawn((
+        Node {
+            flex_grow: 1.0,
+            flex_basis: Val::Px(0.0),
+            min_width: Val::Px(30.0),
+            height: Val::Px(22.0),
+            border: UiRect::all(Val::Px(1.0)),
+            overflow: Overflow::clip(),
+            ..default()
+        },
+        BackgroundColor(FIELD_COLOR),
+        InputField::<T>::default(),
+        SimpleBorderHighlight::default(),
+        format,
+        field,
+        ChildOf(parent),
+    ))
+}
+
+pub(crate) fn update_color_pickers(
+    mut q_changed_pickers: Query<
+        (Entity, &mut ColorPicker, &mut ColorPickerParts),
+        Changed<ColorPicker>,
+    >,
+    mut q_nodes: Query<&mut Node>,
+    mut q_backgrounds: Query<&mut BackgroundColor>,
+    mut q_channel_fields: ParamSet<(
+        Query<(&PickerField, &mut InputField<u8>)>,
+        Query<(&PickerField, &mut InputField<f32>)>,
+        Query<(&PickerField, &mut InputField<HexColor>)>,
+    )>,
+    mut images: ResMut<Assets<Image>>,
+) {
+    for (entity, mut picker, mut parts) in q_changed_pickers.iter_mut() {
+        // Only mutate the picker if a color was set from outside, to not change it every frame
+        if picker.is_set_from_outside() {
+            picker.sync_color();
+        }
+        let hsva = picker.hsva;
+        let srgba = Srgba::from(hsva);
+
+        // The images depend on the hue, and on the whole color for the alpha slider
+        let painted = parts.painted;
+        if painted.is_none_or(|painted| painted.hue != hsva.hue) {
+            if let Some(image) = images.get_mut(&parts.square_image) {
+                images::paint_saturation_value(image, hsva.hue);
+            }
+        }
+        if painted.is_none_or(|painted| painted.with_alpha(1.0) != hsva.with_alpha(1.0)) {
+            if let Some(image) = images.get_mut(&parts.alpha_image) {
+                images::paint_alpha(image, srgba);
+            }
+        }
+        parts.painted = Some(hsva);
+
+        if let Ok(mut node) = q_nodes.get_mut(parts.square_handle) {
+            node.left = Val::Percent(hsva.saturation * 100.0);
+            node.top = Val::Percent((1.0 - hsva.value) * 100.0);
+        }
+        if let Ok(mut node) = q_nodes.get_mut(parts.hue_handle) {
+            node.left = Val::Percent(hsva.hue / 360.0 * 100.0);
+            node.top = Val::Px(1.0);
+        }
+        if let Ok(mut node) = q_nodes.get_mut(parts.alpha_handle) {
+            node.left = Val::Percent(hsva.alpha * 100.0);
+            node.top = Val::Px(1.0);
+        }
+        if let Ok(mut background) = q_backgrounds.get_mut(parts.preview) {
+            background.0 = srgba.into();
+        }
+
+        // Fields which already show the value are left alone, not to reformat text being typed
+        for (field, mut input) in q_channel_fields.p0().iter_mut() {
+            let value = field.channel.get(hsva).round() as u8;
+            if field.picker == entity && input.value != value {
+                input.value = value;
+            }
+        }
+        for (field, mut input) in q_channel_fields.p1().iter_mut() {
+            let value = field.channel.get(hsva);
+            if field.picker == entity && input.value != value {
+                input.value = value;
+            }
+        }
+        for (field, mut input) in q_channel_fields.p2().iter_mut() {
+            let value = HexColor(srgba);
+            if field.picker == entity && input.value.to_string() != value.to_string() {
+                input.value = value;
+            }
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn edits_channels() {
+        let orange = Hsva::from(Srgba::rgb_u8(255, 128, 0));
+        assert_eq!(Channel::Red.get(orange), 255.0);
+        assert_eq!(Channel::Green.get(orange), 128.0);
+        assert_eq!(Channel::Hue.get(orange), 30.1);
+        assert_eq!(Channel::Lightness.get(orange), 50.0);
+
+        let blue = Channel::Blue.set(orange, 255.0);
+        assert_eq!(Srgba::from(blue).to_u8_array(), [255, 128, 255, 255]);
+        let hue = Channel::Hue.set(orange, 200.0);
+        assert_eq!(hue.hue, 200.0);
+        let alpha = Channel::Alpha.set(orange, 50.0);
+        assert_eq!(alpha.alpha, 0.5);
+
+        // Editing a gray keeps the hue
+        let gray = Channel::Saturation.set(orange, 0.0);
+        assert_eq!(gray.hue, orange.hue);
+        assert_eq!(Channel::Saturation.set(gray, 100.0).hue, orange.hue);
+    }
+}
@@ -0,0 +1,80 @@
+//! This example registers a few commands changing the background color, which can be run from
+//! the command palette opened with `Ctrl+Shift+P`, their keybindings or a context menu.
+
+use bevy::prelude::*;
+use bevy_command_palette::{
+    command_menu_option, CommandInfo, CommandPaletteAppExt, CommandPalettePlugin,
+    OpenCommandPalette,
+};
+use bevy_context_menu::{ContextMenu, ContextMenuPlugin};
+use bevy_editor_styles::{StylesPlugin, Theme};
+use bevy_text_editing::keymap::KeyChord;
+
+fn main() {
+    App::new()
+        .add_plugins(DefaultPlugins)
+        .add_plugins((StylesPlugin, ContextMenuPlugin, CommandPalettePlugin))
+        .register_command(
+            CommandInfo::new("color.red", "Red")
+                .with_category("Background")
+                .with_keybinding(KeyChord::new(KeyCode::KeyR).with_alt()),
+            set_background(Color::srgb(0.4, 0.1, 0.1)),
+        )
+        .register_command(
+            CommandInfo::new("color.green", "Green")
+                .with_category("Background")
+                .with_keybinding(KeyChord::new(KeyCode::KeyG).with_alt()),
+            set_background(Color::srgb(0.1, 0.4, 0.1)),
+        )
+        .register_command(
+            CommandInfo::new("color.blue", "Blue").with_category("Background"),
+            set_background(Color::srgb(0.1, 0.1, 0.4)),
+        )
+        .register_command(
+            CommandInfo::new("app.exit", "Exit"),
+            |mut exit: EventWriter<AppExit>| {
+                exit.write(AppExit::Success);
+            },
+        )
+        .add_systems(Startup, setup)
+        .run();
+}
+
+fn set_background(color: Color) -> impl Fn(ResMut<ClearColor>) {
+    move |mut clear_color: ResMut<ClearColor>| {
+        clear_color.0 = color;
+    }
+}
+
+fn setup(mut commands: Commands, theme: Res<Theme>) {
+    commands.spawn(Camera2d);
+
+    commands
+        .spawn((
+            Node {
+                width: Val::Percent(100.0),
+                height: Val::Percent(100.0),
+                align_items: AlignItems::Center,
+                justify_content: JustifyContent::Center,
+                ..default()
+            },
+            ContextMenu::new([
+                command_menu_option("Red", "color.red"),
+                command_menu_option("Green", "color.green"),
+                command_menu_option("Blue", "color.blue"),
+            ]),
+        ))
+        .with_child((
+            Text::new("Press Ctrl+Shift+P, right click, or click here"),
+            TextFont {
+                font: theme.text.font.clone(),
+                font_size: 16.0,
+                ..default()
+            },
+        ))
+        .observe(|trigger: Trigger<Pointer<Click>>, mut commands: Commands| {
+            if trigger.event().button == PointerButton::Primary {
+                commands.trigger(OpenCommandPalette);
+            }
+        });
+}
@@ -3,11 +3,342 @@
 //! This lists a number of commands that can be executed by the user,
 //! allowing for quick access to a variety of functionality.
 //!
-//! Search and keyboard shortcuts will both be supported.
+//! Commands are registered on the [`App`] with [`CommandPaletteAppExt::register_command`], with
+//! a label, a category, an optional keybinding and the system they run. They can then be run from
+//! anywhere with the [`RunCommand`] event, from their keybinding, or from the palette opened with
+//! `Ctrl+Shift+P` (`Cmd+Shift+P` on macOS), which finds them by fuzzy search and lists the most
+//! recently used first.
+//!
+//! Menus run the same commands, so that each of their actions can also be found in the palette:
+//!
+//! ```
+//! # use bevy_command_palette::*;
+//! # use bevy_context_menu::ContextMenu;
+//! let menu = ContextMenu::new([command_menu_option("Undo", "edit.undo")]);
+//! ```
+//!
+//! A command run from a context menu applies to the entity the menu was opened on, its
+//! [`CommandTarget`]. Run from the palette, it has no target and applies to a default one, like the
+//! selection.
+
+mod search;
+mod ui;
+
+use std::{borrow::Cow, collections::VecDeque};
+
+use bevy::{ecs::system::SystemId, prelude::*};
+use bevy_context_menu::ContextMenuOption;
+use bevy_editor_settings::{SettingKey, SettingsType};
+use bevy_focus::Focus;
+use bevy_text_editing::{
+    keymap::{KeyChord, Modifiers},
+    EditableTextLinePlugin,
+};
+
+/// Plugin for the command registry and palette
+pub struct CommandPalettePlugin;
+
+impl Plugin for CommandPalettePlugin {
+    fn build(&self, app: &mut App) {
+        if !app.is_plugin_added::<EditableTextLinePlugin>() {
+            app.add_plugins(EditableTextLinePlugin);
+        }
+
+        app.init_resource::<CommandRegistry>()
+            .init_resource::<RecentCommands>()
+            .init_resource::<CommandTarget>()
+            .init_resource::<CommandPaletteSettings>()
+            .register_type::<CommandPaletteSettings>();
+
+        app.add_event::<RunCommand>();
+        app.add_event::<OpenCommandPalette>();
+
+        app.add_systems(
+            Update,
+            (
+                run_keybindings,
+                ui::palette_keyboard,
+                ui::update_palettes.after(ui::palette_keyboard),
+            ),
+        );
+
+        app.add_observer(run_command)
+            .add_observer(ui::open_palette)
+            .add_observer(ui::on_query_changed)
+            .add_observer(ui::on_row_over)
+            .add_observer(ui::on_row_released);
+    }
+}
+
+/// The description of a command, shown in the palette
+#[derive(Clone, Debug, PartialEq)]
+pub struct CommandInfo {
+    /// Unique identifier, like `edit.undo`, used to run the command with [`RunCommand`]
+    pub id: Cow<'static, str>,
+    /// The name of the command
+    pub label: Cow<'static, str>,
+    /// The group of the command, like `Edit`, shown before its label
+    pub category: Option<Cow<'static, str>>,
+    /// The keys running the command
+    pub keybinding: Option<KeyChord>,
+}
+
+impl CommandInfo {
+    /// Create a new command description
+    pub fn new(id: impl Into<Cow<'static, str>>, label: impl Into<Cow<'static, str>>) -> Self {
+        Self {
+            id: id.into(),
+            label: label.into(),
+            category: None,
+            keybinding: None,
+        }
+    }
+
+    /// Set the category of the command
+    pub fn with_category(mut self, category: impl Into<Cow<'static, str>>) -> Self {
+        self.category = Some(category.into());
+        self
+    }
+
+    /// Run the command when `keybinding` is pressed
+    pub fn with_keybinding(mut self, keybinding: KeyChord) -> Self {
+        self.keybinding = Some(keybinding);
+        self
+    }
+
+    /// The category and label, as searched in the palette
+    pub fn title(&self) -> String {
+        match &self.category {
+            Some(category) => format!("{}: {}", category, self.label),
+            None => self.label.to_string(),
+        }
+    }
+}
+
+/// The commands registered with [`CommandPaletteAppExt::register_command`], in registration order
+#[derive(Resource, Default)]
+pub struct CommandRegistry {
+    commands: Vec<(CommandInfo, SystemId)>,
+}
+
+impl CommandRegistry {
+    /// The command with the given id
+    pub fn get(&self, id: &str) -> Option<&CommandInfo> {
+        self.commands
+            .iter()
+            .find(|(info, _)| info.id == id)
+            .map(|(info, _)| info)
+    }
+
+    /// All the commands
+    pub fn iter(&self) -> impl Iterator<Item = &CommandInfo> {
+        self.commands.iter().map(|(info, _)| info)
+    }
+
+    fn system(&self, id: &str) -> Option<SystemId> {
+        self.commands
+            .iter()
+            .find(|(info, _)| info.id == id)
+            .map(|(_, system)| *system)
+    }
+
+    fn insert(&mut self, info: CommandInfo, system: SystemId) {
+        if let Some(existing) = self
+            .commands
+            .iter_mut()
+            .find(|(other, _)| other.id == info.id)
+        {
+            warn!("Command {} registered twice, replacing it", info.id);
+            *existing = (info, system);
+        } else {
+            self.commands.push((info, system));
+        }
+    }
+}
+
+/// Extension trait for [`App`] to register commands
+pub trait CommandPaletteAppExt {
+    /// Register a command running `system`, replacing any command with the same id
+    fn register_command<M>(
+        &mut self,
+        info: CommandInfo,
+        system: impl IntoSystem<(), (), M> + 'static,
+    ) -> &mut Self;
+}
+
+impl CommandPaletteAppExt for App {
+    fn register_command<M>(
+        &mut self,
+        info: CommandInfo,
+        system: impl IntoSystem<(), (), M> + 'static,
+    ) -> &mut Self {
+        let system = self.world_mut().register_system(system);
+        self.init_resource::<CommandRegistry>();
+        self.world_mut()
+            .resource_mut::<CommandRegistry>()
+            .insert(info, system);
+        self
+    }
+}
 
-/// an add function that adds two numbers
-pub fn add(left: u64, right: u64) -> u64 {
-    left + right
+/// Maximum number of commands remembered in [`RecentCommands`]
+pub const MAX_RECENT_COMMANDS: usize = 10;
+
+/// The ids of the last commands run, most recent first
+#[derive(Resource, Default, Debug)]
+pub struct RecentCommands {
+    ids: VecDeque<Cow<'static, str>>,
+}
+
+impl RecentCommands {
+    /// The ids, most recent first
+    pub fn iter(&self) -> impl Iterator<Item = &str> {
+        self.ids.iter().map(AsRef::as_ref)
+    }
+
+    /// How many commands were run since the command with the given id, if it is remembered
+    pub fn position(&self, id: &str) -> Option<usize> {
+        self.ids.iter().position(|recent| recent == id)
+    }
+
+    /// Remember that a command was run
+    pub fn push(&mut self, id: Cow<'static, str>) {
+        self.ids.retain(|recent| *recent != id);
+        self.ids.push_front(id);
+        self.ids.truncate(MAX_RECENT_COMMANDS);
+    }
+}
+
+/// Global settings of the command palette
+#[derive(Resource, Reflect, Clone, Debug, PartialEq)]
+#[reflect(Resource, @SettingsType::Global, @SettingKey("command_palette"))]
+pub struct CommandPaletteSettings {
+    /// The keys opening the palette
+    pub open: KeyChord,
+}
+
+impl Default for CommandPaletteSettings {
+    fn default() -> Self {
+        Self {
+            open: KeyChord::primary(KeyCode::KeyP).with_shift(),
+        }
+    }
+}
+
+/// Run the registered command with the given id.
+/// Triggered globally with [`Commands::trigger`], or on the entity the command applies to with
+/// [`Commands::trigger_targets`], which is then the [`CommandTarget`] of the command.
+#[derive(Event, Clone, Debug, PartialEq)]
+pub struct RunCommand(pub Cow<'static, str>);
+
+impl RunCommand {
+    /// Run the command with the given id
+    pub fn new(id: impl Into<Cow<'static, str>>) -> Self {
+        Self(id.into())
+    }
+}
+
+/// The entity the running command applies to, like the file a context menu was opened on.
+/// `None` when the command runs from the palette or its keybinding.
+#[derive(Resource, Default, Clone, Copy, Debug, PartialEq, Eq)]
+pub struct CommandTarget(pub Option<Entity>);
+
+/// Open the command palette, if it is not already open.
+/// Must be triggered globally, with [`Commands::trigger`].
+#[derive(Event, Clone, Debug, Default)]
+pub struct OpenCommandPalette;
+
+/// A context menu option running the command with the given id, on the entity the menu was opened
+/// on
+pub fn command_menu_option(
+    label: impl Into<String>,
+    id: impl Into<Cow<'static, str>>,
+) -> ContextMenuOption {
+    let id = id.into();
+    ContextMenuOption::new(label, move |mut commands, entity| {
+        commands.trigger_targets(RunCommand(id.clone()), entity);
+    })
+}
+
+/// The text shown for a keybinding, like `Ctrl+Shift+P`
+pub fn keybinding_label(chord: &KeyChord) -> String {
+    let mac = cfg!(target_os = "macos");
+    let mut parts = Vec::new();
+    if chord.control {
+        parts.push("Ctrl".to_string());
+    }
+    if chord.alt {
+        parts.push(if mac { "Option" } else { "Alt" }.to_string());
+    }
+    if chord.shift {
+        parts.push("Shift".to_string());
+    }
+    if chord.super_key {
+        parts.push(if mac { "Cmd" } else { "Super" }.to_string());
+    }
+    let key = format!("{:?}", chord.key);
+    let key = key
+        .strip_prefix("Key")
+        .or_else(|| key.strip_prefix("Digit"))
+        .unwrap_or(&key);
+    parts.push(key.to_string());
+    parts.join("+")
+}
+
+fn run_command(
+    trigger: Trigger<RunCommand>,
+    mut commands: Commands,
+    registry: Res<CommandRegistry>,
+    mut recent: ResMut<RecentCommands>,
+) {
+    let id = &trigger.event().0;
+    let Some(system) = registry.system(id) else {
+        warn!("Unknown command {}", id);
+        return;
+    };
+    let target = trigger.target();
+    commands.insert_resource(CommandTarget(
+        (target != Entity::PLACEHOLDER).then_some(target),
+    ));
+    commands.run_system(system);
+    recent.push(id.clone());
+}
+
+/// Opens or closes the palette, and runs the commands whose keybinding is pressed
+fn run_keybindings(
+    mut commands: Commands,
+    keys: Res<ButtonInput<KeyCode>>,
+    settings: Res<CommandPaletteSettings>,
+    registry: Res<CommandRegistry>,
+    q_palettes: Query<Entity, With<ui::CommandPalette>>,
+    q_focused: Query<(), With<Focus>>,
+) {
+    let modifiers = Modifiers::pressed(&keys);
+    for key in keys.get_just_pressed() {
+        if settings.open.matches(*key, modifiers, false) {
+            match q_palettes.iter().next() {
+                Some(palette) => ui::close_palette(&mut commands, palette),
+                None => commands.trigger(OpenCommandPalette),
+            }
+            continue;
+        }
+        if !q_palettes.is_empty() {
+            continue;
+        }
+        for info in registry.iter() {
+            let Some(keybinding) = info.keybinding else {
+                continue;
+            };
+            // Keys without modifiers are typed in the focused widget instead
+            let typed = !(keybinding.control || keybinding.alt || keybinding.super_key);
+            if typed && !q_focused.is_empty() {
+                continue;
+            }
+            if keybinding.matches(*key, modifiers, false) {
+                commands.trigger(RunCommand(info.id.clone()));
+            }
+        }
+    }
 }
 
 #[cfg(test)]
@@ -15,8 +346,56 @@ mod tests {
     use super::*;
 
     #[test]
-    fn it_works() {
-        let result = add(2, 2);
-        assert_eq!(result, 4);
+    fn remembers_recent_commands() {
+        let mut recent = RecentCommands::default();
+        for i in 0..MAX_RECENT_COMMANDS + 2 {
+            recent.push(format!("command.{}", i).into());
+        }
+        recent.push("command.5".into());
+        assert_eq!(recent.iter().count(), MAX_RECENT_COMMANDS);
+        assert_eq!(recent.position("command.5"), Some(0));
+        assert_eq!(recent.position("command.11"), Some(1));
+        assert_eq!(recent.position("command.0"), None);
+    }
+
+    #[test]
+    fn runs_commands_on_their_target() {
+        #[derive(Resource, Default)]
+        struct Targets(Vec<Option<Entity>>);
+
+        let mut app = App::new();
+        app.init_resource::<CommandRegistry>()
+            .init_resource::<RecentCommands>()
+            .init_resource::<CommandTarget>()
+            .init_resource::<Targets>()
+            .add_observer(run_command)
+            .register_command(
+                CommandInfo::new("file.delete", "Delete"),
+                |target: Res<CommandTarget>, mut targets: ResMut<Targets>| {
+                    targets.0.push(target.0);
+                },
+            );
+        let file = app.world_mut().spawn_empty().id();
+
+        app.world_mut()
+            .trigger_targets(RunCommand::new("file.delete"), file);
+        app.world_mut().trigger(RunCommand::new("file.delete"));
+        app.world_mut().flush();
+
+        assert_eq!(app.world().resource::<Targets>().0, [Some(file), None]);
+        assert_eq!(
+            app.world()
+                .resource::<RecentCommands>()
+                .position("file.delete"),
+            Some(0)
+        );
+    }
+
+    #[test]
+    fn labels_keybindings() {
+        let chord = KeyChord::new(KeyCode::KeyP).with_control().with_shift();
+        assert_eq!(keybinding_label(&chord), "Ctrl+Shift+P");
+        assert_eq!(keybinding_label(&KeyChord::new(KeyCode::Digit1)), "1");
+        assert_eq!(keybinding_label(&KeyChord::new(KeyCode::F2)), "F2");
     }
 }
@@ -0,0 +1,50 @@
+//! Fuzzy search of the commands shown in the palette
+
+use bevy_editor_core::fuzzy::fuzzy_score;
+
+use crate::{CommandInfo, RecentCommands, MAX_RECENT_COMMANDS};
+
+/// The indices of the commands matching `query`, best first. Recently run commands rank higher,
+/// and come first when the query is empty.
+pub(crate) fn rank_commands<'a>(
+    commands: impl IntoIterator<Item = &'a CommandInfo>,
+    recent: &RecentCommands,
+    query: &str,
+) -> Vec<usize> {
+    let mut ranked: Vec<(usize, i32)> = commands
+        .into_iter()
+        .enumerate()
+        .filter_map(|(index, info)| {
+            let score = fuzzy_score(query, &info.title())?;
+            let recency = recent
+                .position(&info.id)
+                .map_or(0, |position| (MAX_RECENT_COMMANDS - position) as i32);
+            Some((index, score + recency))
+        })
+        .collect();
+    // Stable, so that commands with the same score stay in registration order
+    ranked.sort_by(|(_, a), (_, b)| b.cmp(a));
+    ranked.into_iter().map(|(index, _)| index).collect()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn ranks_recent_commands_first() {
+        let commands = [
+            CommandInfo::new("file.save", "Save").with_category("File"),
+            CommandInfo::new("file.save_as", "Save As").with_category("File"),
+            CommandInfo::new("edit.undo", "Undo").with_category("Edit"),
+        ];
+        let mut recent = RecentCommands::default();
+        assert_eq!(rank_commands(&commands, &recent, ""), [0, 1, 2]);
+        assert_eq!(rank_commands(&commands, &recent, "undo"), [2]);
+
+        recent.push("file.save_as".into());
+        recent.push("edit.undo".into());
+        assert_eq!(rank_commands(&commands, &recent, ""), [2, 1, 0]);
+        assert_eq!(rank_commands(&commands, &recent, "save"), [1, 0]);
+    }
+}
@@ -0,0 +1,307 @@
+use bevy::prelude::*;
+use bevy_editor_styles::Theme;
+use bevy_focus::FocusExt;
+use bevy_text_editing::{EditableTextLine, TextChanged};
+
+use crate::{
+    keybinding_label, search::rank_commands, CommandRegistry, OpenCommandPalette, RecentCommands,
+    RunCommand,
+};
+
+/// Maximum number of commands listed at once
+const MAX_RESULTS: usize = 12;
+
+/// The root of an open palette, covering the window so that clicking outside closes it
+#[derive(Component)]
+pub(crate) struct CommandPalette {
+    /// The text searched
+    query: String,
+    /// The index of the selected row
+    selected: usize,
+    /// The indices in the [`CommandRegistry`] of the listed commands
+    results: Vec<usize>,
+    /// The node listing the commands
+    list: Entity,
+}
+
+/// A row of the list, showing the command at `index` in the results
+#[derive(Component)]
+pub(crate) struct PaletteRow {
+    palette: Entity,
+    index: usize,
+}
+
+pub(crate) fn open_palette(
+    _: Trigger<OpenCommandPalette>,
+    mut commands: Commands,
+    theme: Res<Theme>,
+    q_palettes: Query<(), With<CommandPalette>>,
+) {
+    if !q_palettes.is_empty() {
+        return;
+    }
+
+    let palette = commands
+        .spawn((
+            Node {
+                width: Val::Percent(100.),
+                height: Val::Percent(100.),
+                justify_content: JustifyContent::Center,
+                align_items: AlignItems::Start,
+                padding: UiRect::top(Val::Percent(10.)),
+                ..default()
+            },
+            GlobalZIndex(100),
+        ))
+        .observe(
+            |trigger: Trigger<Pointer<Pressed>>, mut commands: Commands| {
+                close_palette(&mut commands, trigger.target());
+            },
+        )
+        .id();
+
+    let panel = commands
+        .spawn((
+            Node {
+                width: Val::Px(500.),
+                flex_direction: FlexDirection::Column,
+                padding: UiRect::all(Val::Px(5.)),
+                row_gap: Val::Px(5.),
+                ..default()
+            },
+            BoxShadow::from(ShadowStyle {
+                blur_radius: Val::Px(3.),
+                x_offset: Val::ZERO,
+                y_offset: Val::ZERO,
+                color: Color::BLACK.with_alpha(0.8),
+                ..Default::default()
+            }),
+            theme.context_menu.background_color,
+            theme.general.border_radius,
+            ChildOf(palette),
+        ))
+        .observe(|mut trigger: Trigger<Pointer<Pressed>>| {
+            // Prevent the palette root from closing the palette when clicking on the panel
+            trigger.propagate(false);
+        })
+        .id();
+
+    let search = commands
+        .spawn((
+            EditableTextLine::new(""),
+            Node {
+                width: Val::Percent(100.),
+                height: Val::Px(25.),
+                padding: UiRect::horizontal(Val::Px(5.)),
+                ..default()
+            },
+            TextFont {
+                font: theme.text.font.clone(),
+                font_size: 14.,
+                ..default()
+            },
+            theme.button.background_color,
+            theme.context_menu.option_border_radius,
+            ChildOf(panel),
+        ))
+        .id();
+
+    let list = commands
+        .spawn((
+            Node {
+                flex_direction: FlexDirection::Column,
+                ..default()
+            },
+            ChildOf(panel),
+        ))
+        .id();
+
+    commands.entity(palette).insert(CommandPalette {
+        query: String::new(),
+        selected: 0,
+        results: Vec::new(),
+        list,
+    });
+    commands.set_focus(search);
+}
+
+pub(crate) fn close_palette(commands: &mut Commands, palette: Entity) {
+    commands.entity(palette).despawn();
+    commands.clear_focus();
+}
+
+fn run_result(commands: &mut Commands, registry: &CommandRegistry, palette: Entity, index: usize) {
+    close_palette(commands, palette);
+    if let Some(info) = registry.iter().nth(index) {
+        commands.trigger(RunCommand(info.id.clone()));
+    }
+}
+
+pub(crate) fn on_query_changed(
+    trigger: Trigger<TextChanged>,
+    mut q_palettes: Query<&mut CommandPalette>,
+) {
+    let Ok(mut palette) = q_palettes.get_mut(trigger.target()) else {
+        return;
+    };
+    palette.query = trigger.event().new_text.clone();
+    palette.selected = 0;
+}
+
+pub(crate) fn on_row_over(
+    trigger: Trigger<Pointer<Over>>,
+    q_rows: Query<&PaletteRow>,
+    mut q_palettes: Query<&mut CommandPalette>,
+) {
+    let Ok(row) = q_rows.get(trigger.target()) else {
+        return;
+    };
+    let Ok(mut palette) = q_palettes.get_mut(row.palette) else {
+        return;
+    };
+    // Rows are respawned when the selection changes, hovering the new row again
+    if palette.selected != row.index {
+        palette.selected = row.index;
+    }
+}
+
+pub(crate) fn on_row_released(
+    trigger: Trigger<Pointer<Released>>,
+    mut commands: Commands,
+    registry: Res<CommandRegistry>,
+    q_rows: Query<&PaletteRow>,
+    q_palettes: Query<&CommandPalette>,
+) {
+    if trigger.event().button != PointerButton::Primary {
+        return;
+    }
+    let Ok(row) = q_rows.get(trigger.target()) else {
+        return;
+    };
+    let Ok(palette) = q_palettes.get(row.palette) else {
+        return;
+    };
+    if let Some(&index) = palette.results.get(row.index) {
+        run_result(&mut commands, &registry, row.palette, index);
+    }
+}
+
+/// Moves the selection with the arrows, runs the selected command with Enter and closes the
+/// palette with Escape
+pub(crate) fn palette_keyboard(
+    mut commands: Commands,
+    keys: Res<ButtonInput<KeyCode>>,
+    registry: Res<CommandRegistry>,
+    mut q_palettes: Query<(Entity, &mut CommandPalette)>,
+) {
+    for (entity, mut palette) in q_palettes.iter_mut() {
+        let count = palette.results.len();
+        if keys.just_pressed(KeyCode::Escape) {
+            close_palette(&mut commands, entity);
+        } else if keys.just_pressed(KeyCode::Enter) || keys.just_pressed(KeyCode::NumpadEnter) {
+            if let Some(&index) = palette.results.get(palette.selected) {
+                run_result(&mut commands, &registry, entity, index);
+            }
+        } else if count == 0 {
+            continue;
+        } else if keys.just_pressed(KeyCode::ArrowDown) {
+            palette.selected = (palette.selected + 1) % count;
+        } else if keys.just_pressed(KeyCode::ArrowUp) {
+            palette.selected = (palette.selected + count - 1) % count;
+        }
+    }
+}
+
+/// Lists the commands matching the query of the palettes that changed
+pub(crate) fn update_palettes(
+    mut commands: Commands,
+    theme: Res<Theme>,
+    registry: Res<CommandRegistry>,
+    recent: Res<RecentCommands>,
+    mut q_palettes: Query<(Entity, &mut CommandPalette), Changed<CommandPalette>>,
+) {
+    for (entity, mut palette) in q_palettes.iter_mut() {
+        let mut results = rank_commands(registry.iter(), &recent, &palette.query);
+        results.truncate(MAX_RESULTS);
+        let palette = palette.bypass_change_detection();
+        palette.results = results;
+        palette.selected = palette
+            .selected
+            .min(palette.results.len().saturating_sub(1));
+
+        commands.entity(palette.list).despawn_related::<Children>();
+
+        let font = TextFont {
+            font: theme.text.font.clone(),
+            font_size: 12.,
+            ..default()
+        };
+
+        if palette.results.is_empty() {
+            commands.spawn((
+                Node {
+                    padding: UiRect::all(Val::Px(5.)),
+                    ..default()
+                },
+                Text::new("No matching commands"),
+                font.clone(),
+                TextColor(theme.text.low_priority),
+                Pickable::IGNORE,
+                ChildOf(palette.list),
+            ));
+            continue;
+        }
+
+        let infos: Vec<_> = registry.iter().collect();
+        for (row, &index) in palette.results.iter().enumerate() {
+            let info = infos[index];
+            let background = if row == palette.selected {
+                theme.context_menu.hover_color
+            } else {
+                BackgroundColor(Color::NONE)
+            };
+
+            commands
+                .spawn((
+                    PaletteRow {
+                        palette: entity,
+                        index: row,
+                    },
+                    Node {
+                        padding: UiRect::all(Val::Px(5.)),
+                        justify_content: JustifyContent::SpaceBetween,
+                        column_gap: Val::Px(10.),
+                        ..default()
+                    },
+                    background,
+                    theme.context_menu.option_border_radius,
+                    ChildOf(palette.list),
+                ))
+                .with_children(|cmd| {
+                    cmd.spawn((Text::default(), font.clone(), Pickable::IGNORE))
+                        .with_children(|cmd| {
+                            if let Some(category) = &info.category {
+                                cmd.spawn((
+                                    TextSpan::new(format!("{}: ", category)),
+                                    font.clone(),
+                                    TextColor(theme.text.low_priority),
+                                ));
+                            }
+                            cmd.spawn((
+                                TextSpan::new(info.label.clone()),
+                                font.clone(),
+                                TextColor(theme.text.text_color),
+                            ));
+                        });
+                    if let Some(keybinding) = &info.keybinding {
+                        cmd.spawn((
+                            Text::new(keybinding_label(keybinding)),
+                            font.clone(),
+                            TextColor(theme.text.low_priority),
+                            Pickable::IGNORE,
+                        ));
+                    }
+                });
+        }
+    }
+}
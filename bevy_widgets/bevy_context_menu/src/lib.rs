@@ -12,24 +12,23 @@ pub struct ContextMenuPlugin;
 
 impl Plugin for ContextMenuPlugin {
     fn build(&self, app: &mut App) {
-        app.add_observer(on_secondary_button_down_entity_with_context_menu);
+        app.add_observer(on_button_down_entity_with_context_menu);
     }
 }
 
-fn on_secondary_button_down_entity_with_context_menu(
+fn on_button_down_entity_with_context_menu(
     mut trigger: Trigger<Pointer<Released>>,
     mut commands: Commands,
     query: Query<&ContextMenu>,
     theme: Res<Theme>,
 ) {
-    if trigger.event().button != PointerButton::Secondary {
-        return;
-    }
-
     let target = trigger.target();
     let Ok(menu) = query.get(target) else {
         return;
     };
+    if trigger.event().button != menu.button {
+        return;
+    }
 
     trigger.propagate(false);
 
@@ -67,17 +66,29 @@ fn on_secondary_button_down_entity_with_context_menu(
 }
 
 /// Entities with this component will have a context menu.
-/// The menu can be opened by pressing the secondary mouse button over the entity.
+/// The menu can be opened by pressing the secondary mouse button over the entity,
+/// or another button set with [`ContextMenu::with_button`].
 #[derive(Component)]
 pub struct ContextMenu {
     options: Vec<ContextMenuOption>,
+    button: PointerButton,
 }
 
 impl ContextMenu {
     /// Create a new [`ContextMenu`] from a list of [`ContextMenuOption`]s.
     pub fn new(options: impl IntoIterator<Item = ContextMenuOption>) -> Self {
         let options = options.into_iter().collect();
-        ContextMenu { options }
+        ContextMenu {
+            options,
+            button: PointerButton::Secondary,
+        }
+    }
+
+    /// Open the menu with the given button instead of the secondary mouse button.
+    /// Opening it with [`PointerButton::Primary`] makes the entity behave like a drop down button.
+    pub fn with_button(mut self, button: PointerButton) -> Self {
+        self.button = button;
+        self
     }
 }
 
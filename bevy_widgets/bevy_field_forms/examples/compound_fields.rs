@@ -0,0 +1,57 @@
+//! This example demonstrates the compound fields editing vectors, rotations and a uniform scale.
+
+use bevy::prelude::*;
+use bevy_field_forms::{
+    compound_field::{CompoundField, CompoundValue, LinkedAxes},
+    input_field::ValueChanged,
+    FieldFormsPlugin,
+};
+
+fn main() {
+    App::new()
+        .add_plugins(DefaultPlugins)
+        .add_plugins(FieldFormsPlugin)
+        .add_systems(Startup, setup)
+        .run();
+}
+
+fn setup(mut commands: Commands) {
+    commands.spawn(Camera2d);
+
+    commands
+        .spawn(Node {
+            display: Display::Grid,
+            grid_template_columns: vec![
+                RepeatedGridTrack::min_content(1),
+                RepeatedGridTrack::px(1, 300.0),
+            ],
+            align_items: AlignItems::Center,
+            justify_content: JustifyContent::Center,
+            row_gap: Val::Px(5.0),
+            column_gap: Val::Px(10.0),
+            ..default()
+        })
+        .with_children(move |cmd| {
+            spawn_compound_field(cmd, "Vec2", Vec2::new(1.0, 2.0), false);
+            spawn_compound_field(cmd, "Translation", Vec3::ZERO, false);
+            spawn_compound_field(cmd, "Rotation", Quat::from_rotation_y(0.5), false);
+            spawn_compound_field(cmd, "Scale", Vec3::ONE, true);
+            spawn_compound_field(cmd, "Vec4", Vec4::W, false);
+        });
+}
+
+fn spawn_compound_field<T: CompoundValue + std::fmt::Debug>(
+    cmd: &mut ChildSpawnerCommands,
+    label: &str,
+    value: T,
+    linked: bool,
+) {
+    cmd.spawn(Text::new(format!("{}:", label)));
+    let mut field = cmd.spawn(CompoundField::new(value));
+    if linked {
+        field.insert(LinkedAxes { linked });
+    }
+    field.observe(|trigger: Trigger<ValueChanged<T>>| {
+        info!("New value: {:?}", trigger.0);
+    });
+}
@@ -4,6 +4,7 @@ use bevy::prelude::*;
 use bevy_field_forms::{
     drag_input::{DragInput, Draggable},
     input_field::{InputField, Validable},
+    numeric_format::NumericFormat,
     validate_highlight::SimpleBorderHighlight,
     FieldFormsPlugin,
 };
@@ -45,10 +46,43 @@ fn setup(mut commands: Commands) {
             spawn_numeric_field::<u128>(cmd, "u128");
             spawn_numeric_field::<f32>(cmd, "f32");
             spawn_numeric_field::<f64>(cmd, "f64");
+
+            // Type expressions like `2*pi` or `*2`, committed with Enter
+            spawn_formatted_field::<f32>(
+                cmd,
+                "distance",
+                NumericFormat::default().with_min(0.0).with_unit("m"),
+            );
+            spawn_formatted_field::<f32>(
+                cmd,
+                "angle",
+                NumericFormat::default()
+                    .with_soft_range(-180.0, 180.0)
+                    .with_unit("deg"),
+            );
+            spawn_formatted_field::<u8>(
+                cmd,
+                "opacity",
+                NumericFormat::default()
+                    .with_range(0.0, 100.0)
+                    .with_step(5.0)
+                    .with_unit("%"),
+            );
         });
 }
 
-fn spawn_numeric_field<T: Validable + Draggable>(cmd: &mut ChildSpawnerCommands, label: &str) {
+fn spawn_formatted_field<T: Validable + Draggable>(
+    cmd: &mut ChildSpawnerCommands,
+    label: &str,
+    format: NumericFormat,
+) {
+    spawn_numeric_field::<T>(cmd, label).insert(format);
+}
+
+fn spawn_numeric_field<'a, T: Validable + Draggable>(
+    cmd: &'a mut ChildSpawnerCommands,
+    label: &str,
+) -> EntityCommands<'a> {
     cmd.spawn((
         Text::new(format!("{}:", label)),
         Node {
@@ -68,5 +102,5 @@ fn spawn_numeric_field<T: Validable + Draggable>(cmd: &mut ChildSpawnerCommands,
         InputField::<T>::default(),
         SimpleBorderHighlight::default(),
         DragInput::<T>::default(),
-    ));
+    ))
 }
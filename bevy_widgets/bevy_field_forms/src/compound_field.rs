@@ -0,0 +1,446 @@
+//! This module provides fields editing a value made of several numbers, like vectors and
+//! rotations, with one labeled drag input per component.
+
+use bevy::prelude::*;
+
+use crate::{
+    drag_input::{DragInput, Draggable},
+    input_field::{InputField, ValueChanged},
+    validate_highlight::SimpleBorderHighlight,
+};
+
+/// Colors of the labels of the X, Y, Z and W components
+pub const AXIS_COLORS: [Color; 4] = [
+    Color::srgb(0.75, 0.25, 0.25),
+    Color::srgb(0.35, 0.6, 0.2),
+    Color::srgb(0.25, 0.4, 0.8),
+    Color::srgb(0.45, 0.45, 0.45),
+];
+
+/// Plugin for compound fields editing a value of type `T`
+pub struct CompoundFieldPlugin<T: CompoundValue> {
+    _marker: std::marker::PhantomData<T>,
+}
+
+impl<T: CompoundValue> Default for CompoundFieldPlugin<T> {
+    fn default() -> Self {
+        Self {
+            _marker: std::marker::PhantomData,
+        }
+    }
+}
+
+impl<T: CompoundValue> Plugin for CompoundFieldPlugin<T> {
+    fn build(&self, app: &mut App) {
+        if !app.is_plugin_added::<LinkedAxesPlugin>() {
+            app.add_plugins(LinkedAxesPlugin);
+        }
+
+        app.add_event::<ValueChanged<T>>();
+
+        app.add_systems(PreUpdate, on_created::<T>);
+        app.add_systems(PostUpdate, on_value_changed::<T>);
+
+        app.add_observer(on_axis_changed::<T>);
+    }
+}
+
+/// Plugin for toggling the [`LinkedAxes`] of compound fields
+pub struct LinkedAxesPlugin;
+
+impl Plugin for LinkedAxesPlugin {
+    fn build(&self, app: &mut App) {
+        app.add_observer(on_link_toggle_click);
+
+        app.add_systems(PostUpdate, update_link_toggles);
+    }
+}
+
+/// A value edited as several numbers, each one in its own drag input
+pub trait CompoundValue: Send + Sync + Default + PartialEq + Clone + 'static {
+    /// Labels of the components, at most four
+    const LABELS: &'static [&'static str];
+
+    /// Splits the value into its components
+    fn to_components(&self) -> Vec<f32>;
+
+    /// Builds a value from its components
+    fn from_components(components: &[f32]) -> Self;
+
+    /// Returns the ratio of the component change per logical pixel drag
+    fn drag_ratio() -> f32 {
+        f32::default_drag_ratio()
+    }
+}
+
+impl CompoundValue for Vec2 {
+    const LABELS: &'static [&'static str] = &["X", "Y"];
+
+    fn to_components(&self) -> Vec<f32> {
+        self.to_array().to_vec()
+    }
+
+    fn from_components(components: &[f32]) -> Self {
+        Vec2::new(components[0], components[1])
+    }
+}
+
+impl CompoundValue for Vec3 {
+    const LABELS: &'static [&'static str] = &["X", "Y", "Z"];
+
+    fn to_components(&self) -> Vec<f32> {
+        self.to_array().to_vec()
+    }
+
+    fn from_components(components: &[f32]) -> Self {
+        Vec3::new(components[0], components[1], components[2])
+    }
+}
+
+impl CompoundValue for Vec4 {
+    const LABELS: &'static [&'static str] = &["X", "Y", "Z", "W"];
+
+    fn to_components(&self) -> Vec<f32> {
+        self.to_array().to_vec()
+    }
+
+    fn from_components(components: &[f32]) -> Self {
+        Vec4::new(components[0], components[1], components[2], components[3])
+    }
+}
+
+/// Rotations are edited as Euler angles in degrees, applied in the X, Y, Z order
+impl CompoundValue for Quat {
+    const LABELS: &'static [&'static str] = &["X", "Y", "Z"];
+
+    fn to_components(&self) -> Vec<f32> {
+        let (x, y, z) = self.to_euler(EulerRot::XYZ);
+        // Round off the noise of the conversion, so that 90° is not shown as 89.99999°
+        [x, y, z]
+            .map(|angle| (angle.to_degrees() * 1e4).round() / 1e4)
+            .to_vec()
+    }
+
+    fn from_components(components: &[f32]) -> Self {
+        Quat::from_euler(
+            EulerRot::XYZ,
+            components[0].to_radians(),
+            components[1].to_radians(),
+            components[2].to_radians(),
+        )
+    }
+
+    fn drag_ratio() -> f32 {
+        0.5
+    }
+}
+
+/// A row of labeled drag inputs editing the components of a value, spawned as children of the
+/// field's [`Node`]. Emits a single [`ValueChanged<T>`] with the whole value when any component is edited.
+#[derive(Component, Clone)]
+#[require(Node)]
+pub struct CompoundField<T: CompoundValue> {
+    /// The current value
+    pub value: T,
+    /// If true, this field will not update its value automatically
+    /// and will require an external update call to update the value.
+    pub controlled: bool,
+    /// The components shown in the inputs. Kept apart from the value, so that editing an Euler
+    /// angle doesn't make the others jump to an equivalent rotation.
+    components: Vec<f32>,
+    /// The drag inputs of the components
+    axes: Vec<Entity>,
+}
+
+impl<T: CompoundValue> Default for CompoundField<T> {
+    fn default() -> Self {
+        Self::new(T::default())
+    }
+}
+
+impl<T: CompoundValue> CompoundField<T> {
+    /// Create a new compound field with the given value
+    pub fn new(value: T) -> Self {
+        Self {
+            components: value.to_components(),
+            value,
+            controlled: false,
+            axes: Vec::new(),
+        }
+    }
+}
+
+/// Makes the components of a [`CompoundField`] change together, keeping their ratios, while
+/// `linked` is set. Used for uniform scaling, with a toggle button at the end of the field.
+#[derive(Component, Clone, Default)]
+pub struct LinkedAxes {
+    /// Whether editing a component scales the others by the same ratio
+    pub linked: bool,
+}
+
+/// A drag input editing a component of a [`CompoundField`]
+#[derive(Component, Clone)]
+struct CompoundAxis {
+    field: Entity,
+    index: usize,
+}
+
+/// The button toggling the [`LinkedAxes`] of a compound field
+#[derive(Component, Clone)]
+struct LinkToggle {
+    field: Entity,
+}
+
+fn on_created<T: CompoundValue>(
+    mut commands: Commands,
+    mut q_created_fields: Query<
+        (Entity, &mut CompoundField<T>, Has<LinkedAxes>),
+        Added<CompoundField<T>>,
+    >,
+) {
+    for (entity, mut field, linked_axes) in q_created_fields.iter_mut() {
+        field.components = field.value.to_components();
+        field.axes.clear();
+        for (index, label) in T::LABELS.iter().enumerate() {
+            commands.spawn((
+                Node {
+                    height: Val::Px(22.0),
+                    padding: UiRect::horizontal(Val::Px(4.0)),
+                    align_items: AlignItems::Center,
+                    ..default()
+                },
+                BackgroundColor(AXIS_COLORS[index]),
+                BorderRadius::left(Val::Px(3.0)),
+                ChildOf(entity),
+                children![(
+                    Text::new(*label),
+                    TextFont::from_font_size(12.0),
+                    Pickable::IGNORE,
+                )],
+            ));
+            let axis = commands
+                .spawn((
+                    Node {
+                        flex_grow: 1.0,
+                        min_width: Val::Px(40.0),
+                        height: Val::Px(22.0),
+                        border: UiRect::all(Val::Px(1.0)),
+                        margin: UiRect::right(Val::Px(4.0)),
+                        ..default()
+                    },
+                    BackgroundColor(Color::srgb(0.2, 0.2, 0.2)),
+                    InputField::new(field.components[index]),
+                    DragInput::<f32>::default().with_drag_ratio(T::drag_ratio()),
+                    SimpleBorderHighlight::default(),
+                    CompoundAxis {
+                        field: entity,
+                        index,
+                    },
+                    ChildOf(entity),
+                ))
+                .id();
+            field.axes.push(axis);
+        }
+
+        if linked_axes {
+            commands.spawn((
+                Button,
+                Node {
+                    height: Val::Px(22.0),
+                    padding: UiRect::horizontal(Val::Px(6.0)),
+                    align_items: AlignItems::Center,
+                    ..default()
+                },
+                BackgroundColor(Color::srgb(0.2, 0.2, 0.2)),
+                BorderRadius::all(Val::Px(3.0)),
+                LinkToggle { field: entity },
+                ChildOf(entity),
+                children![(
+                    Text::new("Link"),
+                    TextFont::from_font_size(12.0),
+                    Pickable::IGNORE,
+                )],
+            ));
+        }
+    }
+}
+
+fn on_axis_changed<T: CompoundValue>(
+    trigger: Trigger<ValueChanged<f32>>,
+    mut commands: Commands,
+    q_axes: Query<&CompoundAxis>,
+    mut q_fields: Query<(&mut CompoundField<T>, Option<&LinkedAxes>)>,
+) {
+    let Ok(axis) = q_axes.get(trigger.target()) else {
+        return;
+    };
+    let Ok((mut field, linked_axes)) = q_fields.get_mut(axis.field) else {
+        return;
+    };
+
+    let linked = linked_axes.is_some_and(|linked_axes| linked_axes.linked);
+    let components = edit_component(&field.components, axis.index, trigger.0, linked);
+    let new_value = T::from_components(&components);
+
+    commands.trigger_targets(ValueChanged(new_value.clone()), axis.field);
+    // The components are kept even for a controlled field, so that setting the value sent in the
+    // event doesn't reformat the input being edited
+    field.components = components;
+    if !field.controlled {
+        field.value = new_value;
+    }
+}
+
+/// The components after setting the one at `index` to `value`, scaling the others by the same
+/// ratio when `linked`. Components scaled from zero are all set to `value`.
+fn edit_component(components: &[f32], index: usize, value: f32, linked: bool) -> Vec<f32> {
+    let mut components = components.to_vec();
+    let old_value = components[index];
+    if linked && old_value != value {
+        if old_value == 0.0 {
+            components.fill(value);
+        } else {
+            let ratio = value / old_value;
+            components
+                .iter_mut()
+                .for_each(|component| *component *= ratio);
+        }
+    }
+    components[index] = value;
+    components
+}
+
+fn on_value_changed<T: CompoundValue>(
+    mut q_changed_fields: Query<&mut CompoundField<T>, Changed<CompoundField<T>>>,
+    mut q_inputs: Query<&mut InputField<f32>>,
+) {
+    for mut field in q_changed_fields.iter_mut() {
+        if T::from_components(&field.components) != field.value {
+            field.components = field.value.to_components();
+        }
+
+        let field: &CompoundField<T> = &field;
+        for (axis, component) in field.axes.iter().zip(&field.components) {
+            let Ok(mut input) = q_inputs.get_mut(*axis) else {
+                continue;
+            };
+            if input.value != *component {
+                input.value = *component;
+            }
+        }
+    }
+}
+
+fn on_link_toggle_click(
+    trigger: Trigger<Pointer<Click>>,
+    q_toggles: Query<&LinkToggle>,
+    mut q_linked_axes: Query<&mut LinkedAxes>,
+) {
+    let Ok(toggle) = q_toggles.get(trigger.target()) else {
+        return;
+    };
+    if let Ok(mut linked_axes) = q_linked_axes.get_mut(toggle.field) {
+        linked_axes.linked = !linked_axes.linked;
+    }
+}
+
+fn update_link_toggles(
+    mut q_toggles: Query<(&LinkToggle, &mut BackgroundColor)>,
+    q_linked_axes: Query<Ref<LinkedAxes>>,
+) {
+    for (toggle, mut background) in q_toggles.iter_mut() {
+        let Ok(linked_axes) = q_linked_axes.get(toggle.field) else {
+            continue;
+        };
+        if linked_axes.is_changed() {
+            background.0 = if linked_axes.linked {
+                Color::srgb(0.25, 0.4, 0.8)
+            } else {
+                Color::srgb(0.2, 0.2, 0.2)
+            };
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn edits_one_component_when_unlinked() {
+        assert_eq!(
+            edit_component(&[1.0, 2.0, 3.0], 1, 5.0, false),
+            [1.0, 5.0, 3.0]
+        );
+    }
+
+    #[test]
+    fn scales_all_components_when_linked() {
+        assert_eq!(
+            edit_component(&[1.0, 2.0, 4.0], 1, 3.0, true),
+            [1.5, 3.0, 6.0]
+        );
+        // Setting the same value doesn't touch the others
+        assert_eq!(
+            edit_component(&[1.0, 2.0, 4.0], 0, 1.0, true),
+            [1.0, 2.0, 4.0]
+        );
+    }
+
+    #[test]
+    fn scales_from_zero_to_a_uniform_value() {
+        assert_eq!(
+            edit_component(&[0.0, 2.0, 4.0], 0, 3.0, true),
+            [3.0, 3.0, 3.0]
+        );
+        assert_eq!(
+            edit_component(&[0.0, 2.0, 4.0], 0, 3.0, false),
+            [3.0, 2.0, 4.0]
+        );
+    }
+
+    #[test]
+    fn rotations_round_trip_through_euler_degrees() {
+        let components = [30.0, 45.0, 60.0];
+        let rotation = Quat::from_components(&components);
+        assert!(rotation.abs_diff_eq(
+            Quat::from_euler(
+                EulerRot::XYZ,
+                30f32.to_radians(),
+                45f32.to_radians(),
+                60f32.to_radians()
+            ),
+            1e-6
+        ));
+        assert_eq!(rotation.to_components(), components);
+        assert_eq!(
+            Quat::from_rotation_y(90f32.to_radians()).to_components(),
+            [0.0, 90.0, 0.0]
+        );
+    }
+
+    #[test]
+    fn linked_field_emits_the_scaled_value() {
+        let mut app = App::new();
+        app.add_observer(on_axis_changed::<Vec3>);
+        let field = app
+            .world_mut()
+            .spawn((
+                CompoundField::new(Vec3::new(1.0, 2.0, 4.0)),
+                LinkedAxes { linked: true },
+            ))
+            .id();
+        let axis = app.world_mut().spawn(CompoundAxis { field, index: 2 }).id();
+
+        app.world_mut().trigger_targets(ValueChanged(2.0f32), axis);
+        app.world_mut().flush();
+        let value = app.world().get::<CompoundField<Vec3>>(field).unwrap().value;
+        assert_eq!(value, Vec3::new(0.5, 1.0, 2.0));
+
+        app.world_mut().get_mut::<LinkedAxes>(field).unwrap().linked = false;
+        app.world_mut().trigger_targets(ValueChanged(8.0f32), axis);
+        app.world_mut().flush();
+        let value = app.world().get::<CompoundField<Vec3>>(field).unwrap().value;
+        assert_eq!(value, Vec3::new(0.5, 1.0, 8.0));
+    }
+}
@@ -2,7 +2,10 @@
 
 use bevy::prelude::*;
 
-use crate::input_field::{InputField, Validable, ValueChanged};
+use crate::{
+    input_field::{InputField, Validable, ValueChanged},
+    numeric_format::NumericFormat,
+};
 
 /// Plugin for dragging a value stored in an input field
 pub struct DragInputPlugin<T: Draggable> {
@@ -35,6 +38,12 @@ pub trait Draggable:
     /// Converts Self to a f32 value
     fn into_f32(self) -> f32;
 
+    /// Converts a f64 value to Self, rounding and clamping it to the range of the type
+    fn from_f64(value: f64) -> Self;
+
+    /// Converts Self to a f64 value
+    fn into_f64(self) -> f64;
+
     /// Safely adds another value of the same type, handling potential overflows
     fn safe_add(&self, other: Self) -> Self;
 
@@ -68,32 +77,66 @@ impl<T: Draggable> Default for DragInput<T> {
     }
 }
 
+impl<T: Draggable> DragInput<T> {
+    /// Set the ratio of the value change per logical pixel drag
+    pub fn with_drag_ratio(mut self, drag_ratio: f32) -> Self {
+        self.drag_ratio = drag_ratio;
+        self
+    }
+}
+
 fn on_drag<T: Draggable>(
     trigger: Trigger<Pointer<Drag>>,
     mut commands: Commands,
-    mut q_drag_inputs: Query<(&mut DragInput<T>, &mut InputField<T>)>,
+    mut q_drag_inputs: Query<(
+        &mut DragInput<T>,
+        &mut InputField<T>,
+        Option<&NumericFormat>,
+    )>,
 ) {
     let entity = trigger.target();
 
-    let Ok((mut drag_input, mut input_field)) = q_drag_inputs.get_mut(entity) else {
+    let Ok((mut drag_input, mut input_field, format)) = q_drag_inputs.get_mut(entity) else {
         return;
     };
 
     let delta = trigger.delta.x;
     drag_input.drag_accumulate += delta * drag_input.drag_ratio;
 
-    let from_accumulated: T = T::from_f32(drag_input.drag_accumulate.abs());
-    let accumulated_decrease: f32 = from_accumulated.into_f32();
-    if accumulated_decrease != 0.0 {
-        let new_val: T;
-        if drag_input.drag_accumulate > 0.0 {
-            new_val = input_field.value.safe_add(from_accumulated);
-            drag_input.drag_accumulate -= accumulated_decrease;
-        } else {
-            new_val = input_field.value.safe_sub(from_accumulated);
-            drag_input.drag_accumulate += accumulated_decrease;
+    let mut new_val = input_field.value;
+    if let Some(step) = format.and_then(|format| format.step) {
+        // Move by whole steps
+        let steps = (drag_input.drag_accumulate as f64 / step).trunc();
+        if steps != 0.0 {
+            drag_input.drag_accumulate -= (steps * step) as f32;
+            let snapped = format
+                .unwrap_or(&NumericFormat::default())
+                .snap(input_field.value.into_f64() + steps * step);
+            new_val = T::from_f64(snapped);
+        }
+    } else {
+        let from_accumulated: T = T::from_f32(drag_input.drag_accumulate.abs());
+        let accumulated_decrease: f32 = from_accumulated.into_f32();
+        if accumulated_decrease != 0.0 {
+            if drag_input.drag_accumulate > 0.0 {
+                new_val = input_field.value.safe_add(from_accumulated);
+                drag_input.drag_accumulate -= accumulated_decrease;
+            } else {
+                new_val = input_field.value.safe_sub(from_accumulated);
+                drag_input.drag_accumulate += accumulated_decrease;
+            }
+        }
+    }
+
+    if let Some(format) = format {
+        let current = input_field.value.into_f64();
+        let clamped = format.clamp_dragged(new_val.into_f64(), current);
+        if clamped != new_val.into_f64() {
+            new_val = T::from_f64(clamped);
         }
+    }
 
+    if new_val != input_field.value {
         commands.trigger_targets(ValueChanged(new_val), entity);
         if !input_field.controlled {
             input_field.value = new_val;
@@ -135,6 +178,15 @@ macro_rules! impl_draggable_for_numeric {
                 fn into_f32(self) -> f32 {
                     self as f32
                 }
+
+                fn from_f64(value: f64) -> Self {
+                    // Casts saturate to the bounds of the type
+                    value.round() as Self
+                }
+
+                fn into_f64(self) -> f64 {
+                    self as f64
+                }
             }
         )*
     };
@@ -165,6 +217,14 @@ macro_rules! impl_draggable_for_float {
                 fn into_f32(self) -> f32 {
                     self as f32
                 }
+
+                fn from_f64(value: f64) -> Self {
+                    value as Self
+                }
+
+                fn into_f64(self) -> f64 {
+                    self as f64
+                }
             }
         )*
     };
@@ -0,0 +1,207 @@
+//! This module evaluates the simple math expressions that can be typed into numeric fields.
+//!
+//! Expressions support numbers, the `pi`, `tau` and `e` constants, parentheses, unary minus and
+//! the `+`, `-`, `*`, `/`, `%` and `^` operators. An expression starting with `+`, `*`, `/`, `%`
+//! or `^` is relative to the current value, so `*2` doubles it. A leading `-` is a negative
+//! number, not a subtraction.
+
+/// Evaluates `text`, relative to `current` if it starts with an operator
+pub fn evaluate(text: &str, current: f64) -> Result<f64, String> {
+    let text = text.trim();
+    let mut parser = Parser {
+        chars: text.chars().collect(),
+        position: 0,
+    };
+    let value = if is_relative(text) {
+        parser.binary_operations(current, 0)?
+    } else {
+        parser.expression()?
+    };
+    parser.skip_whitespace();
+    if let Some(c) = parser.peek() {
+        return Err(format!("Unexpected '{}'", c));
+    }
+    if !value.is_finite() {
+        return Err("The result is not a finite number".to_string());
+    }
+    Ok(value)
+}
+
+/// Whether `text` starts with an operator, making it relative to the current value
+pub fn is_relative(text: &str) -> bool {
+    matches!(
+        text.trim_start().chars().next(),
+        Some('+' | '*' | '/' | '%' | '^')
+    )
+}
+
+/// A recursive descent parser evaluating the expression as it goes
+struct Parser {
+    chars: Vec<char>,
+    position: usize,
+}
+
+impl Parser {
+    fn peek(&self) -> Option<char> {
+        self.chars.get(self.position).copied()
+    }
+
+    fn skip_whitespace(&mut self) {
+        while self.peek().is_some_and(char::is_whitespace) {
+            self.position += 1;
+        }
+    }
+
+    fn expression(&mut self) -> Result<f64, String> {
+        let left = self.unary()?;
+        self.binary_operations(left, 0)
+    }
+
+    /// Applies the operators following `left` with a precedence of at least `min_precedence`
+    fn binary_operations(&mut self, mut left: f64, min_precedence: u8) -> Result<f64, String> {
+        loop {
+            self.skip_whitespace();
+            let Some(operator) = self.peek() else {
+                return Ok(left);
+            };
+            let Some(precedence) = precedence(operator) else {
+                return Ok(left);
+            };
+            if precedence < min_precedence {
+                return Ok(left);
+            }
+            self.position += 1;
+
+            let mut right = self.unary()?;
+            // `^` is right associative, the others left associative
+            let next_precedence = if operator == '^' {
+                precedence
+            } else {
+                precedence + 1
+            };
+            right = self.binary_operations(right, next_precedence)?;
+
+            left = match operator {
+                '+' => left + right,
+                '-' => left - right,
+                '*' => left * right,
+                '/' => left / right,
+                '%' => left % right,
+                _ => left.powf(right),
+            };
+        }
+    }
+
+    fn unary(&mut self) -> Result<f64, String> {
+        self.skip_whitespace();
+        match self.peek() {
+            Some('-') => {
+                self.position += 1;
+                // Binds looser than `^`, so that `-2^2` is -4
+                let value = self.unary()?;
+                Ok(-self.binary_operations(value, precedence('^').unwrap_or_default())?)
+            }
+            Some('+') => {
+                self.position += 1;
+                self.unary()
+            }
+            _ => self.primary(),
+        }
+    }
+
+    fn primary(&mut self) -> Result<f64, String> {
+        self.skip_whitespace();
+        match self.peek() {
+            Some('(') => {
+                self.position += 1;
+                let value = self.expression()?;
+                self.skip_whitespace();
+                if self.peek() != Some(')') {
+                    return Err("Missing ')'".to_string());
+                }
+                self.position += 1;
+                Ok(value)
+            }
+            Some(c) if c.is_ascii_digit() || c == '.' => self.number(),
+            Some(c) if c.is_alphabetic() => {
+                let start = self.position;
+                while self.peek().is_some_and(char::is_alphanumeric) {
+                    self.position += 1;
+                }
+                let name: String = self.chars[start..self.position].iter().collect();
+                match name.to_lowercase().as_str() {
+                    "pi" => Ok(std::f64::consts::PI),
+                    "tau" => Ok(std::f64::consts::TAU),
+                    "e" => Ok(std::f64::consts::E),
+                    _ => Err(format!("Unknown constant '{}'", name)),
+                }
+            }
+            Some(c) => Err(format!("Unexpected '{}'", c)),
+            None => Err("Missing a number".to_string()),
+        }
+    }
+
+    fn number(&mut self) -> Result<f64, String> {
+        let start = self.position;
+        while self.peek().is_some_and(|c| c.is_ascii_digit() || c == '.') {
+            self.position += 1;
+        }
+        // Exponent, like in `1e-3`
+        if matches!(self.peek(), Some('e' | 'E')) {
+            let mut end = self.position + 1;
+            if matches!(self.chars.get(end), Some('+' | '-')) {
+                end += 1;
+            }
+            if self.chars.get(end).is_some_and(char::is_ascii_digit) {
+                self.position = end;
+                while self.peek().is_some_and(|c| c.is_ascii_digit()) {
+                    self.position += 1;
+                }
+            }
+        }
+        let number: String = self.chars[start..self.position].iter().collect();
+        number
+            .parse()
+            .map_err(|_| format!("Invalid number '{}'", number))
+    }
+}
+
+fn precedence(operator: char) -> Option<u8> {
+    match operator {
+        '+' | '-' => Some(1),
+        '*' | '/' | '%' => Some(2),
+        '^' => Some(3),
+        _ => None,
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn evaluates_expressions() {
+        assert_eq!(evaluate("10+5", 0.0), Ok(15.0));
+        assert_eq!(evaluate("2*pi", 0.0), Ok(std::f64::consts::TAU));
+        assert_eq!(evaluate(" 1 + 2 * 3 ", 0.0), Ok(7.0));
+        assert_eq!(evaluate("(1 + 2) * 3", 0.0), Ok(9.0));
+        assert_eq!(evaluate("2^3^2", 0.0), Ok(512.0));
+        assert_eq!(evaluate("-2^2", 0.0), Ok(-4.0));
+        assert_eq!(evaluate("1.5e2 - -1", 0.0), Ok(151.0));
+        assert_eq!(evaluate("10 - 4 - 3", 0.0), Ok(3.0));
+        assert!(evaluate("1 +", 0.0).is_err());
+        assert!(evaluate("(1 + 2", 0.0).is_err());
+        assert!(evaluate("2 * foo", 0.0).is_err());
+        assert!(evaluate("1 / 0", 0.0).is_err());
+    }
+
+    #[test]
+    fn evaluates_relative_expressions() {
+        assert_eq!(evaluate("*2", 21.0), Ok(42.0));
+        assert_eq!(evaluate("+5*2", 1.0), Ok(11.0));
+        assert_eq!(evaluate("/4", 2.0), Ok(0.5));
+        assert_eq!(evaluate("-5", 20.0), Ok(-5.0));
+        assert!(is_relative(" +5"));
+        assert!(!is_relative("-5"));
+    }
+}
@@ -1,8 +1,11 @@
 //! This module provides a validated input field with a generic value type.
 
 use bevy::prelude::*;
+use bevy_focus::{Focus, LostFocus};
 use bevy_text_editing::*;
 
+use crate::{expression, numeric_format::NumericFormat};
+
 /// Plugin for validated input fields with a generic value type
 pub struct InputFieldPlugin<T: Validable> {
     _marker: std::marker::PhantomData<T>,
@@ -27,9 +30,10 @@ impl<T: Validable> Plugin for InputFieldPlugin<T> {
         app.add_event::<SetValue<T>>();
 
         app.add_systems(PostUpdate, on_value_changed::<T>);
-        app.add_systems(PreUpdate, on_created::<T>);
+        app.add_systems(PreUpdate, (on_created::<T>, commit_on_enter::<T>));
 
         app.add_observer(on_text_changed::<T>);
+        app.add_observer(on_focus_lost::<T>);
     }
 }
 
@@ -83,6 +87,27 @@ pub trait Validable: Send + Sync + Default + PartialEq + Clone + ToString + 'sta
     /// * `Ok(Self)` if the input is valid and can be converted to this type.
     /// * `Err(String)` with an error message if the input is invalid.
     fn validate(text: &str) -> Result<Self, String>;
+
+    /// Validates the text of a field with a [`NumericFormat`], which numeric types check the
+    /// range of after removing the unit. Same as [`Validable::validate`] by default.
+    fn validate_formatted(text: &str, format: &NumericFormat) -> Result<Self, String> {
+        let _ = format;
+        Self::validate(text)
+    }
+
+    /// Evaluates the text committed to a field with Enter or by leaving it. Numeric types accept
+    /// math expressions, relative to the `current` value if they start with an operator, and
+    /// clamp the result to the range. Same as [`Validable::validate_formatted`] by default.
+    fn evaluate(text: &str, current: &Self, format: &NumericFormat) -> Result<Self, String> {
+        let _ = current;
+        Self::validate_formatted(text, format)
+    }
+
+    /// The text shown for the value
+    fn format(&self, format: &NumericFormat) -> String {
+        let _ = format;
+        self.to_string()
+    }
 }
 
 impl Validable for String {
@@ -109,7 +134,7 @@ pub struct ValidationChanged(pub ValidationState);
 
 /// Event that is emitted when the value changes
 #[derive(Event)]
-pub struct ValueChanged<T: Validable>(pub T);
+pub struct ValueChanged<T: Send + Sync + 'static>(pub T);
 
 /// This event is used to set the value of the validated input field.
 #[derive(Event)]
@@ -118,17 +143,17 @@ pub struct SetValue<T: Validable>(pub T);
 fn on_text_changed<T: Validable>(
     mut trigger: Trigger<TextChanged>,
     mut commands: Commands,
-    mut q_validated_input_fields: Query<&mut InputField<T>>,
+    mut q_validated_input_fields: Query<(&mut InputField<T>, Option<&NumericFormat>)>,
 ) {
     let entity = trigger.target();
-    let Ok(mut field) = q_validated_input_fields.get_mut(entity) else {
+    let Ok((mut field, format)) = q_validated_input_fields.get_mut(entity) else {
         return;
     };
 
     let new_text = trigger.new_text.clone();
     trigger.propagate(false);
 
-    match T::validate(&new_text) {
+    match T::validate_formatted(&new_text, format.unwrap_or(&NumericFormat::default())) {
         Ok(value) => {
             commands.trigger_targets(ValueChanged(value.clone()), entity);
             commands.trigger_targets(ValidationChanged(ValidationState::Valid), entity);
@@ -151,16 +176,22 @@ fn on_text_changed<T: Validable>(
 
 fn on_value_changed<T: Validable>(
     mut commands: Commands,
-    mut q_changed_inputs: Query<(Entity, &mut InputField<T>), Changed<InputField<T>>>,
+    mut q_changed_inputs: Query<
+        (Entity, &mut InputField<T>, Option<&NumericFormat>),
+        Changed<InputField<T>>,
+    >,
 ) {
-    for (entity, mut field) in q_changed_inputs.iter_mut() {
+    for (entity, mut field, format) in q_changed_inputs.iter_mut() {
         if field.value != field.old_value {
             // info!("Trigger value rerender by field change for {:?}", entity);
             field.old_value = field.value.clone();
 
             // We will not trigger ValueChanged because it must be triggered only by input change
             // If value field was changed by external code, we will not trigger it again
-            commands.trigger_targets(SetText(field.value.to_string()), entity);
+            let text = field
+                .value
+                .format(format.unwrap_or(&NumericFormat::default()));
+            commands.trigger_targets(SetText(text), entity);
             commands.trigger_targets(ValidationChanged(ValidationState::Valid), entity);
         }
     }
@@ -168,25 +199,168 @@ fn on_value_changed<T: Validable>(
 
 fn on_created<T: Validable>(
     mut commands: Commands,
-    q_created_inputs: Query<(Entity, &InputField<T>), Added<InputField<T>>>,
+    q_created_inputs: Query<(Entity, &InputField<T>, Option<&NumericFormat>), Added<InputField<T>>>,
 ) {
-    for (entity, field) in q_created_inputs.iter() {
+    for (entity, field, format) in q_created_inputs.iter() {
         // Set start state
-        commands.trigger_targets(SetText(field.value.to_string()), entity);
+        let text = field
+            .value
+            .format(format.unwrap_or(&NumericFormat::default()));
+        commands.trigger_targets(SetText(text), entity);
         commands.trigger_targets(ValidationChanged(ValidationState::Valid), entity);
     }
 }
 
+fn commit_on_enter<T: Validable>(
+    mut commands: Commands,
+    keyboard_input: Res<ButtonInput<KeyCode>>,
+    mut q_focused_inputs: Query<
+        (
+            Entity,
+            &mut InputField<T>,
+            &EditableTextLine,
+            Option<&NumericFormat>,
+        ),
+        With<Focus>,
+    >,
+) {
+    if !keyboard_input.any_just_pressed([KeyCode::Enter, KeyCode::NumpadEnter]) {
+        return;
+    }
+    for (entity, mut field, text_line, format) in q_focused_inputs.iter_mut() {
+        commit(&mut commands, entity, &mut field, &text_line.text, format);
+    }
+}
+
+fn on_focus_lost<T: Validable>(
+    trigger: Trigger<LostFocus>,
+    mut commands: Commands,
+    mut q_inputs: Query<(
+        &mut InputField<T>,
+        &EditableTextLine,
+        Option<&NumericFormat>,
+    )>,
+) {
+    let entity = trigger.target();
+    let Ok((mut field, text_line, format)) = q_inputs.get_mut(entity) else {
+        return;
+    };
+    commit(&mut commands, entity, &mut field, &text_line.text, format);
+}
+
+/// Evaluate the text of the field, and show the resulting value formatted
+fn commit<T: Validable>(
+    commands: &mut Commands,
+    entity: Entity,
+    field: &mut InputField<T>,
+    text: &str,
+    format: Option<&NumericFormat>,
+) {
+    let format = format.unwrap_or(&NumericFormat::default()).clone();
+    let Ok(value) = T::evaluate(text, &field.value, &format) else {
+        // Keep the text to be fixed, it is already shown as invalid
+        return;
+    };
+
+    if value != field.value {
+        commands.trigger_targets(ValueChanged(value.clone()), entity);
+        if !field.controlled {
+            field.old_value = value.clone();
+            field.value = value.clone();
+        }
+    }
+    let formatted = value.format(&format);
+    if formatted != text {
+        commands.trigger_targets(SetText(formatted), entity);
+    }
+    commands.trigger_targets(ValidationChanged(ValidationState::Valid), entity);
+}
+
 macro_rules! impl_validable_for_numeric {
-    ($($t:ty),*) => {
+    ($round:expr => $($t:ty),*) => {
         $(
             impl Validable for $t {
                 fn validate(text: &str) -> Result<Self, String> {
+                    // `+5` parses as 5, but is an expression adding 5 to the current value
+                    if expression::is_relative(text) {
+                        return Err("Relative expressions are applied when committed".to_string());
+                    }
                     text.parse().map_err(|_| format!("Invalid {} number", stringify!($t)))
                 }
+
+                fn validate_formatted(text: &str, format: &NumericFormat) -> Result<Self, String> {
+                    let value = Self::validate(format.strip_unit(text))?;
+                    format.check_range(value as f64)?;
+                    Ok(value)
+                }
+
+                fn evaluate(
+                    text: &str,
+                    current: &Self,
+                    format: &NumericFormat,
+                ) -> Result<Self, String> {
+                    let text = format.strip_unit(text);
+                    if expression::is_relative(text) {
+                        let value = expression::evaluate(text, *current as f64)?;
+                        return Ok($round(format.clamp(value)) as Self);
+                    }
+                    let value = match Self::validate(text) {
+                        Ok(value) if format.check_range(value as f64).is_ok() => return Ok(value),
+                        Ok(value) => value as f64,
+                        Err(_) => expression::evaluate(text, *current as f64)?,
+                    };
+                    // Casts saturate to the bounds of the type
+                    Ok($round(format.clamp(value)) as Self)
+                }
+
+                fn format(&self, format: &NumericFormat) -> String {
+                    format.format(&self.to_string())
+                }
             }
         )*
     };
 }
 
-impl_validable_for_numeric!(i8, i16, i32, i64, i128, u8, u16, u32, u64, u128, f32, f64);
+impl_validable_for_numeric!(f64::round => i8, i16, i32, i64, i128, u8, u16, u32, u64, u128);
+impl_validable_for_numeric!(std::convert::identity::<f64> => f32, f64);
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use bevy_focus::LostFocus;
+    use bevy_text_editing::text_change::TextChange;
+
+    #[test]
+    fn relative_expressions_apply_to_the_committed_value() {
+        let mut app = App::new();
+        app.add_observer(on_text_changed::<i32>)
+            .add_observer(on_focus_lost::<i32>);
+        let world = app.world_mut();
+        let entity = world
+            .spawn((InputField::new(10), EditableTextLine::controlled("10")))
+            .id();
+
+        // Typing the expression doesn't change the value
+        for text in ["+", "+5"] {
+            world.trigger_targets(
+                TextChanged {
+                    change: TextChange::nop_change(),
+                    new_text: text.to_string(),
+                    old_cursor_position: None,
+                    new_cursor_position: None,
+                },
+                entity,
+            );
+            world.get_mut::<EditableTextLine>(entity).unwrap().text = text.to_string();
+        }
+        assert_eq!(world.get::<InputField<i32>>(entity).unwrap().value, 10);
+
+        world.trigger_targets(LostFocus, entity);
+        assert_eq!(world.get::<InputField<i32>>(entity).unwrap().value, 15);
+
+        world.get_mut::<EditableTextLine>(entity).unwrap().text = "*2".to_string();
+        world.trigger_targets(LostFocus, entity);
+        assert_eq!(world.get::<InputField<i32>>(entity).unwrap().value, 30);
+        assert_eq!(i32::evaluate("-5", &30, &NumericFormat::default()), Ok(-5));
+    }
+}
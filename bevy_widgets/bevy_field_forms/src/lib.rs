@@ -1,7 +1,10 @@
 //! This crate provides a set of widgets, which are used text input
 
+pub mod compound_field;
 pub mod drag_input;
+pub mod expression;
 pub mod input_field;
+pub mod numeric_format;
 pub mod text_event_mirror;
 pub mod validate_highlight;
 
@@ -55,6 +58,11 @@ impl Plugin for FieldFormsPlugin {
 
         app.add_plugins(input_field::InputFieldPlugin::<String>::default());
 
+        app.add_plugins(compound_field::CompoundFieldPlugin::<Vec2>::default());
+        app.add_plugins(compound_field::CompoundFieldPlugin::<Vec3>::default());
+        app.add_plugins(compound_field::CompoundFieldPlugin::<Vec4>::default());
+        app.add_plugins(compound_field::CompoundFieldPlugin::<Quat>::default());
+
         app.add_plugins(text_event_mirror::TextEventMirrorPlugin);
         app.add_plugins(validate_highlight::SimpleBorderHighlightPlugin);
     }
@@ -0,0 +1,150 @@
+//! This module provides the range, step and unit of numeric input fields
+
+use std::borrow::Cow;
+
+use bevy::prelude::*;
+
+/// Constraints and display unit of a numeric [`InputField`](crate::input_field::InputField) and
+/// [`DragInput`](crate::drag_input::DragInput), on the same entity.
+///
+/// Values typed out of the hard `min`..`max` range are shown invalid until committed with Enter
+/// or by leaving the field, and then clamped. Dragging stays within the soft range, and moves
+/// by multiples of `step`.
+#[derive(Component, Clone, Debug, Default, PartialEq)]
+pub struct NumericFormat {
+    /// The minimum value
+    pub min: Option<f64>,
+    /// The maximum value
+    pub max: Option<f64>,
+    /// The minimum value reached by dragging
+    pub soft_min: Option<f64>,
+    /// The maximum value reached by dragging
+    pub soft_max: Option<f64>,
+    /// Dragged values snap to multiples of the step, counted from `min` if set
+    pub step: Option<f64>,
+    /// Unit shown after the value, like `m`, `deg` or `%`
+    pub unit: Option<Cow<'static, str>>,
+}
+
+impl NumericFormat {
+    /// Limit the value to `min..=max`
+    pub fn with_range(mut self, min: f64, max: f64) -> Self {
+        self.min = Some(min);
+        self.max = Some(max);
+        self
+    }
+
+    /// Set the minimum value
+    pub fn with_min(mut self, min: f64) -> Self {
+        self.min = Some(min);
+        self
+    }
+
+    /// Set the maximum value
+    pub fn with_max(mut self, max: f64) -> Self {
+        self.max = Some(max);
+        self
+    }
+
+    /// Limit dragging to `soft_min..=soft_max`, while typed values can go further
+    pub fn with_soft_range(mut self, soft_min: f64, soft_max: f64) -> Self {
+        self.soft_min = Some(soft_min);
+        self.soft_max = Some(soft_max);
+        self
+    }
+
+    /// Snap dragged values to multiples of `step`
+    pub fn with_step(mut self, step: f64) -> Self {
+        self.step = Some(step).filter(|step| *step > 0.0);
+        self
+    }
+
+    /// Show `unit` after the value
+    pub fn with_unit(mut self, unit: impl Into<Cow<'static, str>>) -> Self {
+        self.unit = Some(unit.into());
+        self
+    }
+
+    /// The text shown for a value
+    pub fn format(&self, value: &str) -> String {
+        match &self.unit {
+            Some(unit) => format!("{} {}", value, unit),
+            None => value.to_string(),
+        }
+    }
+
+    /// The text of a field without the unit, which can be typed or not
+    pub fn strip_unit<'a>(&self, text: &'a str) -> &'a str {
+        let text = text.trim();
+        self.unit
+            .as_deref()
+            .and_then(|unit| text.strip_suffix(unit))
+            .unwrap_or(text)
+            .trim()
+    }
+
+    /// Checks that `value` is in the hard range
+    pub fn check_range(&self, value: f64) -> Result<(), String> {
+        match (self.min, self.max) {
+            (Some(min), _) if value < min => Err(format!("Must be at least {}", min)),
+            (_, Some(max)) if value > max => Err(format!("Must be at most {}", max)),
+            _ => Ok(()),
+        }
+    }
+
+    /// Clamps `value` to the hard range
+    pub fn clamp(&self, value: f64) -> f64 {
+        let value = self.min.map_or(value, |min| value.max(min));
+        self.max.map_or(value, |max| value.min(max))
+    }
+
+    /// Clamps a value dragged from `current` to the soft and hard ranges. The soft range is
+    /// extended to `current`, so that dragging a value typed out of it doesn't make it jump.
+    pub fn clamp_dragged(&self, value: f64, current: f64) -> f64 {
+        let value = self
+            .soft_min
+            .map_or(value, |soft_min| value.max(soft_min.min(current)));
+        let value = self
+            .soft_max
+            .map_or(value, |soft_max| value.min(soft_max.max(current)));
+        self.clamp(value)
+    }
+
+    /// Snaps `value` to the closest multiple of the step
+    pub fn snap(&self, value: f64) -> f64 {
+        let Some(step) = self.step else {
+            return value;
+        };
+        let origin = self.min.unwrap_or(0.0);
+        origin + ((value - origin) / step).round() * step
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn formats_and_constrains_values() {
+        let format = NumericFormat::default()
+            .with_range(0.0, 100.0)
+            .with_soft_range(10.0, 50.0)
+            .with_step(5.0)
+            .with_unit("%");
+        assert_eq!(format.format("20"), "20 %");
+        assert_eq!(format.strip_unit(" 20 % "), "20");
+        assert_eq!(format.strip_unit("20"), "20");
+
+        assert!(format.check_range(100.0).is_ok());
+        assert!(format.check_range(101.0).is_err());
+        assert_eq!(format.clamp(-3.0), 0.0);
+
+        assert_eq!(format.clamp_dragged(60.0, 40.0), 50.0);
+        // Values typed out of the soft range can still be dragged from there
+        assert_eq!(format.clamp_dragged(90.0, 80.0), 80.0);
+        assert_eq!(format.clamp_dragged(70.0, 80.0), 70.0);
+
+        assert_eq!(format.snap(12.4), 10.0);
+        assert_eq!(format.snap(12.6), 15.0);
+    }
+}
@@ -0,0 +1,39 @@
+//! This example shows how to create a multi-line editable text with `bevy_text_editing`
+
+use bevy::prelude::*;
+use bevy_text_editing::editable_text::*;
+
+fn main() {
+    App::new()
+        .add_plugins(DefaultPlugins)
+        .add_plugins(EditableTextPlugin)
+        .add_systems(Startup, setup)
+        .run();
+}
+
+fn setup(mut commands: Commands) {
+    commands.spawn(Camera2d);
+
+    commands
+        .spawn(Node {
+            width: Val::Percent(100.),
+            height: Val::Percent(100.),
+            justify_content: JustifyContent::Center,
+            align_items: AlignItems::Center,
+            ..Default::default()
+        })
+        .with_children(|cmd| {
+            cmd.spawn((
+                EditableText::new(
+                    "Hello, World!\n\nLines longer than the width of the text are wrapped at word boundaries, and the text scrolls to keep the cursor visible.",
+                ),
+                Node {
+                    // The text is wrapped to the width and scrolls in the height
+                    width: Val::Px(300.0),
+                    height: Val::Px(120.0),
+                    ..Default::default()
+                },
+                BackgroundColor(Color::srgba(0.0, 0.0, 0.0, 0.5)),
+            ));
+        });
+}
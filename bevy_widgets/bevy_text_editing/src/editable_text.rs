@@ -0,0 +1,334 @@
+//! This file contains the [`EditableText`] component which allow create editable multi-line text
+//! by keyboard and mouse, with word wrap
+//!
+//! The text can be colored with [`TextHighlights`], parts of it can be marked with [`TextMarks`],
+//! and [`Indentation`] makes it indent like a code editor.
+
+mod input;
+pub mod layout;
+mod render;
+
+use std::ops::Range;
+
+use bevy::{
+    prelude::*,
+    ui::{RelativeCursorPosition, UiSystem},
+};
+use bevy_clipboard::ClipboardPlugin;
+use bevy_focus::{FocusPlugin, Focusable};
+
+use crate::{
+    cursor::CursorPlugin, get_byte_position, history::TextEditHistory, keymap::TextEditKeymap,
+    CharPosition, SetCursorPosition, SetText, TextChanged,
+};
+
+use input::*;
+use layout::TextLines;
+use render::*;
+
+/// Plugin for multi-line editable text
+pub struct EditableTextPlugin;
+
+impl Plugin for EditableTextPlugin {
+    fn build(&self, app: &mut App) {
+        // Check that our required plugins are loaded.
+        if !app.is_plugin_added::<CursorPlugin>() {
+            app.add_plugins(CursorPlugin);
+        }
+        if !app.is_plugin_added::<FocusPlugin>() {
+            app.add_plugins(FocusPlugin);
+        }
+        if !app.is_plugin_added::<ClipboardPlugin>() {
+            app.add_plugins(ClipboardPlugin);
+        }
+
+        app.init_resource::<TextEditKeymap>()
+            .register_type::<TextEditKeymap>();
+
+        app.add_event::<SetText>();
+        app.add_event::<TextChanged>();
+        app.add_event::<SetCursorPosition>();
+
+        app.add_systems(
+            PreUpdate,
+            (
+                spawn_system,
+                keyboard_input,
+                mouse_wheel_scroll,
+                propagate_text_font,
+                propagate_text_color,
+            ),
+        );
+        app.add_systems(
+            PostUpdate,
+            (
+                update_text.before(UiSystem::Content),
+                update_layout.after(UiSystem::PostLayout),
+            ),
+        );
+
+        app.add_observer(set_text_trigger);
+        app.add_observer(on_pressed);
+        app.add_observer(on_drag);
+        app.add_observer(on_focus_lost);
+        app.add_observer(on_set_cursor_position);
+    }
+}
+
+/// A component representing editable text spanning several lines.
+///
+/// Works like [`EditableTextLine`](crate::EditableTextLine), with the same events, but Enter
+/// inserts a line break and lines longer than the width of the node are wrapped at word
+/// boundaries. The node must be given a size; the text scrolls vertically to keep the cursor
+/// visible, and with the mouse wheel while hovered.
+///
+/// # Examples
+///
+/// ```
+/// use bevy_text_editing::EditableText;
+///
+/// // Create an uncontrolled editable text
+/// let uncontrolled = EditableText::new("Hello,\nWorld!");
+///
+/// // Create a controlled editable text
+/// let controlled = EditableText::controlled("Editable\nText");
+/// ```
+#[derive(Component, Reflect, Default)]
+#[reflect(Component, Default)]
+#[require(Node, Focusable, TextEditHistory, RelativeCursorPosition)]
+pub struct EditableText {
+    /// Text content
+    pub text: String,
+    /// Cursor position. Measured in characters
+    pub cursor_position: Option<CharPosition>,
+    /// Selection start. Measured in characters
+    pub selection_start: Option<CharPosition>,
+    /// Controlled widgets do not update their state by themselves,
+    /// while uncontrolled widgets can edit their own state.
+    pub controlled_widget: bool,
+}
+
+impl EditableText {
+    /// Create uncontrolled editable text
+    pub fn new(text: impl Into<String>) -> Self {
+        Self {
+            text: text.into(),
+            ..default()
+        }
+    }
+
+    /// Create controlled editable text
+    pub fn controlled(text: impl Into<String>) -> Self {
+        Self {
+            text: text.into(),
+            controlled_widget: true,
+            ..default()
+        }
+    }
+
+    /// Change mode to controlled
+    pub fn with_controlled(mut self) -> Self {
+        self.controlled_widget = true;
+        self
+    }
+
+    /// Get selection char range
+    pub fn selection_range(&self) -> Option<(CharPosition, CharPosition)> {
+        let (start, cursor) = (self.selection_start?, self.cursor_position?);
+        if start.0 < cursor.0 {
+            Some((start, cursor))
+        } else {
+            Some((cursor, start))
+        }
+    }
+
+    /// Returns the text within the specified character range, or `None` if the range is invalid
+    /// (start > end) or out of bounds.
+    ///
+    /// ```
+    /// use bevy_text_editing::{CharPosition, EditableText};
+    ///
+    /// let text = EditableText::new("Hello,\nworld!");
+    /// let range = (CharPosition(5), CharPosition(8));
+    /// assert_eq!(text.get_text_range(range), Some(",\nw".to_string()));
+    /// ```
+    pub fn get_text_range(&self, range: (CharPosition, CharPosition)) -> Option<String> {
+        let len = self.text.chars().count();
+        if range.0 .0 > range.1 .0 || range.1 .0 > len {
+            return None;
+        }
+        let start = get_byte_position(&self.text, range.0);
+        let end = get_byte_position(&self.text, range.1);
+        Some(self.text[start..end].to_string())
+    }
+
+    /// Returns the selected text, or `None` if there is no selection.
+    pub fn get_selected_text(&self) -> Option<String> {
+        self.get_text_range(self.selection_range()?)
+    }
+}
+
+/// The colors of parts of the lines of an [`EditableText`], like syntax highlighting.
+///
+/// Each line of the text has its colored ranges, as byte ranges in the line. The rest of the text
+/// has the [`TextColor`] of the editable text.
+#[derive(Component, Clone, Debug, Default, PartialEq)]
+pub struct TextHighlights(pub Vec<Vec<(Range<usize>, Color)>>);
+
+/// Parts of an [`EditableText`] shown with a background color behind the text, like search
+/// matches.
+#[derive(Component, Clone, Debug, Default, PartialEq)]
+pub struct TextMarks(pub Vec<(CharPosition, CharPosition, Color)>);
+
+/// Makes an [`EditableText`] indent like a code editor: Tab inserts the indentation, and a new
+/// line starts with the indentation of the line before it, one level more after an opening
+/// bracket.
+#[derive(Component, Clone, Debug, PartialEq, Eq)]
+pub struct Indentation(pub String);
+
+impl Default for Indentation {
+    fn default() -> Self {
+        Self("    ".to_string())
+    }
+}
+
+/// Hidden component for storing inner entities and the layout of editable text
+#[derive(Component)]
+pub struct EditableTextLayout {
+    text: Entity,
+    canvas: Entity,
+    selection: Entity,
+    cursor: Entity,
+
+    /// Lines of the text as laid out in the last frame
+    pub lines: TextLines,
+
+    /// Height scrolled from the top of the text to keep the cursor visible
+    scroll: f32,
+
+    /// Horizontal position the cursor keeps while moving up and down
+    preferred_x: Option<f32>,
+
+    /// Restart the blinking of the cursor, so that it's visible right after moving
+    show_cursor: bool,
+}
+
+impl EditableTextLayout {
+    /// Height scrolled from the top of the text, in logical pixels
+    pub fn scroll(&self) -> f32 {
+        self.scroll
+    }
+}
+
+fn spawn_system(
+    mut commands: Commands,
+    mut q_texts: Query<
+        (
+            Entity,
+            &EditableText,
+            &mut Node,
+            Option<&TextFont>,
+            Option<&TextColor>,
+        ),
+        Without<EditableTextLayout>,
+    >,
+) {
+    for (e, editable_text, mut node, font, color) in q_texts.iter_mut() {
+        // Set important properties of the node
+        node.overflow = Overflow::clip();
+
+        let font = font.cloned().unwrap_or_default();
+        let color = color.copied().unwrap_or_default();
+
+        let selection = commands
+            .spawn(Node {
+                position_type: PositionType::Absolute,
+                left: Val::Px(0.0),
+                top: Val::Px(0.0),
+                width: Val::Percent(100.0),
+                height: Val::Percent(100.0),
+                ..default()
+            })
+            .id();
+
+        let text = commands
+            .spawn((
+                Text::new(editable_text.text.clone()),
+                Node {
+                    width: Val::Percent(100.0),
+                    ..default()
+                },
+                TextLayout::new_with_linebreak(LineBreak::WordBoundary),
+                font,
+                color,
+            ))
+            .id();
+
+        let cursor = commands
+            .spawn((
+                Node {
+                    width: Val::Px(2.0),
+                    position_type: PositionType::Absolute,
+                    ..default()
+                },
+                BackgroundColor(Color::srgb(1.0, 1.0, 1.0)),
+                Visibility::Hidden,
+            ))
+            .id();
+
+        let canvas = commands
+            .spawn(Node {
+                position_type: PositionType::Absolute,
+                left: Val::Px(0.0),
+                top: Val::Px(0.0),
+                width: Val::Percent(100.0),
+                ..default()
+            })
+            .add_children(&[selection, text, cursor])
+            .id();
+
+        commands
+            .entity(e)
+            .insert(EditableTextLayout {
+                text,
+                canvas,
+                selection,
+                cursor,
+                lines: TextLines::default(),
+                scroll: 0.0,
+                preferred_x: None,
+                show_cursor: false,
+            })
+            .add_child(canvas);
+    }
+}
+
+fn set_text_trigger(trigger: Trigger<SetText>, mut q_texts: Query<&mut EditableText>) {
+    let Ok(mut editable_text) = q_texts.get_mut(trigger.target()) else {
+        return;
+    };
+    editable_text.text = trigger.0.clone();
+}
+
+fn propagate_text_font(
+    mut commands: Commands,
+    q_texts: Query<(&TextFont, &EditableTextLayout), Changed<TextFont>>,
+    q_children: Query<&Children>,
+) {
+    for (font, inner) in q_texts.iter() {
+        commands.entity(inner.text).insert(font.clone());
+        // The spans of highlighted text
+        for span in q_children.iter_descendants(inner.text) {
+            commands.entity(span).insert(font.clone());
+        }
+    }
+}
+
+fn propagate_text_color(
+    mut commands: Commands,
+    q_texts: Query<(&TextColor, &EditableTextLayout), Changed<TextColor>>,
+) {
+    for (color, inner) in q_texts.iter() {
+        commands.entity(inner.text).insert(*color);
+    }
+}
@@ -0,0 +1,407 @@
+use crate::{
+    keymap::{Modifiers, TextEditAction},
+    text_change::TextChange,
+    words::{next_word_end, previous_word_start, word_at},
+};
+
+use super::*;
+use bevy::input::{
+    keyboard::{Key, KeyboardInput},
+    mouse::{MouseScrollUnit, MouseWheel},
+};
+use bevy_clipboard::BevyClipboard;
+use bevy_focus::{Focus, LostFocus, SetFocus};
+
+/// The number of lines scrolled by a step of the mouse wheel
+const SCROLL_LINES: f32 = 3.0;
+
+/// Move the cursor where the text is pressed, extending the selection with Shift
+pub fn on_pressed(
+    trigger: Trigger<Pointer<Pressed>>,
+    mut commands: Commands,
+    mut q_editable_texts: Query<(&mut EditableText, &mut EditableTextLayout)>,
+    q_texts: Query<(&ComputedNode, &GlobalTransform)>,
+    key_states: Res<ButtonInput<KeyCode>>,
+) {
+    if trigger.event().button != PointerButton::Primary {
+        return;
+    }
+    let entity = trigger.target();
+    let Ok((mut editable_text, mut inner)) = q_editable_texts.get_mut(entity) else {
+        return;
+    };
+    let Some(position) = pointer_position(&inner, &q_texts, trigger.pointer_location.position)
+    else {
+        return;
+    };
+
+    let shift_pressed = key_states.any_pressed([KeyCode::ShiftLeft, KeyCode::ShiftRight]);
+    if shift_pressed && editable_text.selection_start.is_none() {
+        // Set selection start on previous cursor position
+        editable_text.selection_start = editable_text.cursor_position;
+    } else if !shift_pressed {
+        editable_text.selection_start = None;
+    }
+    editable_text.cursor_position = Some(position);
+    inner.preferred_x = None;
+    inner.show_cursor = true;
+
+    commands.trigger_targets(SetFocus, entity);
+}
+
+/// Select the text the pointer is dragged over, across lines
+pub fn on_drag(
+    trigger: Trigger<Pointer<Drag>>,
+    mut q_editable_texts: Query<(&mut EditableText, &mut EditableTextLayout)>,
+    q_texts: Query<(&ComputedNode, &GlobalTransform)>,
+) {
+    if trigger.event().button != PointerButton::Primary {
+        return;
+    }
+    let Ok((mut editable_text, mut inner)) = q_editable_texts.get_mut(trigger.target()) else {
+        return;
+    };
+    let Some(position) = pointer_position(&inner, &q_texts, trigger.pointer_location.position)
+    else {
+        return;
+    };
+
+    if editable_text.selection_start.is_none() {
+        editable_text.selection_start = editable_text.cursor_position;
+    }
+    editable_text.cursor_position = Some(position);
+    inner.preferred_x = None;
+    inner.show_cursor = true;
+}
+
+/// The position in the text under the pointer
+fn pointer_position(
+    inner: &EditableTextLayout,
+    q_texts: &Query<(&ComputedNode, &GlobalTransform)>,
+    pointer_position: Vec2,
+) -> Option<CharPosition> {
+    let (node, global_transform) = q_texts.get(inner.text).ok()?;
+    let scale = node.inverse_scale_factor();
+    let top_left = (global_transform.translation().truncate() - node.size() / 2.0) * scale;
+    Some(inner.lines.hit(pointer_position - top_left))
+}
+
+pub fn keyboard_input(
+    mut commands: Commands,
+    mut q_editable_texts: Query<
+        (
+            Entity,
+            &mut EditableText,
+            &mut EditableTextLayout,
+            &mut TextEditHistory,
+            &ComputedNode,
+            Option<&Indentation>,
+        ),
+        With<Focus>,
+    >,
+    mut events: EventReader<KeyboardInput>,
+    key_states: Res<ButtonInput<KeyCode>>,
+    keymap: Res<TextEditKeymap>,
+    mut clipboard: ResMut<BevyClipboard>,
+) {
+    let Ok((entity, mut editable_text, mut inner, mut history, node, indentation)) =
+        q_editable_texts.single_mut()
+    else {
+        events.clear();
+        return;
+    };
+    let Some(mut cursor) = editable_text.cursor_position else {
+        events.clear();
+        return;
+    };
+
+    let modifiers = Modifiers::pressed(&key_states);
+    let shift = modifiers.shift;
+    // Characters typed with these modifiers are shortcuts, not text
+    let shortcut = modifiers.control || modifiers.super_key;
+    let page_lines = inner
+        .lines
+        .lines_in(node.size().y * node.inverse_scale_factor()) as isize;
+
+    for event in events.read() {
+        if !event.state.is_pressed() {
+            continue;
+        }
+        let len = editable_text.text.chars().count();
+        cursor.0 = cursor.0.min(len);
+        let selection = editable_text.selection_range();
+
+        let mut edit = None;
+        // Changes made by undo and redo are not recorded in the history
+        let mut history_edit = None;
+        let mut motion = None;
+        let mut vertical_motion = None;
+
+        if let Some(action) = keymap.action(event.key_code, modifiers) {
+            match action {
+                TextEditAction::Copy | TextEditAction::Cut => {
+                    if let Some(selected_text) = editable_text.get_selected_text() {
+                        if let Err(e) = clipboard.set_text(selected_text) {
+                            warn!("Clipboard error: {}", e);
+                        }
+                    }
+                    if action == TextEditAction::Cut {
+                        if let Some(range) = selection {
+                            edit = Some((TextChange::remove_change(range), range.0));
+                        }
+                    }
+                }
+                TextEditAction::Paste => match clipboard.get_text() {
+                    Ok(text) => edit = Some(replace_selection(cursor, selection, &text)),
+                    Err(e) => warn!("Clipboard error: {}", e),
+                },
+                TextEditAction::SelectAll => {
+                    editable_text.selection_start = Some(CharPosition(0));
+                    cursor = CharPosition(len);
+                }
+                TextEditAction::SelectWord => {
+                    let (start, end) = word_at(&editable_text.text, cursor);
+                    editable_text.selection_start = Some(start);
+                    cursor = end;
+                }
+                TextEditAction::Undo => history_edit = history.undo(&editable_text.text),
+                TextEditAction::Redo => history_edit = history.redo(&editable_text.text),
+                TextEditAction::WordLeft => {
+                    motion = Some(previous_word_start(&editable_text.text, cursor));
+                }
+                TextEditAction::WordRight => {
+                    motion = Some(next_word_end(&editable_text.text, cursor));
+                }
+                TextEditAction::DeleteWordBackward => {
+                    if let Some(range) = selection {
+                        edit = Some((TextChange::remove_change(range), range.0));
+                    } else if cursor.0 > 0 {
+                        let start = previous_word_start(&editable_text.text, cursor);
+                        edit = Some((TextChange::remove_change((start, cursor)), start));
+                    }
+                }
+                TextEditAction::DeleteWordForward => {
+                    if let Some(range) = selection {
+                        edit = Some((TextChange::remove_change(range), range.0));
+                    } else if cursor.0 < len {
+                        let end = next_word_end(&editable_text.text, cursor);
+                        edit = Some((TextChange::remove_change((cursor, end)), cursor));
+                    }
+                }
+                TextEditAction::LineStart => motion = Some(inner.lines.line_start(cursor)),
+                TextEditAction::LineEnd => motion = Some(inner.lines.line_end(cursor)),
+                TextEditAction::TextStart => motion = Some(CharPosition(0)),
+                TextEditAction::TextEnd => motion = Some(CharPosition(len)),
+            }
+        } else {
+            match &event.logical_key {
+                // Ignore the shortcuts that are not in the keymap
+                Key::Character(_) | Key::Space if shortcut => {}
+                Key::Character(c) => edit = Some(replace_selection(cursor, selection, c)),
+                Key::Space => edit = Some(replace_selection(cursor, selection, " ")),
+                Key::Enter => {
+                    let start = selection.map_or(cursor, |(start, _)| start);
+                    let line_break = line_break(&editable_text.text, start, indentation);
+                    edit = Some(replace_selection(cursor, selection, &line_break));
+                }
+                Key::Tab => {
+                    if let Some(indentation) = indentation {
+                        edit = Some(replace_selection(cursor, selection, &indentation.0));
+                    }
+                }
+                Key::Backspace => {
+                    if let Some(range) = selection {
+                        edit = Some((TextChange::remove_change(range), range.0));
+                    } else if cursor.0 > 0 {
+                        edit = Some((TextChange::remove_change((cursor - 1, cursor)), cursor - 1));
+                    }
+                }
+                Key::Delete => {
+                    if let Some(range) = selection {
+                        edit = Some((TextChange::remove_change(range), range.0));
+                    } else if cursor.0 < len {
+                        edit = Some((TextChange::remove_change((cursor, cursor + 1)), cursor));
+                    }
+                }
+                Key::ArrowLeft => {
+                    motion = Some(match selection {
+                        Some((start, _)) if !shift => start,
+                        _ => CharPosition(cursor.0.saturating_sub(1)),
+                    });
+                }
+                Key::ArrowRight => {
+                    motion = Some(match selection {
+                        Some((_, end)) if !shift => end,
+                        _ => CharPosition((cursor.0 + 1).min(len)),
+                    });
+                }
+                Key::ArrowUp => vertical_motion = Some(-1),
+                Key::ArrowDown => vertical_motion = Some(1),
+                Key::PageUp => vertical_motion = Some(-page_lines),
+                Key::PageDown => vertical_motion = Some(page_lines),
+                Key::Escape => editable_text.selection_start = None,
+                _ => {}
+            }
+        }
+
+        if let Some(lines) = vertical_motion {
+            let x = inner.preferred_x.unwrap_or_else(|| {
+                inner
+                    .lines
+                    .caret(cursor)
+                    .map_or(0.0, |(position, _)| position.x)
+            });
+            inner.preferred_x = Some(x);
+            motion = Some(inner.lines.vertical_move(cursor, lines, x));
+        } else {
+            inner.preferred_x = None;
+        }
+
+        if let Some(position) = motion {
+            if !shift {
+                editable_text.selection_start = None;
+            } else if editable_text.selection_start.is_none() {
+                editable_text.selection_start = Some(cursor);
+            }
+            cursor = position;
+        }
+
+        let mut selection_after = None;
+        if let Some(history_edit) = history_edit {
+            selection_after = history_edit.selection_start;
+            edit = Some((history_edit.change, history_edit.cursor_position));
+        } else if let Some((text_change, new_cursor)) = &edit {
+            history.record(
+                &editable_text.text,
+                text_change,
+                Some(cursor),
+                editable_text.selection_start,
+                *new_cursor,
+            );
+        }
+
+        if let Some((text_change, new_cursor)) = edit {
+            let old_cursor_position = editable_text.cursor_position;
+            editable_text.selection_start = selection_after;
+            cursor = new_cursor;
+
+            // Send the text change event with the new text
+            let mut new_text = editable_text.text.clone();
+            text_change.apply(&mut new_text);
+            commands.trigger_targets(
+                TextChanged {
+                    change: text_change.clone(),
+                    new_text,
+                    old_cursor_position,
+                    new_cursor_position: Some(cursor),
+                },
+                entity,
+            );
+
+            // If the text is not controlled, apply the text change to it
+            if !editable_text.controlled_widget {
+                text_change.apply(&mut editable_text.text);
+            }
+        }
+
+        if editable_text.cursor_position != Some(cursor) {
+            editable_text.cursor_position = Some(cursor);
+        }
+        inner.show_cursor = true;
+    }
+}
+
+/// The change replacing the selection, or inserting at the cursor without one, by `text`, and
+/// the position of the cursor after it
+fn replace_selection(
+    cursor: CharPosition,
+    selection: Option<(CharPosition, CharPosition)>,
+    text: &str,
+) -> (TextChange, CharPosition) {
+    // Pasted text may come with Windows line breaks
+    let text = text.replace("\r\n", "\n");
+    let count = text.chars().count();
+    match selection {
+        Some(range) => (TextChange::new(range, text), range.0 + count),
+        None => (TextChange::insert_change(cursor, text), cursor + count),
+    }
+}
+
+/// The text inserted by Enter at `position`: a line break, followed by the indentation of the
+/// line of `position` with [`Indentation`], one level more after an opening bracket
+fn line_break(text: &str, position: CharPosition, indentation: Option<&Indentation>) -> String {
+    let Some(indentation) = indentation else {
+        return "\n".to_string();
+    };
+    let before = &text[..get_byte_position(text, position)];
+    let line = &before[before.rfind('\n').map_or(0, |index| index + 1)..];
+    let mut line_break = format!("\n{}", &line[..line.len() - line.trim_start().len()]);
+    if line.trim_end().ends_with(['{', '[', '(']) {
+        line_break.push_str(&indentation.0);
+    }
+    line_break
+}
+
+/// Scroll the hovered editable texts with the mouse wheel
+pub fn mouse_wheel_scroll(
+    mut events: EventReader<MouseWheel>,
+    mut q_editable_texts: Query<(&RelativeCursorPosition, &mut EditableTextLayout)>,
+) {
+    for event in events.read() {
+        for (cursor, mut inner) in q_editable_texts.iter_mut() {
+            if !cursor.mouse_over() {
+                continue;
+            }
+            let line_height = inner.lines.0.first().map_or(0.0, |line| line.height);
+            inner.scroll -= match event.unit {
+                MouseScrollUnit::Line => event.y * SCROLL_LINES * line_height,
+                MouseScrollUnit::Pixel => event.y,
+            };
+        }
+    }
+}
+
+pub fn on_focus_lost(trigger: Trigger<LostFocus>, mut q_editable_texts: Query<&mut EditableText>) {
+    let Ok(mut editable_text) = q_editable_texts.get_mut(trigger.target()) else {
+        return;
+    };
+    editable_text.cursor_position = None;
+    editable_text.selection_start = None;
+}
+
+pub fn on_set_cursor_position(
+    trigger: Trigger<SetCursorPosition>,
+    mut q_editable_texts: Query<(&mut EditableText, &mut EditableTextLayout)>,
+) {
+    let Ok((mut editable_text, mut inner)) = q_editable_texts.get_mut(trigger.target()) else {
+        return;
+    };
+    editable_text.cursor_position = Some(trigger.0);
+    inner.preferred_x = None;
+    inner.show_cursor = true;
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn line_breaks_keep_the_indentation() {
+        let indentation = Indentation::default();
+        let text = "fn main() {\n    let a = [\n        1,";
+        assert_eq!(line_break(text, CharPosition(5), None), "\n");
+        assert_eq!(
+            line_break(text, CharPosition(11), Some(&indentation)),
+            "\n    "
+        );
+        assert_eq!(line_break(text, CharPosition(5), Some(&indentation)), "\n");
+        assert_eq!(
+            line_break(text, CharPosition(25), Some(&indentation)),
+            "\n        "
+        );
+        assert_eq!(
+            line_break(text, CharPosition(36), Some(&indentation)),
+            "\n        "
+        );
+    }
+}
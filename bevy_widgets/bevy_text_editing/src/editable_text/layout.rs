@@ -0,0 +1,329 @@
+//! The lines of an [`EditableText`](super::EditableText) as laid out by Bevy, used to place the
+//! cursor and the selection and to move through wrapped lines
+
+use bevy::{prelude::*, text::cosmic_text::Buffer};
+
+use crate::CharPosition;
+
+/// Extra width of the selection at the end of a line, showing that its line break is selected
+const LINE_BREAK_SELECTION_WIDTH: f32 = 4.0;
+
+/// A line of text as shown on screen: a whole line of the text, or a part of it when the line is
+/// wrapped
+#[derive(Clone, Debug, Default, PartialEq)]
+pub struct VisualLine {
+    /// Position of the first character of the line in the text
+    pub start: CharPosition,
+    /// Horizontal positions of the boundaries between the characters of the line, from the left
+    /// of the first character to the right of the last one
+    pub carets: Vec<f32>,
+    /// Top of the line
+    pub top: f32,
+    /// Height of the line
+    pub height: f32,
+}
+
+impl VisualLine {
+    /// Position just after the last character of the line
+    pub fn end(&self) -> CharPosition {
+        self.start + self.carets.len().saturating_sub(1)
+    }
+
+    /// Horizontal position of the cursor placed at `position`, clamped to the line
+    pub fn caret(&self, position: CharPosition) -> f32 {
+        let index = position.0.saturating_sub(self.start.0);
+        self.carets
+            .get(index.min(self.carets.len().saturating_sub(1)))
+            .copied()
+            .unwrap_or_default()
+    }
+
+    /// The position in the line closest to the horizontal position `x`
+    fn closest(&self, x: f32) -> CharPosition {
+        let index = self
+            .carets
+            .iter()
+            .enumerate()
+            .min_by(|(_, a), (_, b)| (*a - x).abs().total_cmp(&(*b - x).abs()))
+            .map_or(0, |(index, _)| index);
+        self.start + index
+    }
+}
+
+/// The visual lines of a text, in logical pixels from the top left corner of the text
+#[derive(Clone, Debug, Default, PartialEq)]
+pub struct TextLines(pub Vec<VisualLine>);
+
+impl TextLines {
+    /// Read the layout of `text` from the text buffer Bevy laid it out in, with the physical
+    /// pixels of the buffer multiplied by `scale` to get logical pixels
+    pub fn from_buffer(buffer: &Buffer, text: &str, scale: f32) -> Self {
+        // Start of each line of the text, which Bevy lays out as a paragraph of the buffer
+        let mut paragraph_starts = vec![0];
+        let mut position = 0;
+        for paragraph in text.split('\n') {
+            position += paragraph.chars().count() + 1;
+            paragraph_starts.push(position);
+        }
+
+        let mut lines = Vec::new();
+        for run in buffer.layout_runs() {
+            let Some(&paragraph_start) = paragraph_starts.get(run.line_i) else {
+                continue;
+            };
+            let (Some(byte_start), Some(byte_end)) = (
+                run.glyphs.iter().map(|glyph| glyph.start).min(),
+                run.glyphs.iter().map(|glyph| glyph.end).max(),
+            ) else {
+                lines.push(VisualLine {
+                    start: CharPosition(paragraph_start),
+                    carets: vec![0.0],
+                    top: run.line_top * scale,
+                    height: run.line_height * scale,
+                });
+                continue;
+            };
+            let byte_end = byte_end.min(run.text.len());
+            let byte_start = byte_start.min(byte_end);
+
+            let mut carets = Vec::new();
+            for (byte, _) in run.text[byte_start..byte_end].char_indices() {
+                let byte = byte_start + byte;
+                let x = run
+                    .glyphs
+                    .iter()
+                    .find(|glyph| glyph.start <= byte && byte < glyph.end)
+                    .map_or(0.0, |glyph| {
+                        // Characters merged into a single glyph share its width
+                        let glyph_text = &run.text[glyph.start..glyph.end.min(run.text.len())];
+                        let before = run.text[glyph.start..byte].chars().count() as f32;
+                        let count = glyph_text.chars().count().max(1) as f32;
+                        glyph.x + glyph.w * before / count
+                    });
+                carets.push(x * scale);
+            }
+            let right = run
+                .glyphs
+                .iter()
+                .map(|glyph| glyph.x + glyph.w)
+                .fold(0.0, f32::max);
+            carets.push(right * scale);
+
+            lines.push(VisualLine {
+                start: CharPosition(paragraph_start + run.text[..byte_start].chars().count()),
+                carets,
+                top: run.line_top * scale,
+                height: run.line_height * scale,
+            });
+        }
+        Self(lines)
+    }
+
+    /// Height of all the lines
+    pub fn height(&self) -> f32 {
+        self.0.last().map_or(0.0, |line| line.top + line.height)
+    }
+
+    /// Index of the line the cursor is on when placed at `position`.
+    /// The position between two parts of a wrapped line is at the start of the second part.
+    pub fn line_index(&self, position: CharPosition) -> usize {
+        self.0
+            .iter()
+            .rposition(|line| line.start.0 <= position.0)
+            .unwrap_or(0)
+    }
+
+    /// Top left corner of the cursor placed at `position`, and the height of its line
+    pub fn caret(&self, position: CharPosition) -> Option<(Vec2, f32)> {
+        let line = self.0.get(self.line_index(position))?;
+        Some((Vec2::new(line.caret(position), line.top), line.height))
+    }
+
+    /// Whether the line at `index` goes on in the next line, so that its end is shown at the
+    /// start of the next line
+    pub fn is_wrapped(&self, index: usize) -> bool {
+        self.0
+            .get(index + 1)
+            .is_some_and(|next| next.start == self.0[index].end())
+    }
+
+    /// Keep a position at the end of a wrapped line on that line, before its last character
+    fn clamp_to_line(&self, index: usize, position: CharPosition) -> CharPosition {
+        let line = &self.0[index];
+        if position.0 >= line.end().0 && self.is_wrapped(index) && line.end().0 > line.start.0 {
+            line.end() - 1
+        } else {
+            position
+        }
+    }
+
+    /// Position of the character closest to `point`
+    pub fn hit(&self, point: Vec2) -> CharPosition {
+        let Some(index) = self
+            .0
+            .iter()
+            .position(|line| point.y < line.top + line.height)
+            .or(self.0.len().checked_sub(1))
+        else {
+            return CharPosition(0);
+        };
+        self.clamp_to_line(index, self.0[index].closest(point.x))
+    }
+
+    /// Position on the line `lines` lines below the one of `position` (above when negative),
+    /// closest to the horizontal position `x`.
+    /// Moving past the first or last line goes to the start or the end of the text.
+    pub fn vertical_move(&self, position: CharPosition, lines: isize, x: f32) -> CharPosition {
+        if self.0.is_empty() {
+            return position;
+        }
+        let index = self.line_index(position) as isize + lines;
+        if index < 0 {
+            return CharPosition(0);
+        }
+        let Some(line) = self.0.get(index as usize) else {
+            return self.0[self.0.len() - 1].end();
+        };
+        self.clamp_to_line(index as usize, line.closest(x))
+    }
+
+    /// Start of the visual line of `position`
+    pub fn line_start(&self, position: CharPosition) -> CharPosition {
+        self.0
+            .get(self.line_index(position))
+            .map_or(position, |line| line.start)
+    }
+
+    /// End of the visual line of `position`, staying on that line when it is wrapped
+    pub fn line_end(&self, position: CharPosition) -> CharPosition {
+        let index = self.line_index(position);
+        self.0
+            .get(index)
+            .map_or(position, |line| self.clamp_to_line(index, line.end()))
+    }
+
+    /// The number of lines in `height`, at least one
+    pub fn lines_in(&self, height: f32) -> usize {
+        let line_height = self
+            .0
+            .first()
+            .map_or(0.0, |line| line.height)
+            .max(f32::EPSILON);
+        ((height / line_height) as usize).max(1)
+    }
+
+    /// The rectangles covering the text between `start` and `end`, one for each line
+    pub fn selection_rects(&self, start: CharPosition, end: CharPosition) -> Vec<Rect> {
+        let mut rects = Vec::new();
+        for (index, line) in self.0.iter().enumerate() {
+            if line.end().0 < start.0 || line.start.0 > end.0 {
+                continue;
+            }
+            let left = line.caret(CharPosition(start.0.max(line.start.0)));
+            let mut right = line.caret(CharPosition(end.0.min(line.end().0)));
+            // The selection goes on past a line break
+            if end.0 > line.end().0 && !self.is_wrapped(index) {
+                right += LINE_BREAK_SELECTION_WIDTH;
+            }
+            if right > left {
+                rects.push(Rect::new(left, line.top, right, line.top + line.height));
+            }
+        }
+        rects
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    /// The lines of "ab cd\n\nef" with "ab cd" wrapped after the space, with 10 pixel wide
+    /// characters and 20 pixel high lines
+    fn lines() -> TextLines {
+        let line = |start: usize, chars: usize, row: usize| VisualLine {
+            start: CharPosition(start),
+            carets: (0..=chars).map(|index| index as f32 * 10.0).collect(),
+            top: row as f32 * 20.0,
+            height: 20.0,
+        };
+        TextLines(vec![
+            line(0, 3, 0),
+            line(3, 2, 1),
+            line(6, 0, 2),
+            line(7, 2, 3),
+        ])
+    }
+
+    #[test]
+    fn finds_the_line_of_a_position() {
+        let lines = lines();
+        assert_eq!(lines.line_index(CharPosition(2)), 0);
+        // The wrapping point belongs to the second part of the line
+        assert_eq!(lines.line_index(CharPosition(3)), 1);
+        // The end of a line before a line break stays on it
+        assert_eq!(lines.line_index(CharPosition(5)), 1);
+        assert_eq!(lines.line_index(CharPosition(6)), 2);
+        assert_eq!(
+            lines.caret(CharPosition(9)),
+            Some((Vec2::new(20.0, 60.0), 20.0))
+        );
+    }
+
+    #[test]
+    fn hits_the_closest_character() {
+        let lines = lines();
+        assert_eq!(lines.hit(Vec2::new(14.0, 5.0)), CharPosition(1));
+        // Past the end of a wrapped line, before its trailing space
+        assert_eq!(lines.hit(Vec2::new(100.0, 5.0)), CharPosition(2));
+        assert_eq!(lines.hit(Vec2::new(100.0, 25.0)), CharPosition(5));
+        assert_eq!(lines.hit(Vec2::new(100.0, 45.0)), CharPosition(6));
+        assert_eq!(lines.hit(Vec2::new(6.0, 500.0)), CharPosition(8));
+    }
+
+    #[test]
+    fn moves_through_wrapped_lines() {
+        let lines = lines();
+        assert_eq!(
+            lines.vertical_move(CharPosition(1), 1, 10.0),
+            CharPosition(4)
+        );
+        assert_eq!(
+            lines.vertical_move(CharPosition(4), 1, 10.0),
+            CharPosition(6)
+        );
+        assert_eq!(
+            lines.vertical_move(CharPosition(4), -1, 30.0),
+            CharPosition(2)
+        );
+        assert_eq!(
+            lines.vertical_move(CharPosition(4), -5, 10.0),
+            CharPosition(0)
+        );
+        assert_eq!(
+            lines.vertical_move(CharPosition(4), 5, 0.0),
+            CharPosition(9)
+        );
+
+        assert_eq!(lines.line_start(CharPosition(4)), CharPosition(3));
+        assert_eq!(lines.line_end(CharPosition(1)), CharPosition(2));
+        assert_eq!(lines.line_end(CharPosition(3)), CharPosition(5));
+        assert_eq!(lines.lines_in(70.0), 3);
+    }
+
+    #[test]
+    fn covers_the_selection_line_by_line() {
+        let lines = lines();
+        assert_eq!(
+            lines.selection_rects(CharPosition(1), CharPosition(8)),
+            vec![
+                Rect::new(10.0, 0.0, 30.0, 20.0),
+                Rect::new(0.0, 20.0, 24.0, 40.0),
+                Rect::new(0.0, 40.0, 4.0, 60.0),
+                Rect::new(0.0, 60.0, 10.0, 80.0),
+            ]
+        );
+        assert!(lines
+            .selection_rects(CharPosition(4), CharPosition(4))
+            .is_empty());
+    }
+}
@@ -0,0 +1,262 @@
+use super::{layout::VisualLine, *};
+use crate::{cursor::Cursor, TEXT_SELECTION_COLOR};
+use bevy::text::{ComputedTextBlock, LineHeight};
+
+/// Show the text of the changed editable texts, before Bevy lays it out.
+/// Highlighted text is shown as a span for each part of a different color.
+pub fn update_text(
+    mut commands: Commands,
+    q_editable_texts: Query<
+        (
+            &EditableText,
+            &EditableTextLayout,
+            Option<&TextHighlights>,
+            Option<&TextColor>,
+            Option<&TextFont>,
+        ),
+        Or<(
+            Changed<EditableText>,
+            Changed<TextHighlights>,
+            Changed<TextColor>,
+        )>,
+    >,
+    mut q_texts: Query<(&mut Text, Option<&Children>)>,
+    mut q_spans: Query<(&mut TextSpan, &mut TextColor)>,
+) {
+    for (editable_text, inner, highlights, color, font) in q_editable_texts.iter() {
+        let Ok((mut text, children)) = q_texts.get_mut(inner.text) else {
+            continue;
+        };
+        let children = children.map_or(&[][..], |children| &children[..]);
+        let Some(highlights) = highlights else {
+            if text.0 != editable_text.text {
+                text.0 = editable_text.text.clone();
+            }
+            for &span in children {
+                commands.entity(span).despawn();
+            }
+            continue;
+        };
+
+        if !text.0.is_empty() {
+            text.0.clear();
+        }
+        let color = color.copied().unwrap_or_default();
+        let spans = highlighted_spans(&editable_text.text, highlights, color.0);
+        // Reuse the spans of the previous text
+        for (index, (span_text, span_color)) in spans.iter().enumerate() {
+            match children
+                .get(index)
+                .and_then(|&span| q_spans.get_mut(span).ok())
+            {
+                Some((mut span, mut color)) => {
+                    if span.0 != *span_text {
+                        span.0.clone_from(span_text);
+                    }
+                    if color.0 != *span_color {
+                        color.0 = *span_color;
+                    }
+                }
+                None => {
+                    commands.spawn((
+                        TextSpan::new(span_text.clone()),
+                        TextColor(*span_color),
+                        font.cloned().unwrap_or_default(),
+                        ChildOf(inner.text),
+                    ));
+                }
+            }
+        }
+        for &span in children.iter().skip(spans.len()) {
+            commands.entity(span).despawn();
+        }
+    }
+}
+
+/// The parts of `text` of a different color, with their color: the color of their highlight, or
+/// `color` when they aren't highlighted
+pub fn highlighted_spans(
+    text: &str,
+    highlights: &TextHighlights,
+    color: Color,
+) -> Vec<(String, Color)> {
+    let mut spans: Vec<(String, Color)> = Vec::new();
+    let mut push = |part: &str, part_color: Color| match spans.last_mut() {
+        Some((last, last_color)) if *last_color == part_color => last.push_str(part),
+        _ if part.is_empty() => {}
+        _ => spans.push((part.to_string(), part_color)),
+    };
+    for (index, line) in text.split('\n').enumerate() {
+        if index > 0 {
+            push("\n", color);
+        }
+        let mut end = 0;
+        for (range, highlight_color) in highlights.0.get(index).map_or(&[][..], Vec::as_slice) {
+            // Ignore highlights that don't fit the line, while they are being updated
+            let Some(part) = line.get(range.clone()).filter(|_| range.start >= end) else {
+                continue;
+            };
+            push(&line[end..range.start], color);
+            push(part, *highlight_color);
+            end = range.end;
+        }
+        push(&line[end..], color);
+    }
+    spans
+}
+
+/// Read the lines of the laid out texts, then place the cursor, the selection and the marks on
+/// them and scroll to the cursor
+pub fn update_layout(
+    mut commands: Commands,
+    mut q_editable_texts: Query<(
+        Ref<EditableText>,
+        &mut EditableTextLayout,
+        &ComputedNode,
+        Option<Ref<TextMarks>>,
+    )>,
+    q_texts: Query<(Ref<ComputedTextBlock>, &ComputedNode, &TextFont)>,
+    q_cursors: Query<(), With<Cursor>>,
+    mut q_nodes: Query<&mut Node>,
+) {
+    for (editable_text, mut inner, node, marks) in q_editable_texts.iter_mut() {
+        let Ok((block, text_node, font)) = q_texts.get(inner.text) else {
+            continue;
+        };
+        // Scrolling with the mouse wheel doesn't bring the cursor back into view
+        let follow_cursor = block.is_changed() || editable_text.is_changed() || inner.show_cursor;
+        if block.is_changed() {
+            inner.lines = if editable_text.text.is_empty() {
+                // Bevy doesn't lay out empty text, which has no line height
+                let height = match font.line_height {
+                    LineHeight::Px(height) => height,
+                    LineHeight::RelativeToFont(scale) => scale * font.font_size,
+                };
+                TextLines(vec![VisualLine {
+                    carets: vec![0.0],
+                    height,
+                    ..default()
+                }])
+            } else {
+                TextLines::from_buffer(
+                    block.buffer(),
+                    &editable_text.text,
+                    text_node.inverse_scale_factor(),
+                )
+            };
+        } else if !follow_cursor
+            && !inner.is_changed()
+            && !marks.as_ref().is_some_and(DetectChanges::is_changed)
+        {
+            continue;
+        }
+
+        let view_height = node.size().y * node.inverse_scale_factor();
+        let max_scroll = (inner.lines.height() - view_height).max(0.0);
+        inner.scroll = inner.scroll.clamp(0.0, max_scroll);
+
+        // Cursor
+        let caret = editable_text
+            .cursor_position
+            .and_then(|position| inner.lines.caret(position));
+        if let Some((position, height)) = caret {
+            if follow_cursor {
+                if position.y < inner.scroll {
+                    inner.scroll = position.y;
+                } else if position.y + height > inner.scroll + view_height {
+                    inner.scroll = position.y + height - view_height;
+                }
+            }
+            if let Ok(mut cursor_node) = q_nodes.get_mut(inner.cursor) {
+                cursor_node.left = Val::Px(position.x);
+                cursor_node.top = Val::Px(position.y);
+                cursor_node.height = Val::Px(height);
+            }
+            if inner.show_cursor || !q_cursors.contains(inner.cursor) {
+                commands
+                    .entity(inner.cursor)
+                    .insert((Cursor::default(), Visibility::Visible));
+            }
+        } else {
+            commands
+                .entity(inner.cursor)
+                .remove::<Cursor>()
+                .insert(Visibility::Hidden);
+        }
+        inner.show_cursor = false;
+
+        if let Ok(mut canvas_node) = q_nodes.get_mut(inner.canvas) {
+            canvas_node.top = Val::Px(-inner.scroll);
+        }
+
+        // Marks, then the selection over them
+        commands
+            .entity(inner.selection)
+            .despawn_related::<Children>();
+        let mut rects = Vec::new();
+        for &(start, end, color) in marks.iter().flat_map(|marks| &marks.0) {
+            rects.extend(
+                inner
+                    .lines
+                    .selection_rects(start, end)
+                    .into_iter()
+                    .map(|rect| (rect, color)),
+            );
+        }
+        if let Some((start, end)) = editable_text.selection_range() {
+            rects.extend(
+                inner
+                    .lines
+                    .selection_rects(start, end)
+                    .into_iter()
+                    .map(|rect| (rect, TEXT_SELECTION_COLOR)),
+            );
+        }
+        if !rects.is_empty() {
+            commands.entity(inner.selection).with_children(|parent| {
+                for (rect, color) in rects {
+                    parent.spawn((
+                        Node {
+                            position_type: PositionType::Absolute,
+                            left: Val::Px(rect.min.x),
+                            top: Val::Px(rect.min.y),
+                            width: Val::Px(rect.width()),
+                            height: Val::Px(rect.height()),
+                            ..default()
+                        },
+                        BackgroundColor(color),
+                    ));
+                }
+            });
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn splits_highlighted_text_into_spans() {
+        let red = Color::srgb(1.0, 0.0, 0.0);
+        let blue = Color::srgb(0.0, 0.0, 1.0);
+        let white = Color::WHITE;
+        let highlights = TextHighlights(vec![
+            vec![(0..2, red), (3..7, blue)],
+            vec![],
+            // Out of date highlights are ignored
+            vec![(0..1, red), (5..9, blue)],
+        ]);
+        assert_eq!(
+            highlighted_spans("fn main\nlet\nx", &highlights, white),
+            vec![
+                ("fn".to_string(), red),
+                (" ".to_string(), white),
+                ("main".to_string(), blue),
+                ("\nlet\n".to_string(), white),
+                ("x".to_string(), red),
+            ]
+        );
+        assert!(highlighted_spans("", &highlights, white).is_empty());
+    }
+}
@@ -9,6 +9,8 @@ use bevy_focus::{FocusPlugin, Focusable, SetFocus};
 
 use crate::{
     cursor::{Cursor, CursorPlugin},
+    history::TextEditHistory,
+    keymap::TextEditKeymap,
     CharPosition, SetCursorPosition, SetText, TextChanged, TEXT_SELECTION_COLOR,
 };
 
@@ -31,6 +33,9 @@ impl Plugin for EditableTextLinePlugin {
             app.add_plugins(ClipboardPlugin);
         }
 
+        app.init_resource::<TextEditKeymap>()
+            .register_type::<TextEditKeymap>();
+
         app.add_event::<SetText>();
         app.add_event::<TextChanged>();
         app.add_event::<RenderWidget>();
@@ -41,6 +46,7 @@ impl Plugin for EditableTextLinePlugin {
             (
                 spawn_system,
                 keyboard_input,
+                update_ime,
                 check_cursor_overflow,
                 set_cursor_pos,
                 propagate_text_font,
@@ -88,7 +94,7 @@ impl Plugin for EditableTextLinePlugin {
 
 #[derive(Component, Reflect, Default)]
 #[reflect(Component, Default)]
-#[require(Node, Focusable)]
+#[require(Node, Focusable, TextEditHistory)]
 pub struct EditableTextLine {
     /// Text content
     pub text: String,
@@ -223,6 +229,12 @@ pub struct EditableTextInner {
     fake_text_before_selection: Entity,
     fake_selection_text: Entity,
 
+    fake_text_before_preedit: Entity,
+    fake_preedit_text: Entity,
+
+    /// Text being composed with an IME, shown underlined at the cursor until it is committed
+    ime_preedit: String,
+
     /// Canvas shift to the left to keep cursor visible in the text field
     text_shift: f32,
 
@@ -338,6 +350,46 @@ fn spawn_system(
             .entity(selection_canvas)
             .add_child(fake_selection_text);
 
+        let fake_text_before_preedit = commands
+            .spawn((
+                Text::new("".to_string()),
+                TextColor(Color::srgba(0.0, 0.0, 0.0, 0.0)),
+                Node { ..default() },
+                TextLayout::new_with_no_wrap(),
+                font.clone(),
+            ))
+            .id();
+
+        // Underline of the text being composed with an IME
+        let fake_preedit_text = commands
+            .spawn((
+                Text::new("".to_string()),
+                TextColor(Color::srgba(0.0, 0.0, 0.0, 0.0)),
+                BorderColor(Color::srgb(1.0, 1.0, 1.0)),
+                Visibility::Hidden,
+                Node {
+                    border: UiRect::bottom(Val::Px(1.0)),
+                    ..default()
+                },
+                TextLayout::new_with_no_wrap(),
+                font.clone(),
+            ))
+            .id();
+
+        let preedit_canvas = commands
+            .spawn(Node {
+                position_type: PositionType::Absolute,
+                left: Val::Px(0.0),
+                top: Val::Px(0.0),
+                width: Val::Percent(100.0),
+                height: Val::Percent(100.0),
+                display: Display::Flex,
+                flex_direction: FlexDirection::Row,
+                ..default()
+            })
+            .add_children(&[fake_text_before_preedit, fake_preedit_text])
+            .id();
+
         let text = commands
             .spawn((
                 Text::new(text.text.clone()),
@@ -365,6 +417,7 @@ fn spawn_system(
 
         commands.entity(canvas).add_child(selection_canvas);
         commands.entity(canvas).add_child(text);
+        commands.entity(canvas).add_child(preedit_canvas);
         commands.entity(canvas).add_child(cursor_canvas);
 
         commands
@@ -376,6 +429,9 @@ fn spawn_system(
                 canvas,
                 fake_text_before_selection,
                 fake_selection_text,
+                fake_text_before_preedit,
+                fake_preedit_text,
+                ime_preedit: String::new(),
                 text_shift: 0.0,
                 skip_cursor_overflow_check: false,
             })
@@ -412,6 +468,12 @@ fn propagate_text_font(
         commands
             .entity(inner.fake_selection_text)
             .insert(font.clone());
+        commands
+            .entity(inner.fake_text_before_preedit)
+            .insert(font.clone());
+        commands
+            .entity(inner.fake_preedit_text)
+            .insert(font.clone());
         commands.entity(inner.text).insert(font.clone());
     }
 }
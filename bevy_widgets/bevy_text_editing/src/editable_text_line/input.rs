@@ -1,30 +1,52 @@
-use crate::text_change::TextChange;
+use crate::{
+    keymap::{Modifiers, TextEditAction, TextEditKeymap},
+    text_change::TextChange,
+    words::{next_word_end, previous_word_start, word_at},
+};
 
 use super::*;
 use bevy::{
     input::keyboard::{Key, KeyboardInput},
     prelude::*,
+    window::{Ime, PrimaryWindow},
 };
 use bevy_clipboard::BevyClipboard;
 use bevy_focus::{Focus, LostFocus};
 
+/// Maximum time between the clicks of a double or triple click, in seconds
+const MULTI_CLICK_TIME: f64 = 0.5;
+
 pub fn on_click(
     click: Trigger<Pointer<Click>>,
     mut commands: Commands,
     mut q_editable_texts: Query<(&mut EditableTextLine, &mut EditableTextInner)>,
     q_texts: Query<(&ComputedNode, &GlobalTransform)>,
     key_states: Res<ButtonInput<KeyCode>>,
+    time: Res<Time<Real>>,
+    mut last_click: Local<Option<(Entity, f64, usize)>>,
 ) {
     let entity = click.target();
     let Ok((mut text_line, mut inner)) = q_editable_texts.get_mut(entity) else {
         return;
     };
 
+    // Count the clicks made in a row, to select a word on double click and all on triple click
+    let now = time.elapsed_secs_f64();
+    let clicks = match *last_click {
+        Some((last_entity, last_time, clicks))
+            if last_entity == entity && now - last_time < MULTI_CLICK_TIME =>
+        {
+            clicks + 1
+        }
+        _ => 1,
+    };
+    *last_click = Some((entity, now, clicks));
+
     let Ok((node, global_transform)) = q_texts.get(inner.text) else {
         return;
     };
 
-    let shift_pressed = key_states.pressed(KeyCode::ShiftLeft);
+    let shift_pressed = key_states.any_pressed([KeyCode::ShiftLeft, KeyCode::ShiftRight]);
 
     info!("Clicked on editable text line {}", entity);
 
@@ -42,13 +64,22 @@ pub fn on_click(
         cursor_pos = text_line.text.chars().count();
     }
 
-    if shift_pressed && text_line.selection_start.is_none() {
-        // Set selection start on previous cursor position
-        text_line.selection_start = text_line.cursor_position;
-    } else if !shift_pressed {
-        text_line.selection_start = None;
+    if clicks == 2 {
+        let (word_start, word_end) = word_at(&text_line.text, CharPosition(cursor_pos));
+        text_line.selection_start = Some(word_start);
+        text_line.cursor_position = Some(word_end);
+    } else if clicks > 2 {
+        text_line.selection_start = Some(CharPosition(0));
+        text_line.cursor_position = Some(CharPosition(text_line.text.chars().count()));
+    } else {
+        if shift_pressed && text_line.selection_start.is_none() {
+            // Set selection start on previous cursor position
+            text_line.selection_start = text_line.cursor_position;
+        } else if !shift_pressed {
+            text_line.selection_start = None;
+        }
+        text_line.cursor_position = Some(CharPosition(cursor_pos));
     }
-    text_line.cursor_position = Some(CharPosition(cursor_pos));
     inner.skip_cursor_overflow_check = true;
 
     commands.trigger_targets(SetFocus, entity);
@@ -57,192 +88,252 @@ pub fn on_click(
 
 pub fn keyboard_input(
     mut commands: Commands,
-    mut q_text_fields: Query<(Entity, &mut EditableTextLine), With<Focus>>,
+    mut q_text_fields: Query<
+        (
+            Entity,
+            &mut EditableTextLine,
+            &mut EditableTextInner,
+            &mut TextEditHistory,
+        ),
+        With<Focus>,
+    >,
     mut events: EventReader<KeyboardInput>,
+    mut ime_events: EventReader<Ime>,
     key_states: Res<ButtonInput<KeyCode>>,
+    keymap: Res<TextEditKeymap>,
     mut clipboard: ResMut<BevyClipboard>,
 ) {
-    let Ok((entity, mut text_field)) = q_text_fields.single_mut() else {
+    let Ok((entity, mut text_field, mut inner, mut history)) = q_text_fields.single_mut() else {
+        ime_events.clear();
         return;
     };
 
     let Some(mut current_cursor) = text_field.cursor_position else {
+        ime_events.clear();
         return;
     };
     current_cursor.0 = current_cursor.0.clamp(0, text_field.text.chars().count());
 
     let mut need_render = false;
     let mut text_change = TextChange::nop_change();
-
-    // check for Ctrl-C, Ctrl-V, Ctrl-A etc
-    if key_states.pressed(KeyCode::ControlLeft) {
-        if key_states.just_pressed(KeyCode::KeyC) {
-            need_render = true;
-            events.clear(); // clear events that were triggered by pasting
-
-            if let Some(selected_text) = text_field.get_selected_text() {
-                if let Err(e) = clipboard.set_text(selected_text) {
-                    warn!("Clipboard error: {}", e);
-                }
+    // Changes made by undo and redo are not recorded in the history
+    let mut from_history = false;
+    let selection_before = text_field.selection_start;
+    let modifiers = Modifiers::pressed(&key_states);
+
+    // Text composed with an IME is shown at the cursor, and inserted as typed once committed
+    let mut preedit_changed = false;
+    for event in ime_events.read() {
+        match event {
+            Ime::Preedit { value, .. } if *value != inner.ime_preedit => {
+                inner.ime_preedit = value.clone();
+                preedit_changed = true;
             }
-        } else if key_states.pressed(KeyCode::KeyC) {
-            events.clear(); // clear events that were triggered by pasting (for example it can be holded and we need to process it only once)
-        } else if key_states.just_pressed(KeyCode::KeyV) {
-            need_render = true;
-            events.clear(); // clear events that were triggered by pasting
-
-            match clipboard.get_text() {
-                Ok(mut text) => {
-                    if let Some(allowed_chars) = &text_field.allowed_chars {
-                        text.retain(|c| allowed_chars.contains(&c));
-                    }
-                    if text_field.selection_start.is_none() {
-                        text_change = TextChange::insert_change(current_cursor, text.clone());
-                        current_cursor = CharPosition(current_cursor.0 + text.chars().count());
-                    } else {
-                        let selected_range = text_field.selection_range().unwrap();
-                        text_change = TextChange::new(selected_range, text.clone());
-                        current_cursor = selected_range.0 + CharPosition(text.chars().count());
-                        text_field.selection_start = None;
-                    }
-                }
-                Err(e) => {
-                    warn!("Clipboard error: {}", e);
-                }
-            }
-        } else if key_states.pressed(KeyCode::KeyV) {
-            events.clear(); // clear events that were triggered by pasting (for example it can be holded and we need to process it only once)
-        } else if key_states.just_pressed(KeyCode::KeyX) {
-            need_render = true;
-            events.clear(); // clear events that were triggered by cut
-            if let Some(selected_text) = text_field.get_selected_text() {
-                if let Err(e) = clipboard.set_text(selected_text) {
-                    warn!("Clipboard error: {}", e);
-                }
+            Ime::Commit { value, .. } => {
+                need_render = true;
+                events.clear();
+                inner.ime_preedit.clear();
+                (text_change, current_cursor) =
+                    typed_change(&mut text_field, current_cursor, value);
             }
-            if let Some(selected_range) = text_field.selection_range() {
-                text_change = TextChange::remove_change(selected_range);
-                current_cursor = selected_range.0;
-                text_field.selection_start = None;
-            }
-        } else if key_states.pressed(KeyCode::KeyX) {
-            events.clear(); // clear events that were triggered by pasting (for example it can be holded and we need to process it only once)
-        } else if key_states.just_pressed(KeyCode::KeyA) {
-            // Select all text
-            need_render = true;
-            text_field.selection_start = Some(CharPosition(0));
-            current_cursor = CharPosition(text_field.text.chars().count());
-            events.clear();
-        } else if key_states.pressed(KeyCode::KeyA) {
-            events.clear(); // clear events that were triggered by pasting (for example it can be holded and we need to process it only once)
+            _ => {}
         }
     }
+    // Keys pressed while composing are handled by the IME
+    if !inner.ime_preedit.is_empty() {
+        events.clear();
+    }
 
-    if !need_render {
-        for event in events.read() {
-            if !event.state.is_pressed() {
-                continue;
-            }
-            match &event.logical_key {
-                Key::Space => {
-                    need_render = true;
+    for event in events.read() {
+        if !event.state.is_pressed() {
+            continue;
+        }
 
-                    if let Some(allowed_chars) = &text_field.allowed_chars {
-                        if !allowed_chars.contains(&' ') {
-                            continue;
+        // Shortcuts of the keymap, like Ctrl+C, Ctrl+V or Ctrl+A
+        if let Some(action) = keymap.action(event.key_code, modifiers) {
+            need_render = true;
+            let text_len = text_field.text.chars().count();
+            match action {
+                TextEditAction::Copy | TextEditAction::Cut => {
+                    if let Some(selected_text) = text_field.get_selected_text() {
+                        if let Err(e) = clipboard.set_text(selected_text) {
+                            warn!("Clipboard error: {}", e);
                         }
                     }
-
-                    if let Some((start, end)) = text_field.selection_range() {
-                        text_change = TextChange::new((start, end), " ");
-                        current_cursor = start + CharPosition(1);
+                    if action == TextEditAction::Cut {
+                        if let Some(selected_range) = text_field.selection_range() {
+                            text_change = TextChange::remove_change(selected_range);
+                            current_cursor = selected_range.0;
+                            text_field.selection_start = None;
+                        }
+                    }
+                }
+                TextEditAction::Paste => match clipboard.get_text() {
+                    Ok(text) => {
+                        (text_change, current_cursor) =
+                            typed_change(&mut text_field, current_cursor, &text);
+                    }
+                    Err(e) => {
+                        warn!("Clipboard error: {}", e);
+                    }
+                },
+                TextEditAction::SelectAll => {
+                    text_field.selection_start = Some(CharPosition(0));
+                    current_cursor = CharPosition(text_len);
+                }
+                TextEditAction::SelectWord => {
+                    let (word_start, word_end) = word_at(&text_field.text, current_cursor);
+                    text_field.selection_start = Some(word_start);
+                    current_cursor = word_end;
+                }
+                TextEditAction::Undo | TextEditAction::Redo => {
+                    let edit = if action == TextEditAction::Redo {
+                        history.redo(&text_field.text)
                     } else {
-                        text_change = TextChange::insert_change(current_cursor, " ");
-                        current_cursor = CharPosition(current_cursor.0 + 1);
+                        history.undo(&text_field.text)
+                    };
+                    if let Some(edit) = edit {
+                        text_change = edit.change;
+                        current_cursor = edit.cursor_position;
+                        text_field.selection_start = edit.selection_start;
+                        from_history = true;
                     }
-
-                    text_field.selection_start = None; // clear selection if we write any text
                 }
-                Key::Backspace => {
-                    need_render = true;
-
+                TextEditAction::WordLeft => {
+                    let position = previous_word_start(&text_field.text, current_cursor);
+                    move_cursor(
+                        &mut text_field,
+                        &mut current_cursor,
+                        position,
+                        modifiers.shift,
+                    );
+                }
+                TextEditAction::WordRight => {
+                    let position = next_word_end(&text_field.text, current_cursor);
+                    move_cursor(
+                        &mut text_field,
+                        &mut current_cursor,
+                        position,
+                        modifiers.shift,
+                    );
+                }
+                TextEditAction::LineStart | TextEditAction::TextStart => {
+                    let position = CharPosition(0);
+                    move_cursor(
+                        &mut text_field,
+                        &mut current_cursor,
+                        position,
+                        modifiers.shift,
+                    );
+                }
+                TextEditAction::LineEnd | TextEditAction::TextEnd => {
+                    let position = CharPosition(text_len);
+                    move_cursor(
+                        &mut text_field,
+                        &mut current_cursor,
+                        position,
+                        modifiers.shift,
+                    );
+                }
+                TextEditAction::DeleteWordBackward => {
                     if let Some((start, end)) = text_field.selection_range() {
                         text_change = TextChange::remove_change((start, end));
                         current_cursor = start;
                     } else if current_cursor > CharPosition(0) {
-                        text_change = TextChange::remove_change((
-                            current_cursor - CharPosition(1),
-                            current_cursor,
-                        ));
-                        current_cursor = current_cursor - 1;
+                        let start = previous_word_start(&text_field.text, current_cursor);
+                        text_change = TextChange::remove_change((start, current_cursor));
+                        current_cursor = start;
                     }
-                    text_field.selection_start = None; // clear selection if we write any text
+                    text_field.selection_start = None;
                 }
-                Key::Delete => {
-                    need_render = true;
+                TextEditAction::DeleteWordForward => {
                     if let Some((start, end)) = text_field.selection_range() {
                         text_change = TextChange::remove_change((start, end));
                         current_cursor = start;
-                    } else if current_cursor < CharPosition(text_field.text.chars().count()) {
-                        text_change = TextChange::remove_change((
-                            current_cursor,
-                            current_cursor + CharPosition(1),
-                        ));
+                    } else if current_cursor < CharPosition(text_len) {
+                        let end = next_word_end(&text_field.text, current_cursor);
+                        text_change = TextChange::remove_change((current_cursor, end));
                     }
-                    text_field.selection_start = None; // clear selection if we write any text
+                    text_field.selection_start = None;
                 }
-                Key::Character(c) => {
-                    if key_states.pressed(KeyCode::ControlLeft) {
-                        continue; // ignore control characters
-                    }
-                    let mut chars = c.chars().collect::<Vec<_>>();
-                    if let Some(allowed_chars) = &text_field.allowed_chars {
-                        chars.retain(|c| allowed_chars.contains(c));
-                    }
-                    need_render = true;
+            }
+            continue;
+        }
 
-                    if let Some((start, end)) = text_field.selection_range() {
-                        text_change =
-                            TextChange::new((start, end), chars.iter().collect::<String>());
-                        current_cursor = start + CharPosition(chars.len());
-                    } else {
-                        text_change = TextChange::insert_change(
-                            current_cursor,
-                            chars.iter().collect::<String>(),
-                        );
-                        current_cursor = current_cursor + CharPosition(chars.len());
+        match &event.logical_key {
+            Key::Space => {
+                need_render = true;
+
+                if let Some(allowed_chars) = &text_field.allowed_chars {
+                    if !allowed_chars.contains(&' ') {
+                        continue;
                     }
-                    text_field.selection_start = None; // clear selection if we write any text
                 }
-                Key::ArrowLeft => {
-                    if current_cursor > 0 {
-                        if key_states.pressed(KeyCode::ShiftLeft) {
-                            if text_field.selection_start.is_none() {
-                                text_field.selection_start = Some(current_cursor);
-                            }
-                        } else {
-                            text_field.selection_start = None;
-                        }
-                        current_cursor = current_cursor - 1;
-                        need_render = true;
-                    }
+
+                if let Some((start, end)) = text_field.selection_range() {
+                    text_change = TextChange::new((start, end), " ");
+                    current_cursor = start + CharPosition(1);
+                } else {
+                    text_change = TextChange::insert_change(current_cursor, " ");
+                    current_cursor = CharPosition(current_cursor.0 + 1);
                 }
-                Key::ArrowRight => {
-                    if current_cursor < CharPosition(text_field.text.chars().count()) {
-                        if key_states.pressed(KeyCode::ShiftLeft) {
-                            if text_field.selection_start.is_none() {
-                                text_field.selection_start = Some(current_cursor);
-                            }
-                        } else {
-                            text_field.selection_start = None;
-                        }
 
-                        current_cursor = current_cursor + 1;
-                        need_render = true;
-                    }
+                text_field.selection_start = None; // clear selection if we write any text
+            }
+            Key::Backspace => {
+                need_render = true;
+
+                if let Some((start, end)) = text_field.selection_range() {
+                    text_change = TextChange::remove_change((start, end));
+                    current_cursor = start;
+                } else if current_cursor > CharPosition(0) {
+                    text_change = TextChange::remove_change((current_cursor - 1, current_cursor));
+                    current_cursor = current_cursor - 1;
+                }
+                text_field.selection_start = None; // clear selection if we write any text
+            }
+            Key::Delete => {
+                need_render = true;
+                if let Some((start, end)) = text_field.selection_range() {
+                    text_change = TextChange::remove_change((start, end));
+                    current_cursor = start;
+                } else if current_cursor < CharPosition(text_field.text.chars().count()) {
+                    text_change = TextChange::remove_change((current_cursor, current_cursor + 1));
                 }
-                _ => {}
+                text_field.selection_start = None; // clear selection if we write any text
             }
+            Key::Character(c) => {
+                if modifiers.control || modifiers.super_key {
+                    continue; // ignore shortcuts that are not in the keymap
+                }
+                need_render = true;
+                (text_change, current_cursor) = typed_change(&mut text_field, current_cursor, c);
+            }
+            Key::ArrowLeft => {
+                if current_cursor > 0 {
+                    let position = current_cursor - 1;
+                    move_cursor(
+                        &mut text_field,
+                        &mut current_cursor,
+                        position,
+                        modifiers.shift,
+                    );
+                    need_render = true;
+                }
+            }
+            Key::ArrowRight => {
+                if current_cursor < CharPosition(text_field.text.chars().count()) {
+                    let position = current_cursor + 1;
+                    move_cursor(
+                        &mut text_field,
+                        &mut current_cursor,
+                        position,
+                        modifiers.shift,
+                    );
+                    need_render = true;
+                }
+            }
+            _ => {}
         }
     }
 
@@ -253,6 +344,16 @@ pub fn keyboard_input(
         commands.trigger_targets(RenderWidget::show_cursor(), entity);
 
         if text_field.text != text_change.new_text {
+            if !from_history {
+                history.record(
+                    &text_field.text,
+                    &text_change,
+                    old_cursor_position,
+                    selection_before,
+                    current_cursor,
+                );
+            }
+
             // Send the text change event with the new text
             let mut new_text = text_field.text.clone();
             text_change.apply(&mut new_text);
@@ -271,21 +372,100 @@ pub fn keyboard_input(
                 text_change.apply(&mut text_field.text);
             }
         }
+    } else if preedit_changed {
+        commands.trigger_targets(RenderWidget::show_cursor(), entity);
+    }
+}
+
+/// Move the cursor to `position`, extending the selection from the current cursor position if
+/// `select` is set
+fn move_cursor(
+    text_field: &mut EditableTextLine,
+    current_cursor: &mut CharPosition,
+    position: CharPosition,
+    select: bool,
+) {
+    if select {
+        if text_field.selection_start.is_none() {
+            text_field.selection_start = Some(*current_cursor);
+        }
+    } else {
+        text_field.selection_start = None;
+    }
+    *current_cursor = position;
+}
+
+/// The change made by typing `text` at the cursor, replacing the selection if any, and the cursor
+/// position after it. Clears the selection.
+fn typed_change(
+    text_field: &mut EditableTextLine,
+    current_cursor: CharPosition,
+    text: &str,
+) -> (TextChange, CharPosition) {
+    let mut chars = text.chars().collect::<Vec<_>>();
+    if let Some(allowed_chars) = &text_field.allowed_chars {
+        chars.retain(|c| allowed_chars.contains(c));
+    }
+    let typed = chars.iter().collect::<String>();
+
+    let change = if let Some((start, end)) = text_field.selection_range() {
+        (TextChange::new((start, end), typed), start + chars.len())
+    } else {
+        (
+            TextChange::insert_change(current_cursor, typed),
+            current_cursor + chars.len(),
+        )
+    };
+    text_field.selection_start = None; // clear selection if we write any text
+    change
+}
+
+/// Enable IME input in the primary window while an editable text line is focused, with the
+/// candidate box of the IME placed at its cursor
+pub fn update_ime(
+    q_focused: Query<&EditableTextInner, (With<EditableTextLine>, With<Focus>)>,
+    q_cursors: Query<(&ComputedNode, &GlobalTransform)>,
+    mut q_windows: Query<&mut Window, With<PrimaryWindow>>,
+    mut ime_enabled: Local<bool>,
+) {
+    let Ok(mut window) = q_windows.single_mut() else {
+        return;
+    };
+    let Ok(inner) = q_focused.single() else {
+        // Only disable the IME enabled here
+        if *ime_enabled {
+            window.ime_enabled = false;
+            *ime_enabled = false;
+        }
+        return;
+    };
+
+    if !window.ime_enabled {
+        window.ime_enabled = true;
+    }
+    *ime_enabled = true;
+    if let Ok((node, global_transform)) = q_cursors.get(inner.cursor) {
+        let bottom = global_transform.translation().truncate() + Vec2::Y * node.size().y / 2.0;
+        let position = bottom * node.inverse_scale_factor();
+        if window.ime_position != position {
+            window.ime_position = position;
+        }
     }
 }
 
 pub fn on_focus_lost(
     trigger: Trigger<LostFocus>,
     mut commands: Commands,
-    mut q_editable_texts: Query<&mut EditableTextLine>,
+    mut q_editable_texts: Query<(&mut EditableTextLine, &mut EditableTextInner)>,
 ) {
     let entity = trigger.target();
-    let Ok(mut text_field) = q_editable_texts.get_mut(entity) else {
+    let Ok((mut text_field, mut inner)) = q_editable_texts.get_mut(entity) else {
         return;
     };
 
     text_field.cursor_position = None;
     text_field.selection_start = None;
+    inner.ime_preedit.clear();
 
     info!("Focus lost from {:?}", entity);
 
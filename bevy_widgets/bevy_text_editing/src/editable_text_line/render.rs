@@ -17,8 +17,20 @@ pub fn render_system(
         // info!("Cursor position: {:?}", text_line.cursor_position);
         // info!("Selection range: {:?}", text_line.selection_range());
 
+        // Text being composed with an IME is shown at the cursor
+        let preedit_position = text_line
+            .cursor_position
+            .filter(|_| !inner.ime_preedit.is_empty());
+        let text_before_preedit = preedit_position
+            .map(|cursor_pos| text_line.text[..text_line.get_byte_position(cursor_pos)].to_string())
+            .unwrap_or_default();
+
         // Change text to stored in text line state
         text.0 = text_line.text.clone();
+        if preedit_position.is_some() {
+            text.0
+                .insert_str(text_before_preedit.len(), &inner.ime_preedit);
+        }
 
         // Render cursor
         if let Some(cursor_pos) = text_line.cursor_position {
@@ -28,6 +40,7 @@ pub fn render_system(
             cursor_fake_text.0 = text_line
                 .get_text_range((CharPosition(0), cursor_pos))
                 .unwrap_or_default();
+            cursor_fake_text.0.push_str(&inner.ime_preedit);
 
             if !q_cursors.contains(inner.cursor) {
                 commands.entity(inner.cursor).insert((
@@ -50,6 +63,25 @@ pub fn render_system(
                 .insert(Visibility::Hidden);
         }
 
+        // Render the underline of the text being composed
+        if preedit_position.is_some() {
+            if let Ok(mut fake_text_before_preedit) =
+                q_texts.get_mut(inner.fake_text_before_preedit)
+            {
+                fake_text_before_preedit.0 = text_before_preedit;
+            }
+            if let Ok(mut fake_preedit_text) = q_texts.get_mut(inner.fake_preedit_text) {
+                fake_preedit_text.0 = inner.ime_preedit.clone();
+            }
+            commands
+                .entity(inner.fake_preedit_text)
+                .insert(Visibility::Visible);
+        } else {
+            commands
+                .entity(inner.fake_preedit_text)
+                .insert(Visibility::Hidden);
+        }
+
         // Render selection
         if let Some((selection_start, selection_end)) = text_line.selection_range() {
             let Ok(mut fake_text_before_selection) =
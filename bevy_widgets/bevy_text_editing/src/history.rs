@@ -0,0 +1,391 @@
+//! Per-widget undo/redo history of the [`TextChange`]s made in an editable text.
+//!
+//! Editable text widgets record the changes made by typing, deleting, cutting and pasting in
+//! their [`TextEditHistory`], and undo and redo them with the
+//! [`TextEditKeymap`](crate::keymap::TextEditKeymap) bindings while focused, `Ctrl+Z` and
+//! `Ctrl+Shift+Z` or `Ctrl+Y` by default. Undoing and redoing emit [`TextChanged`](crate::TextChanged) like any
+//! other edit, so controlled widgets keep working.
+//!
+//! Widgets undoing their edits some other way have the [`HandlesUndo`] marker, which
+//! [`TextEditHistory`] requires, so that the global undo shortcuts leave them alone while focused.
+
+use std::collections::VecDeque;
+
+use bevy::prelude::*;
+
+use crate::{text_change::TextChange, CharPosition};
+
+/// Default number of steps a [`TextEditHistory`] can undo
+pub const DEFAULT_HISTORY_SIZE: usize = 100;
+
+/// How a step was made, to merge consecutive keystrokes into word-sized steps
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+enum StepKind {
+    /// A character typed at the cursor
+    Typing,
+    /// A character deleted before the cursor
+    Backspace,
+    /// A character deleted after the cursor
+    Delete,
+    /// Any other change, which is never merged
+    Other,
+}
+
+/// A step of the history: a change, the text it replaced, and the cursor around it
+#[derive(Clone, Debug)]
+struct HistoryStep {
+    change: TextChange,
+    replaced_text: String,
+    kind: StepKind,
+    cursor_before: Option<CharPosition>,
+    selection_before: Option<CharPosition>,
+    cursor_after: CharPosition,
+}
+
+impl HistoryStep {
+    /// The change undoing this step
+    fn inverse(&self) -> TextChange {
+        let start = self.change.range.0;
+        TextChange::new(
+            (start, start + self.change.new_text.chars().count()),
+            self.replaced_text.clone(),
+        )
+    }
+
+    /// Merge `next` into this step if it goes on typing or deleting the same word, returning
+    /// whether it was merged
+    fn merge(&mut self, next: &HistoryStep) -> bool {
+        if self.kind != next.kind || next.cursor_before != Some(self.cursor_after) {
+            return false;
+        }
+        match self.kind {
+            StepKind::Typing => {
+                let end = self.change.range.0 + self.change.new_text.chars().count();
+                let (Some(previous), Some(typed)) = (
+                    self.change.new_text.chars().last(),
+                    next.change.new_text.chars().next(),
+                ) else {
+                    return false;
+                };
+                if next.change.range != (end, end) || starts_word(previous, typed) {
+                    return false;
+                }
+                self.change.new_text.push_str(&next.change.new_text);
+            }
+            StepKind::Backspace => {
+                let (Some(previous), Some(deleted)) = (
+                    self.replaced_text.chars().next(),
+                    next.replaced_text.chars().last(),
+                ) else {
+                    return false;
+                };
+                if next.change.range.1 != self.change.range.0 || starts_word(previous, deleted) {
+                    return false;
+                }
+                self.change.range.0 = next.change.range.0;
+                self.replaced_text.insert_str(0, &next.replaced_text);
+            }
+            StepKind::Delete => {
+                let (Some(previous), Some(deleted)) = (
+                    self.replaced_text.chars().last(),
+                    next.replaced_text.chars().next(),
+                ) else {
+                    return false;
+                };
+                if next.change.range.0 != self.change.range.0 || starts_word(previous, deleted) {
+                    return false;
+                }
+                self.change.range.1 = self.change.range.1 + next.replaced_text.chars().count();
+                self.replaced_text.push_str(&next.replaced_text);
+            }
+            StepKind::Other => return false,
+        }
+        self.cursor_after = next.cursor_after;
+        true
+    }
+}
+
+/// Whether `next`, typed or deleted next to `previous`, starts another word
+fn starts_word(previous: char, next: char) -> bool {
+    previous.is_whitespace() && !next.is_whitespace()
+}
+
+/// A change to apply to undo or redo a step, with the cursor and selection to restore
+#[derive(Clone, Debug)]
+pub struct HistoryEdit {
+    /// The change to apply to the text
+    pub change: TextChange,
+    /// Cursor position after the change
+    pub cursor_position: CharPosition,
+    /// Selection start after the change
+    pub selection_start: Option<CharPosition>,
+}
+
+/// Marks the widgets undoing and redoing their own edits while focused, instead of the global
+/// undo shortcuts
+#[derive(Component, Clone, Copy, Debug, Default)]
+pub struct HandlesUndo;
+
+/// A bounded history of the changes made to the text of an editable text widget, which can be
+/// undone and redone
+#[derive(Component, Clone, Debug)]
+#[require(HandlesUndo)]
+pub struct TextEditHistory {
+    undo_steps: VecDeque<HistoryStep>,
+    redo_steps: Vec<HistoryStep>,
+    /// Text after the last change of the history. The history is cleared when the text no
+    /// longer matches it, as its changes can't be applied to text changed from outside.
+    text: Option<String>,
+    /// Whether the next change can be merged into the last step
+    can_merge: bool,
+    /// Maximum number of steps that can be undone
+    pub max_steps: usize,
+}
+
+impl Default for TextEditHistory {
+    fn default() -> Self {
+        Self::new(DEFAULT_HISTORY_SIZE)
+    }
+}
+
+impl TextEditHistory {
+    /// Create an empty history keeping up to `max_steps` steps
+    pub fn new(max_steps: usize) -> Self {
+        Self {
+            undo_steps: VecDeque::new(),
+            redo_steps: Vec::new(),
+            text: None,
+            can_merge: false,
+            max_steps,
+        }
+    }
+
+    /// Forget all the steps
+    pub fn clear(&mut self) {
+        self.undo_steps.clear();
+        self.redo_steps.clear();
+        self.text = None;
+    }
+
+    /// Whether there is a step to undo
+    pub fn can_undo(&self) -> bool {
+        !self.undo_steps.is_empty()
+    }
+
+    /// Whether there is a step to redo
+    pub fn can_redo(&self) -> bool {
+        !self.redo_steps.is_empty()
+    }
+
+    /// Record `change`, about to be applied to `text` by an edit moving the cursor from
+    /// `cursor_before` (with the selection starting at `selection_before`) to `cursor_after`.
+    pub fn record(
+        &mut self,
+        text: &str,
+        change: &TextChange,
+        cursor_before: Option<CharPosition>,
+        selection_before: Option<CharPosition>,
+        cursor_after: CharPosition,
+    ) {
+        if change.is_nop() {
+            return;
+        }
+        if self.text.as_deref() != Some(text) {
+            self.clear();
+        }
+
+        let replaced_text = change.inverse(text).new_text;
+        let has_selection = selection_before.is_some_and(|start| Some(start) != cursor_before);
+        let single_char = |text: &str| text.chars().count() == 1;
+        let kind = if has_selection {
+            StepKind::Other
+        } else if change.range.0 == change.range.1 && single_char(&change.new_text) {
+            StepKind::Typing
+        } else if change.new_text.is_empty() && single_char(&replaced_text) {
+            if cursor_before == Some(change.range.1) {
+                StepKind::Backspace
+            } else {
+                StepKind::Delete
+            }
+        } else {
+            StepKind::Other
+        };
+        let step = HistoryStep {
+            change: change.clone(),
+            replaced_text,
+            kind,
+            cursor_before,
+            selection_before,
+            cursor_after,
+        };
+
+        self.redo_steps.clear();
+        let merged = self.can_merge
+            && self
+                .undo_steps
+                .back_mut()
+                .is_some_and(|last| last.merge(&step));
+        if !merged {
+            self.undo_steps.push_back(step);
+            while self.undo_steps.len() > self.max_steps {
+                self.undo_steps.pop_front();
+            }
+        }
+        self.can_merge = true;
+
+        let mut new_text = text.to_string();
+        change.apply(&mut new_text);
+        self.text = Some(new_text);
+    }
+
+    /// Undo the last step made to `text`, returning the change to apply and where to put the
+    /// cursor and selection back
+    pub fn undo(&mut self, text: &str) -> Option<HistoryEdit> {
+        if self.text.as_deref() != Some(text) {
+            self.clear();
+            return None;
+        }
+        let step = self.undo_steps.pop_back()?;
+        let change = step.inverse();
+        let edit = HistoryEdit {
+            cursor_position: step.cursor_before.unwrap_or(change.range.1),
+            selection_start: step.selection_before,
+            change,
+        };
+        self.redo_steps.push(step);
+        self.apply(text, &edit.change);
+        Some(edit)
+    }
+
+    /// Redo the last undone step on `text`, returning the change to apply and where to put the
+    /// cursor
+    pub fn redo(&mut self, text: &str) -> Option<HistoryEdit> {
+        if self.text.as_deref() != Some(text) {
+            self.clear();
+            return None;
+        }
+        let step = self.redo_steps.pop()?;
+        let edit = HistoryEdit {
+            change: step.change.clone(),
+            cursor_position: step.cursor_after,
+            selection_start: None,
+        };
+        self.undo_steps.push_back(step);
+        self.apply(text, &edit.change);
+        Some(edit)
+    }
+
+    fn apply(&mut self, text: &str, change: &TextChange) {
+        let mut new_text = text.to_string();
+        change.apply(&mut new_text);
+        self.text = Some(new_text);
+        self.can_merge = false;
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    /// Type `typed` at the end of `text`, one character at a time
+    fn type_text(history: &mut TextEditHistory, text: &mut String, typed: &str) {
+        for c in typed.chars() {
+            let cursor = CharPosition(text.chars().count());
+            let change = TextChange::insert_change(cursor, c);
+            history.record(text, &change, Some(cursor), None, cursor + 1);
+            change.apply(text);
+        }
+    }
+
+    fn undo(history: &mut TextEditHistory, text: &mut String) -> Option<HistoryEdit> {
+        let edit = history.undo(text)?;
+        edit.change.apply(text);
+        Some(edit)
+    }
+
+    fn redo(history: &mut TextEditHistory, text: &mut String) -> Option<HistoryEdit> {
+        let edit = history.redo(text)?;
+        edit.change.apply(text);
+        Some(edit)
+    }
+
+    #[test]
+    fn merges_typing_into_words() {
+        let mut history = TextEditHistory::default();
+        let mut text = String::new();
+        type_text(&mut history, &mut text, "hello big world");
+
+        let edit = undo(&mut history, &mut text).unwrap();
+        assert_eq!(text, "hello big ");
+        assert_eq!(edit.cursor_position, CharPosition(10));
+        undo(&mut history, &mut text);
+        assert_eq!(text, "hello ");
+        undo(&mut history, &mut text);
+        assert_eq!(text, "");
+        assert!(undo(&mut history, &mut text).is_none());
+
+        let edit = redo(&mut history, &mut text).unwrap();
+        assert_eq!(text, "hello ");
+        assert_eq!(edit.cursor_position, CharPosition(6));
+        redo(&mut history, &mut text);
+        redo(&mut history, &mut text);
+        assert_eq!(text, "hello big world");
+        assert!(redo(&mut history, &mut text).is_none());
+    }
+
+    #[test]
+    fn merges_deletions_and_restores_the_selection() {
+        let mut history = TextEditHistory::default();
+        let mut text = String::from("one two");
+        // Backspace the "two" and the space before it, then the end of "one"
+        for cursor in (5..=7).rev().chain([4, 3]) {
+            let change =
+                TextChange::remove_change((CharPosition(cursor - 1), CharPosition(cursor)));
+            history.record(
+                &text,
+                &change,
+                Some(CharPosition(cursor)),
+                None,
+                CharPosition(cursor - 1),
+            );
+            change.apply(&mut text);
+        }
+        assert_eq!(text, "on");
+        undo(&mut history, &mut text);
+        assert_eq!(text, "one");
+        undo(&mut history, &mut text);
+        assert_eq!(text, "one two");
+
+        // Replace a selection
+        let change = TextChange::new((CharPosition(4), CharPosition(7)), "2");
+        history.record(
+            &text,
+            &change,
+            Some(CharPosition(7)),
+            Some(CharPosition(4)),
+            CharPosition(5),
+        );
+        change.apply(&mut text);
+        assert_eq!(text, "one 2");
+        let edit = undo(&mut history, &mut text).unwrap();
+        assert_eq!(text, "one two");
+        assert_eq!(edit.cursor_position, CharPosition(7));
+        assert_eq!(edit.selection_start, Some(CharPosition(4)));
+    }
+
+    #[test]
+    fn is_bounded_and_cleared_by_outside_changes() {
+        let mut history = TextEditHistory::new(2);
+        let mut text = String::new();
+        type_text(&mut history, &mut text, "a b c");
+        undo(&mut history, &mut text);
+        undo(&mut history, &mut text);
+        assert_eq!(text, "a ");
+        assert!(!history.can_undo());
+
+        redo(&mut history, &mut text);
+        text.push('!');
+        assert!(history.undo(&text).is_none());
+        assert!(!history.can_redo());
+    }
+}
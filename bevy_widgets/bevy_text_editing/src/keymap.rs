@@ -0,0 +1,374 @@
+//! Key bindings of the text editing actions.
+//!
+//! The [`TextEditKeymap`] resource maps each [`TextEditAction`] to the [`KeyChord`]s triggering
+//! it, with the usual shortcuts of the platform by default: `Cmd` on macOS and `Ctrl` elsewhere.
+//! It is a global editor setting, so the bindings can be changed in the settings file:
+//!
+//! ```toml
+//! [text_edit_keymap]
+//! redo = [{ key = "KeyY", control = true }]
+//! select_word = []
+//! ```
+
+use bevy::prelude::*;
+use bevy_editor_settings::{SettingKey, SettingsType};
+
+/// An action of the editable text widgets that can be bound to keys
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Hash, Reflect)]
+pub enum TextEditAction {
+    /// Copy the selected text to the clipboard
+    Copy,
+    /// Move the selected text to the clipboard
+    Cut,
+    /// Insert the text of the clipboard
+    Paste,
+    /// Select all the text
+    SelectAll,
+    /// Select the word at the cursor
+    SelectWord,
+    /// Undo the last change
+    Undo,
+    /// Redo the last undone change
+    Redo,
+    /// Move the cursor to the start of the previous word
+    WordLeft,
+    /// Move the cursor to the end of the next word
+    WordRight,
+    /// Delete up to the start of the previous word
+    DeleteWordBackward,
+    /// Delete up to the end of the next word
+    DeleteWordForward,
+    /// Move the cursor to the start of the line
+    LineStart,
+    /// Move the cursor to the end of the line
+    LineEnd,
+    /// Move the cursor to the start of the text
+    TextStart,
+    /// Move the cursor to the end of the text
+    TextEnd,
+}
+
+impl TextEditAction {
+    /// All the actions
+    pub const ALL: [TextEditAction; 15] = [
+        TextEditAction::Copy,
+        TextEditAction::Cut,
+        TextEditAction::Paste,
+        TextEditAction::SelectAll,
+        TextEditAction::SelectWord,
+        TextEditAction::Undo,
+        TextEditAction::Redo,
+        TextEditAction::WordLeft,
+        TextEditAction::WordRight,
+        TextEditAction::DeleteWordBackward,
+        TextEditAction::DeleteWordForward,
+        TextEditAction::LineStart,
+        TextEditAction::LineEnd,
+        TextEditAction::TextStart,
+        TextEditAction::TextEnd,
+    ];
+
+    /// Whether the action moves the cursor, so that its chords also match with Shift held to
+    /// extend the selection
+    pub fn is_motion(self) -> bool {
+        matches!(
+            self,
+            TextEditAction::WordLeft
+                | TextEditAction::WordRight
+                | TextEditAction::LineStart
+                | TextEditAction::LineEnd
+                | TextEditAction::TextStart
+                | TextEditAction::TextEnd
+        )
+    }
+}
+
+/// The modifier keys held, either the left or the right one
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq)]
+pub struct Modifiers {
+    /// `Ctrl` is held
+    pub control: bool,
+    /// `Shift` is held
+    pub shift: bool,
+    /// `Alt`, or `Option` on macOS, is held
+    pub alt: bool,
+    /// The `Super` key, `Cmd` on macOS or the Windows key, is held
+    pub super_key: bool,
+}
+
+impl Modifiers {
+    /// The modifier keys currently held
+    pub fn pressed(keys: &ButtonInput<KeyCode>) -> Self {
+        Self {
+            control: keys.any_pressed([KeyCode::ControlLeft, KeyCode::ControlRight]),
+            shift: keys.any_pressed([KeyCode::ShiftLeft, KeyCode::ShiftRight]),
+            alt: keys.any_pressed([KeyCode::AltLeft, KeyCode::AltRight]),
+            super_key: keys.any_pressed([KeyCode::SuperLeft, KeyCode::SuperRight]),
+        }
+    }
+
+    /// Whether the platform shortcut modifier, `Cmd` on macOS and `Ctrl` elsewhere, is held
+    pub fn primary(&self) -> bool {
+        if cfg!(target_os = "macos") {
+            self.super_key
+        } else {
+            self.control
+        }
+    }
+}
+
+/// A key pressed while holding modifier keys
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Reflect)]
+pub struct KeyChord {
+    /// The key pressed
+    pub key: KeyCode,
+    /// `Ctrl` is held
+    #[reflect(default)]
+    pub control: bool,
+    /// `Shift` is held
+    #[reflect(default)]
+    pub shift: bool,
+    /// `Alt`, or `Option` on macOS, is held
+    #[reflect(default)]
+    pub alt: bool,
+    /// The `Super` key, `Cmd` on macOS or the Windows key, is held
+    #[reflect(default)]
+    pub super_key: bool,
+}
+
+impl KeyChord {
+    /// The key pressed alone
+    pub fn new(key: KeyCode) -> Self {
+        Self {
+            key,
+            control: false,
+            shift: false,
+            alt: false,
+            super_key: false,
+        }
+    }
+
+    /// The key pressed with the platform shortcut modifier, `Cmd` on macOS and `Ctrl` elsewhere
+    pub fn primary(key: KeyCode) -> Self {
+        if cfg!(target_os = "macos") {
+            Self::new(key).with_super()
+        } else {
+            Self::new(key).with_control()
+        }
+    }
+
+    /// Also hold `Ctrl`
+    pub fn with_control(mut self) -> Self {
+        self.control = true;
+        self
+    }
+
+    /// Also hold `Shift`
+    pub fn with_shift(mut self) -> Self {
+        self.shift = true;
+        self
+    }
+
+    /// Also hold `Alt`
+    pub fn with_alt(mut self) -> Self {
+        self.alt = true;
+        self
+    }
+
+    /// Also hold the `Super` key
+    pub fn with_super(mut self) -> Self {
+        self.super_key = true;
+        self
+    }
+
+    /// Whether pressing `key` with `modifiers` held triggers the chord, holding Shift or not when
+    /// `ignore_shift` is set
+    pub fn matches(&self, key: KeyCode, modifiers: Modifiers, ignore_shift: bool) -> bool {
+        self.key == key
+            && self.control == modifiers.control
+            && self.alt == modifiers.alt
+            && self.super_key == modifiers.super_key
+            && (self.shift == modifiers.shift || (ignore_shift && !self.shift))
+    }
+}
+
+/// The key chords triggering each [`TextEditAction`], see the [module docs](self)
+#[derive(Resource, Reflect, Clone, Debug, PartialEq)]
+#[reflect(Resource, @SettingsType::Global, @SettingKey("text_edit_keymap"))]
+pub struct TextEditKeymap {
+    /// Chords of [`TextEditAction::Copy`]
+    pub copy: Vec<KeyChord>,
+    /// Chords of [`TextEditAction::Cut`]
+    pub cut: Vec<KeyChord>,
+    /// Chords of [`TextEditAction::Paste`]
+    pub paste: Vec<KeyChord>,
+    /// Chords of [`TextEditAction::SelectAll`]
+    pub select_all: Vec<KeyChord>,
+    /// Chords of [`TextEditAction::SelectWord`]
+    pub select_word: Vec<KeyChord>,
+    /// Chords of [`TextEditAction::Undo`]
+    pub undo: Vec<KeyChord>,
+    /// Chords of [`TextEditAction::Redo`]
+    pub redo: Vec<KeyChord>,
+    /// Chords of [`TextEditAction::WordLeft`]
+    pub word_left: Vec<KeyChord>,
+    /// Chords of [`TextEditAction::WordRight`]
+    pub word_right: Vec<KeyChord>,
+    /// Chords of [`TextEditAction::DeleteWordBackward`]
+    pub delete_word_backward: Vec<KeyChord>,
+    /// Chords of [`TextEditAction::DeleteWordForward`]
+    pub delete_word_forward: Vec<KeyChord>,
+    /// Chords of [`TextEditAction::LineStart`]
+    pub line_start: Vec<KeyChord>,
+    /// Chords of [`TextEditAction::LineEnd`]
+    pub line_end: Vec<KeyChord>,
+    /// Chords of [`TextEditAction::TextStart`]
+    pub text_start: Vec<KeyChord>,
+    /// Chords of [`TextEditAction::TextEnd`]
+    pub text_end: Vec<KeyChord>,
+}
+
+impl Default for TextEditKeymap {
+    fn default() -> Self {
+        let primary = KeyChord::primary;
+        let key = KeyChord::new;
+        if cfg!(target_os = "macos") {
+            Self {
+                copy: vec![primary(KeyCode::KeyC)],
+                cut: vec![primary(KeyCode::KeyX)],
+                paste: vec![primary(KeyCode::KeyV)],
+                select_all: vec![primary(KeyCode::KeyA)],
+                select_word: vec![primary(KeyCode::KeyD)],
+                undo: vec![primary(KeyCode::KeyZ)],
+                redo: vec![primary(KeyCode::KeyZ).with_shift()],
+                word_left: vec![key(KeyCode::ArrowLeft).with_alt()],
+                word_right: vec![key(KeyCode::ArrowRight).with_alt()],
+                delete_word_backward: vec![key(KeyCode::Backspace).with_alt()],
+                delete_word_forward: vec![key(KeyCode::Delete).with_alt()],
+                line_start: vec![key(KeyCode::Home), primary(KeyCode::ArrowLeft)],
+                line_end: vec![key(KeyCode::End), primary(KeyCode::ArrowRight)],
+                text_start: vec![primary(KeyCode::ArrowUp), primary(KeyCode::Home)],
+                text_end: vec![primary(KeyCode::ArrowDown), primary(KeyCode::End)],
+            }
+        } else {
+            Self {
+                copy: vec![primary(KeyCode::KeyC)],
+                cut: vec![primary(KeyCode::KeyX)],
+                paste: vec![primary(KeyCode::KeyV)],
+                select_all: vec![primary(KeyCode::KeyA)],
+                select_word: vec![primary(KeyCode::KeyD)],
+                undo: vec![primary(KeyCode::KeyZ)],
+                redo: vec![primary(KeyCode::KeyZ).with_shift(), primary(KeyCode::KeyY)],
+                word_left: vec![primary(KeyCode::ArrowLeft)],
+                word_right: vec![primary(KeyCode::ArrowRight)],
+                delete_word_backward: vec![primary(KeyCode::Backspace)],
+                delete_word_forward: vec![primary(KeyCode::Delete)],
+                line_start: vec![key(KeyCode::Home)],
+                line_end: vec![key(KeyCode::End)],
+                text_start: vec![primary(KeyCode::Home)],
+                text_end: vec![primary(KeyCode::End)],
+            }
+        }
+    }
+}
+
+impl TextEditKeymap {
+    /// The chords bound to `action`
+    pub fn chords(&self, action: TextEditAction) -> &[KeyChord] {
+        match action {
+            TextEditAction::Copy => &self.copy,
+            TextEditAction::Cut => &self.cut,
+            TextEditAction::Paste => &self.paste,
+            TextEditAction::SelectAll => &self.select_all,
+            TextEditAction::SelectWord => &self.select_word,
+            TextEditAction::Undo => &self.undo,
+            TextEditAction::Redo => &self.redo,
+            TextEditAction::WordLeft => &self.word_left,
+            TextEditAction::WordRight => &self.word_right,
+            TextEditAction::DeleteWordBackward => &self.delete_word_backward,
+            TextEditAction::DeleteWordForward => &self.delete_word_forward,
+            TextEditAction::LineStart => &self.line_start,
+            TextEditAction::LineEnd => &self.line_end,
+            TextEditAction::TextStart => &self.text_start,
+            TextEditAction::TextEnd => &self.text_end,
+        }
+    }
+
+    /// The action triggered by pressing `key` with `modifiers` held, if any
+    pub fn action(&self, key: KeyCode, modifiers: Modifiers) -> Option<TextEditAction> {
+        TextEditAction::ALL.into_iter().find(|action| {
+            self.chords(*action)
+                .iter()
+                .any(|chord| chord.matches(key, modifiers, action.is_motion()))
+        })
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn recognizes_modifiers_of_either_side() {
+        let mut keys = ButtonInput::<KeyCode>::default();
+        keys.press(KeyCode::ControlRight);
+        keys.press(KeyCode::ShiftLeft);
+        let modifiers = Modifiers::pressed(&keys);
+        assert_eq!(
+            modifiers,
+            Modifiers {
+                control: true,
+                shift: true,
+                ..default()
+            }
+        );
+
+        let chord = KeyChord::new(KeyCode::KeyZ).with_control().with_shift();
+        assert!(chord.matches(KeyCode::KeyZ, modifiers, false));
+        assert!(!chord.matches(KeyCode::KeyY, modifiers, false));
+        let modifiers = Modifiers {
+            alt: true,
+            ..modifiers
+        };
+        assert!(!chord.matches(KeyCode::KeyZ, modifiers, false));
+    }
+
+    #[test]
+    fn finds_the_action_of_a_key() {
+        let keymap = TextEditKeymap::default();
+        let primary = KeyChord::primary(KeyCode::KeyZ);
+        let modifiers = Modifiers {
+            control: primary.control,
+            super_key: primary.super_key,
+            ..default()
+        };
+        assert_eq!(
+            keymap.action(KeyCode::KeyZ, modifiers),
+            Some(TextEditAction::Undo)
+        );
+        let with_shift = Modifiers {
+            shift: true,
+            ..modifiers
+        };
+        assert_eq!(
+            keymap.action(KeyCode::KeyZ, with_shift),
+            Some(TextEditAction::Redo)
+        );
+        // Motions also extend the selection with Shift
+        assert_eq!(
+            keymap.action(KeyCode::Home, with_shift),
+            Some(TextEditAction::TextStart)
+        );
+        assert_eq!(keymap.action(KeyCode::KeyZ, Modifiers::default()), None);
+
+        let keymap = TextEditKeymap {
+            undo: vec![KeyChord::new(KeyCode::F1)],
+            ..keymap
+        };
+        assert_eq!(
+            keymap.action(KeyCode::F1, Modifiers::default()),
+            Some(TextEditAction::Undo)
+        );
+        assert_eq!(keymap.action(KeyCode::KeyZ, modifiers), None);
+    }
+}
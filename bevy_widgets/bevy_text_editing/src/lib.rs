@@ -6,12 +6,17 @@
 mod char_position;
 pub mod child_traversal;
 pub(crate) mod cursor;
+pub mod editable_text;
 pub mod editable_text_line;
+pub mod history;
+pub mod keymap;
 pub mod text_change;
+pub mod words;
 
 use bevy::prelude::*;
 pub use char_position::*;
 use child_traversal::CachedFirsChild;
+pub use editable_text::*;
 pub use editable_text_line::*;
 use text_change::TextChange;
 
@@ -76,6 +76,22 @@ impl TextChange {
         }
     }
 
+    /// Returns the change undoing this one, given the text it applies to.
+    ///
+    /// # Arguments
+    /// * `text` - The text before this change is applied.
+    pub fn inverse(&self, text: &str) -> Self {
+        let start_byte_pos = get_byte_position(text, self.range.0);
+        let end_byte_pos = get_byte_position(text, self.range.1);
+        Self {
+            range: (
+                self.range.0,
+                self.range.0 + CharPosition(self.new_text.chars().count()),
+            ),
+            new_text: text[start_byte_pos..end_byte_pos].to_string(),
+        }
+    }
+
     /// Returns true if the text change is not changing the text
     pub fn is_nop(&self) -> bool {
         self.range.0 == self.range.1 && self.new_text.is_empty()
@@ -109,4 +125,15 @@ mod tests {
         change.apply(&mut text);
         assert_eq!(text, "Hello, beautiful world!");
     }
+
+    #[test]
+    fn test_inverse() {
+        let mut text = String::from("Héllo, world!");
+        let change = TextChange::new((CharPosition(1), CharPosition(5)), "i");
+        let inverse = change.inverse(&text);
+        change.apply(&mut text);
+        assert_eq!(text, "Hi, world!");
+        inverse.apply(&mut text);
+        assert_eq!(text, "Héllo, world!");
+    }
 }
@@ -0,0 +1,98 @@
+//! Word boundaries of a text, following the Unicode word segmentation rules (UAX #29), used for
+//! moving the cursor and deleting word by word and for selecting words.
+
+use unicode_segmentation::UnicodeSegmentation;
+
+use crate::CharPosition;
+
+/// The segments of `text` between word boundaries, as character ranges, and whether each one is a
+/// word rather than whitespace or punctuation
+fn segments(text: &str) -> impl Iterator<Item = (CharPosition, CharPosition, bool)> + '_ {
+    let mut position = 0;
+    text.split_word_bounds().map(move |segment| {
+        let start = position;
+        position += segment.chars().count();
+        let is_word = segment.chars().any(char::is_alphanumeric);
+        (CharPosition(start), CharPosition(position), is_word)
+    })
+}
+
+/// The start of the word before `position`, or the start of the text
+pub fn previous_word_start(text: &str, position: CharPosition) -> CharPosition {
+    segments(text)
+        .filter(|(start, _, is_word)| *is_word && start.0 < position.0)
+        .last()
+        .map_or(CharPosition(0), |(start, _, _)| start)
+}
+
+/// The end of the word after `position`, or the end of the text
+pub fn next_word_end(text: &str, position: CharPosition) -> CharPosition {
+    segments(text)
+        .find(|(_, end, is_word)| *is_word && end.0 > position.0)
+        .map_or(CharPosition(text.chars().count()), |(_, end, _)| end)
+}
+
+/// The range of the segment at `position`: the word it is in, or the run of whitespace or the
+/// punctuation mark. At the end of the text, the last segment.
+pub fn word_at(text: &str, position: CharPosition) -> (CharPosition, CharPosition) {
+    let mut last = (CharPosition(0), CharPosition(0));
+    for (start, end, _) in segments(text) {
+        if position.0 < end.0 {
+            return (start, end);
+        }
+        last = (start, end);
+    }
+    last
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn jumps_over_words() {
+        let text = "Hello, wörld!  can't stop";
+        assert_eq!(
+            previous_word_start(text, CharPosition(25)),
+            CharPosition(21)
+        );
+        assert_eq!(
+            previous_word_start(text, CharPosition(21)),
+            CharPosition(15)
+        );
+        assert_eq!(previous_word_start(text, CharPosition(12)), CharPosition(7));
+        assert_eq!(previous_word_start(text, CharPosition(7)), CharPosition(0));
+        assert_eq!(previous_word_start(text, CharPosition(0)), CharPosition(0));
+
+        assert_eq!(next_word_end(text, CharPosition(0)), CharPosition(5));
+        assert_eq!(next_word_end(text, CharPosition(5)), CharPosition(12));
+        // "can't" is a single word
+        assert_eq!(next_word_end(text, CharPosition(13)), CharPosition(20));
+        assert_eq!(next_word_end(text, CharPosition(25)), CharPosition(25));
+    }
+
+    #[test]
+    fn finds_the_word_at_a_position() {
+        let text = "Hello, wörld!  bye";
+        assert_eq!(
+            word_at(text, CharPosition(2)),
+            (CharPosition(0), CharPosition(5))
+        );
+        assert_eq!(
+            word_at(text, CharPosition(9)),
+            (CharPosition(7), CharPosition(12))
+        );
+        assert_eq!(
+            word_at(text, CharPosition(13)),
+            (CharPosition(13), CharPosition(15))
+        );
+        assert_eq!(
+            word_at(text, CharPosition(18)),
+            (CharPosition(15), CharPosition(18))
+        );
+        assert_eq!(
+            word_at("", CharPosition(0)),
+            (CharPosition(0), CharPosition(0))
+        );
+    }
+}
@@ -0,0 +1,117 @@
+//! This example demonstrates a toolbar with a radio group bound to a state, a toggle bound to a
+//! resource and a plain button.
+
+use bevy::prelude::*;
+use bevy_editor_styles::{StylesPlugin, Theme};
+use bevy_toolbar::{binding::*, *};
+
+fn main() {
+    App::new()
+        .add_plugins(DefaultPlugins)
+        .add_plugins((
+            StylesPlugin,
+            ToolbarPlugin,
+            ToolbarStatePlugin::<GizmoMode>::default(),
+            ToolbarResourcePlugin::<ShowGrid>::default(),
+        ))
+        .init_state::<GizmoMode>()
+        .init_resource::<ShowGrid>()
+        .add_systems(Startup, setup)
+        .add_systems(Update, show_status)
+        .run();
+}
+
+#[derive(States, Clone, Copy, Debug, Default, PartialEq, Eq, Hash)]
+enum GizmoMode {
+    #[default]
+    Translate,
+    Rotate,
+    Scale,
+}
+
+#[derive(Resource, Clone, Default, PartialEq)]
+struct ShowGrid(bool);
+
+/// The text showing the state and resource
+#[derive(Component)]
+struct Status;
+
+fn setup(mut commands: Commands, theme: Res<Theme>) {
+    commands.spawn(Camera2d);
+
+    commands
+        .spawn(Node {
+            width: Val::Percent(100.0),
+            height: Val::Percent(100.0),
+            flex_direction: FlexDirection::Column,
+            row_gap: Val::Px(20.0),
+            align_items: AlignItems::Center,
+            justify_content: JustifyContent::Center,
+            ..default()
+        })
+        .with_children(|cmd| {
+            cmd.spawn((
+                Toolbar,
+                Node {
+                    padding: UiRect::all(Val::Px(4.0)),
+                    ..default()
+                },
+                theme.pane.header_background_color,
+                theme.general.border_radius,
+            ))
+            .with_children(|cmd| {
+                cmd.spawn(ToolbarGroup).with_children(|cmd| {
+                    for (mode, icon, tooltip) in [
+                        (GizmoMode::Translate, "T", "Translate"),
+                        (GizmoMode::Rotate, "R", "Rotate"),
+                        (GizmoMode::Scale, "S", "Scale"),
+                    ] {
+                        cmd.spawn((
+                            ToolbarButton::new(ToolbarIcon::text(icon)),
+                            StateRadio(mode),
+                            Tooltip::new(tooltip),
+                        ));
+                    }
+                });
+                cmd.spawn(ToolbarSeparator);
+                cmd.spawn((
+                    ToolbarButton::new(ToolbarIcon::text("Grid")),
+                    ResourceToggle::<ShowGrid>::new(|show| show.0, |show, on| show.0 = on),
+                    Tooltip::new("Show the grid"),
+                ));
+                cmd.spawn(ToolbarSeparator);
+                cmd.spawn((
+                    ToolbarButton::new(ToolbarIcon::text("Reset")),
+                    Tooltip::new("Reset the mode and the grid"),
+                ))
+                .observe(
+                    |_: Trigger<Pointer<Click>>,
+                     mut next_mode: ResMut<NextState<GizmoMode>>,
+                     mut show_grid: ResMut<ShowGrid>| {
+                        next_mode.set(GizmoMode::default());
+                        show_grid.0 = false;
+                    },
+                );
+            });
+
+            cmd.spawn((
+                Text::default(),
+                TextFont {
+                    font: theme.text.font.clone(),
+                    font_size: 14.0,
+                    ..default()
+                },
+                Status,
+            ));
+        });
+}
+
+fn show_status(
+    mode: Res<State<GizmoMode>>,
+    show_grid: Res<ShowGrid>,
+    mut q_status: Query<&mut Text, With<Status>>,
+) {
+    for mut text in q_status.iter_mut() {
+        text.0 = format!("Mode: {:?}, grid shown: {}", mode.get(), show_grid.0);
+    }
+}
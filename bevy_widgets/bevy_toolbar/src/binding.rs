@@ -0,0 +1,292 @@
+//! Toggles and radio buttons bound to a resource or a state.
+//!
+//! The bound widgets are controlled: they show the value of the resource or state, and clicking
+//! them changes it. Each resource type needs a [`ToolbarResourcePlugin`], and each state type a
+//! [`ToolbarStatePlugin`].
+
+use bevy::{prelude::*, state::state::FreelyMutableState};
+
+use crate::{RadioSelected, Toggled, ToolbarPlugin, ToolbarRadio, ToolbarToggle};
+
+/// Plugin for the toolbar widgets bound to the resource `R`
+pub struct ToolbarResourcePlugin<R: Resource + Clone + PartialEq> {
+    _marker: std::marker::PhantomData<R>,
+}
+
+impl<R: Resource + Clone + PartialEq> Default for ToolbarResourcePlugin<R> {
+    fn default() -> Self {
+        Self {
+            _marker: std::marker::PhantomData,
+        }
+    }
+}
+
+impl<R: Resource + Clone + PartialEq> Plugin for ToolbarResourcePlugin<R> {
+    fn build(&self, app: &mut App) {
+        if !app.is_plugin_added::<ToolbarPlugin>() {
+            app.add_plugins(ToolbarPlugin);
+        }
+
+        app.add_systems(
+            Update,
+            (sync_resource_toggles::<R>, sync_resource_radios::<R>),
+        );
+
+        app.add_observer(on_resource_toggled::<R>)
+            .add_observer(on_resource_radio_selected::<R>);
+    }
+}
+
+/// Plugin for the toolbar widgets bound to the state `S`
+pub struct ToolbarStatePlugin<S: FreelyMutableState> {
+    _marker: std::marker::PhantomData<S>,
+}
+
+impl<S: FreelyMutableState> Default for ToolbarStatePlugin<S> {
+    fn default() -> Self {
+        Self {
+            _marker: std::marker::PhantomData,
+        }
+    }
+}
+
+impl<S: FreelyMutableState> Plugin for ToolbarStatePlugin<S> {
+    fn build(&self, app: &mut App) {
+        if !app.is_plugin_added::<ToolbarPlugin>() {
+            app.add_plugins(ToolbarPlugin);
+        }
+
+        app.add_systems(Update, (sync_state_toggles::<S>, sync_state_radios::<S>));
+
+        app.add_observer(on_state_toggled::<S>)
+            .add_observer(on_state_radio_selected::<S>);
+    }
+}
+
+/// A [`ToolbarToggle`] showing and switching a boolean of the resource `R`
+#[derive(Component)]
+#[require(ToolbarToggle = ToolbarToggle::new(false).with_controlled(true))]
+pub struct ResourceToggle<R: Resource> {
+    get: fn(&R) -> bool,
+    set: fn(&mut R, bool),
+}
+
+impl<R: Resource> ResourceToggle<R> {
+    /// Create a new toggle reading the boolean with `get` and writing it with `set`
+    pub fn new(get: fn(&R) -> bool, set: fn(&mut R, bool)) -> Self {
+        Self { get, set }
+    }
+}
+
+/// A [`ToolbarRadio`] selected while the resource `R` equals its value, setting it when clicked
+#[derive(Component)]
+#[require(ToolbarRadio = ToolbarRadio::new(false).with_controlled(true))]
+pub struct ResourceRadio<R: Resource + Clone + PartialEq>(pub R);
+
+/// A [`ToolbarToggle`] on while the state `S` is `on`, switching it between `on` and `off`
+#[derive(Component)]
+#[require(ToolbarToggle = ToolbarToggle::new(false).with_controlled(true))]
+pub struct StateToggle<S: FreelyMutableState> {
+    /// The state when the toggle is on
+    pub on: S,
+    /// The state set when the toggle is switched off
+    pub off: S,
+}
+
+/// A [`ToolbarRadio`] selected while the state `S` equals its value, setting it when clicked
+#[derive(Component)]
+#[require(ToolbarRadio = ToolbarRadio::new(false).with_controlled(true))]
+pub struct StateRadio<S: FreelyMutableState>(pub S);
+
+fn sync_resource_toggles<R: Resource>(
+    resource: Option<Res<R>>,
+    mut q_toggles: Query<(&ResourceToggle<R>, &mut ToolbarToggle)>,
+) {
+    let Some(resource) = resource else {
+        return;
+    };
+    for (binding, mut toggle) in q_toggles.iter_mut() {
+        let on = (binding.get)(&resource);
+        if toggle.on != on {
+            toggle.on = on;
+        }
+    }
+}
+
+fn sync_resource_radios<R: Resource + Clone + PartialEq>(
+    resource: Option<Res<R>>,
+    mut q_radios: Query<(&ResourceRadio<R>, &mut ToolbarRadio)>,
+) {
+    let Some(resource) = resource else {
+        return;
+    };
+    for (binding, mut radio) in q_radios.iter_mut() {
+        let selected = binding.0 == *resource;
+        if radio.selected != selected {
+            radio.selected = selected;
+        }
+    }
+}
+
+fn on_resource_toggled<R: Resource>(
+    trigger: Trigger<Toggled>,
+    resource: Option<ResMut<R>>,
+    q_toggles: Query<&ResourceToggle<R>>,
+) {
+    let Ok(binding) = q_toggles.get(trigger.target()) else {
+        return;
+    };
+    if let Some(mut resource) = resource {
+        (binding.set)(&mut resource, trigger.event().0);
+    }
+}
+
+fn on_resource_radio_selected<R: Resource + Clone + PartialEq>(
+    trigger: Trigger<RadioSelected>,
+    resource: Option<ResMut<R>>,
+    q_radios: Query<&ResourceRadio<R>>,
+) {
+    // Only handled at the radio button, not while propagating to its ancestors
+    if trigger.target() != trigger.event().radio {
+        return;
+    }
+    let Ok(binding) = q_radios.get(trigger.target()) else {
+        return;
+    };
+    if let Some(mut resource) = resource {
+        resource.set_if_neq(binding.0.clone());
+    }
+}
+
+fn sync_state_toggles<S: FreelyMutableState>(
+    state: Option<Res<State<S>>>,
+    mut q_toggles: Query<(&StateToggle<S>, &mut ToolbarToggle)>,
+) {
+    let Some(state) = state else {
+        return;
+    };
+    for (binding, mut toggle) in q_toggles.iter_mut() {
+        let on = binding.on == *state.get();
+        if toggle.on != on {
+            toggle.on = on;
+        }
+    }
+}
+
+fn sync_state_radios<S: FreelyMutableState>(
+    state: Option<Res<State<S>>>,
+    mut q_radios: Query<(&StateRadio<S>, &mut ToolbarRadio)>,
+) {
+    let Some(state) = state else {
+        return;
+    };
+    for (binding, mut radio) in q_radios.iter_mut() {
+        let selected = binding.0 == *state.get();
+        if radio.selected != selected {
+            radio.selected = selected;
+        }
+    }
+}
+
+fn on_state_toggled<S: FreelyMutableState>(
+    trigger: Trigger<Toggled>,
+    next_state: Option<ResMut<NextState<S>>>,
+    q_toggles: Query<&StateToggle<S>>,
+) {
+    let Ok(binding) = q_toggles.get(trigger.target()) else {
+        return;
+    };
+    if let Some(mut next_state) = next_state {
+        let state = if trigger.event().0 {
+            &binding.on
+        } else {
+            &binding.off
+        };
+        next_state.set(state.clone());
+    }
+}
+
+fn on_state_radio_selected<S: FreelyMutableState>(
+    trigger: Trigger<RadioSelected>,
+    next_state: Option<ResMut<NextState<S>>>,
+    q_radios: Query<&StateRadio<S>>,
+) {
+    // Only handled at the radio button, not while propagating to its ancestors
+    if trigger.target() != trigger.event().radio {
+        return;
+    }
+    let Ok(binding) = q_radios.get(trigger.target()) else {
+        return;
+    };
+    if let Some(mut next_state) = next_state {
+        next_state.set(binding.0.clone());
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::{on_button_click, tests::click, ToolbarGroup};
+
+    #[derive(Resource, Clone, PartialEq, Debug)]
+    struct ShowGrid(bool);
+
+    #[derive(Resource, Clone, Copy, PartialEq, Debug)]
+    enum Mode {
+        Move,
+        Rotate,
+    }
+
+    #[test]
+    fn resource_toggles_follow_and_write_the_resource() {
+        let mut world = World::new();
+        world.add_observer(on_button_click);
+        world.add_observer(on_resource_toggled::<ShowGrid>);
+        world.insert_resource(ShowGrid(true));
+        let toggle = world
+            .spawn(ResourceToggle::<ShowGrid>::new(
+                |show| show.0,
+                |show, on| show.0 = on,
+            ))
+            .id();
+        let sync = |world: &mut World| {
+            world
+                .run_system_cached(sync_resource_toggles::<ShowGrid>)
+                .unwrap();
+            world.get::<ToolbarToggle>(toggle).unwrap().on
+        };
+
+        assert!(sync(&mut world));
+        world.resource_mut::<ShowGrid>().0 = false;
+        assert!(!sync(&mut world));
+
+        click(&mut world, toggle);
+        assert_eq!(*world.resource::<ShowGrid>(), ShowGrid(true));
+        assert!(sync(&mut world));
+    }
+
+    #[test]
+    fn resource_radios_follow_and_write_the_resource() {
+        let mut world = World::new();
+        world.add_observer(on_button_click);
+        world.add_observer(on_resource_radio_selected::<Mode>);
+        world.insert_resource(Mode::Move);
+        let group = world.spawn(ToolbarGroup).id();
+        let radios = [Mode::Move, Mode::Rotate]
+            .map(|mode| world.spawn((ResourceRadio(mode), ChildOf(group))).id());
+        let sync = |world: &mut World| {
+            world
+                .run_system_cached(sync_resource_radios::<Mode>)
+                .unwrap();
+            radios.map(|radio| world.get::<ToolbarRadio>(radio).unwrap().selected)
+        };
+
+        assert_eq!(sync(&mut world), [true, false]);
+        *world.resource_mut::<Mode>() = Mode::Rotate;
+        assert_eq!(sync(&mut world), [false, true]);
+
+        click(&mut world, radios[0]);
+        assert_eq!(*world.resource::<Mode>(), Mode::Move);
+        assert_eq!(sync(&mut world), [true, false]);
+    }
+}
@@ -2,19 +2,338 @@
 //!
 //! Toolbars are a common UI element in many applications, providing quick access to frequently used commands,
 //! and typically display small icons with on-hover tooltips.
+//!
+//! A [`Toolbar`] is a row of [`ToolbarButton`]s, [`ToolbarSeparator`]s and [`ToolbarGroup`]s,
+//! styled by the [`Theme`](bevy_editor_styles::Theme), that can be placed anywhere, like in the
+//! header of a pane. Buttons run actions from a [`Pointer<Click>`] observer, and show the
+//! [`Tooltip`] added to them. A [`ToolbarToggle`] turns a button into a toggle, and a
+//! [`ToolbarRadio`] into a radio button, exclusive with the other radio buttons of its parent.
+//!
+//! Toggles and radio buttons can be bound to a resource or a state with the components of the
+//! [`binding`] module, so they follow it and change it when clicked:
+//!
+//! ```
+//! # use bevy::prelude::*;
+//! # use bevy_toolbar::{binding::*, *};
+//! #[derive(Resource, Clone, PartialEq)]
+//! struct ShowGrid(bool);
+//!
+//! # fn spawn(mut commands: Commands) {
+//! commands.spawn(Toolbar).with_child((
+//!     ToolbarButton::new(ToolbarIcon::text("Grid")),
+//!     ResourceToggle::<ShowGrid>::new(|show| show.0, |show, on| show.0 = on),
+//!     Tooltip::new("Show the grid"),
+//! ));
+//! # }
+//! ```
+
+pub mod binding;
+mod ui;
+
+use std::borrow::Cow;
+
+use bevy::prelude::*;
+use bevy_tooltips::TooltipPlugin;
+
+pub use bevy_tooltips::Tooltip;
+
+/// Plugin for the toolbar widgets
+pub struct ToolbarPlugin;
+
+impl Plugin for ToolbarPlugin {
+    fn build(&self, app: &mut App) {
+        if !app.is_plugin_added::<TooltipPlugin>() {
+            app.add_plugins(TooltipPlugin);
+        }
+
+        app.add_event::<Toggled>();
+        app.add_event::<RadioSelected>();
+
+        app.add_systems(
+            PreUpdate,
+            (
+                ui::spawn_toolbars,
+                ui::spawn_groups,
+                ui::spawn_buttons,
+                ui::spawn_separators,
+            ),
+        );
+        app.add_systems(PostUpdate, ui::update_button_colors);
+
+        app.add_observer(ui::on_button_over)
+            .add_observer(ui::on_button_out)
+            .add_observer(on_button_click);
+    }
+}
+
+/// A row of toolbar widgets, its children
+#[derive(Component, Default)]
+#[require(Node)]
+pub struct Toolbar;
+
+/// A row of toolbar widgets without space between them, like the radio buttons of a choice
+#[derive(Component, Default)]
+#[require(Node)]
+pub struct ToolbarGroup;
+
+/// A vertical line between toolbar widgets
+#[derive(Component, Default)]
+#[require(Node)]
+pub struct ToolbarSeparator;
+
+/// What is shown in a [`ToolbarButton`]
+#[derive(Clone, Debug)]
+pub enum ToolbarIcon {
+    /// An image, tinted with the color of the text
+    Image(Handle<Image>),
+    /// A short text, like a label or a symbol
+    Text(Cow<'static, str>),
+}
+
+impl ToolbarIcon {
+    /// A text icon
+    pub fn text(text: impl Into<Cow<'static, str>>) -> Self {
+        Self::Text(text.into())
+    }
+}
+
+/// A button of a toolbar.
+/// Clicks can be observed with [`Pointer<Click>`], and the tooltip is a [`Tooltip`] component.
+#[derive(Component, Clone, Debug)]
+#[require(Node)]
+pub struct ToolbarButton {
+    /// The icon, which is read when the button is spawned
+    pub icon: ToolbarIcon,
+    /// Whether the pointer is over the button
+    hovered: bool,
+}
+
+impl ToolbarButton {
+    /// Create a new button showing `icon`
+    pub fn new(icon: ToolbarIcon) -> Self {
+        Self {
+            icon,
+            hovered: false,
+        }
+    }
+}
+
+/// Makes a [`ToolbarButton`] a toggle, switched on and off by clicks
+#[derive(Component, Clone, Debug, Default)]
+pub struct ToolbarToggle {
+    /// Whether the toggle is on
+    pub on: bool,
+    /// If true, this toggle will not switch by itself when clicked
+    /// and will require an external update to change.
+    pub controlled: bool,
+}
+
+impl ToolbarToggle {
+    /// Create a new toggle, on or off
+    pub fn new(on: bool) -> Self {
+        Self {
+            on,
+            controlled: false,
+        }
+    }
+
+    /// Make the toggle controlled, see [`ToolbarToggle::controlled`]
+    pub fn with_controlled(mut self, controlled: bool) -> Self {
+        self.controlled = controlled;
+        self
+    }
+}
 
-/// an add function that adds two numbers
-pub fn add(left: u64, right: u64) -> u64 {
-    left + right
+/// Makes a [`ToolbarButton`] a radio button. Selecting it unselects the other radio buttons with
+/// the same parent, usually a [`ToolbarGroup`].
+#[derive(Component, Clone, Debug, Default)]
+pub struct ToolbarRadio {
+    /// Whether the radio button is selected
+    pub selected: bool,
+    /// If true, this radio button will not be selected by itself when clicked
+    /// and will require an external update to change.
+    pub controlled: bool,
+}
+
+impl ToolbarRadio {
+    /// Create a new radio button, selected or not
+    pub fn new(selected: bool) -> Self {
+        Self {
+            selected,
+            controlled: false,
+        }
+    }
+
+    /// Make the radio button controlled, see [`ToolbarRadio::controlled`]
+    pub fn with_controlled(mut self, controlled: bool) -> Self {
+        self.controlled = controlled;
+        self
+    }
+}
+
+/// Event emitted on a [`ToolbarToggle`] when it is clicked, with the state it switches to
+#[derive(Event, Clone, Copy, Debug, PartialEq, Eq)]
+pub struct Toggled(pub bool);
+
+/// Event emitted on a [`ToolbarRadio`] when it is clicked, propagated to its ancestors
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub struct RadioSelected {
+    /// The radio button clicked
+    pub radio: Entity,
+}
+
+impl Event for RadioSelected {
+    type Traversal = &'static ChildOf;
+
+    const AUTO_PROPAGATE: bool = true;
+}
+
+fn on_button_click(
+    trigger: Trigger<Pointer<Click>>,
+    mut commands: Commands,
+    q_parents: Query<&ChildOf>,
+    q_children: Query<&Children>,
+    mut q_toggles: Query<&mut ToolbarToggle>,
+    mut q_radios: Query<&mut ToolbarRadio>,
+) {
+    if trigger.event().button != PointerButton::Primary {
+        return;
+    }
+    let entity = trigger.target();
+
+    if let Ok(mut toggle) = q_toggles.get_mut(entity) {
+        let on = !toggle.on;
+        commands.trigger_targets(Toggled(on), entity);
+        if !toggle.controlled {
+            toggle.on = on;
+        }
+    }
+
+    if let Ok(radio) = q_radios.get(entity) {
+        commands.trigger_targets(RadioSelected { radio: entity }, entity);
+        if radio.controlled {
+            return;
+        }
+        let siblings = q_parents
+            .get(entity)
+            .ok()
+            .and_then(|child_of| q_children.get(child_of.parent()).ok());
+        for sibling in siblings.into_iter().flatten() {
+            if let Ok(mut radio) = q_radios.get_mut(*sibling) {
+                let selected = *sibling == entity;
+                if radio.selected != selected {
+                    radio.selected = selected;
+                }
+            }
+        }
+    }
 }
 
 #[cfg(test)]
 mod tests {
+    use std::time::Duration;
+
+    use bevy::{
+        picking::{
+            backend::HitData,
+            pointer::{Location, PointerId},
+        },
+        render::camera::{ManualTextureViewHandle, NormalizedRenderTarget},
+    };
+
     use super::*;
 
+    /// Click `entity` with the primary mouse button
+    pub(crate) fn click(world: &mut World, entity: Entity) {
+        let location = Location {
+            target: NormalizedRenderTarget::TextureView(ManualTextureViewHandle(0)),
+            position: Vec2::ZERO,
+        };
+        let click = Click {
+            button: PointerButton::Primary,
+            hit: HitData::new(Entity::PLACEHOLDER, 0.0, None, None),
+            duration: Duration::ZERO,
+        };
+        world.trigger_targets(
+            Pointer::new(PointerId::Mouse, location, entity, click),
+            entity,
+        );
+        world.flush();
+    }
+
+    fn selected(world: &World, radios: &[Entity]) -> Vec<bool> {
+        radios
+            .iter()
+            .map(|radio| world.get::<ToolbarRadio>(*radio).unwrap().selected)
+            .collect()
+    }
+
     #[test]
-    fn it_works() {
-        let result = add(2, 2);
-        assert_eq!(result, 4);
+    fn clicking_a_radio_unselects_its_siblings() {
+        let mut world = World::new();
+        world.add_observer(on_button_click);
+        let group = world.spawn(ToolbarGroup).id();
+        let radios = [true, false, false].map(|selected| {
+            world
+                .spawn((ToolbarRadio::new(selected), ChildOf(group)))
+                .id()
+        });
+        let other_group = world.spawn(ToolbarGroup).id();
+        let other_radio = world
+            .spawn((ToolbarRadio::new(true), ChildOf(other_group)))
+            .id();
+
+        click(&mut world, radios[1]);
+        assert_eq!(selected(&world, &radios), [false, true, false]);
+        assert!(world.get::<ToolbarRadio>(other_radio).unwrap().selected);
+
+        // Clicking the selected radio keeps it selected
+        click(&mut world, radios[1]);
+        assert_eq!(selected(&world, &radios), [false, true, false]);
+    }
+
+    #[test]
+    fn controlled_radios_only_emit_the_selection() {
+        let mut world = World::new();
+        world.add_observer(on_button_click);
+        world.init_resource::<Events<RadioSelected>>();
+        world.add_observer(
+            |trigger: Trigger<RadioSelected>, mut events: EventWriter<RadioSelected>| {
+                events.write(*trigger.event());
+            },
+        );
+        let group = world.spawn(ToolbarGroup).id();
+        let radios = [true, false].map(|selected| {
+            world
+                .spawn((
+                    ToolbarRadio::new(selected).with_controlled(true),
+                    ChildOf(group),
+                ))
+                .id()
+        });
+
+        click(&mut world, radios[1]);
+        assert_eq!(selected(&world, &radios), [true, false]);
+        let selections = world
+            .resource_mut::<Events<RadioSelected>>()
+            .drain()
+            .collect::<Vec<_>>();
+        // Observed on the radio button and propagated to its group
+        assert_eq!(selections, [RadioSelected { radio: radios[1] }; 2]);
+    }
+
+    #[test]
+    fn clicking_a_toggle_switches_it() {
+        let mut world = World::new();
+        world.add_observer(on_button_click);
+        let toggle = world.spawn(ToolbarToggle::new(false)).id();
+        let controlled = world
+            .spawn(ToolbarToggle::new(false).with_controlled(true))
+            .id();
+
+        click(&mut world, toggle);
+        click(&mut world, controlled);
+        assert!(world.get::<ToolbarToggle>(toggle).unwrap().on);
+        assert!(!world.get::<ToolbarToggle>(controlled).unwrap().on);
     }
 }
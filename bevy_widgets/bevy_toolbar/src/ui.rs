@@ -0,0 +1,163 @@
+use bevy::prelude::*;
+use bevy_editor_styles::Theme;
+
+use crate::{
+    Toolbar, ToolbarButton, ToolbarGroup, ToolbarIcon, ToolbarRadio, ToolbarSeparator,
+    ToolbarToggle,
+};
+
+/// Height of the buttons, fitting in the header of a pane
+const BUTTON_SIZE: f32 = 20.;
+
+/// Size of the image icons
+const ICON_SIZE: f32 = 14.;
+
+/// The icon of a toolbar button, tinted with the state of the button
+#[derive(Component)]
+pub(crate) struct ToolbarIconNode;
+
+pub(crate) fn spawn_toolbars(mut q_toolbars: Query<&mut Node, Added<Toolbar>>) {
+    for mut node in q_toolbars.iter_mut() {
+        node.flex_direction = FlexDirection::Row;
+        node.align_items = AlignItems::Center;
+        node.column_gap = Val::Px(2.);
+    }
+}
+
+pub(crate) fn spawn_groups(mut q_groups: Query<&mut Node, Added<ToolbarGroup>>) {
+    for mut node in q_groups.iter_mut() {
+        node.flex_direction = FlexDirection::Row;
+        node.align_items = AlignItems::Center;
+    }
+}
+
+pub(crate) fn spawn_separators(
+    mut commands: Commands,
+    theme: Res<Theme>,
+    mut q_separators: Query<(Entity, &mut Node), Added<ToolbarSeparator>>,
+) {
+    for (entity, mut node) in q_separators.iter_mut() {
+        node.width = Val::Px(1.);
+        node.height = Val::Px(BUTTON_SIZE - 4.);
+        node.margin = UiRect::horizontal(Val::Px(4.));
+        commands
+            .entity(entity)
+            .insert(BackgroundColor(theme.text.low_priority));
+    }
+}
+
+pub(crate) fn spawn_buttons(
+    mut commands: Commands,
+    theme: Res<Theme>,
+    mut q_buttons: Query<(Entity, &ToolbarButton, &mut Node), Added<ToolbarButton>>,
+) {
+    for (entity, button, mut node) in q_buttons.iter_mut() {
+        node.min_width = Val::Px(BUTTON_SIZE);
+        node.height = Val::Px(BUTTON_SIZE);
+        node.padding = UiRect::horizontal(Val::Px(4.));
+        node.align_items = AlignItems::Center;
+        node.justify_content = JustifyContent::Center;
+
+        commands
+            .entity(entity)
+            .insert((theme.button.background_color, theme.button.border_radius));
+
+        let icon = match &button.icon {
+            ToolbarIcon::Image(image) => commands
+                .spawn((
+                    ImageNode::new(image.clone()),
+                    Node {
+                        width: Val::Px(ICON_SIZE),
+                        height: Val::Px(ICON_SIZE),
+                        ..default()
+                    },
+                ))
+                .id(),
+            ToolbarIcon::Text(text) => commands
+                .spawn((
+                    Text::new(text.clone()),
+                    TextFont {
+                        font: theme.text.font.clone(),
+                        font_size: 12.,
+                        ..default()
+                    },
+                ))
+                .id(),
+        };
+        commands
+            .entity(icon)
+            .insert((ToolbarIconNode, Pickable::IGNORE, ChildOf(entity)));
+    }
+}
+
+pub(crate) fn on_button_over(
+    trigger: Trigger<Pointer<Over>>,
+    mut q_buttons: Query<&mut ToolbarButton>,
+) {
+    if let Ok(mut button) = q_buttons.get_mut(trigger.target()) {
+        button.hovered = true;
+    }
+}
+
+pub(crate) fn on_button_out(
+    trigger: Trigger<Pointer<Out>>,
+    mut q_buttons: Query<&mut ToolbarButton>,
+) {
+    if let Ok(mut button) = q_buttons.get_mut(trigger.target()) {
+        button.hovered = false;
+    }
+}
+
+/// Shows whether the buttons are hovered, and whether the toggles and radio buttons are on
+#[allow(clippy::type_complexity)]
+pub(crate) fn update_button_colors(
+    theme: Res<Theme>,
+    mut q_buttons: Query<
+        (
+            &ToolbarButton,
+            Option<&ToolbarToggle>,
+            Option<&ToolbarRadio>,
+            &mut BackgroundColor,
+            &Children,
+        ),
+        Or<(
+            Changed<ToolbarButton>,
+            Changed<ToolbarToggle>,
+            Changed<ToolbarRadio>,
+            Added<Children>,
+        )>,
+    >,
+    mut q_icons: Query<(Option<&mut TextColor>, Option<&mut ImageNode>), With<ToolbarIconNode>>,
+) {
+    for (button, toggle, radio, mut background, children) in q_buttons.iter_mut() {
+        let checkable = toggle.is_some() || radio.is_some();
+        let on =
+            toggle.is_some_and(|toggle| toggle.on) || radio.is_some_and(|radio| radio.selected);
+
+        background.0 = if on {
+            theme.button.hover_color.with_alpha(0.35)
+        } else if button.hovered {
+            theme.context_menu.hover_color.0
+        } else {
+            theme.button.background_color.0
+        };
+
+        // Like the toggles of the viewport headers, checkable buttons are dimmed when off
+        let icon_color = if checkable && !on {
+            theme.text.low_priority
+        } else {
+            theme.text.text_color
+        };
+        for child in children {
+            let Ok((text_color, image)) = q_icons.get_mut(*child) else {
+                continue;
+            };
+            if let Some(mut text_color) = text_color {
+                text_color.0 = icon_color;
+            }
+            if let Some(mut image) = image {
+                image.color = icon_color;
+            }
+        }
+    }
+}
@@ -0,0 +1,62 @@
+//! This example demonstrates tooltips on buttons, shown on hover or keyboard focus.
+
+use std::time::Duration;
+
+use bevy::prelude::*;
+use bevy_editor_styles::{StylesPlugin, Theme};
+use bevy_focus::{FocusPlugin, Focusable};
+use bevy_tooltips::{Tooltip, TooltipPlugin};
+
+fn main() {
+    App::new()
+        .add_plugins(DefaultPlugins)
+        .add_plugins((StylesPlugin, FocusPlugin, TooltipPlugin))
+        .add_systems(Startup, setup)
+        .run();
+}
+
+fn setup(mut commands: Commands, theme: Res<Theme>) {
+    commands.spawn(Camera2d);
+
+    let buttons = [
+        Tooltip::new("Move the selected entities").with_low_priority("  G"),
+        Tooltip::new("Delete the selected entities\n")
+            .with_high_priority("This cannot be undone")
+            .with_delay(Duration::from_secs(1)),
+        Tooltip::new("Shown while focused, click to focus").with_focus_trigger(true),
+    ];
+
+    commands
+        .spawn(Node {
+            width: Val::Percent(100.0),
+            height: Val::Percent(100.0),
+            // Put the buttons in a corner, to see the tooltips staying within the window
+            align_items: AlignItems::End,
+            justify_content: JustifyContent::End,
+            column_gap: Val::Px(8.0),
+            padding: UiRect::all(Val::Px(8.0)),
+            ..default()
+        })
+        .with_children(|cmd| {
+            for (index, tooltip) in buttons.into_iter().enumerate() {
+                cmd.spawn((
+                    Node {
+                        padding: UiRect::all(Val::Px(8.0)),
+                        ..default()
+                    },
+                    theme.button.background_color,
+                    theme.button.border_radius,
+                    Focusable,
+                    tooltip,
+                ))
+                .with_child((
+                    Text::new(format!("Button {}", index + 1)),
+                    TextFont {
+                        font: theme.text.font.clone(),
+                        font_size: 14.0,
+                        ..default()
+                    },
+                ));
+            }
+        });
+}
@@ -1,17 +1,261 @@
 //! A tooltip system for Bevy applications, providing contextual information on hover.
+//!
+//! Adding a [`Tooltip`] to an entity shows its text after the pointer stayed over the entity, or
+//! any of its descendants, for a short delay. Tooltips can also be shown while their entity has
+//! the keyboard [`Focus`](bevy_focus::Focus), see [`Tooltip::with_focus_trigger`]. The tooltip is
+//! placed below the pointer or the focused entity, or above when there is no room left in the
+//! window, and hides when the pointer leaves the entity or a button is pressed.
 
-/// an add function that adds two numbers
-pub fn add(left: u64, right: u64) -> u64 {
-    left + right
+mod ui;
+
+use std::{borrow::Cow, time::Duration};
+
+use bevy::{
+    picking::{
+        hover::HoverMap,
+        pointer::{PointerId, PointerLocation},
+    },
+    prelude::*,
+    ui::UiSystem,
+};
+use bevy_editor_settings::{SettingKey, SettingsType};
+use bevy_editor_styles::Theme;
+use bevy_focus::Focus;
+
+/// Plugin for the tooltip system
+pub struct TooltipPlugin;
+
+impl Plugin for TooltipPlugin {
+    fn build(&self, app: &mut App) {
+        app.init_resource::<TooltipSettings>()
+            .register_type::<TooltipSettings>()
+            .init_resource::<TooltipState>();
+
+        app.add_systems(Update, update_tooltips);
+        app.add_systems(PostUpdate, ui::place_tooltips.after(UiSystem::Layout));
+    }
+}
+
+/// Text shown when hovering the entity or one of its descendants
+#[derive(Component, Clone, Debug, Default)]
+pub struct Tooltip {
+    /// The sections of the text, each with its own style
+    pub sections: Vec<TooltipSection>,
+    /// How long the entity must be hovered before showing the tooltip,
+    /// [`TooltipSettings::delay`] if not set
+    pub delay: Option<Duration>,
+    /// If true, the tooltip is also shown while the entity has the keyboard focus
+    pub show_on_focus: bool,
+}
+
+impl Tooltip {
+    /// Create a new tooltip with the given text
+    pub fn new(text: impl Into<Cow<'static, str>>) -> Self {
+        Self::default().with_text(text)
+    }
+
+    /// Append a section of text with the normal style
+    pub fn with_text(self, text: impl Into<Cow<'static, str>>) -> Self {
+        self.with_section(text, TooltipTextStyle::Normal)
+    }
+
+    /// Append a section of text with the high priority style, for warnings and key points
+    pub fn with_high_priority(self, text: impl Into<Cow<'static, str>>) -> Self {
+        self.with_section(text, TooltipTextStyle::HighPriority)
+    }
+
+    /// Append a section of text with the low priority style, for shortcuts and details
+    pub fn with_low_priority(self, text: impl Into<Cow<'static, str>>) -> Self {
+        self.with_section(text, TooltipTextStyle::LowPriority)
+    }
+
+    /// Append a section of text with the given style
+    pub fn with_section(
+        mut self,
+        text: impl Into<Cow<'static, str>>,
+        style: TooltipTextStyle,
+    ) -> Self {
+        self.sections.push(TooltipSection {
+            text: text.into(),
+            style,
+        });
+        self
+    }
+
+    /// Set the hover delay of this tooltip, overriding [`TooltipSettings::delay`]
+    pub fn with_delay(mut self, delay: Duration) -> Self {
+        self.delay = Some(delay);
+        self
+    }
+
+    /// Show the tooltip while the entity has the keyboard focus, see [`Tooltip::show_on_focus`]
+    pub fn with_focus_trigger(mut self, show_on_focus: bool) -> Self {
+        self.show_on_focus = show_on_focus;
+        self
+    }
 }
 
-#[cfg(test)]
-mod tests {
-    use super::*;
+/// A section of the text of a [`Tooltip`]
+#[derive(Clone, Debug, PartialEq)]
+pub struct TooltipSection {
+    /// The text of the section, which can contain line breaks
+    pub text: Cow<'static, str>,
+    /// The style of the text
+    pub style: TooltipTextStyle,
+}
+
+/// Style of a [`TooltipSection`], following the text colors of the [`Theme`]
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq)]
+pub enum TooltipTextStyle {
+    /// [`TextStyles::text_color`](bevy_editor_styles::TextStyles::text_color)
+    #[default]
+    Normal,
+    /// [`TextStyles::high_priority`](bevy_editor_styles::TextStyles::high_priority)
+    HighPriority,
+    /// [`TextStyles::low_priority`](bevy_editor_styles::TextStyles::low_priority)
+    LowPriority,
+}
+
+/// Global settings of the tooltips
+#[derive(Resource, Reflect, Clone, Debug, PartialEq)]
+#[reflect(Resource, @SettingsType::Global, @SettingKey("tooltips"))]
+pub struct TooltipSettings {
+    /// If false, no tooltip is shown
+    pub enabled: bool,
+    /// How long an entity must be hovered before showing its tooltip, in seconds
+    pub delay: f32,
+}
+
+impl Default for TooltipSettings {
+    fn default() -> Self {
+        Self {
+            enabled: true,
+            delay: 0.5,
+        }
+    }
+}
+
+/// What shows the current tooltip
+#[derive(Clone, Copy, Debug, PartialEq)]
+enum TooltipTrigger {
+    Hover,
+    Focus,
+}
+
+/// The entity whose tooltip is pending or shown
+#[derive(Resource, Default)]
+struct TooltipState {
+    /// The entity with the [`Tooltip`] and what triggers it
+    owner: Option<(Entity, TooltipTrigger)>,
+    /// When the owner was hovered or focused
+    since: Duration,
+    /// Whether a button was pressed since, which hides the tooltip until the owner changes
+    dismissed: bool,
+    /// The tooltip node
+    shown: Option<Entity>,
+}
 
-    #[test]
-    fn it_works() {
-        let result = add(2, 2);
-        assert_eq!(result, 4);
+impl TooltipState {
+    fn hide(&mut self, commands: &mut Commands) {
+        if let Some(shown) = self.shown.take() {
+            commands.entity(shown).try_despawn();
+        }
     }
 }
+
+#[allow(clippy::too_many_arguments)]
+fn update_tooltips(
+    mut commands: Commands,
+    mut state: ResMut<TooltipState>,
+    time: Res<Time<Real>>,
+    settings: Res<TooltipSettings>,
+    theme: Res<Theme>,
+    hover_map: Res<HoverMap>,
+    mouse: Res<ButtonInput<MouseButton>>,
+    keys: Res<ButtonInput<KeyCode>>,
+    q_pointers: Query<(&PointerId, &PointerLocation)>,
+    q_tooltips: Query<Ref<Tooltip>>,
+    q_parents: Query<&ChildOf>,
+    q_focused: Query<Entity, With<Focus>>,
+    q_nodes: Query<(&ComputedNode, &GlobalTransform)>,
+) {
+    // The closest entity with a tooltip, among the hovered entities and their ancestors
+    let hovered = hover_map.get(&PointerId::Mouse).and_then(|hovered| {
+        hovered
+            .iter()
+            .filter_map(|(&entity, hit)| {
+                let owner = std::iter::once(entity)
+                    .chain(q_parents.iter_ancestors(entity))
+                    .find(|ancestor| q_tooltips.contains(*ancestor))?;
+                Some((owner, hit.depth))
+            })
+            .min_by(|(_, a), (_, b)| a.total_cmp(b))
+            .map(|(owner, _)| (owner, TooltipTrigger::Hover))
+    });
+    let focused = q_focused
+        .iter()
+        .find(|entity| {
+            q_tooltips
+                .get(*entity)
+                .is_ok_and(|tooltip| tooltip.show_on_focus)
+        })
+        .map(|entity| (entity, TooltipTrigger::Focus));
+    let owner = hovered.or(focused);
+
+    if owner != state.owner {
+        state.hide(&mut commands);
+        state.owner = owner;
+        state.since = time.elapsed();
+        state.dismissed = false;
+    } else if mouse.get_just_pressed().next().is_some() || keys.get_just_pressed().next().is_some()
+    {
+        // Checked for the same owner only, so that moving the focus with a key shows its tooltip
+        state.dismissed = true;
+    }
+
+    let Some((owner, trigger)) = state.owner else {
+        return;
+    };
+    let Ok(tooltip) = q_tooltips.get(owner) else {
+        state.hide(&mut commands);
+        return;
+    };
+    if state.dismissed || !settings.enabled {
+        state.hide(&mut commands);
+        return;
+    }
+    if tooltip.is_changed() {
+        // Rebuild the text
+        state.hide(&mut commands);
+    }
+
+    // An infinite or huge delay never shows the tooltip, instead of panicking
+    let delay = tooltip.delay.unwrap_or_else(|| {
+        Duration::try_from_secs_f32(settings.delay.max(0.0)).unwrap_or(Duration::MAX)
+    });
+    if state.shown.is_some() || time.elapsed() - state.since < delay {
+        return;
+    }
+
+    let pointer = q_pointers
+        .iter()
+        .find(|(id, _)| **id == PointerId::Mouse)
+        .and_then(|(_, location)| location.location())
+        .map(|location| location.position);
+    let owner_rect = q_nodes.get(owner).ok().map(|(node, transform)| {
+        Rect::from_center_size(
+            transform.translation().truncate() * node.inverse_scale_factor(),
+            node.size() * node.inverse_scale_factor(),
+        )
+    });
+    // Hover tooltips follow the pointer, focus ones the focused node
+    let anchor = match (trigger, pointer) {
+        (TooltipTrigger::Hover, Some(pointer)) => Some(ui::pointer_anchor(pointer)),
+        _ => owner_rect.or(pointer.map(ui::pointer_anchor)),
+    };
+    let Some(anchor) = anchor else {
+        return;
+    };
+
+    state.shown = Some(ui::spawn_tooltip(&mut commands, &theme, &tooltip, anchor));
+}
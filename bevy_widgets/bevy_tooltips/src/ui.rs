@@ -0,0 +1,144 @@
+use bevy::{prelude::*, window::PrimaryWindow};
+use bevy_editor_styles::Theme;
+
+use crate::{Tooltip, TooltipTextStyle};
+
+/// Space between the tooltip and what it is anchored to
+const GAP: f32 = 4.0;
+
+/// Height left below the pointer for the cursor icon
+const CURSOR_HEIGHT: f32 = 16.0;
+
+/// The node of a shown tooltip
+#[derive(Component)]
+pub(crate) struct TooltipNode {
+    /// The rect the tooltip is placed around, in logical pixels
+    anchor: Rect,
+}
+
+/// The rect to place a tooltip shown for the pointer at `position` around
+pub(crate) fn pointer_anchor(position: Vec2) -> Rect {
+    Rect::new(
+        position.x,
+        position.y,
+        position.x,
+        position.y + CURSOR_HEIGHT,
+    )
+}
+
+pub(crate) fn spawn_tooltip(
+    commands: &mut Commands,
+    theme: &Theme,
+    tooltip: &Tooltip,
+    anchor: Rect,
+) -> Entity {
+    let font = TextFont {
+        font: theme.text.font.clone(),
+        font_size: 12.0,
+        ..default()
+    };
+
+    commands
+        .spawn((
+            TooltipNode { anchor },
+            Node {
+                position_type: PositionType::Absolute,
+                max_width: Val::Px(320.0),
+                padding: UiRect::axes(Val::Px(8.0), Val::Px(4.0)),
+                border: UiRect::all(Val::Px(1.0)),
+                ..default()
+            },
+            theme.context_menu.background_color,
+            BorderColor(theme.pane.header_background_color.0),
+            theme.context_menu.option_border_radius,
+            GlobalZIndex(i32::MAX),
+            Pickable::IGNORE,
+            // Shown once placed, see `place_tooltips`
+            Visibility::Hidden,
+        ))
+        .with_children(|cmd| {
+            cmd.spawn((Text::default(), font.clone(), Pickable::IGNORE))
+                .with_children(|cmd| {
+                    for section in &tooltip.sections {
+                        let color = match section.style {
+                            TooltipTextStyle::Normal => theme.text.text_color,
+                            TooltipTextStyle::HighPriority => theme.text.high_priority,
+                            TooltipTextStyle::LowPriority => theme.text.low_priority,
+                        };
+                        cmd.spawn((
+                            TextSpan::new(section.text.clone()),
+                            font.clone(),
+                            TextColor(color),
+                        ));
+                    }
+                });
+        })
+        .id()
+}
+
+/// Places the tooltips once their size is known, and shows them when the layout follows
+pub(crate) fn place_tooltips(
+    q_window: Query<&Window, With<PrimaryWindow>>,
+    mut q_tooltips: Query<(&TooltipNode, &ComputedNode, &mut Node, &mut Visibility)>,
+) {
+    let Ok(window) = q_window.single() else {
+        return;
+    };
+    let window_size = Vec2::new(window.width(), window.height());
+
+    for (tooltip, computed, mut node, mut visibility) in q_tooltips.iter_mut() {
+        let size = computed.size() * computed.inverse_scale_factor();
+        if size == Vec2::ZERO {
+            continue;
+        }
+        let position = place(tooltip.anchor, size, window_size);
+        let (left, top) = (Val::Px(position.x), Val::Px(position.y));
+        if node.left != left || node.top != top {
+            // The layout only moves the node on the next frame
+            node.left = left;
+            node.top = top;
+        } else if *visibility == Visibility::Hidden {
+            *visibility = Visibility::Inherited;
+        }
+    }
+}
+
+/// The top left corner of a tooltip of `size` below `anchor`, or above it if there is not enough
+/// room below, kept within the window
+fn place(anchor: Rect, size: Vec2, window_size: Vec2) -> Vec2 {
+    let mut position = Vec2::new(anchor.min.x, anchor.max.y + GAP);
+    if position.y + size.y > window_size.y {
+        position.y = anchor.min.y - GAP - size.y;
+    }
+    position.min(window_size - size).max(Vec2::ZERO).round()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn places_tooltips_within_the_window() {
+        let window = Vec2::new(800.0, 600.0);
+        let size = Vec2::new(100.0, 30.0);
+
+        let below = place(Rect::new(10.0, 10.0, 50.0, 30.0), size, window);
+        assert_eq!(below, Vec2::new(10.0, 34.0));
+
+        // No room below the anchor
+        let above = place(Rect::new(10.0, 560.0, 50.0, 580.0), size, window);
+        assert_eq!(above, Vec2::new(10.0, 526.0));
+
+        // Pushed left from the right edge
+        let right = place(pointer_anchor(Vec2::new(780.0, 100.0)), size, window);
+        assert_eq!(right, Vec2::new(700.0, 120.0));
+
+        // Larger than the window
+        let large = place(
+            Rect::new(10.0, 10.0, 50.0, 30.0),
+            Vec2::new(900.0, 30.0),
+            window,
+        );
+        assert_eq!(large, Vec2::new(0.0, 34.0));
+    }
+}
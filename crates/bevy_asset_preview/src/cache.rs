@@ -0,0 +1,167 @@
+//! The on-disk cache of previews.
+
+use std::path::{Path, PathBuf};
+
+use bevy::{
+    asset::{io::Reader, AssetPath},
+    prelude::*,
+    tasks::{block_on, poll_once, IoTaskPool, Task},
+};
+use image::{DynamicImage, ImageFormat};
+
+use crate::{PreviewJob, PreviewKind, PreviewRequests, PREVIEW_SIZE};
+
+/// The directory previews are cached in, relative to the cache directory of the user.
+const CACHE_DIR: &str = "bevy_editor/asset_previews";
+
+/// Part of the cache keys, increment this to invalidate all cached previews when the way previews
+/// are made changes.
+const CACHE_VERSION: u32 = 1;
+
+pub(crate) fn default_cache_dir() -> Option<PathBuf> {
+    Some(directories::BaseDirs::new()?.cache_dir().join(CACHE_DIR))
+}
+
+/// The result of looking up a preview in the cache.
+enum CacheLookup {
+    /// The cached preview.
+    Hit(Image),
+    /// The preview isn't cached yet, and should be saved under this key once it's made.
+    Miss(String),
+    /// The asset file couldn't be read.
+    Failed,
+}
+
+/// The task that looks up a requested preview in the cache.
+#[derive(Component)]
+pub(crate) struct CacheLookupTask(PreviewJob, Task<CacheLookup>);
+
+/// Spawn a [`CacheLookupTask`] for each new preview request.
+pub(crate) fn look_up_previews(
+    mut commands: Commands,
+    mut requests: ResMut<PreviewRequests>,
+    asset_server: Res<AssetServer>,
+) {
+    let cache_dir = requests.cache_dir.clone();
+    for job in requests.pending.drain(..) {
+        let asset_server = asset_server.clone();
+        let cache_dir = cache_dir.clone();
+        let path = job.path.clone();
+        let task = IoTaskPool::get().spawn(async move {
+            let Some(bytes) = read_asset_file(&asset_server, &path).await else {
+                return CacheLookup::Failed;
+            };
+            let key = cache_key(&path, &bytes);
+            let cached = cache_dir
+                .and_then(|dir| std::fs::read(dir.join(format!("{key}.png"))).ok())
+                .and_then(|bytes| {
+                    image::load_from_memory_with_format(&bytes, ImageFormat::Png).ok()
+                });
+            match cached {
+                Some(image) => CacheLookup::Hit(Image::from_dynamic(image, true, default())),
+                None => CacheLookup::Miss(key),
+            }
+        });
+        commands.spawn(CacheLookupTask(job, task));
+    }
+}
+
+/// Poll the [`CacheLookupTask`]s, and start making the previews that aren't cached.
+pub(crate) fn poll_cache_lookups(
+    mut commands: Commands,
+    mut tasks: Query<(Entity, &mut CacheLookupTask)>,
+    mut requests: ResMut<PreviewRequests>,
+    mut images: ResMut<Assets<Image>>,
+    asset_server: Res<AssetServer>,
+) {
+    for (entity, mut task) in &mut tasks {
+        let Some(lookup) = block_on(poll_once(&mut task.1)) else {
+            continue;
+        };
+        commands.entity(entity).despawn();
+        let job = task.0.clone();
+        match lookup {
+            CacheLookup::Hit(image) => {
+                images.insert(&job.handle, image);
+            }
+            CacheLookup::Miss(key) if job.kind == PreviewKind::Image => {
+                let source = asset_server.load(job.path.clone());
+                requests.loading_images.push((job, key, source));
+            }
+            CacheLookup::Miss(key) => requests.render_queue.push_back((job, key)),
+            CacheLookup::Failed => warn!("Can't make a preview of {}: failed to read it", job.path),
+        }
+    }
+}
+
+/// Save a preview to the cache in the background.
+pub(crate) fn save_preview(cache_dir: Option<&Path>, key: &str, image: DynamicImage) {
+    let Some(cache_dir) = cache_dir else {
+        return;
+    };
+    let cache_dir = cache_dir.to_path_buf();
+    let file = cache_dir.join(format!("{key}.png"));
+    IoTaskPool::get()
+        .spawn(async move {
+            let result = std::fs::create_dir_all(&cache_dir)
+                .map_err(image::ImageError::IoError)
+                .and_then(|()| image.save_with_format(&file, ImageFormat::Png));
+            if let Err(error) = result {
+                warn!("Failed to cache asset preview {}: {error}", file.display());
+            }
+        })
+        .detach();
+}
+
+/// The key of the preview of an asset in the cache, based on the content of its file.
+///
+/// For assets that consist of multiple files, like glTF files with external buffers, only the
+/// main file is hashed.
+fn cache_key(path: &AssetPath, bytes: &[u8]) -> String {
+    let mut hasher = blake3::Hasher::new();
+    hasher.update(&CACHE_VERSION.to_le_bytes());
+    hasher.update(&PREVIEW_SIZE.to_le_bytes());
+    hasher.update(path.label().unwrap_or_default().as_bytes());
+    hasher.update(bytes);
+    hasher.finalize().to_hex().to_string()
+}
+
+async fn read_asset_file(asset_server: &AssetServer, path: &AssetPath<'_>) -> Option<Vec<u8>> {
+    let source = asset_server.get_source(path.source().clone()).ok()?;
+    let mut reader = source.reader().read(path.path()).await.ok()?;
+    let mut bytes = Vec::new();
+    reader.read_to_end(&mut bytes).await.ok()?;
+    Some(bytes)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn cache_key_is_stable() {
+        let path = AssetPath::from("models/cube.glb");
+        assert_eq!(cache_key(&path, b"content"), cache_key(&path, b"content"));
+    }
+
+    #[test]
+    fn cache_key_depends_on_content() {
+        let path = AssetPath::from("models/cube.glb");
+        assert_ne!(cache_key(&path, b"content"), cache_key(&path, b"changed"));
+    }
+
+    #[test]
+    fn cache_key_depends_on_label() {
+        let scene = AssetPath::from("models/cube.glb#Scene0");
+        let mesh = AssetPath::from("models/cube.glb#Mesh0/Primitive0");
+        assert_ne!(cache_key(&scene, b"content"), cache_key(&mesh, b"content"));
+    }
+
+    #[test]
+    fn cache_key_ignores_file_location() {
+        // Moving or copying an unchanged file can reuse its preview
+        let path = AssetPath::from("models/cube.glb");
+        let moved = AssetPath::from("other/cube_copy.glb");
+        assert_eq!(cache_key(&path, b"content"), cache_key(&moved, b"content"));
+    }
+}
@@ -1,13 +1,260 @@
-use bevy::prelude::*;
-
-/// This crate is a work in progress and is not yet ready for use.
-/// The intention is to provide a way to load/render/unload assets in the background and provide previews of them in the Bevy Editor.
-/// For 2d assets this will be a simple sprite, for 3d assets this will require a quick render of the asset at a low resolution, just enough for a user to be able to tell quickly what it is.
-/// This code may be reused for the Bevy Marketplace Viewer to provide previews of assets and plugins.
-/// So long as the assets are unchanged, the previews will be cached and will not need to be re-rendered.
-/// In theory this can be done passively in the background, and the previews will be ready when the user needs them.
+//! Thumbnail previews of assets for the Bevy Editor.
+//!
+//! The intention is to provide a way to load/render/unload assets in the background and provide previews of them in the Bevy Editor.
+//! For 2d assets this will be a simple sprite, for 3d assets this will require a quick render of the asset at a low resolution, just enough for a user to be able to tell quickly what it is.
+//! This code may be reused for the Bevy Marketplace Viewer to provide previews of assets and plugins.
+//! So long as the assets are unchanged, the previews will be cached and will not need to be re-rendered.
+//!
+//! Previews are requested with the [`AssetPreviews`] system param, which hands out an image
+//! handle right away and fills it in once the preview is ready:
+//! - images are downscaled,
+//! - fonts are rendered as a line of sample glyphs,
+//! - meshes, glTF files and scenes are rendered offscreen.
+//!
+//! Finished previews are cached on disk, keyed by a hash of the content of the asset file.
+
+use std::{collections::VecDeque, path::PathBuf};
+
+use bevy::{
+    asset::AssetPath, ecs::system::SystemParam, image::ImageLoader, platform::collections::HashMap,
+    prelude::*,
+};
+
+mod cache;
+mod render;
+
+/// The width and height of previews, in pixels.
+///
+/// Rendered previews are read back from the GPU, which pads rows to 256 bytes, so a row of
+/// `PREVIEW_SIZE` RGBA pixels must be a multiple of that.
+pub const PREVIEW_SIZE: u32 = 128;
+
+/// The render layer used to render previews offscreen.
+///
+/// This layer is reserved: other editor cameras must not render it, or they would show the
+/// content of previews.
+pub const PREVIEW_RENDER_LAYER: usize = 31;
+
+/// Plugin for rendering and caching asset previews, see the [crate docs](crate).
 pub struct AssetPreviewPlugin;
 
 impl Plugin for AssetPreviewPlugin {
-    fn build(&self, _app: &mut App) {}
+    fn build(&self, app: &mut App) {
+        app.init_resource::<PreviewRequests>()
+            .add_systems(Startup, render::setup_preview_material)
+            .add_systems(
+                Update,
+                (
+                    cache::look_up_previews,
+                    cache::poll_cache_lookups,
+                    downscale_images,
+                    render::start_preview_render,
+                    render::update_preview_render,
+                )
+                    .chain(),
+            );
+    }
+}
+
+/// The kinds of assets that previews can be made of.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum PreviewKind {
+    /// An image, previewed by downscaling it.
+    Image,
+    /// A font, previewed by rendering a line of sample glyphs.
+    Font,
+    /// A glTF file or one of its scenes, rendered offscreen.
+    Scene,
+    /// A dynamic scene file, rendered offscreen.
+    DynamicScene,
+    /// A single mesh, like a primitive of a glTF mesh, rendered offscreen.
+    Mesh,
+}
+
+impl PreviewKind {
+    /// The kind of preview for the asset at `path`, or `None` if previews of the asset are not
+    /// supported.
+    pub fn of(path: &AssetPath) -> Option<PreviewKind> {
+        if let Some(label) = path.label() {
+            return if label.starts_with("Mesh") && label.contains("Primitive") {
+                Some(PreviewKind::Mesh)
+            } else if label.starts_with("Scene") {
+                Some(PreviewKind::Scene)
+            } else {
+                None
+            };
+        }
+        // The full extension of `wall.diffuse.png` is `diffuse.png`, so also try the extensions it
+        // ends with, from the longest like `scn.ron` to the last one
+        let extension = path.get_full_extension()?;
+        std::iter::successors(Some(extension.as_str()), |extension| {
+            extension.split_once('.').map(|(_, rest)| rest)
+        })
+        .find_map(Self::of_extension)
+    }
+
+    fn of_extension(extension: &str) -> Option<PreviewKind> {
+        match extension.to_lowercase().as_str() {
+            "ttf" | "otf" => Some(PreviewKind::Font),
+            "gltf" | "glb" => Some(PreviewKind::Scene),
+            "scn" | "scn.ron" => Some(PreviewKind::DynamicScene),
+            extension if ImageLoader::SUPPORTED_FILE_EXTENSIONS.contains(&extension) => {
+                Some(PreviewKind::Image)
+            }
+            _ => None,
+        }
+    }
+}
+
+/// Requests asset previews.
+#[derive(SystemParam)]
+pub struct AssetPreviews<'w> {
+    requests: ResMut<'w, PreviewRequests>,
+    images: Res<'w, Assets<Image>>,
+}
+
+impl AssetPreviews<'_> {
+    /// Get the preview of the asset at `path`, or `None` if previews of the asset are not
+    /// supported.
+    ///
+    /// The image of the returned handle is added once the preview is ready, which may take a few
+    /// frames. If the preview can't be made, for example because the asset fails to load, the
+    /// image is never added.
+    pub fn get<'a>(&mut self, path: impl Into<AssetPath<'a>>) -> Option<Handle<Image>> {
+        let path = path.into().into_owned();
+        let kind = PreviewKind::of(&path)?;
+        if let Some(handle) = self.requests.previews.get(&path) {
+            return Some(handle.clone());
+        }
+        let handle = self.images.reserve_handle();
+        self.requests.previews.insert(path.clone(), handle.clone());
+        self.requests.pending.push_back(PreviewJob {
+            path,
+            kind,
+            handle: handle.clone(),
+        });
+        Some(handle)
+    }
+
+    /// Forget the preview of the asset at `path`, so it is made again the next time it is
+    /// requested. Use this when the asset has changed.
+    pub fn invalidate<'a>(&mut self, path: impl Into<AssetPath<'a>>) {
+        self.requests.previews.remove(&path.into().into_owned());
+    }
+}
+
+/// The previews that have been requested so far.
+#[derive(Resource)]
+pub struct PreviewRequests {
+    previews: HashMap<AssetPath<'static>, Handle<Image>>,
+    /// Requests that haven't been looked up in the cache yet.
+    pending: VecDeque<PreviewJob>,
+    /// Previews of images that are being loaded to be downscaled.
+    loading_images: Vec<(PreviewJob, String, Handle<Image>)>,
+    /// Previews waiting to be rendered offscreen, one at a time.
+    render_queue: VecDeque<(PreviewJob, String)>,
+    cache_dir: Option<PathBuf>,
+}
+
+impl Default for PreviewRequests {
+    fn default() -> Self {
+        PreviewRequests {
+            previews: default(),
+            pending: default(),
+            loading_images: default(),
+            render_queue: default(),
+            cache_dir: cache::default_cache_dir(),
+        }
+    }
+}
+
+/// A requested preview.
+#[derive(Clone, Debug)]
+struct PreviewJob {
+    path: AssetPath<'static>,
+    kind: PreviewKind,
+    /// The handle of the preview image, which is added once the preview is ready.
+    handle: Handle<Image>,
+}
+
+/// Downscale the images that previews were requested for once they are loaded.
+fn downscale_images(
+    mut requests: ResMut<PreviewRequests>,
+    mut images: ResMut<Assets<Image>>,
+    asset_server: Res<AssetServer>,
+) {
+    let requests = &mut *requests;
+    requests.loading_images.retain(|(job, key, source)| {
+        if asset_server.load_state(source).is_failed() {
+            return false;
+        }
+        let Some(image) = images.get(source) else {
+            return true;
+        };
+        let thumbnail = match image.clone().try_into_dynamic() {
+            Ok(image) => image.thumbnail(PREVIEW_SIZE, PREVIEW_SIZE),
+            Err(error) => {
+                warn!("Can't make a preview of {}: {error}", job.path);
+                return false;
+            }
+        };
+        cache::save_preview(requests.cache_dir.as_deref(), key, thumbnail.clone());
+        images.insert(&job.handle, Image::from_dynamic(thumbnail, true, default()));
+        false
+    });
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn kind_of(path: &str) -> Option<PreviewKind> {
+        PreviewKind::of(&AssetPath::from(path))
+    }
+
+    #[test]
+    fn preview_kind_by_extension() {
+        assert_eq!(kind_of("textures/grass.png"), Some(PreviewKind::Image));
+        assert_eq!(kind_of("textures/GRASS.PNG"), Some(PreviewKind::Image));
+        assert_eq!(kind_of("fonts/FiraSans.ttf"), Some(PreviewKind::Font));
+        assert_eq!(kind_of("fonts/FiraSans.otf"), Some(PreviewKind::Font));
+        assert_eq!(kind_of("models/cube.gltf"), Some(PreviewKind::Scene));
+        assert_eq!(kind_of("models/cube.glb"), Some(PreviewKind::Scene));
+        assert_eq!(
+            kind_of("levels/start.scn.ron"),
+            Some(PreviewKind::DynamicScene)
+        );
+        assert_eq!(kind_of("levels/start.scn"), Some(PreviewKind::DynamicScene));
+    }
+
+    #[test]
+    fn preview_kind_of_names_with_dots() {
+        assert_eq!(
+            kind_of("textures/wall.diffuse.png"),
+            Some(PreviewKind::Image)
+        );
+        assert_eq!(kind_of("fonts/Inter.v4.ttf"), Some(PreviewKind::Font));
+        assert_eq!(
+            kind_of("levels/level.1.scn.ron"),
+            Some(PreviewKind::DynamicScene)
+        );
+        assert_eq!(kind_of("scripts/player.test.rs"), None);
+    }
+
+    #[test]
+    fn preview_kind_by_label() {
+        assert_eq!(kind_of("models/cube.glb#Scene0"), Some(PreviewKind::Scene));
+        assert_eq!(
+            kind_of("models/cube.glb#Mesh0/Primitive0"),
+            Some(PreviewKind::Mesh)
+        );
+        assert_eq!(kind_of("models/cube.glb#Mesh0"), None);
+        assert_eq!(kind_of("models/cube.glb#Material0"), None);
+    }
+
+    #[test]
+    fn unsupported_assets_have_no_preview() {
+        assert_eq!(kind_of("scripts/player.rs"), None);
+        assert_eq!(kind_of("README"), None);
+    }
 }
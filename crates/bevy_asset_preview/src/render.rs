@@ -0,0 +1,386 @@
+//! Offscreen rendering of previews of fonts, meshes and scenes.
+//!
+//! Previews are rendered one at a time, by a camera on [`PREVIEW_RENDER_LAYER`] that renders into
+//! a scratch image. Once the content is drawn, the image is read back from the GPU, cached, and
+//! only then stored as the preview image, so half-rendered previews are never shown.
+
+use std::f32::consts::FRAC_PI_4;
+
+use bevy::{
+    asset::RenderAssetUsages,
+    gltf::GltfAssetLabel,
+    prelude::*,
+    render::{
+        camera::RenderTarget,
+        gpu_readback::{Readback, ReadbackComplete},
+        primitives::Aabb,
+        render_resource::{Extent3d, TextureDimension, TextureFormat, TextureUsages},
+        view::RenderLayers,
+    },
+};
+
+use bevy_editor_core::EditorEntity;
+
+use crate::{cache, PreviewJob, PreviewKind, PreviewRequests, PREVIEW_RENDER_LAYER, PREVIEW_SIZE};
+
+/// The text rendered for font previews.
+const FONT_SAMPLE: &str = "Aa Bb 123";
+
+/// The number of frames to wait for a scene to be spawned and its meshes to get their bounds,
+/// before rendering it anyway.
+const MAX_FRAMING_FRAMES: u32 = 60;
+
+/// The number of frames rendered before the preview is read back.
+const RENDER_FRAMES: u32 = 2;
+
+/// The number of frames to wait for the previewed asset and its dependencies to load, before
+/// giving up on the preview to render the next one.
+const MAX_LOADING_FRAMES: u32 = 600;
+
+/// The number of frames to wait for the preview to be read back from the GPU, before giving up on
+/// the preview to render the next one.
+const MAX_READBACK_FRAMES: u32 = 60;
+
+/// The material of mesh previews.
+#[derive(Resource)]
+pub(crate) struct PreviewMaterial(Handle<StandardMaterial>);
+
+pub(crate) fn setup_preview_material(
+    mut commands: Commands,
+    mut materials: ResMut<Assets<StandardMaterial>>,
+) {
+    commands.insert_resource(PreviewMaterial(materials.add(StandardMaterial::default())));
+}
+
+/// The preview that is currently being rendered.
+#[derive(Resource)]
+pub(crate) struct ActivePreview {
+    job: PreviewJob,
+    /// The key to cache the preview under.
+    key: String,
+    /// The previewed asset, kept loaded until the preview is done.
+    source: UntypedHandle,
+    /// The image the preview is rendered into, until it is read back.
+    target: Handle<Image>,
+    stage: RenderStage,
+    frames: u32,
+    camera: Entity,
+    content: Entity,
+    readback: Entity,
+}
+
+#[derive(Clone, Copy, PartialEq, Eq)]
+enum RenderStage {
+    /// Waiting for the previewed asset to load.
+    Loading,
+    /// Waiting for the content to be spawned, to point the camera at it.
+    Framing,
+    /// Rendering the content for a few frames.
+    Rendering,
+    /// Waiting for the image to be read back from the GPU.
+    ReadingBack,
+}
+
+/// Start rendering the next preview in the queue, if no preview is being rendered.
+pub(crate) fn start_preview_render(
+    mut commands: Commands,
+    mut requests: ResMut<PreviewRequests>,
+    active: Option<Res<ActivePreview>>,
+    asset_server: Res<AssetServer>,
+) {
+    if active.is_some() {
+        return;
+    }
+    let Some((job, key)) = requests.render_queue.pop_front() else {
+        return;
+    };
+    let path = job.path.clone();
+    let source = match job.kind {
+        PreviewKind::Font => asset_server.load::<Font>(path).untyped(),
+        PreviewKind::Scene if path.label().is_none() => asset_server
+            .load::<Scene>(GltfAssetLabel::Scene(0).from_asset(path))
+            .untyped(),
+        PreviewKind::Scene => asset_server.load::<Scene>(path).untyped(),
+        PreviewKind::DynamicScene => asset_server.load::<DynamicScene>(path).untyped(),
+        PreviewKind::Mesh => asset_server.load::<Mesh>(path).untyped(),
+        PreviewKind::Image => unreachable!("previews of images are downscaled, not rendered"),
+    };
+    commands.insert_resource(ActivePreview {
+        job,
+        key,
+        source,
+        target: Handle::default(),
+        stage: RenderStage::Loading,
+        frames: 0,
+        camera: Entity::PLACEHOLDER,
+        content: Entity::PLACEHOLDER,
+        readback: Entity::PLACEHOLDER,
+    });
+}
+
+/// Advance the [`ActivePreview`] through its [stages](RenderStage).
+#[expect(clippy::too_many_arguments)]
+pub(crate) fn update_preview_render(
+    mut commands: Commands,
+    active: Option<ResMut<ActivePreview>>,
+    asset_server: Res<AssetServer>,
+    mut images: ResMut<Assets<Image>>,
+    material: Res<PreviewMaterial>,
+    children_query: Query<&Children>,
+    content_query: Query<(
+        Has<RenderLayers>,
+        Has<Mesh3d>,
+        Option<&Aabb>,
+        &GlobalTransform,
+    )>,
+    mut camera_query: Query<&mut Transform, With<Camera>>,
+) {
+    let Some(mut active) = active else {
+        return;
+    };
+    let active = &mut *active;
+    active.frames += 1;
+
+    if active.stage == RenderStage::Loading {
+        if asset_server.load_state(&active.source).is_failed()
+            || asset_server
+                .recursive_dependency_load_state(&active.source)
+                .is_failed()
+        {
+            warn!(
+                "Can't make a preview of {}: failed to load it or one of its dependencies",
+                active.job.path
+            );
+            cancel_preview(&mut commands, active);
+        } else if asset_server.is_loaded_with_dependencies(&active.source) {
+            active.target = images.add(render_target_image());
+            spawn_preview_content(&mut commands, active, &material);
+            active.frames = 0;
+        } else if active.frames >= MAX_LOADING_FRAMES {
+            warn!(
+                "Can't make a preview of {}: it didn't finish loading",
+                active.job.path
+            );
+            cancel_preview(&mut commands, active);
+        }
+        return;
+    }
+
+    // Scenes spawn their entities over a few frames, make sure they are all rendered offscreen
+    let content: Vec<Entity> = children_query
+        .iter_descendants(active.content)
+        .chain([active.content])
+        .collect();
+    for &entity in &content {
+        if content_query
+            .get(entity)
+            .is_ok_and(|(has_layers, ..)| !has_layers)
+        {
+            commands
+                .entity(entity)
+                .insert(RenderLayers::layer(PREVIEW_RENDER_LAYER));
+        }
+    }
+
+    match active.stage {
+        RenderStage::Framing => {
+            let mut min = Vec3::INFINITY;
+            let mut max = Vec3::NEG_INFINITY;
+            let mut spawned =
+                children_query.get(active.content).is_ok() || active.job.kind == PreviewKind::Mesh;
+            for (_, has_mesh, aabb, transform) in content_query.iter_many(&content) {
+                match aabb {
+                    Some(aabb) => {
+                        for corner in aabb_corners(aabb) {
+                            let corner = transform.transform_point(corner);
+                            min = min.min(corner);
+                            max = max.max(corner);
+                        }
+                    }
+                    None if has_mesh => spawned = false,
+                    None => {}
+                }
+            }
+            if !spawned && active.frames < MAX_FRAMING_FRAMES {
+                return;
+            }
+            let (center, radius) = if min.cmple(max).all() {
+                ((min + max) / 2., (max - min).length().max(0.001) / 2.)
+            } else {
+                (Vec3::ZERO, 1.)
+            };
+            if let Ok(mut transform) = camera_query.get_mut(active.camera) {
+                // Fit the bounding sphere in the default field of view of perspective cameras
+                let distance = radius / (FRAC_PI_4 / 2.).sin();
+                let direction = Vec3::new(1., 0.7, 1.).normalize();
+                *transform = Transform::from_translation(center + direction * distance)
+                    .looking_at(center, Vec3::Y);
+            }
+            commands
+                .entity(active.content)
+                .insert(Visibility::Inherited);
+            active.stage = RenderStage::Rendering;
+            active.frames = 0;
+        }
+        RenderStage::Rendering if active.frames >= RENDER_FRAMES => {
+            active.readback = commands
+                .spawn(Readback::texture(active.target.clone()))
+                .observe(finish_preview)
+                .id();
+            active.stage = RenderStage::ReadingBack;
+            active.frames = 0;
+        }
+        RenderStage::ReadingBack if active.frames >= MAX_READBACK_FRAMES => {
+            warn!(
+                "Can't make a preview of {}: the render was never read back",
+                active.job.path
+            );
+            cancel_preview(&mut commands, active);
+        }
+        _ => {}
+    }
+}
+
+/// Give up on the [`ActivePreview`] and clean up, to render the next preview.
+/// The image of the preview is never added.
+fn cancel_preview(commands: &mut Commands, active: &ActivePreview) {
+    commands.remove_resource::<ActivePreview>();
+    for entity in [active.camera, active.content, active.readback] {
+        if entity != Entity::PLACEHOLDER {
+            commands.entity(entity).despawn();
+        }
+    }
+}
+
+/// Spawn the camera and the previewed content on the preview render layer.
+fn spawn_preview_content(
+    commands: &mut Commands,
+    active: &mut ActivePreview,
+    material: &PreviewMaterial,
+) {
+    let layer = RenderLayers::layer(PREVIEW_RENDER_LAYER);
+    let camera = Camera {
+        target: RenderTarget::Image(active.target.clone().into()),
+        clear_color: ClearColorConfig::Custom(Color::NONE),
+        ..default()
+    };
+
+    if active.job.kind == PreviewKind::Font {
+        active.camera = commands
+            .spawn((Camera2d, camera, layer.clone(), EditorEntity))
+            .id();
+        active.content = commands
+            .spawn((
+                EditorEntity,
+                Text2d::new(FONT_SAMPLE),
+                TextFont {
+                    font: active.source.clone().typed(),
+                    font_size: 24.,
+                    ..default()
+                },
+                layer,
+            ))
+            .id();
+        active.stage = RenderStage::Rendering;
+        return;
+    }
+
+    active.camera = commands
+        .spawn((Camera3d::default(), camera, layer.clone(), EditorEntity))
+        .with_child((
+            DirectionalLight::default(),
+            Transform::from_rotation(Quat::from_euler(EulerRot::XYZ, -0.4, 0.4, 0.)),
+            layer.clone(),
+        ))
+        .id();
+    // Hidden until all of its entities are on the preview render layer
+    let mut content = commands.spawn((
+        EditorEntity,
+        Transform::default(),
+        Visibility::Hidden,
+        layer,
+    ));
+    match active.job.kind {
+        PreviewKind::Scene => {
+            content.insert(SceneRoot(active.source.clone().typed()));
+        }
+        PreviewKind::DynamicScene => {
+            content.insert(DynamicSceneRoot(active.source.clone().typed()));
+        }
+        PreviewKind::Mesh => {
+            content.insert((
+                Mesh3d(active.source.clone().typed()),
+                MeshMaterial3d(material.0.clone()),
+            ));
+        }
+        PreviewKind::Image | PreviewKind::Font => unreachable!(),
+    }
+    active.content = content.id();
+    active.stage = RenderStage::Framing;
+}
+
+/// Store the rendered preview, cache it, and clean up to render the next one.
+///
+/// The scratch image the preview was rendered into is dropped along with the [`ActivePreview`].
+fn finish_preview(
+    trigger: Trigger<ReadbackComplete>,
+    mut commands: Commands,
+    active: Option<Res<ActivePreview>>,
+    requests: Res<PreviewRequests>,
+    mut images: ResMut<Assets<Image>>,
+) {
+    commands.entity(trigger.target()).despawn();
+    let Some(active) = active else {
+        return;
+    };
+    commands.remove_resource::<ActivePreview>();
+    commands.entity(active.camera).despawn();
+    commands.entity(active.content).despawn();
+
+    let image = Image::new(
+        preview_extent(),
+        TextureDimension::D2,
+        trigger.event().0.clone(),
+        TextureFormat::Rgba8UnormSrgb,
+        RenderAssetUsages::default(),
+    );
+    if let Ok(dynamic_image) = image.clone().try_into_dynamic() {
+        cache::save_preview(requests.cache_dir.as_deref(), &active.key, dynamic_image);
+    }
+    images.insert(&active.job.handle, image);
+}
+
+fn preview_extent() -> Extent3d {
+    Extent3d {
+        width: PREVIEW_SIZE,
+        height: PREVIEW_SIZE,
+        depth_or_array_layers: 1,
+    }
+}
+
+fn render_target_image() -> Image {
+    let mut image = Image::new_fill(
+        preview_extent(),
+        TextureDimension::D2,
+        &[0; 4],
+        TextureFormat::Rgba8UnormSrgb,
+        RenderAssetUsages::default(),
+    );
+    image.texture_descriptor.usage |= TextureUsages::RENDER_ATTACHMENT | TextureUsages::COPY_SRC;
+    image
+}
+
+fn aabb_corners(aabb: &Aabb) -> [Vec3; 8] {
+    let min = Vec3::from(aabb.min());
+    let max = Vec3::from(aabb.max());
+    [
+        Vec3::new(min.x, min.y, min.z),
+        Vec3::new(max.x, min.y, min.z),
+        Vec3::new(min.x, max.y, min.z),
+        Vec3::new(max.x, max.y, min.z),
+        Vec3::new(min.x, min.y, max.z),
+        Vec3::new(max.x, min.y, max.z),
+        Vec3::new(min.x, max.y, max.z),
+        Vec3::new(max.x, max.y, max.z),
+    ]
+}
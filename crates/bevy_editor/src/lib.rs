@@ -16,20 +16,25 @@ use bevy::prelude::*;
 // Re-export Bevy for project use
 pub use bevy;
 
+use bevy_command_palette::CommandPalettePlugin;
 use bevy_context_menu::ContextMenuPlugin;
 use bevy_editor_core::{EditorCorePlugin, SceneRootMarker};
+use bevy_editor_settings::EditorSettingsPlugin;
 use bevy_editor_styles::StylesPlugin;
 
 // Panes
 use bevy_2d_viewport::Viewport2dPanePlugin;
 use bevy_3d_viewport::Viewport3dPanePlugin;
 use bevy_asset_browser::AssetBrowserPanePlugin;
+use bevy_code_editor::CodeEditorPanePlugin;
 
-use crate::load_gltf::LoadGltfPlugin;
+use crate::{load_gltf::LoadGltfPlugin, spawn_asset::SpawnAssetPlugin, undo::UndoShortcutsPlugin};
 
 mod load_gltf;
 pub mod project;
+mod spawn_asset;
 mod ui;
+mod undo;
 
 /// The plugin that handle the bare minimum to run the application
 pub struct RuntimePlugin;
@@ -50,14 +55,19 @@ impl Plugin for EditorPlugin {
         info!("Loading Bevy Editor");
         bevy_app
             .add_plugins((
+                CommandPalettePlugin,
                 EditorCorePlugin,
+                EditorSettingsPlugin,
                 ContextMenuPlugin,
                 StylesPlugin,
                 Viewport2dPanePlugin,
                 Viewport3dPanePlugin,
                 ui::EditorUIPlugin,
                 AssetBrowserPanePlugin,
+                CodeEditorPanePlugin,
                 LoadGltfPlugin,
+                SpawnAssetPlugin,
+                UndoShortcutsPlugin,
             ))
             .add_systems(Startup, build_scene);
     }
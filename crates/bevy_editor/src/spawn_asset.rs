@@ -0,0 +1,361 @@
+//! Spawning assets into the scene when they are dropped onto a viewport or the scene tree.
+
+use std::sync::Arc;
+
+use bevy::{asset::AssetPath, image::ImageLoader, platform::collections::HashMap, prelude::*};
+use bevy_editor_core::{SceneRootMarker, SelectedEntity, SpawnAsset};
+use bevy_proto_bsn::{BsnPlugin, ConstructHandle, Prefab, ReflectedBsn};
+use bevy_undo::{get_entity_with_remap, ChangeResult, EditorChange, NewChange, UndoPlugin};
+
+pub(crate) struct SpawnAssetPlugin;
+
+impl Plugin for SpawnAssetPlugin {
+    fn build(&self, app: &mut App) {
+        if !app.is_plugin_added::<BsnPlugin>() {
+            app.add_plugins(BsnPlugin);
+        }
+        if !app.is_plugin_added::<UndoPlugin>() {
+            app.add_plugins(UndoPlugin);
+        }
+
+        app.add_systems(Update, spawn_assets);
+    }
+}
+
+/// The kinds of assets that can be spawned into the scene.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+enum SpawnableAsset {
+    /// A glTF scene, the first scene of the file if the path has no label.
+    Scene,
+    /// A `.bsn` file, spawned as a [`Prefab`].
+    Prefab,
+    /// An image, spawned as a sprite in 2D and as a textured quad in 3D.
+    Image,
+    /// A glTF mesh, like `model.glb#Mesh0`.
+    Mesh,
+}
+
+impl SpawnableAsset {
+    fn of(path: &AssetPath) -> Option<Self> {
+        if let Some(label) = path.label() {
+            return if label.starts_with("Scene") {
+                Some(SpawnableAsset::Scene)
+            } else if label.starts_with("Mesh") {
+                Some(SpawnableAsset::Mesh)
+            } else {
+                None
+            };
+        }
+        let extension = path.path().extension()?.to_str()?.to_ascii_lowercase();
+        match extension.as_str() {
+            "gltf" | "glb" => Some(SpawnableAsset::Scene),
+            "bsn" | "proto_bsn" => Some(SpawnableAsset::Prefab),
+            extension if ImageLoader::SUPPORTED_FILE_EXTENSIONS.contains(&extension) => {
+                Some(SpawnableAsset::Image)
+            }
+            _ => None,
+        }
+    }
+}
+
+fn spawn_assets(mut commands: Commands, mut events: EventReader<SpawnAsset>) {
+    for request in events.read() {
+        let request = request.clone();
+        commands.queue(move |world: &mut World| {
+            let Some(change) = SpawnedAsset::spawn(world, request) else {
+                return;
+            };
+            world.resource_mut::<SelectedEntity>().0 = Some(change.entity);
+            world.send_event(NewChange::new(change));
+        });
+    }
+}
+
+/// An undoable spawn of an asset.
+#[derive(Clone)]
+struct SpawnedAsset {
+    entity: Entity,
+    /// The spawn request, with the parent and transform resolved to the scene root.
+    request: SpawnAsset,
+    /// Whether reverting this change spawns the entity again instead of despawning it.
+    respawn: bool,
+}
+
+impl SpawnedAsset {
+    fn spawn(world: &mut World, mut request: SpawnAsset) -> Option<SpawnedAsset> {
+        if request.parent.is_none() {
+            let scene_root = world
+                .query_filtered::<(Entity, &GlobalTransform), With<SceneRootMarker>>()
+                .iter(world)
+                .next()
+                .map(|(entity, transform)| (entity, *transform));
+            if let Some((scene_root, root_transform)) = scene_root {
+                request.parent = Some(scene_root);
+                request.transform =
+                    GlobalTransform::from(request.transform).reparented_to(&root_transform);
+            }
+        }
+
+        let Some(entity) = spawn_asset(world, &request) else {
+            warn!(
+                "Assets like {} can't be spawned into the scene",
+                request.path
+            );
+            return None;
+        };
+        Some(SpawnedAsset {
+            entity,
+            request,
+            respawn: false,
+        })
+    }
+}
+
+impl EditorChange for SpawnedAsset {
+    fn revert(
+        &self,
+        world: &mut World,
+        entity_remap: &HashMap<Entity, Entity>,
+    ) -> Result<ChangeResult, String> {
+        let entity = get_entity_with_remap(self.entity, entity_remap);
+        if !self.respawn {
+            if let Ok(entity) = world.get_entity_mut(entity) {
+                entity.despawn();
+            }
+            return Ok(ChangeResult::Success);
+        }
+
+        let mut request = self.request.clone();
+        request.parent = request
+            .parent
+            .map(|parent| get_entity_with_remap(parent, entity_remap));
+        let new_entity = spawn_asset(world, &request)
+            .ok_or_else(|| format!("Failed to spawn {} again", request.path))?;
+        Ok(ChangeResult::SuccessWithRemap(vec![(
+            self.entity,
+            new_entity,
+        )]))
+    }
+
+    fn debug_text(&self) -> String {
+        format!("Spawned {}", self.request.path)
+    }
+
+    fn get_inverse(&self) -> Arc<dyn EditorChange + Send + Sync> {
+        Arc::new(SpawnedAsset {
+            respawn: !self.respawn,
+            ..self.clone()
+        })
+    }
+}
+
+/// Spawn the entity for the asset of `request`, or return `None` if the asset can't be spawned.
+fn spawn_asset(world: &mut World, request: &SpawnAsset) -> Option<Entity> {
+    let kind = SpawnableAsset::of(&request.path)?;
+    let asset_server = world.resource::<AssetServer>().clone();
+    let path = request.path.clone();
+    let name = path
+        .label()
+        .map(ToString::to_string)
+        .or_else(|| {
+            let stem = path.path().file_stem()?;
+            Some(stem.to_string_lossy().into_owned())
+        })
+        .unwrap_or_default();
+
+    let entity = world
+        .spawn((Name::new(name), request.transform, Visibility::default()))
+        .id();
+    match kind {
+        SpawnableAsset::Scene => {
+            let path = if path.label().is_some() {
+                path
+            } else {
+                GltfAssetLabel::Scene(0).from_asset(path)
+            };
+            world
+                .entity_mut(entity)
+                .insert(SceneRoot(asset_server.load(path)));
+        }
+        SpawnableAsset::Prefab => {
+            let bsn = asset_server.load::<ReflectedBsn>(path);
+            world
+                .entity_mut(entity)
+                .insert(Prefab(ConstructHandle(bsn)));
+        }
+        SpawnableAsset::Image if request.in_2d => {
+            world
+                .entity_mut(entity)
+                .insert(Sprite::from_image(asset_server.load(path)));
+        }
+        SpawnableAsset::Image => {
+            let mesh = world
+                .resource_mut::<Assets<Mesh>>()
+                .add(Rectangle::default());
+            let material = world
+                .resource_mut::<Assets<StandardMaterial>>()
+                .add(StandardMaterial {
+                    base_color_texture: Some(asset_server.load(path)),
+                    alpha_mode: AlphaMode::Blend,
+                    cull_mode: None,
+                    ..default()
+                });
+            world
+                .entity_mut(entity)
+                .insert((Mesh3d(mesh), MeshMaterial3d(material)));
+        }
+        SpawnableAsset::Mesh => {
+            // `MeshN` labels a glTF mesh, which is made of one or more primitives
+            let path = match path.label() {
+                Some(label) if !label.contains('/') => {
+                    let label = format!("{label}/Primitive0");
+                    path.with_label(label)
+                }
+                _ => path,
+            };
+            let material = world
+                .resource_mut::<Assets<StandardMaterial>>()
+                .add(StandardMaterial::default());
+            world
+                .entity_mut(entity)
+                .insert((Mesh3d(asset_server.load(path)), MeshMaterial3d(material)));
+        }
+    }
+
+    if let Some(parent) = request
+        .parent
+        .filter(|parent| world.get_entity(*parent).is_ok())
+    {
+        world.entity_mut(entity).insert(ChildOf(parent));
+    }
+    Some(entity)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use bevy_undo::UndoRedo;
+
+    fn app() -> App {
+        let mut app = App::new();
+        app.add_plugins((MinimalPlugins, AssetPlugin::default(), UndoPlugin))
+            .init_asset::<Image>()
+            .init_asset::<Mesh>()
+            .init_asset::<StandardMaterial>()
+            .init_asset::<Scene>()
+            .init_asset::<ReflectedBsn>()
+            .init_resource::<SelectedEntity>()
+            .add_event::<SpawnAsset>()
+            .add_systems(Update, spawn_assets);
+        app
+    }
+
+    /// Spawn the asset at `path`, returning the selected spawned entity
+    fn spawn(app: &mut App, path: &str, in_2d: bool) -> Option<Entity> {
+        app.world_mut().send_event(SpawnAsset {
+            path: AssetPath::parse(path).into_owned(),
+            transform: Transform::from_xyz(1.0, 2.0, 3.0),
+            parent: None,
+            in_2d,
+        });
+        app.update();
+        app.world_mut().resource_mut::<SelectedEntity>().0.take()
+    }
+
+    fn loaded_path<A: Asset>(handle: &Handle<A>) -> String {
+        handle.path().unwrap().to_string()
+    }
+
+    #[test]
+    fn spawns_each_kind_of_asset() {
+        let mut app = app();
+
+        let scene = spawn(&mut app, "models/alien.glb", false).unwrap();
+        let world = app.world();
+        assert_eq!(world.get::<Name>(scene).unwrap().as_str(), "alien");
+        assert_eq!(
+            world.get::<Transform>(scene).unwrap().translation,
+            Vec3::new(1.0, 2.0, 3.0)
+        );
+        assert_eq!(
+            loaded_path(&world.get::<SceneRoot>(scene).unwrap().0),
+            "models/alien.glb#Scene0"
+        );
+
+        let mesh = spawn(&mut app, "models/alien.glb#Mesh1", false).unwrap();
+        let world = app.world();
+        assert_eq!(world.get::<Name>(mesh).unwrap().as_str(), "Mesh1");
+        assert_eq!(
+            loaded_path(&world.get::<Mesh3d>(mesh).unwrap().0),
+            "models/alien.glb#Mesh1/Primitive0"
+        );
+        assert!(world
+            .get::<MeshMaterial3d<StandardMaterial>>(mesh)
+            .is_some());
+
+        let prefab = spawn(&mut app, "scenes/door.bsn", false).unwrap();
+        assert!(app.world().get::<Prefab>(prefab).is_some());
+
+        let sprite = spawn(&mut app, "textures/wall.png", true).unwrap();
+        let world = app.world();
+        assert_eq!(
+            loaded_path(&world.get::<Sprite>(sprite).unwrap().image),
+            "textures/wall.png"
+        );
+
+        let quad = spawn(&mut app, "textures/wall.png", false).unwrap();
+        let world = app.world();
+        assert!(world.get::<Sprite>(quad).is_none());
+        let material = &world
+            .get::<MeshMaterial3d<StandardMaterial>>(quad)
+            .unwrap()
+            .0;
+        let texture = world
+            .resource::<Assets<StandardMaterial>>()
+            .get(material)
+            .unwrap();
+        assert_eq!(
+            loaded_path(texture.base_color_texture.as_ref().unwrap()),
+            "textures/wall.png"
+        );
+
+        assert_eq!(spawn(&mut app, "notes.txt", false), None);
+        assert_eq!(spawn(&mut app, "models/alien.glb#Animation0", false), None);
+    }
+
+    #[test]
+    fn undoes_the_spawn() {
+        let mut app = app();
+        let scene_root = app
+            .world_mut()
+            .spawn((
+                SceneRootMarker,
+                Transform::from_xyz(10.0, 0.0, 0.0),
+                GlobalTransform::from_xyz(10.0, 0.0, 0.0),
+            ))
+            .id();
+
+        let entity = spawn(&mut app, "models/alien.glb", false).unwrap();
+        let world = app.world();
+        assert_eq!(world.get::<ChildOf>(entity).unwrap().parent(), scene_root);
+        assert_eq!(
+            world.get::<Transform>(entity).unwrap().translation,
+            Vec3::new(-9.0, 2.0, 3.0)
+        );
+
+        app.world_mut().send_event(UndoRedo::Undo);
+        app.update();
+        assert!(app.world().get_entity(entity).is_err());
+
+        app.world_mut().send_event(UndoRedo::Redo);
+        app.update();
+        let world = app.world_mut();
+        let (respawned, child_of, transform) = world
+            .query_filtered::<(Entity, &ChildOf, &Transform), With<SceneRoot>>()
+            .single(world)
+            .unwrap();
+        assert_ne!(respawned, entity);
+        assert_eq!(child_of.parent(), scene_root);
+        assert_eq!(transform.translation, Vec3::new(-9.0, 2.0, 3.0));
+    }
+}
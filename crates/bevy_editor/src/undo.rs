@@ -0,0 +1,129 @@
+//! Keyboard shortcuts and commands for undoing and redoing changes made in the editor.
+
+use bevy::prelude::*;
+use bevy_command_palette::{CommandInfo, CommandPaletteAppExt, CommandPalettePlugin};
+use bevy_focus::Focus;
+use bevy_text_editing::{
+    history::HandlesUndo,
+    keymap::{Modifiers, TextEditAction, TextEditKeymap},
+};
+use bevy_undo::{UndoPlugin, UndoRedo};
+
+pub(crate) struct UndoShortcutsPlugin;
+
+impl Plugin for UndoShortcutsPlugin {
+    fn build(&self, app: &mut App) {
+        if !app.is_plugin_added::<UndoPlugin>() {
+            app.add_plugins(UndoPlugin);
+        }
+
+        if !app.is_plugin_added::<CommandPalettePlugin>() {
+            app.add_plugins(CommandPalettePlugin);
+        }
+
+        // The chords are handled by `undo_redo_shortcuts`, so the commands have no keybinding
+        app.init_resource::<TextEditKeymap>()
+            .register_command(
+                CommandInfo::new("edit.undo", "Undo").with_category("Edit"),
+                |mut undo_redo: EventWriter<UndoRedo>| {
+                    undo_redo.write(UndoRedo::Undo);
+                },
+            )
+            .register_command(
+                CommandInfo::new("edit.redo", "Redo").with_category("Edit"),
+                |mut undo_redo: EventWriter<UndoRedo>| {
+                    undo_redo.write(UndoRedo::Redo);
+                },
+            )
+            .add_systems(Update, undo_redo_shortcuts);
+    }
+}
+
+/// Undo and redo with the chords of the [`TextEditKeymap`], `Ctrl+Z` and `Ctrl+Shift+Z` or
+/// `Ctrl+Y` by default.
+/// Focused widgets with their own history, like text fields and the code editor, undo their own
+/// edits instead.
+fn undo_redo_shortcuts(
+    keyboard_input: Res<ButtonInput<KeyCode>>,
+    keymap: Res<TextEditKeymap>,
+    focused_texts: Query<(), (With<Focus>, With<HandlesUndo>)>,
+    mut undo_redo: EventWriter<UndoRedo>,
+) {
+    if !focused_texts.is_empty() {
+        return;
+    }
+    let modifiers = Modifiers::pressed(&keyboard_input);
+    let bound = |action, key| {
+        keymap
+            .chords(action)
+            .iter()
+            .any(|chord| chord.matches(key, modifiers, false))
+    };
+    for &key in keyboard_input.get_just_pressed() {
+        if bound(TextEditAction::Undo, key) {
+            undo_redo.write(UndoRedo::Undo);
+        } else if bound(TextEditAction::Redo, key) {
+            undo_redo.write(UndoRedo::Redo);
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use bevy_text_editing::keymap::KeyChord;
+
+    fn pressed_events(app: &mut App, keys: &[KeyCode]) -> Vec<&'static str> {
+        let mut input = app.world_mut().resource_mut::<ButtonInput<KeyCode>>();
+        input.reset_all();
+        for &key in keys {
+            input.press(key);
+        }
+        app.world_mut()
+            .run_system_cached(undo_redo_shortcuts)
+            .unwrap();
+        app.world_mut()
+            .resource_mut::<Events<UndoRedo>>()
+            .drain()
+            .map(|event| match event {
+                UndoRedo::Undo => "undo",
+                UndoRedo::Redo => "redo",
+            })
+            .collect()
+    }
+
+    #[test]
+    fn shortcuts_follow_the_keymap() {
+        let mut app = App::new();
+        app.add_event::<UndoRedo>()
+            .init_resource::<ButtonInput<KeyCode>>()
+            .init_resource::<TextEditKeymap>();
+        let primary = if cfg!(target_os = "macos") {
+            KeyCode::SuperLeft
+        } else {
+            KeyCode::ControlLeft
+        };
+
+        assert_eq!(
+            pressed_events(&mut app, &[primary, KeyCode::KeyZ]),
+            ["undo"]
+        );
+        assert_eq!(
+            pressed_events(&mut app, &[primary, KeyCode::ShiftLeft, KeyCode::KeyZ]),
+            ["redo"]
+        );
+        assert!(pressed_events(&mut app, &[KeyCode::KeyZ]).is_empty());
+
+        let mut keymap = app.world_mut().resource_mut::<TextEditKeymap>();
+        keymap.undo = vec![KeyChord::new(KeyCode::KeyU).with_alt()];
+        assert!(pressed_events(&mut app, &[primary, KeyCode::KeyZ]).is_empty());
+        assert_eq!(
+            pressed_events(&mut app, &[KeyCode::AltRight, KeyCode::KeyU]),
+            ["undo"]
+        );
+
+        // Text fields undo their own edits
+        app.world_mut().spawn((Focus, HandlesUndo));
+        assert!(pressed_events(&mut app, &[KeyCode::AltRight, KeyCode::KeyU]).is_empty());
+    }
+}
@@ -1,5 +1,6 @@
 //! A `bevy_editor_cam` extension that adds the ability to smoothly rotate the camera about its
-//! anchor point until it is looking in the specified direction.
+//! anchor point until it is looking in the specified direction, or to smoothly fly the camera to a
+//! new position and orientation.
 
 use std::{f32::consts::PI, time::Duration};
 
@@ -21,11 +22,12 @@ impl Plugin for LookToPlugin {
     fn build(&self, app: &mut App) {
         app.init_resource::<LookTo>()
             .add_event::<LookToTrigger>()
+            .add_event::<FlyToTrigger>()
             .add_systems(
                 PreUpdate,
                 LookTo::update.before(EditorCam::update_camera_positions),
             )
-            .add_systems(PostUpdate, LookToTrigger::receive) // In PostUpdate so we don't miss users sending this in Update. LookTo::update will catch the changes next frame.
+            .add_systems(PostUpdate, (LookToTrigger::receive, FlyToTrigger::receive)) // In PostUpdate so we don't miss users sending this in Update. LookTo::update will catch the changes next frame.
             .register_type::<LookTo>();
     }
 }
@@ -114,6 +116,7 @@ impl LookToTrigger {
                     e.initial_up_direction = transform.up();
                     e.target_facing_direction = event.target_facing_direction;
                     e.target_up_direction = event.target_up_direction;
+                    e.fly_to = None;
                     e.complete = false;
                 })
                 .or_insert(LookToEntry {
@@ -122,6 +125,7 @@ impl LookToTrigger {
                     initial_up_direction: transform.up(),
                     target_facing_direction: event.target_facing_direction,
                     target_up_direction: event.target_up_direction,
+                    fly_to: None,
                     complete: false,
                 });
 
@@ -131,15 +135,92 @@ impl LookToTrigger {
     }
 }
 
+/// Send this event to smoothly move the camera to the given position and orientation. Animation
+/// speed is configured with the [`LookTo`] resource.
+///
+/// During the animation, the camera orbits about a point that moves from its current anchor to the
+/// target anchor, which is `target_anchor_depth` in front of the target transform. This keeps the
+/// thing the user is looking at in view, instead of swinging the view about the camera's position.
+#[derive(Debug, Event)]
+pub struct FlyToTrigger {
+    /// The position and orientation of the camera when finished moving. Scale is ignored.
+    pub target_transform: Transform,
+    /// The distance from the target position to the anchor the camera will be orbiting about when
+    /// finished moving, see [`EditorCam::last_anchor_depth`].
+    pub target_anchor_depth: f64,
+    /// The camera to update.
+    pub camera: Entity,
+}
+
+impl FlyToTrigger {
+    fn receive(
+        mut events: EventReader<Self>,
+        mut state: ResMut<LookTo>,
+        mut cameras: Query<(&mut EditorCam, &Transform)>,
+        mut redraw: EventWriter<RequestRedraw>,
+    ) {
+        for event in events.read() {
+            let Ok((mut controller, transform)) = cameras.get_mut(event.camera) else {
+                continue;
+            };
+            redraw.write(RequestRedraw);
+
+            let initial_anchor_depth = controller.last_anchor_depth();
+            let target_anchor_depth = -event.target_anchor_depth.abs();
+            let fly_to = FlyTo {
+                initial_anchor: view_to_world(
+                    transform,
+                    DVec3::new(0.0, 0.0, initial_anchor_depth),
+                ),
+                target_anchor: view_to_world(
+                    &event.target_transform,
+                    DVec3::new(0.0, 0.0, target_anchor_depth),
+                ),
+                initial_anchor_depth,
+                target_anchor_depth,
+            };
+
+            state.map.insert(
+                event.camera,
+                LookToEntry {
+                    start: Instant::now(),
+                    initial_facing_direction: transform.forward(),
+                    initial_up_direction: transform.up(),
+                    target_facing_direction: event.target_transform.forward(),
+                    target_up_direction: event.target_transform.up(),
+                    fly_to: Some(fly_to),
+                    complete: false,
+                },
+            );
+
+            controller.end_move();
+            controller.current_motion = motion::CurrentMotion::Stationary;
+        }
+    }
+}
+
+fn view_to_world(transform: &Transform, view_space: DVec3) -> DVec3 {
+    transform.rotation.as_dquat() * view_space + transform.translation.as_dvec3()
+}
+
 struct LookToEntry {
     start: Instant,
     initial_facing_direction: Dir3,
     initial_up_direction: Dir3,
     target_facing_direction: Dir3,
     target_up_direction: Dir3,
+    fly_to: Option<FlyTo>,
     complete: bool,
 }
 
+/// The anchor points to interpolate between when flying to a new position.
+struct FlyTo {
+    initial_anchor: DVec3,
+    target_anchor: DVec3,
+    initial_anchor_depth: f64,
+    target_anchor_depth: f64,
+}
+
 /// Stores settings and state for the dolly zoom plugin.
 #[derive(Resource, Reflect)]
 pub struct LookTo {
@@ -165,7 +246,7 @@ impl Default for LookTo {
 impl LookTo {
     fn update(
         mut state: ResMut<Self>,
-        mut cameras: Query<(&mut Transform, &EditorCam)>,
+        mut cameras: Query<(&mut Transform, &mut EditorCam)>,
         mut redraw: EventWriter<RequestRedraw>,
     ) {
         let animation_duration = state.animation_duration;
@@ -178,11 +259,12 @@ impl LookTo {
                 initial_up_direction,
                 target_facing_direction,
                 target_up_direction,
+                fly_to,
                 complete,
             },
         ) in state.map.iter_mut()
         {
-            let Ok((mut transform, controller)) = cameras.get_mut(*camera) else {
+            let Ok((mut transform, mut controller)) = cameras.get_mut(*camera) else {
                 *complete = true;
                 continue;
             };
@@ -218,10 +300,22 @@ impl LookTo {
                 .rotation;
 
             let rot_next = rot_init.slerp(rot_target, progress);
-            let rot_last = transform.rotation;
-            let rot_delta = rot_next * rot_last.inverse();
 
-            rotate_around(&mut transform, anchor_world, rot_delta.as_dquat());
+            if let Some(fly_to) = fly_to {
+                let progress = progress as f64;
+                let anchor = fly_to.initial_anchor.lerp(fly_to.target_anchor, progress);
+                let depth = fly_to.initial_anchor_depth
+                    + (fly_to.target_anchor_depth - fly_to.initial_anchor_depth) * progress;
+                transform.rotation = rot_next.normalize();
+                transform.translation =
+                    (anchor - rot_next.as_dquat() * DVec3::new(0.0, 0.0, depth)).as_vec3();
+                controller.last_anchor_depth = depth;
+            } else {
+                let rot_last = transform.rotation;
+                let rot_delta = rot_next * rot_last.inverse();
+
+                rotate_around(&mut transform, anchor_world, rot_delta.as_dquat());
+            }
 
             if progress_t >= 1.0 {
                 *complete = true;
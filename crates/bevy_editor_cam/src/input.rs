@@ -1,5 +1,7 @@
 //! Provides a default input plugin for the camera. See [`DefaultInputPlugin`].
 
+pub mod touch;
+
 use bevy::input::{
     mouse::{MouseScrollUnit, MouseWheel},
     prelude::*,
@@ -18,6 +20,8 @@ use bevy::picking::pointer::{PointerId, PointerInteraction, PointerLocation, Poi
 
 use crate::prelude::{component::EditorCam, inputs::MotionInputs};
 
+use touch::TouchGestures;
+
 /// The type of mutually exclusive camera motion.
 #[derive(Debug, Clone, Copy, Reflect, PartialEq, Eq)]
 pub enum MotionKind {
@@ -49,6 +53,7 @@ impl Plugin for DefaultInputPlugin {
     fn build(&self, app: &mut App) {
         app.add_event::<EditorCamInputEvent>()
             .init_resource::<CameraPointerMap>()
+            .init_resource::<TouchGestures>()
             .add_systems(
                 PreUpdate,
                 (
@@ -61,17 +66,25 @@ impl Plugin for DefaultInputPlugin {
                     .before(EditorCam::update_camera_positions),
             )
             .register_type::<CameraPointerMap>()
+            .register_type::<TouchGestures>()
             .register_type::<EditorCamInputEvent>();
     }
 }
 
-/// A default implementation of an input system
+/// A default implementation of an input system.
+///
+/// The mouse orbits with the right button, pans with the left button, and zooms with the scroll
+/// wheel. Touch inputs are recognized with [`TouchGestures`]: one finger or a pen orbits, two
+/// fingers pan, and pinching zooms.
+#[expect(clippy::too_many_arguments)]
 pub fn default_camera_inputs(
     pointers: Query<(&PointerId, &PointerLocation)>,
     pointer_map: Res<CameraPointerMap>,
     mut controller: EventWriter<EditorCamInputEvent>,
     mut mouse_wheel: EventReader<MouseWheel>,
     mouse_input: Res<ButtonInput<MouseButton>>,
+    mut pointer_inputs: EventReader<PointerInput>,
+    mut touch_gestures: ResMut<TouchGestures>,
     cameras: Query<(Entity, &Camera, &EditorCam)>,
     primary_window: Query<Entity, With<PrimaryWindow>>,
 ) {
@@ -135,10 +148,43 @@ pub fn default_camera_inputs(
                     });
                 }
             }
+            // Touch pointers are handled by the gesture recognizer below.
             PointerId::Touch(_) | PointerId::Custom(_) => continue,
         }
     }
 
+    let transition = touch_gestures.update(pointer_inputs.read());
+    if let Some(&camera) = transition
+        .ended
+        .and_then(|gesture| pointer_map.get(&gesture.pointer))
+    {
+        controller.write(EditorCamInputEvent::End { camera });
+    }
+
+    // Keep trying to start the gesture until the camera accepts it. The touch pointer may not have
+    // been spawned yet when the finger is first placed, and the camera ignores new motions for a
+    // short time after the previous one ends, which happens when switching from orbit to pan.
+    if let Some(gesture) = touch_gestures
+        .gesture()
+        .filter(|gesture| !pointer_map.contains_key(&gesture.pointer))
+        .filter(|gesture| pointers.iter().any(|(&id, _)| id == gesture.pointer))
+    {
+        if let Some((camera, ..)) = touch_gestures
+            .location(gesture.pointer)
+            .and_then(|location| {
+                cameras
+                    .iter()
+                    .find(|(_, camera, _)| location.is_in_viewport(camera, &primary_window))
+            })
+        {
+            controller.write(EditorCamInputEvent::Start {
+                kind: gesture.kind,
+                camera,
+                pointer: gesture.pointer,
+            });
+        }
+    }
+
     // This must be cleared manually because reading these inputs is conditional - we are not
     // guaranteed to be flushing the events every frame.
     mouse_wheel.clear();
@@ -269,6 +315,7 @@ impl EditorCamInputEvent {
     /// movement information.
     pub fn send_pointer_inputs(
         camera_map: Res<CameraPointerMap>,
+        touch_gestures: Res<TouchGestures>,
         mut camera_controllers: Query<&mut EditorCam>,
         mut mouse_wheel: EventReader<MouseWheel>,
         mut moves: EventReader<PointerInput>,
@@ -279,6 +326,15 @@ impl EditorCamInputEvent {
                 continue;
             };
 
+            // Touch gestures combine the motion of several fingers into a single input.
+            if pointer.is_touch() {
+                if touch_gestures.gesture().map(|gesture| gesture.pointer) == Some(*pointer) {
+                    camera_controller.send_screenspace_input(touch_gestures.screenspace_delta());
+                    camera_controller.send_zoom_input(touch_gestures.zoom_delta());
+                }
+                continue;
+            }
+
             let screenspace_input = moves_list
                 .iter()
                 .filter(|m| m.pointer_id.eq(pointer))
@@ -292,7 +348,6 @@ impl EditorCamInputEvent {
                 .sum();
 
             let zoom_amount = match pointer {
-                // TODO: add pinch zoom support, probably in bevy_picking
                 PointerId::Mouse => mouse_wheel
                     .read()
                     .map(|mw| {
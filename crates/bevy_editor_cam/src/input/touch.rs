@@ -0,0 +1,332 @@
+//! Touch gesture recognition for the default input plugin. See [`TouchGestures`].
+
+use bevy::ecs::prelude::*;
+use bevy::math::prelude::*;
+use bevy::picking::pointer::{Location, PointerAction, PointerId, PointerInput};
+use bevy::reflect::prelude::*;
+
+use super::MotionKind;
+
+/// Converts the relative change in distance between two fingers into zoom input.
+///
+/// This matches the scaling the controller applies to orthographic zoom inputs, so that pinching
+/// keeps the content under the fingers at roughly the same size as the fingers move.
+const PINCH_ZOOM_SCALE: f32 = 1.0 / 0.0015;
+
+/// Recognizes camera gestures from touch [`PointerInput`]s.
+///
+/// - One finger (or a pen, which is reported as a touch) orbits about the point under it.
+/// - Two fingers pan with the midpoint between them, and pinching zooms.
+///
+/// When a second finger is placed while orbiting, the orbit ends and a pan is started. When one of
+/// two fingers is lifted, the pan ends and no new gesture starts until all fingers are lifted, to
+/// prevent the camera from jumping when the user lets go one finger at a time.
+///
+/// The first finger placed is the *primary* pointer of the gesture. This is the pointer used in
+/// [`EditorCamInputEvent::Start`](super::EditorCamInputEvent::Start), and is the key of the gesture
+/// in the [`CameraPointerMap`](super::CameraPointerMap).
+#[derive(Debug, Clone, Default, Reflect, Resource)]
+pub struct TouchGestures {
+    /// Touches that are currently down, in the order they were placed.
+    touches: Vec<TouchPoint>,
+    /// The gesture recognized from the current touches, if any.
+    gesture: Option<TouchGesture>,
+    /// Set after a two finger gesture ends while a finger is still down.
+    wait_for_release: bool,
+    /// Screen space motion of the current gesture since the last update.
+    screenspace_delta: Vec2,
+    /// Zoom input of the current gesture since the last update.
+    zoom_delta: f32,
+}
+
+/// A touch that is currently down.
+#[derive(Debug, Clone, Reflect)]
+pub struct TouchPoint {
+    /// The pointer of this touch.
+    pub pointer: PointerId,
+    /// The latest location of this touch.
+    pub location: Location,
+}
+
+/// A camera gesture recognized by [`TouchGestures`].
+#[derive(Debug, Clone, Copy, Reflect, PartialEq, Eq)]
+pub struct TouchGesture {
+    /// The kind of camera motion this gesture controls.
+    pub kind: MotionKind,
+    /// The first finger of the gesture, used to start the camera motion.
+    pub pointer: PointerId,
+}
+
+/// The change in the recognized gesture after a call to [`TouchGestures::update`].
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+pub struct GestureTransition {
+    /// The gesture that ended, if any.
+    pub ended: Option<TouchGesture>,
+    /// The gesture that started, if any.
+    pub started: Option<TouchGesture>,
+}
+
+impl TouchGestures {
+    /// Update the recognizer with the latest pointer inputs. Inputs from non-touch pointers are
+    /// ignored.
+    ///
+    /// This should be called once per frame, with all inputs received since the last call.
+    pub fn update<'a>(
+        &mut self,
+        inputs: impl IntoIterator<Item = &'a PointerInput>,
+    ) -> GestureTransition {
+        let previous: Vec<(PointerId, Vec2)> = self
+            .touches
+            .iter()
+            .map(|touch| (touch.pointer, touch.location.position))
+            .collect();
+
+        for input in inputs
+            .into_iter()
+            .filter(|input| input.pointer_id.is_touch())
+        {
+            let index = self
+                .touches
+                .iter()
+                .position(|touch| touch.pointer == input.pointer_id);
+            match (input.action, index) {
+                (PointerAction::Press(_), None) => self.touches.push(TouchPoint {
+                    pointer: input.pointer_id,
+                    location: input.location.clone(),
+                }),
+                (PointerAction::Move { .. }, Some(index)) => {
+                    self.touches[index].location = input.location.clone();
+                }
+                (PointerAction::Release(_) | PointerAction::Cancel, Some(index)) => {
+                    self.touches.remove(index);
+                }
+                _ => (),
+            }
+        }
+
+        let was_pan_zoom = matches!(
+            self.gesture,
+            Some(TouchGesture {
+                kind: MotionKind::PanZoom,
+                ..
+            })
+        );
+        if self.touches.is_empty() {
+            self.wait_for_release = false;
+        } else if was_pan_zoom && self.touches.len() == 1 {
+            self.wait_for_release = true;
+        }
+
+        let next = match self.touches.as_slice() {
+            [] => None,
+            [_] if self.wait_for_release => None,
+            [touch] => Some(TouchGesture {
+                kind: MotionKind::OrbitZoom,
+                pointer: touch.pointer,
+            }),
+            [first, ..] => Some(TouchGesture {
+                kind: MotionKind::PanZoom,
+                pointer: first.pointer,
+            }),
+        };
+
+        self.screenspace_delta = Vec2::ZERO;
+        self.zoom_delta = 0.0;
+
+        if next != self.gesture {
+            let transition = GestureTransition {
+                ended: self.gesture,
+                started: next,
+            };
+            self.gesture = next;
+            return transition;
+        }
+
+        let previous_position = |touch: &TouchPoint| {
+            previous
+                .iter()
+                .find(|(pointer, _)| *pointer == touch.pointer)
+                .map(|(_, position)| *position)
+        };
+
+        match self.touches.as_slice() {
+            [touch] if self.gesture.is_some() => {
+                if let Some(last) = previous_position(touch) {
+                    self.screenspace_delta = touch.location.position - last;
+                }
+            }
+            [a, b, ..] => {
+                // If either finger was just placed, skip this update so the camera doesn't jump.
+                let (Some(last_a), Some(last_b)) = (previous_position(a), previous_position(b))
+                else {
+                    return GestureTransition::default();
+                };
+                let (a, b) = (a.location.position, b.location.position);
+                self.screenspace_delta = (a + b) / 2.0 - (last_a + last_b) / 2.0;
+
+                let spread = a.distance(b);
+                let last_spread = last_a.distance(last_b);
+                if spread > f32::EPSILON && last_spread > f32::EPSILON {
+                    self.zoom_delta = (spread / last_spread).ln() * PINCH_ZOOM_SCALE;
+                }
+            }
+            _ => (),
+        }
+
+        GestureTransition::default()
+    }
+
+    /// The gesture currently recognized, if any.
+    pub fn gesture(&self) -> Option<TouchGesture> {
+        self.gesture
+    }
+
+    /// The touches that are currently down, in the order they were placed.
+    pub fn touches(&self) -> &[TouchPoint] {
+        &self.touches
+    }
+
+    /// The latest location of the given touch pointer, if it is down.
+    pub fn location(&self, pointer: PointerId) -> Option<&Location> {
+        self.touches
+            .iter()
+            .find(|touch| touch.pointer == pointer)
+            .map(|touch| &touch.location)
+    }
+
+    /// Screen space motion of the current gesture since the last update. For a two finger gesture,
+    /// this is the motion of the midpoint between the fingers.
+    pub fn screenspace_delta(&self) -> Vec2 {
+        self.screenspace_delta
+    }
+
+    /// Zoom input of the current gesture since the last update, from pinching two fingers.
+    pub fn zoom_delta(&self) -> f32 {
+        self.zoom_delta
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use bevy::ecs::entity::Entity;
+    use bevy::picking::pointer::PointerButton;
+    use bevy::render::camera::RenderTarget;
+    use bevy::window::WindowRef;
+
+    use super::*;
+
+    fn location(position: Vec2) -> Location {
+        Location {
+            target: RenderTarget::Window(WindowRef::Entity(Entity::PLACEHOLDER))
+                .normalize(None)
+                .unwrap(),
+            position,
+        }
+    }
+
+    fn press(id: u64, x: f32, y: f32) -> PointerInput {
+        PointerInput::new(
+            PointerId::Touch(id),
+            location(Vec2::new(x, y)),
+            PointerAction::Press(PointerButton::Primary),
+        )
+    }
+
+    fn move_to(id: u64, x: f32, y: f32) -> PointerInput {
+        PointerInput::new(
+            PointerId::Touch(id),
+            location(Vec2::new(x, y)),
+            PointerAction::Move { delta: Vec2::ZERO },
+        )
+    }
+
+    fn release(id: u64, x: f32, y: f32) -> PointerInput {
+        PointerInput::new(
+            PointerId::Touch(id),
+            location(Vec2::new(x, y)),
+            PointerAction::Release(PointerButton::Primary),
+        )
+    }
+
+    fn gesture(kind: MotionKind, id: u64) -> Option<TouchGesture> {
+        Some(TouchGesture {
+            kind,
+            pointer: PointerId::Touch(id),
+        })
+    }
+
+    #[test]
+    fn one_finger_orbits() {
+        let mut gestures = TouchGestures::default();
+
+        let transition = gestures.update(&[press(0, 10.0, 10.0)]);
+        assert_eq!(transition.started, gesture(MotionKind::OrbitZoom, 0));
+        assert_eq!(transition.ended, None);
+
+        let transition = gestures.update(&[move_to(0, 15.0, 8.0)]);
+        assert_eq!(transition, GestureTransition::default());
+        assert_eq!(gestures.screenspace_delta(), Vec2::new(5.0, -2.0));
+        assert_eq!(gestures.zoom_delta(), 0.0);
+
+        let transition = gestures.update(&[release(0, 15.0, 8.0)]);
+        assert_eq!(transition.ended, gesture(MotionKind::OrbitZoom, 0));
+        assert_eq!(transition.started, None);
+        assert_eq!(gestures.gesture(), None);
+    }
+
+    #[test]
+    fn two_fingers_pan_and_pinch() {
+        let mut gestures = TouchGestures::default();
+        gestures.update(&[press(0, 0.0, 0.0)]);
+
+        let transition = gestures.update(&[press(1, 100.0, 0.0)]);
+        assert_eq!(transition.ended, gesture(MotionKind::OrbitZoom, 0));
+        assert_eq!(transition.started, gesture(MotionKind::PanZoom, 0));
+
+        // Both fingers move the same amount: pure pan of the midpoint.
+        gestures.update(&[move_to(0, 10.0, 5.0), move_to(1, 110.0, 5.0)]);
+        assert_eq!(gestures.screenspace_delta(), Vec2::new(10.0, 5.0));
+        assert_eq!(gestures.zoom_delta(), 0.0);
+
+        // Fingers spread apart symmetrically: pure zoom in.
+        gestures.update(&[move_to(0, -40.0, 5.0), move_to(1, 160.0, 5.0)]);
+        assert_eq!(gestures.screenspace_delta(), Vec2::ZERO);
+        assert!(gestures.zoom_delta() > 0.0);
+
+        // Fingers pinch together: zoom out.
+        gestures.update(&[move_to(0, 10.0, 5.0), move_to(1, 110.0, 5.0)]);
+        assert!(gestures.zoom_delta() < 0.0);
+    }
+
+    #[test]
+    fn lifting_one_finger_waits_for_release() {
+        let mut gestures = TouchGestures::default();
+        gestures.update(&[press(0, 0.0, 0.0), press(1, 100.0, 0.0)]);
+        assert_eq!(gestures.gesture(), gesture(MotionKind::PanZoom, 0));
+
+        let transition = gestures.update(&[release(0, 0.0, 0.0)]);
+        assert_eq!(transition.ended, gesture(MotionKind::PanZoom, 0));
+        assert_eq!(transition.started, None);
+
+        // The remaining finger must not start orbiting.
+        gestures.update(&[move_to(1, 120.0, 0.0)]);
+        assert_eq!(gestures.gesture(), None);
+        assert_eq!(gestures.screenspace_delta(), Vec2::ZERO);
+
+        gestures.update(&[release(1, 120.0, 0.0)]);
+        let transition = gestures.update(&[press(2, 50.0, 50.0)]);
+        assert_eq!(transition.started, gesture(MotionKind::OrbitZoom, 2));
+    }
+
+    #[test]
+    fn ignores_mouse_inputs() {
+        let mut gestures = TouchGestures::default();
+        let transition = gestures.update(&[PointerInput::new(
+            PointerId::Mouse,
+            location(Vec2::ZERO),
+            PointerAction::Press(PointerButton::Primary),
+        )]);
+        assert_eq!(transition, GestureTransition::default());
+        assert!(gestures.touches().is_empty());
+    }
+}
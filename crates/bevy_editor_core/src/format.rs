@@ -0,0 +1,30 @@
+//! Formatting values for display in the editor, so every pane shows them the same way.
+
+/// Formats a file size with the largest fitting binary unit, like `12.3 KiB`.
+pub fn format_file_size(bytes: u64) -> String {
+    const UNITS: [&str; 5] = ["B", "KiB", "MiB", "GiB", "TiB"];
+    let mut size = bytes as f64;
+    let mut unit = 0;
+    while size >= 1024.0 && unit < UNITS.len() - 1 {
+        size /= 1024.0;
+        unit += 1;
+    }
+    if unit == 0 {
+        format!("{bytes} B")
+    } else {
+        format!("{size:.1} {}", UNITS[unit])
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn formats_file_sizes() {
+        assert_eq!(format_file_size(12), "12 B");
+        assert_eq!(format_file_size(1536), "1.5 KiB");
+        assert_eq!(format_file_size(5 * 1024 * 1024), "5.0 MiB");
+        assert_eq!(format_file_size(u64::MAX), "16777216.0 TiB");
+    }
+}
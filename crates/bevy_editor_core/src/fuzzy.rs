@@ -0,0 +1,73 @@
+//! Fuzzy matching, shared by the searches of the editor like the command palette and the asset
+//! browser.
+
+/// Bonus for a character matched at the start of a word.
+const WORD_START_BONUS: i32 = 8;
+
+/// Bonus for a character matched right after the previous one.
+const CONSECUTIVE_BONUS: i32 = 5;
+
+/// Scores how well `query` matches `text`, if all its characters appear in order, ignoring case
+/// and the whitespace of `query`.
+/// Matches at the start of words and runs of consecutive characters score higher.
+pub fn fuzzy_score(query: &str, text: &str) -> Option<i32> {
+    let query: Vec<char> = query
+        .chars()
+        .filter(|c| !c.is_whitespace())
+        .flat_map(char::to_lowercase)
+        .collect();
+    let text: Vec<char> = text.chars().collect();
+
+    let mut score = 0;
+    let mut matched = 0;
+    let mut previous: Option<usize> = None;
+    for (i, c) in text.iter().enumerate() {
+        if matched == query.len() {
+            break;
+        }
+        if c.to_lowercase().next() != Some(query[matched]) {
+            continue;
+        }
+
+        score += 1;
+        let word_start = i == 0
+            || !text[i - 1].is_alphanumeric()
+            || (text[i - 1].is_lowercase() && c.is_uppercase());
+        if word_start {
+            score += WORD_START_BONUS;
+        }
+        match previous {
+            Some(previous) if previous + 1 == i => score += CONSECUTIVE_BONUS,
+            Some(previous) => score -= (i - previous - 1).min(3) as i32,
+            None => score -= i.min(3) as i32,
+        }
+        previous = Some(i);
+        matched += 1;
+    }
+
+    (matched == query.len()).then_some(score)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn matches_in_order_ignoring_case() {
+        assert!(fuzzy_score("sv", "File: Save").is_some());
+        assert!(fuzzy_score("SAVE", "File: Save").is_some());
+        assert!(fuzzy_score("vs", "File: Save").is_none());
+        assert!(fuzzy_score("bvy", "bevy_icon.png").is_some());
+        assert!(fuzzy_score("ybv", "bevy_icon.png").is_none());
+        assert_eq!(fuzzy_score("", "File: Save"), Some(0));
+    }
+
+    #[test]
+    fn prefers_word_starts_and_consecutive_characters() {
+        assert!(fuzzy_score("fs", "File: Save") > fuzzy_score("fs", "Flip Axes"));
+        assert!(fuzzy_score("save", "File: Save") > fuzzy_score("save", "Sample Average"));
+        assert!(fuzzy_score("ot", "Open Tab") > fuzzy_score("ot", "Rotate"));
+        assert!(fuzzy_score("icon", "bevy_icon.png") > fuzzy_score("icon", "i_can_open.png"));
+        assert!(fuzzy_score("bi", "bevy_icon.png") > fuzzy_score("bi", "cube_big.png"));
+    }
+}
@@ -1,6 +1,10 @@
 //! This crate provides core functionality for the Bevy Engine Editor.
 
-use bevy::{ecs::entity::Entities, prelude::*};
+use bevy::{asset::AssetPath, ecs::entity::Entities, prelude::*};
+
+pub mod format;
+pub mod fuzzy;
+pub mod meta;
 
 /// Plugin for the editor scene tree pane.
 pub struct EditorCorePlugin;
@@ -8,8 +12,18 @@ pub struct EditorCorePlugin;
 impl Plugin for EditorCorePlugin {
     fn build(&self, app: &mut App) {
         app.init_resource::<SelectedEntity>()
+            .init_resource::<SelectedAsset>()
             .register_type::<SelectedEntity>()
-            .add_systems(PostUpdate, reset_selected_entity_if_entity_despawned);
+            .register_type::<SelectedAsset>()
+            .add_event::<SpawnAsset>()
+            .add_event::<OpenAsset>()
+            .add_systems(
+                PostUpdate,
+                (
+                    reset_selected_entity_if_entity_despawned,
+                    reset_selected_asset_if_entity_selected,
+                ),
+            );
     }
 }
 
@@ -18,6 +32,60 @@ impl Plugin for EditorCorePlugin {
 #[reflect(Resource, Default)]
 pub struct SelectedEntity(pub Option<Entity>);
 
+/// The currently selected asset, for example in the asset browser.
+///
+/// Only one of [`SelectedEntity`] and [`SelectedAsset`] is set at a time, so selecting an asset
+/// should clear the selected entity.
+#[derive(Resource, Default, Reflect)]
+#[reflect(Resource, Default)]
+pub struct SelectedAsset(pub Option<AssetPath<'static>>);
+
+/// Marker for the entities the editor spawns for itself, like gizmos and asset previews, as opposed
+/// to the entities of the scene being edited.
+///
+/// The descendants of an [`EditorEntity`] are owned by the editor too.
+#[derive(Component, Clone, Copy, Default, Debug)]
+pub struct EditorEntity;
+
+/// A UI node representing an asset, like a file in the asset browser.
+///
+/// Dropping the node onto a viewport or the scene tree sends a [`SpawnAsset`] event.
+#[derive(Component, Clone, Debug)]
+pub struct AssetNode(pub AssetPath<'static>);
+
+/// Request to spawn the asset at `path` into the scene, for example after an [`AssetNode`] was
+/// dropped onto a viewport.
+#[derive(Event, Clone, Debug)]
+pub struct SpawnAsset {
+    /// The path of the asset to spawn.
+    pub path: AssetPath<'static>,
+    /// The transform of the spawned entity, relative to `parent`, or in world space if `parent`
+    /// is `None`.
+    pub transform: Transform,
+    /// The entity to spawn the asset as a child of, or `None` to spawn it under the scene root.
+    pub parent: Option<Entity>,
+    /// Whether the asset is spawned into a 2D scene.
+    /// Images are spawned as sprites in 2D, and as textured quads in 3D.
+    pub in_2d: bool,
+}
+
+/// Request to open an asset for editing, for example after a file was double-clicked in the
+/// asset browser.
+///
+/// Panes that can edit the asset, like the code editor for text files, react to this event.
+#[derive(Event, Clone, Debug)]
+pub struct OpenAsset(pub AssetPath<'static>);
+
+/// System to reset [`SelectedAsset`] when an entity gets selected.
+pub fn reset_selected_asset_if_entity_selected(
+    selected_entity: Res<SelectedEntity>,
+    mut selected_asset: ResMut<SelectedAsset>,
+) {
+    if selected_entity.is_changed() && selected_entity.0.is_some() && selected_asset.0.is_some() {
+        selected_asset.0 = None;
+    }
+}
+
 /// System to reset [`SelectedEntity`] when the entity is despawned.
 pub fn reset_selected_entity_if_entity_despawned(
     mut selected_entity: ResMut<SelectedEntity>,
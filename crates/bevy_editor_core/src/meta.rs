@@ -0,0 +1,272 @@
+//! Locate the values of `.meta` files, so they can be edited without knowing the type of the
+//! loader settings.
+//!
+//! `.meta` files are RON documents. Every value of a named struct field that is not itself a
+//! struct with named fields is a [`MetaField`], whose text can be replaced in place. This keeps
+//! the formatting and comments of the file intact.
+
+use std::ops::Range;
+
+/// An editable value of a `.meta` file.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct MetaField {
+    /// The field names leading to the value, separated by dots, like `asset.settings.is_srgb`.
+    pub key: String,
+    /// The byte range of the value in the file.
+    pub span: Range<usize>,
+}
+
+/// Find all the editable values of the RON document `meta`.
+///
+/// Returns `None` if `meta` is not valid RON.
+pub fn meta_fields(meta: &str) -> Option<Vec<MetaField>> {
+    let mut parser = Parser { src: meta, pos: 0 };
+    let mut fields = Vec::new();
+    parser.skip_whitespace();
+    parser.value("", &mut fields)?;
+    parser.skip_whitespace();
+    (parser.pos == meta.len()).then_some(fields)
+}
+
+/// Replace the values of `fields` with the given texts.
+pub fn apply_edits<'a>(
+    meta: &str,
+    edits: impl IntoIterator<Item = (&'a MetaField, &'a str)>,
+) -> String {
+    let mut edits = edits.into_iter().collect::<Vec<_>>();
+    // Replace from the end so the spans of the other fields stay valid
+    edits.sort_by_key(|(field, _)| core::cmp::Reverse(field.span.start));
+    let mut meta = meta.to_string();
+    for (field, value) in edits {
+        meta.replace_range(field.span.clone(), value);
+    }
+    meta
+}
+
+struct Parser<'a> {
+    src: &'a str,
+    pos: usize,
+}
+
+impl<'a> Parser<'a> {
+    fn rest(&self) -> &'a str {
+        &self.src[self.pos..]
+    }
+
+    fn peek(&self) -> Option<char> {
+        self.rest().chars().next()
+    }
+
+    fn skip_whitespace(&mut self) {
+        loop {
+            let rest = self.rest();
+            let trimmed = rest.trim_start();
+            self.pos += rest.len() - trimmed.len();
+            if trimmed.starts_with("//") {
+                self.pos += trimmed.find('\n').unwrap_or(trimmed.len());
+            } else if trimmed.starts_with("/*") {
+                self.pos += trimmed.find("*/").map_or(trimmed.len(), |end| end + 2);
+            } else if trimmed.starts_with("#!") {
+                // Extension attributes like `#![enable(implicit_some)]`
+                self.pos += trimmed.find(']').map_or(trimmed.len(), |end| end + 1);
+            } else {
+                return;
+            }
+        }
+    }
+
+    fn identifier(&mut self) -> Option<&'a str> {
+        let rest = self.rest();
+        let rest = rest.strip_prefix("r#").unwrap_or(rest);
+        let len = rest
+            .find(|c: char| !(c.is_alphanumeric() || c == '_'))
+            .unwrap_or(rest.len());
+        if len == 0 || rest.starts_with(|c: char| c.is_ascii_digit()) {
+            return None;
+        }
+        let start = self.src.len() - rest.len();
+        self.pos = start + len;
+        Some(&self.src[start..self.pos])
+    }
+
+    /// Whether the next value is a struct with named fields, like `(a: 1)` or `Name(a: 1)`.
+    fn at_named_struct(&mut self) -> bool {
+        let start = self.pos;
+        self.identifier();
+        self.skip_whitespace();
+        let mut is_struct = false;
+        if self.peek() == Some('(') {
+            self.pos += 1;
+            self.skip_whitespace();
+            if self.identifier().is_some() {
+                self.skip_whitespace();
+                is_struct = self.peek() == Some(':');
+            }
+        }
+        self.pos = start;
+        is_struct
+    }
+
+    fn value(&mut self, key: &str, fields: &mut Vec<MetaField>) -> Option<()> {
+        if !self.at_named_struct() {
+            let start = self.pos;
+            self.skip_value()?;
+            fields.push(MetaField {
+                key: key.to_string(),
+                span: start..self.pos,
+            });
+            return Some(());
+        }
+
+        self.identifier();
+        self.skip_whitespace();
+        self.pos += 1; // (
+        loop {
+            self.skip_whitespace();
+            if self.peek() == Some(')') {
+                self.pos += 1;
+                return Some(());
+            }
+            let name = self.identifier()?.to_string();
+            self.skip_whitespace();
+            self.rest().starts_with(':').then_some(())?;
+            self.pos += 1;
+            self.skip_whitespace();
+            let key = if key.is_empty() {
+                name
+            } else {
+                format!("{key}.{name}")
+            };
+            self.value(&key, fields)?;
+            self.skip_whitespace();
+            match self.peek()? {
+                ',' => self.pos += 1,
+                ')' => {}
+                _ => return None,
+            }
+        }
+    }
+
+    /// Skip a value of any kind, stopping before the `,` or closing bracket that ends it.
+    fn skip_value(&mut self) -> Option<()> {
+        let start = self.pos;
+        let mut depth = 0usize;
+        // The end of the value, without trailing whitespace and comments
+        let mut end = self.pos;
+        loop {
+            self.skip_whitespace();
+            let Some(c) = self.peek() else {
+                self.pos = end;
+                return (depth == 0 && end > start).then_some(());
+            };
+            match c {
+                ')' | ']' | '}' | ',' if depth == 0 => {
+                    self.pos = end;
+                    return (end > start).then_some(());
+                }
+                '(' | '[' | '{' => {
+                    depth += 1;
+                    self.pos += 1;
+                }
+                ')' | ']' | '}' => {
+                    depth -= 1;
+                    self.pos += 1;
+                }
+                '"' => self.skip_string()?,
+                '\'' => self.pos += self.rest()[1..].find('\'')? + 2,
+                _ => self.pos += c.len_utf8(),
+            }
+            end = self.pos;
+        }
+    }
+
+    fn skip_string(&mut self) -> Option<()> {
+        let mut escaped = false;
+        for (i, c) in self.rest().char_indices().skip(1) {
+            match c {
+                '\\' if !escaped => escaped = true,
+                '"' if !escaped => {
+                    self.pos += i + 1;
+                    return Some(());
+                }
+                _ => escaped = false,
+            }
+        }
+        None
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const IMAGE_META: &str = r#"(
+    meta_format_version: "1.0",
+    asset: Load(
+        loader: "bevy_image::image_loader::ImageLoader",
+        settings: (
+            format: FromExtension,
+            is_srgb: true,
+            sampler: Descriptor((
+                label: None,
+                address_mode_u: Repeat,
+            )),
+            asset_usage: ("MAIN_WORLD | RENDER_WORLD"), // comment
+        ),
+    ),
+)"#;
+
+    fn field<'a>(fields: &'a [MetaField], key: &str) -> &'a MetaField {
+        fields.iter().find(|field| field.key == key).unwrap()
+    }
+
+    #[test]
+    fn finds_values() {
+        let fields = meta_fields(IMAGE_META).unwrap();
+        let values = fields
+            .iter()
+            .map(|field| (field.key.as_str(), &IMAGE_META[field.span.clone()]))
+            .collect::<Vec<_>>();
+        assert_eq!(
+            values,
+            [
+                ("meta_format_version", "\"1.0\""),
+                ("asset.loader", "\"bevy_image::image_loader::ImageLoader\""),
+                ("asset.settings.format", "FromExtension"),
+                ("asset.settings.is_srgb", "true"),
+                (
+                    "asset.settings.sampler",
+                    "Descriptor((\n                label: None,\n                address_mode_u: Repeat,\n            ))"
+                ),
+                (
+                    "asset.settings.asset_usage",
+                    "(\"MAIN_WORLD | RENDER_WORLD\")"
+                ),
+            ]
+        );
+    }
+
+    #[test]
+    fn edits_values_in_place() {
+        let fields = meta_fields(IMAGE_META).unwrap();
+        let edited = apply_edits(
+            IMAGE_META,
+            [
+                (field(&fields, "asset.settings.is_srgb"), "false"),
+                (field(&fields, "asset.settings.format"), "Format(Png)"),
+            ],
+        );
+        assert_eq!(
+            edited,
+            IMAGE_META
+                .replace("is_srgb: true", "is_srgb: false")
+                .replace("format: FromExtension", "format: Format(Png)")
+        );
+    }
+
+    #[test]
+    fn rejects_invalid_ron() {
+        assert_eq!(meta_fields("(a: (b: 1)"), None);
+        assert_eq!(meta_fields("(a: \"unterminated)"), None);
+    }
+}
@@ -2,8 +2,10 @@ use std::path::PathBuf;
 
 use bevy::log::{error, warn};
 use de::{load_preferences, load_toml_file};
+use ser::save_preferences;
 
 pub mod de;
+pub mod ser;
 
 use crate::{GlobalSettingsPath, SettingsType};
 
@@ -30,9 +32,12 @@ pub fn load_settings(app: &mut bevy::app::App) {
     load_project_settings(app.world_mut());
 }
 
+fn project_settings_path() -> PathBuf {
+    PathBuf::from(env!("CARGO_MANIFEST_DIR")).join("Bevy.toml")
+}
+
 pub fn load_project_settings(world: &mut bevy::prelude::World) {
-    let path = PathBuf::from(env!("CARGO_MANIFEST_DIR"));
-    let Ok(file) = load_toml_file(path.join("Bevy.toml")) else {
+    let Ok(file) = load_toml_file(project_settings_path()) else {
         warn!("Failed to load project settings");
         return;
     };
@@ -49,3 +54,35 @@ pub fn load_global_settings(world: &mut bevy::prelude::World) {
 
     load_preferences(world, file, SettingsType::Global);
 }
+
+pub fn save_settings(world: &bevy::prelude::World, settings_type: SettingsType) {
+    let path = match settings_type {
+        SettingsType::Project => project_settings_path(),
+        SettingsType::Global => match world.get_resource::<GlobalSettingsPath>() {
+            Some(path) => path.0.join("global.toml"),
+            None => {
+                warn!("Failed to save global settings");
+                return;
+            }
+        },
+        SettingsType::Workspace => {
+            warn!("Saving workspace settings is not supported yet");
+            return;
+        }
+    };
+
+    // Keep any values in the file that don't belong to a settings resource in this app.
+    let mut table = load_toml_file(&path).unwrap_or_default();
+    save_preferences(world, &mut table, settings_type);
+
+    let contents = match toml::to_string_pretty(&table) {
+        Ok(contents) => contents,
+        Err(e) => {
+            error!("Failed to serialize settings: {}", e);
+            return;
+        }
+    };
+    if let Err(e) = std::fs::write(&path, contents) {
+        error!("Failed to write settings to {:?}: {}", path, e);
+    }
+}
@@ -0,0 +1,250 @@
+use bevy::{
+    prelude::*,
+    reflect::{ReflectFromPtr, ReflectRef, TypeInfo, VariantField, VariantType},
+};
+use heck::ToSnakeCase;
+
+use crate::{SettingKey, SettingsType};
+
+/// Convert a reflected value into a TOML value, in the same format that is read when loading
+/// settings.
+///
+/// Returns `None` if the value, or any value it contains, can not be represented in TOML.
+pub fn to_toml_value(value: &dyn PartialReflect) -> Option<toml::Value> {
+    match value.reflect_ref() {
+        ReflectRef::Struct(strct) => {
+            let mut table = toml::Table::new();
+            for i in 0..strct.field_len() {
+                let name = strct.name_at(i)?;
+                table.insert(name.to_string(), to_toml_value(strct.field_at(i)?)?);
+            }
+            Some(toml::Value::Table(table))
+        }
+        ReflectRef::TupleStruct(tuple_struct) => tuple_struct
+            .iter_fields()
+            .map(to_toml_value)
+            .collect::<Option<Vec<_>>>()
+            .map(toml::Value::Array),
+        ReflectRef::Tuple(tuple) => tuple
+            .iter_fields()
+            .map(to_toml_value)
+            .collect::<Option<Vec<_>>>()
+            .map(toml::Value::Array),
+        ReflectRef::List(list) => list
+            .iter()
+            .map(to_toml_value)
+            .collect::<Option<Vec<_>>>()
+            .map(toml::Value::Array),
+        ReflectRef::Array(array) => array
+            .iter()
+            .map(to_toml_value)
+            .collect::<Option<Vec<_>>>()
+            .map(toml::Value::Array),
+        ReflectRef::Set(set) => set
+            .iter()
+            .map(to_toml_value)
+            .collect::<Option<Vec<_>>>()
+            .map(toml::Value::Array),
+        ReflectRef::Map(map) => {
+            let mut table = toml::Table::new();
+            for (key, value) in map.iter() {
+                let Some(key) = key.try_downcast_ref::<String>() else {
+                    warn!("Preferences: Map key must be a String");
+                    return None;
+                };
+                table.insert(key.clone(), to_toml_value(value)?);
+            }
+            Some(toml::Value::Table(table))
+        }
+        ReflectRef::Enum(enm) => {
+            let name = enm.variant_name().to_string();
+            let value = match enm.variant_type() {
+                VariantType::Unit => return Some(toml::Value::String(name)),
+                VariantType::Struct => {
+                    let mut table = toml::Table::new();
+                    for field in enm.iter_fields() {
+                        if let VariantField::Struct(field_name, value) = field {
+                            table.insert(field_name.to_string(), to_toml_value(value)?);
+                        }
+                    }
+                    toml::Value::Table(table)
+                }
+                VariantType::Tuple => toml::Value::Array(
+                    enm.iter_fields()
+                        .map(|field| to_toml_value(field.value()))
+                        .collect::<Option<Vec<_>>>()?,
+                ),
+            };
+            let mut table = toml::Table::new();
+            table.insert(name, value);
+            Some(toml::Value::Table(table))
+        }
+        ReflectRef::Opaque(value) => opaque_to_toml_value(value),
+        #[allow(unreachable_patterns)]
+        _ => {
+            warn!(
+                "Preferences: Unsupported type: {:?}",
+                value.reflect_type_path()
+            );
+            None
+        }
+    }
+}
+
+fn opaque_to_toml_value(value: &dyn PartialReflect) -> Option<toml::Value> {
+    // Use the shortest representation of `f32`s, so `0.1` isn't written as `0.10000000149011612`.
+    let float = |value: f32| value.to_string().parse::<f64>().unwrap_or(value as f64);
+
+    if let Some(value) = value.try_downcast_ref::<String>() {
+        Some(toml::Value::String(value.clone()))
+    } else if let Some(value) = value.try_downcast_ref::<bool>() {
+        Some(toml::Value::Boolean(*value))
+    } else if let Some(value) = value.try_downcast_ref::<f64>() {
+        Some(toml::Value::Float(*value))
+    } else if let Some(value) = value.try_downcast_ref::<f32>() {
+        Some(toml::Value::Float(float(*value)))
+    } else if let Some(value) = value.try_downcast_ref::<i64>() {
+        Some(toml::Value::Integer(*value))
+    } else if let Some(value) = value.try_downcast_ref::<i32>() {
+        Some(toml::Value::Integer(*value as i64))
+    } else if let Some(value) = value.try_downcast_ref::<i16>() {
+        Some(toml::Value::Integer(*value as i64))
+    } else if let Some(value) = value.try_downcast_ref::<i8>() {
+        Some(toml::Value::Integer(*value as i64))
+    } else if let Some(value) = value.try_downcast_ref::<u64>() {
+        Some(toml::Value::Integer((*value).min(i64::MAX as u64) as i64))
+    } else if let Some(value) = value.try_downcast_ref::<u32>() {
+        Some(toml::Value::Integer(*value as i64))
+    } else if let Some(value) = value.try_downcast_ref::<u16>() {
+        Some(toml::Value::Integer(*value as i64))
+    } else if let Some(value) = value.try_downcast_ref::<u8>() {
+        Some(toml::Value::Integer(*value as i64))
+    } else {
+        warn!(
+            "Preferences: Unsupported type: {:?}",
+            value.reflect_type_path()
+        );
+        None
+    }
+}
+
+/// Write all settings resources of the given [`SettingsType`] into the table, replacing any values
+/// that are already stored under the same keys.
+pub fn save_preferences(world: &World, table: &mut toml::Table, settings_type: SettingsType) {
+    let registry = world.resource::<AppTypeRegistry>().read();
+
+    for (info, ptr) in world.iter_resources() {
+        let Some(type_reg) = info.type_id().and_then(|type_id| registry.get(type_id)) else {
+            continue;
+        };
+        let attributes = match type_reg.type_info() {
+            TypeInfo::Struct(info) => info.custom_attributes(),
+            TypeInfo::Enum(info) => info.custom_attributes(),
+            TypeInfo::TupleStruct(info) => info.custom_attributes(),
+            _ => continue,
+        };
+        if attributes.get::<SettingsType>() != Some(&settings_type) {
+            continue;
+        }
+        let Some(reflect_from_ptr) = type_reg.data::<ReflectFromPtr>() else {
+            continue;
+        };
+        #[allow(unsafe_code)]
+        // SAFETY: `ptr` is of the type registered in `type_reg`, which the `ReflectFromPtr` was created for
+        let value = unsafe { reflect_from_ptr.as_reflect(ptr) };
+
+        let name = attributes
+            .get::<SettingKey>()
+            .map(|key| key.0.to_string())
+            .unwrap_or_else(|| value.reflect_type_ident().unwrap().to_snake_case());
+
+        let Some(toml_value) = to_toml_value(value.as_partial_reflect()) else {
+            warn!("Preferences: Failed to save {}", name);
+            continue;
+        };
+
+        // Top level enums and tuple structs are wrapped in a table, see `load_preferences`.
+        let toml_value = match type_reg.type_info() {
+            TypeInfo::Enum(_) => toml::Value::Table(toml::Table::from_iter([(
+                "variant".to_string(),
+                toml_value,
+            )])),
+            TypeInfo::TupleStruct(_) => {
+                toml::Value::Table(toml::Table::from_iter([("fields".to_string(), toml_value)]))
+            }
+            _ => toml_value,
+        };
+
+        table.insert(name, toml_value);
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use bevy::reflect::{DynamicTyped as _, Reflect};
+
+    use super::*;
+    use crate::file_system::de::LoadStructure;
+
+    #[derive(Debug, Clone, Reflect, Default, PartialEq)]
+    enum Shape {
+        #[default]
+        Empty,
+        Circle(f32),
+        Rect {
+            width: u32,
+            height: u32,
+        },
+    }
+
+    #[derive(Debug, Clone, Reflect, Default, PartialEq)]
+    struct Values {
+        name: String,
+        enabled: bool,
+        scale: f32,
+        offset: Vec3,
+        shapes: Vec<Shape>,
+        pair: (i32, String),
+        key: Option<u8>,
+        unbound_key: Option<u8>,
+    }
+
+    #[tracing_test::traced_test]
+    #[test]
+    fn round_trip() {
+        let values = Values {
+            name: "Hello".to_string(),
+            enabled: true,
+            scale: 0.1,
+            offset: Vec3::new(1.0, 2.5, -3.0),
+            shapes: vec![
+                Shape::Empty,
+                Shape::Circle(2.0),
+                Shape::Rect {
+                    width: 4,
+                    height: 5,
+                },
+            ],
+            pair: (7, "seven".to_string()),
+            key: Some(3),
+            unbound_key: None,
+        };
+
+        let toml_value = to_toml_value(&values).unwrap();
+        assert_eq!(
+            toml_value.get("scale").and_then(toml::Value::as_float),
+            Some(0.1)
+        );
+
+        let mut loaded = Values::default();
+        LoadStructure {
+            type_info: loaded.reflect_type_info(),
+            table: &toml_value,
+            structure: &mut loaded,
+            custom_attributes: None,
+        }
+        .load();
+
+        assert_eq!(loaded, values);
+    }
+}
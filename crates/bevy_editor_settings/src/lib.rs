@@ -47,6 +47,11 @@ pub struct SettingKey(pub &'static str);
 /// Store the path for the global preferences directory.
 pub struct GlobalSettingsPath(pub std::path::PathBuf);
 
+#[derive(Event, Debug, Clone)]
+/// Send this event to write the current value of all settings of the given type to disk.
+/// Only [`SettingsType::Project`] and [`SettingsType::Global`] settings can be saved.
+pub struct SaveSettings(pub SettingsType);
+
 /// A Bevy plugin for editor settings.
 /// This plugin loads the workspace settings, user settings, and project settings.
 pub struct EditorSettingsPlugin;
@@ -62,6 +67,9 @@ impl Plugin for EditorSettingsPlugin {
                 warn!("Failed to load global settings");
             }
         };
+
+        app.add_event::<SaveSettings>()
+            .add_systems(Last, save_settings.run_if(on_event::<SaveSettings>));
     }
 
     fn finish(&self, app: &mut App) {
@@ -69,6 +77,18 @@ impl Plugin for EditorSettingsPlugin {
     }
 }
 
+fn save_settings(world: &mut World) {
+    let mut settings_types = Vec::new();
+    for SaveSettings(settings_type) in world.resource_mut::<Events<SaveSettings>>().drain() {
+        if !settings_types.contains(&settings_type) {
+            settings_types.push(settings_type);
+        }
+    }
+    for settings_type in settings_types {
+        file_system::save_settings(world, settings_type);
+    }
+}
+
 #[cfg(test)]
 mod tests {
 
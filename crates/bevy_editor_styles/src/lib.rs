@@ -32,6 +32,8 @@ pub struct Theme {
     pub viewport: ViewportStyles,
     /// The styles for scroll boxes in the editor.
     pub scroll_box: ScrollBoxStyles,
+    /// The styles for code in the editor.
+    pub code: CodeStyles,
 }
 
 /// The general styles for the editor.
@@ -116,6 +118,38 @@ pub struct ScrollBoxStyles {
     pub border_radius: BorderRadius,
 }
 
+/// The styles for code in the editor, like the syntax highlighting colors.
+pub struct CodeStyles {
+    /// The background color of the code.
+    pub background_color: BackgroundColor,
+    /// The background color of the line of the cursor.
+    pub current_line_color: Color,
+    /// The color of the line numbers.
+    pub line_number_color: Color,
+    /// The background color of selected text.
+    pub selection_color: Color,
+    /// The background color of search matches.
+    pub search_match_color: Color,
+    /// The color of code that isn't highlighted.
+    pub text_color: Color,
+    /// The color of keywords.
+    pub keyword_color: Color,
+    /// The color of type names.
+    pub type_color: Color,
+    /// The color of function and macro names.
+    pub function_color: Color,
+    /// The color of string literals.
+    pub string_color: Color,
+    /// The color of number literals.
+    pub number_color: Color,
+    /// The color of comments.
+    pub comment_color: Color,
+    /// The color of attributes.
+    pub attribute_color: Color,
+    /// The color of struct fields and keys.
+    pub field_color: Color,
+}
+
 impl FromWorld for Theme {
     fn from_world(world: &mut World) -> Self {
         let asset_server = world.resource::<AssetServer>();
@@ -162,6 +196,22 @@ impl FromWorld for Theme {
                 handle_color: Color::oklch(0.325, 0.0, 0.0),
                 border_radius: BorderRadius::all(Val::Px(8.)),
             },
+            code: CodeStyles {
+                background_color: BackgroundColor(Color::oklch(0.25, 0.0, 0.0)),
+                current_line_color: Color::oklch(0.29, 0.0, 0.0),
+                line_number_color: Color::oklch(0.50, 0.0, 0.0),
+                selection_color: Color::oklch(0.40, 0.08, 255.0),
+                search_match_color: Color::oklch(0.42, 0.08, 85.0),
+                text_color: Color::oklch(0.9219, 0.0, 0.0),
+                keyword_color: Color::oklch(0.72, 0.13, 300.0),
+                type_color: Color::oklch(0.80, 0.11, 190.0),
+                function_color: Color::oklch(0.80, 0.12, 250.0),
+                string_color: Color::oklch(0.78, 0.13, 140.0),
+                number_color: Color::oklch(0.78, 0.12, 60.0),
+                comment_color: Color::oklch(0.60, 0.0, 0.0),
+                attribute_color: Color::oklch(0.76, 0.12, 20.0),
+                field_color: Color::oklch(0.82, 0.08, 230.0),
+            },
         }
     }
 }
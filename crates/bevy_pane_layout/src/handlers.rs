@@ -1,21 +1,37 @@
 use bevy::prelude::*;
+use bevy_command_palette::CommandTarget;
 use bevy_editor_styles::Theme;
+use bevy_focus::Focus;
 
 use crate::{
     ui::{spawn_divider, spawn_pane, spawn_resize_handle},
-    Divider, PaneRootNode, RootPaneLayoutNode, Size,
+    Divider, OpenPane, PaneRootNode, RootPaneLayoutNode, Size,
 };
 
+/// The root of the pane containing `entity`, or `entity` itself if it's a pane root
+fn pane_root(
+    entity: Entity,
+    parent_query: &Query<&ChildOf>,
+    pane_root_query: &Query<&PaneRootNode>,
+) -> Option<Entity> {
+    std::iter::once(entity)
+        .chain(parent_query.iter_ancestors(entity))
+        .find(|entity| pane_root_query.contains(*entity))
+}
+
+/// Remove the pane containing the `target` entity
 pub(crate) fn remove_pane(
     target: In<Entity>,
     mut commands: Commands,
     parent_query: Query<&ChildOf>,
     children_query: Query<&Children>,
+    pane_root_query: Query<&PaneRootNode>,
     root_query: Query<(), With<RootPaneLayoutNode>>,
     mut size_query: Query<&mut Size>,
 ) {
-    // Grab the id of the pane root
-    let target = parent_query.iter_ancestors(*target).nth(1).unwrap();
+    let Some(target) = pane_root(*target, &parent_query, &pane_root_query) else {
+        return;
+    };
 
     let parent = parent_query.get(target).unwrap().parent();
 
@@ -53,17 +69,12 @@ pub(crate) fn remove_pane(
     commands.entity(target).despawn();
 }
 
-/// Right clicking dividers the pane horizontally
-/// Holding left shift and right clicking dividers the pane vertically
-#[expect(clippy::too_many_arguments)]
+/// Split the pane containing the `target` entity in two, side by side or one above the other if
+/// `vertical`
 pub(crate) fn split_pane(
     In((target, vertical)): In<(Entity, bool)>,
     mut commands: Commands,
-    theme: Res<Theme>,
-    divider_query: Query<&Divider>,
     pane_root_query: Query<&PaneRootNode>,
-    mut size_query: Query<&mut Size>,
-    children_query: Query<&Children>,
     parent_query: Query<&ChildOf>,
 ) {
     let divider = if vertical {
@@ -72,11 +83,81 @@ pub(crate) fn split_pane(
         Divider::Horizontal
     };
 
-    // Grab the id of the pane root
-    let target = parent_query.iter_ancestors(target).nth(1).unwrap();
+    let Some(target) = pane_root(target, &parent_query, &pane_root_query) else {
+        return;
+    };
+
+    // TODO The new pane should inherit the state of the existing pane
+    let name = pane_root_query.get(target).unwrap().name.clone();
+    commands.run_system_cached_with(insert_pane, (target, divider, name));
+}
+
+/// The entity the pane commands apply to: the [`CommandTarget`] when run from the context menu of
+/// a pane header, or the focused entity when run from the command palette
+fn command_target(target: &CommandTarget, focused: &Query<Entity, With<Focus>>) -> Option<Entity> {
+    target.0.or_else(|| focused.iter().next())
+}
+
+/// Close a pane, see [`command_target`]
+pub(crate) fn close_pane_command(
+    mut commands: Commands,
+    target: Res<CommandTarget>,
+    focused: Query<Entity, With<Focus>>,
+) {
+    if let Some(target) = command_target(&target, &focused) {
+        commands.run_system_cached_with(remove_pane, target);
+    }
+}
+
+/// Split a pane, see [`command_target`]
+pub(crate) fn split_pane_command(
+    vertical: bool,
+) -> impl FnMut(Commands, Res<CommandTarget>, Query<Entity, With<Focus>>) {
+    move |mut commands, target, focused| {
+        if let Some(target) = command_target(&target, &focused) {
+            commands.run_system_cached_with(split_pane, (target, vertical));
+        }
+    }
+}
 
-    let pane = pane_root_query.get(target).unwrap();
+/// Open the panes requested by [`OpenPane`] events, that aren't open yet, by splitting the
+/// largest pane along its longest side
+pub(crate) fn open_panes(
+    mut events: EventReader<OpenPane>,
+    mut commands: Commands,
+    pane_query: Query<(Entity, &PaneRootNode, &ComputedNode)>,
+) {
+    let mut opened = Vec::new();
+    for OpenPane(name) in events.read() {
+        if opened.contains(name) || pane_query.iter().any(|(_, pane, _)| pane.name == *name) {
+            continue;
+        }
+        let Some((target, _, node)) = pane_query.iter().max_by(|(_, _, a), (_, _, b)| {
+            let (a, b) = (a.size(), b.size());
+            (a.x * a.y).total_cmp(&(b.x * b.y))
+        }) else {
+            continue;
+        };
+        let divider = if node.size().x >= node.size().y {
+            Divider::Horizontal
+        } else {
+            Divider::Vertical
+        };
+        commands.run_system_cached_with(insert_pane, (target, divider, name.clone()));
+        opened.push(name.clone());
+    }
+}
 
+/// Insert a new pane named `name` after the pane root `target`, splitting it along `divider`
+fn insert_pane(
+    In((target, divider, name)): In<(Entity, Divider, String)>,
+    mut commands: Commands,
+    theme: Res<Theme>,
+    divider_query: Query<&Divider>,
+    mut size_query: Query<&mut Size>,
+    children_query: Query<&Children>,
+    parent_query: Query<&ChildOf>,
+) {
     let parent = parent_query.get(target).unwrap().parent();
 
     // Find the index of this pane among its siblings
@@ -92,8 +173,7 @@ pub(crate) fn split_pane(
     let mut size = size_query.get_mut(target).unwrap();
     let new_size = if matching_direction { size.0 / 2. } else { 0.5 };
 
-    // TODO The new pane should inherit the state of the existing pane
-    let new_pane = spawn_pane(&mut commands, &theme, new_size, &pane.name).id();
+    let new_pane = spawn_pane(&mut commands, &theme, new_size, name).id();
 
     let resize_handle = spawn_resize_handle(&mut commands, divider).id();
 
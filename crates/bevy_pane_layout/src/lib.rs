@@ -19,9 +19,11 @@ mod ui;
 ///   - If a pane can not be sensibly resized, it can overflow under the other panes.
 /// - Panes must not interfere with each other, only temporary/absolute positioned elements are allowed to overlap panes.
 use bevy::prelude::*;
+use bevy_command_palette::{CommandInfo, CommandPaletteAppExt, CommandPalettePlugin};
 use bevy_editor_styles::Theme;
 
 use crate::{
+    handlers::{close_pane_command, open_panes, split_pane_command},
     registry::PaneRegistryPlugin,
     ui::{spawn_divider, spawn_pane, spawn_resize_handle},
 };
@@ -30,7 +32,7 @@ use crate::{
 pub mod prelude {
     pub use crate::{
         registry::{PaneAppExt, PaneStructure},
-        PaneAreaNode, PaneContentNode, PaneHeaderNode,
+        OpenPane, PaneAreaNode, PaneContentNode, PaneHeaderNode,
     };
 }
 
@@ -39,12 +41,30 @@ pub struct PaneLayoutPlugin;
 
 impl Plugin for PaneLayoutPlugin {
     fn build(&self, app: &mut App) {
+        if !app.is_plugin_added::<CommandPalettePlugin>() {
+            app.add_plugins(CommandPalettePlugin);
+        }
+
+        app.register_command(
+            CommandInfo::new("pane.close", "Close").with_category("Pane"),
+            close_pane_command,
+        )
+        .register_command(
+            CommandInfo::new("pane.split_horizontal", "Split - Horizontal").with_category("Pane"),
+            split_pane_command(false),
+        )
+        .register_command(
+            CommandInfo::new("pane.split_vertical", "Split - Vertical").with_category("Pane"),
+            split_pane_command(true),
+        );
+
         app.add_plugins(PaneRegistryPlugin)
             .init_resource::<DragState>()
+            .add_event::<OpenPane>()
             .add_systems(Startup, setup.in_set(PaneLayoutSet))
             .add_systems(
                 Update,
-                (cleanup_divider_single_child, apply_size)
+                (open_panes, cleanup_divider_single_child, apply_size)
                     .chain()
                     .in_set(PaneLayoutSet),
             );
@@ -176,6 +196,11 @@ struct ResizeHandle;
 #[derive(Component)]
 struct Size(f32);
 
+/// Request to open the pane registered with this name, next to the largest open pane.
+/// Nothing happens if a pane with that name is already open.
+#[derive(Event, Clone, Debug)]
+pub struct OpenPane(pub String);
+
 /// Root node to capture all editor UI elements, nothing but the layout system should modify this.
 #[derive(Component)]
 pub struct RootPaneLayoutNode;
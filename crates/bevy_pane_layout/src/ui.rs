@@ -1,10 +1,11 @@
 use bevy::{prelude::*, window::SystemCursorIcon, winit::cursor::CursorIcon};
-use bevy_context_menu::{ContextMenu, ContextMenuOption};
+use bevy_command_palette::command_menu_option;
+use bevy_context_menu::ContextMenu;
 use bevy_editor_styles::Theme;
 
 use crate::{
-    handlers::*, registry::PaneStructure, Divider, DragState, PaneAreaNode, PaneContentNode,
-    PaneHeaderNode, PaneRootNode, ResizeHandle, Size,
+    registry::PaneStructure, Divider, DragState, PaneAreaNode, PaneContentNode, PaneHeaderNode,
+    PaneRootNode, ResizeHandle, Size,
 };
 
 pub(crate) fn spawn_pane<'a>(
@@ -57,15 +58,9 @@ pub(crate) fn spawn_pane<'a>(
             theme.pane.header_background_color,
             theme.pane.header_border_radius,
             ContextMenu::new([
-                ContextMenuOption::new("Close", |mut commands, entity| {
-                    commands.run_system_cached_with(remove_pane, entity);
-                }),
-                ContextMenuOption::new("Split - Horizontal", |mut commands, entity| {
-                    commands.run_system_cached_with(split_pane, (entity, false));
-                }),
-                ContextMenuOption::new("Split - Vertical", |mut commands, entity| {
-                    commands.run_system_cached_with(split_pane, (entity, true));
-                }),
+                command_menu_option("Close", "pane.close"),
+                command_menu_option("Split - Horizontal", "pane.split_horizontal"),
+                command_menu_option("Split - Vertical", "pane.split_vertical"),
             ]),
             PaneHeaderNode,
             ChildOf(area),
@@ -1 +1,3 @@
 //! Gizmos used for the user interface to manipulate transforms.
+
+pub mod snapping;
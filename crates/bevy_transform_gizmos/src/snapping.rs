@@ -0,0 +1,285 @@
+//! Snapping of transforms to increments, the grid of a viewport and scene geometry.
+//!
+//! Snapping is enabled per camera with the [`Snapping`] component, while the increments are shared
+//! by all cameras and stored in the project settings as [`SnapSettings`]. Anything that moves
+//! entities with the pointer, like transform gizmos or spawning assets by dragging them into a
+//! viewport, should go through the [`Snapper`] system param:
+//!
+//! ```ignore
+//! fn place(mut snapper: Snapper, ...) {
+//!     let ray = camera.viewport_to_world(camera_transform, cursor_position)?;
+//!     if let Some(hit) = snapper.place(camera_entity, ray, &|_| true) {
+//!         transform.translation = hit.point;
+//!     }
+//! }
+//! ```
+
+use bevy::{
+    ecs::system::SystemParam,
+    picking::mesh_picking::ray_cast::{MeshRayCast, MeshRayCastSettings, RayCastVisibility},
+    prelude::*,
+};
+use bevy_editor_settings::{SettingKey, SettingsType};
+use bevy_infinite_grid::InfiniteGridSettings;
+
+/// Plugin for snapping transforms, see the [module docs](self).
+pub struct SnappingPlugin;
+
+impl Plugin for SnappingPlugin {
+    fn build(&self, app: &mut App) {
+        app.init_resource::<SnapSettings>()
+            .register_type::<SnapSettings>()
+            .register_type::<Snapping>();
+    }
+}
+
+/// The increments that transforms are snapped to.
+#[derive(Resource, Reflect, Clone, Debug, PartialEq)]
+#[reflect(Resource, @SettingsType::Project, @SettingKey("snapping"))]
+pub struct SnapSettings {
+    /// Snap translations to the grid of the viewport instead of
+    /// [`translate_increment`](Self::translate_increment).
+    ///
+    /// The grid spacing is the [`InfiniteGridSettings::scale`] of the grid, and positions are
+    /// snapped in the local space of the grid, so this also works for rotated grids.
+    pub snap_to_grid: bool,
+    /// The increment translations are snapped to when not snapping to the grid.
+    pub translate_increment: f32,
+    /// The increment rotations are snapped to, in radians.
+    pub rotate_increment: f32,
+    /// The increment scales are snapped to.
+    pub scale_increment: f32,
+    /// What scene geometry pointer placement snaps to.
+    pub geometry: GeometrySnap,
+}
+
+impl Default for SnapSettings {
+    fn default() -> Self {
+        SnapSettings {
+            snap_to_grid: true,
+            translate_increment: 1.,
+            rotate_increment: 15_f32.to_radians(),
+            scale_increment: 0.1,
+            geometry: GeometrySnap::None,
+        }
+    }
+}
+
+/// What scene geometry is snapped to when placing things with the pointer.
+#[derive(Reflect, Clone, Copy, Default, Debug, PartialEq, Eq)]
+pub enum GeometrySnap {
+    /// Ignore the scene geometry, and place things on the grid.
+    #[default]
+    None,
+    /// Snap to the closest vertex of the mesh under the pointer.
+    Vertex,
+    /// Snap to the surface of the mesh under the pointer.
+    Surface,
+}
+
+/// Enables snapping for the pointer interactions of a camera.
+#[derive(Component, Reflect, Clone, Copy, Default, Debug)]
+#[reflect(Component)]
+pub struct Snapping {
+    /// Whether snapping is enabled.
+    pub enabled: bool,
+    /// The [`InfiniteGrid`](bevy_infinite_grid::InfiniteGrid) drawn by this camera, used to
+    /// snap to the grid and to place things on its plane.
+    pub grid: Option<Entity>,
+}
+
+/// A point picked by [`Snapper::place`].
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub struct SnapHit {
+    /// The snapped point in world space.
+    pub point: Vec3,
+    /// The normal of the surface at the point.
+    pub normal: Dir3,
+    /// The entity that was hit, or `None` if the point is on the grid plane.
+    pub entity: Option<Entity>,
+}
+
+/// Snaps transforms according to the [`SnapSettings`] and the [`Snapping`] of a camera.
+///
+/// All methods return their input unchanged if snapping is disabled for the camera.
+#[derive(SystemParam)]
+pub struct Snapper<'w, 's> {
+    settings: Res<'w, SnapSettings>,
+    cameras: Query<'w, 's, &'static Snapping>,
+    grids: Query<'w, 's, (&'static GlobalTransform, &'static InfiniteGridSettings)>,
+    ray_cast: MeshRayCast<'w, 's>,
+}
+
+impl Snapper<'_, '_> {
+    /// The snap settings.
+    pub fn settings(&self) -> &SnapSettings {
+        &self.settings
+    }
+
+    /// Whether snapping is enabled for the camera.
+    pub fn is_enabled(&self, camera: Entity) -> bool {
+        self.cameras
+            .get(camera)
+            .is_ok_and(|snapping| snapping.enabled)
+    }
+
+    /// Snap a translation in world space.
+    pub fn translation(&self, camera: Entity, translation: Vec3) -> Vec3 {
+        if !self.is_enabled(camera) {
+            return translation;
+        }
+        match self.grid(camera).filter(|_| self.settings.snap_to_grid) {
+            Some((grid, spacing)) => {
+                let local = grid.inverse_transform_point(translation);
+                grid.transform_point(snap_vec3(local.into(), spacing))
+                    .into()
+            }
+            None => snap_vec3(translation, self.settings.translate_increment),
+        }
+    }
+
+    /// Snap a rotation to the rotation increment about each axis.
+    pub fn rotation(&self, camera: Entity, rotation: Quat) -> Quat {
+        if !self.is_enabled(camera) {
+            return rotation;
+        }
+        snap_rotation(rotation, self.settings.rotate_increment)
+    }
+
+    /// Snap a scale to the scale increment.
+    pub fn scale(&self, camera: Entity, scale: Vec3) -> Vec3 {
+        if !self.is_enabled(camera) {
+            return scale;
+        }
+        snap_vec3(scale, self.settings.scale_increment)
+    }
+
+    /// Find the point to place something at along a ray cast from the camera, for example from
+    /// the pointer position.
+    ///
+    /// Meshes for which `filter` returns `false` are ignored. If snapping is disabled, the point
+    /// is on the surface of the first mesh that is hit. If it is enabled, the point snaps to the
+    /// scene geometry as configured by [`SnapSettings::geometry`]. Otherwise, or if no mesh is
+    /// hit, the point is on the plane of the camera's grid, snapped like [`Self::translation`].
+    pub fn place(
+        &mut self,
+        camera: Entity,
+        ray: Ray3d,
+        filter: &dyn Fn(Entity) -> bool,
+    ) -> Option<SnapHit> {
+        let geometry = if self.is_enabled(camera) {
+            self.settings.geometry
+        } else {
+            GeometrySnap::Surface
+        };
+        if geometry != GeometrySnap::None {
+            if let Some(hit) = self.ray_cast_geometry(ray, geometry, filter) {
+                return Some(hit);
+            }
+        }
+
+        let grid = self
+            .grid(camera)
+            .map_or(Isometry3d::IDENTITY, |(grid, _)| grid);
+        let normal = grid.rotation * Dir3::Y;
+        let distance =
+            ray.intersect_plane(grid.translation.into(), InfinitePlane3d::new(normal))?;
+        Some(SnapHit {
+            point: self.translation(camera, ray.get_point(distance)),
+            normal,
+            entity: None,
+        })
+    }
+
+    fn ray_cast_geometry(
+        &mut self,
+        ray: Ray3d,
+        geometry: GeometrySnap,
+        filter: &dyn Fn(Entity) -> bool,
+    ) -> Option<SnapHit> {
+        let filter = |entity| filter(entity);
+        let settings = MeshRayCastSettings::default()
+            .with_filter(&filter)
+            .with_visibility(RayCastVisibility::VisibleInView);
+        let (entity, hit) = self.ray_cast.cast_ray(ray, &settings).first()?;
+
+        let point = match (geometry, hit.triangle) {
+            (GeometrySnap::Vertex, Some(triangle)) => triangle
+                .into_iter()
+                .min_by(|a, b| {
+                    a.distance_squared(hit.point)
+                        .total_cmp(&b.distance_squared(hit.point))
+                })
+                .unwrap(),
+            _ => hit.point,
+        };
+        Some(SnapHit {
+            point,
+            normal: Dir3::new(hit.normal).unwrap_or(Dir3::Y),
+            entity: Some(*entity),
+        })
+    }
+
+    /// The position and rotation of the grid of the camera, and its spacing.
+    fn grid(&self, camera: Entity) -> Option<(Isometry3d, f32)> {
+        let grid = self.cameras.get(camera).ok()?.grid?;
+        let (transform, settings) = self.grids.get(grid).ok()?;
+        Some((transform.to_isometry(), settings.scale))
+    }
+}
+
+/// Round `value` to the nearest multiple of `increment`.
+///
+/// Returns `value` unchanged if the increment is not positive.
+pub fn snap_value(value: f32, increment: f32) -> f32 {
+    if increment > 0. {
+        (value / increment).round() * increment
+    } else {
+        value
+    }
+}
+
+/// Round each component of `value` to the nearest multiple of `increment`.
+pub fn snap_vec3(value: Vec3, increment: f32) -> Vec3 {
+    Vec3::new(
+        snap_value(value.x, increment),
+        snap_value(value.y, increment),
+        snap_value(value.z, increment),
+    )
+}
+
+/// Round the Euler angles of `rotation` to the nearest multiple of `increment`.
+pub fn snap_rotation(rotation: Quat, increment: f32) -> Quat {
+    let (x, y, z) = rotation.to_euler(EulerRot::XYZ);
+    Quat::from_euler(
+        EulerRot::XYZ,
+        snap_value(x, increment),
+        snap_value(y, increment),
+        snap_value(z, increment),
+    )
+}
+
+#[cfg(test)]
+mod tests {
+    use std::f32::consts::FRAC_PI_2;
+
+    use super::*;
+
+    #[test]
+    fn snap_to_increment() {
+        assert_eq!(snap_value(1.3, 0.5), 1.5);
+        assert_eq!(snap_value(-1.2, 0.5), -1.0);
+        assert_eq!(snap_value(1.3, 0.), 1.3);
+        assert_eq!(
+            snap_vec3(Vec3::new(0.4, 2.6, -7.1), 1.),
+            Vec3::new(0., 3., -7.)
+        );
+    }
+
+    #[test]
+    fn snap_rotation_to_increment() {
+        let rotation = Quat::from_rotation_y(80_f32.to_radians());
+        let snapped = snap_rotation(rotation, FRAC_PI_2);
+        assert!(snapped.abs_diff_eq(Quat::from_rotation_y(FRAC_PI_2), 1e-5));
+    }
+}
@@ -361,23 +361,31 @@ fn undo_redo_logic(world: &mut World) {
                     match event {
                         UndoRedo::Undo => {
                             if let Some(change) = change_chain.changes.pop() {
-                                let res = change.revert(world, &change_chain.entity_remap).unwrap();
-                                if let ChangeResult::SuccessWithRemap(remap) = res {
-                                    change_chain.entity_remap.extend(remap);
+                                match change.revert(world, &change_chain.entity_remap) {
+                                    Ok(res) => {
+                                        change_chain.update_remap(res);
+                                        change_chain.changes_for_redo.push(change);
+                                    }
+                                    Err(e) => {
+                                        error!("Failed to undo {}: {}", change.debug_text(), e);
+                                        change_chain.changes.push(change);
+                                    }
                                 }
-                                change_chain.changes_for_redo.push(change);
                             }
                         }
                         UndoRedo::Redo => {
                             if let Some(change) = change_chain.changes_for_redo.pop() {
                                 let inverse_change = change.get_inverse();
-                                let res = inverse_change
-                                    .revert(world, &change_chain.entity_remap)
-                                    .unwrap();
-                                if let ChangeResult::SuccessWithRemap(remap) = res {
-                                    change_chain.entity_remap.extend(remap);
+                                match inverse_change.revert(world, &change_chain.entity_remap) {
+                                    Ok(res) => {
+                                        change_chain.update_remap(res);
+                                        change_chain.changes.push(change);
+                                    }
+                                    Err(e) => {
+                                        error!("Failed to redo {}: {}", change.debug_text(), e);
+                                        change_chain.changes_for_redo.push(change);
+                                    }
                                 }
-                                change_chain.changes.push(change);
                             }
                         }
                     }
@@ -419,21 +427,37 @@ impl Default for ChangeChainSettings {
 
 impl ChangeChain {
     /// Undo last registered change
+    /// A change that fails to revert stays in the chain.
     pub fn undo(&mut self, world: &mut World) {
         if let Some(change) = self.changes.pop() {
-            let res = change.revert(world, &self.entity_remap).unwrap();
-            self.changes_for_redo.push(change);
-            self.update_remap(res);
+            match change.revert(world, &self.entity_remap) {
+                Ok(res) => {
+                    self.changes_for_redo.push(change);
+                    self.update_remap(res);
+                }
+                Err(e) => {
+                    error!("Failed to undo {}: {}", change.debug_text(), e);
+                    self.changes.push(change);
+                }
+            }
         }
     }
 
     /// Redo last undone change
+    /// A change that fails to apply again stays in the chain.
     pub fn redo(&mut self, world: &mut World) {
         if let Some(change) = self.changes_for_redo.pop() {
             let inverse_change = change.get_inverse();
-            let res = inverse_change.revert(world, &self.entity_remap).unwrap();
-            self.changes.push(change);
-            self.update_remap(res);
+            match inverse_change.revert(world, &self.entity_remap) {
+                Ok(res) => {
+                    self.changes.push(change);
+                    self.update_remap(res);
+                }
+                Err(e) => {
+                    error!("Failed to redo {}: {}", change.debug_text(), e);
+                    self.changes_for_redo.push(change);
+                }
+            }
         }
     }
 